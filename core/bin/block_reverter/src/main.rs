@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, sync::Arc};
 
 use anyhow::Context as _;
 use clap::{Parser, Subcommand};
@@ -27,6 +27,7 @@ use zksync_env_config::{object_store::SnapshotsObjectStoreConfig, FromEnv};
 use zksync_object_store::ObjectStoreFactory;
 use zksync_protobuf_config::proto;
 use zksync_types::{Address, L1BatchNumber};
+use zksync_vm_runner::impls::Erc20TransfersIndexer;
 
 #[derive(Debug, Parser)]
 #[command(author = "Matter Labs", version, about = "Block revert utility", long_about = None)]
@@ -381,6 +382,9 @@ async fn main() -> anyhow::Result<()> {
 
             if rollback_postgres {
                 block_reverter.enable_rolling_back_postgres();
+                // Data of the built-in custom workloads may be present regardless of the node configuration.
+                block_reverter
+                    .add_vm_runner_workload_to_rollback(Arc::new(Erc20TransfersIndexer::new()));
                 if rollback_snapshots {
                     let object_store_config = SnapshotsObjectStoreConfig::from_env()
                         .context("SnapshotsObjectStoreConfig::from_env()")?;
@@ -11,7 +11,10 @@ use anyhow::Context;
 use serde::Deserialize;
 use zksync_config::{
     configs::{
-        api::{MaxResponseSize, MaxResponseSizeOverrides},
+        api::{
+            ApiKeyQuotas, ApiKeysConfig, MaxResponseSize, MaxResponseSizeOverrides,
+            MethodComputeUnits,
+        },
         consensus::{ConsensusConfig, ConsensusSecrets},
         en_config::ENConfig,
         GeneralConfig, Secrets,
@@ -25,14 +28,14 @@ use zksync_core_leftovers::temp_config_store::read_yaml_repr;
 use zksync_dal::{ConnectionPool, Core};
 use zksync_metadata_calculator::MetadataCalculatorRecoveryConfig;
 use zksync_node_api_server::{
-    tx_sender::{TimestampAsserterParams, TxSenderConfig},
+    tx_sender::{forwarding_queue::TxForwardingConfig, TimestampAsserterParams, TxSenderConfig},
     web3::{state::InternalApiConfig, Namespace},
 };
 use zksync_protobuf_config::proto;
 use zksync_snapshots_applier::SnapshotsApplierConfig;
 use zksync_types::{
     api::BridgeAddresses, commitment::L1BatchCommitmentMode, url::SensitiveUrl, Address,
-    L1BatchNumber, L1ChainId, L2ChainId, SLChainId, ETHEREUM_ADDRESS,
+    L1BatchNumber, L1ChainId, L2ChainId, SLChainId, ETHEREUM_ADDRESS, H256,
 };
 use zksync_web3_decl::{
     client::{DynClient, L2},
@@ -290,6 +293,12 @@ pub(crate) struct OptionalENConfig {
     /// Max number of cache misses during one VM execution. If the number of cache misses exceeds this value, the API server panics.
     /// This is a temporary solution to mitigate API request resulting in thousands of DB queries.
     pub vm_execution_cache_misses_limit: Option<usize>,
+    /// Max wall-clock duration in milliseconds of a single VM execution for `eth_call`-like methods and gas estimation.
+    /// If not specified, the duration is not limited.
+    vm_execution_timeout_ms: Option<u64>,
+    /// Max number of VM cycles in a single VM execution for `eth_call`-like methods and gas estimation.
+    /// If not specified, the number of cycles is only limited by the gas limit.
+    pub vm_execution_cycles_limit: Option<u64>,
     /// Limit for fee history block range.
     #[serde(default = "OptionalENConfig::default_fee_history_limit")]
     pub fee_history_limit: u64,
@@ -327,6 +336,9 @@ pub(crate) struct OptionalENConfig {
     /// values cache will be disabled.
     #[serde(default = "OptionalENConfig::default_latest_values_cache_size_mb")]
     latest_values_cache_size_mb: usize,
+    /// Path to the RocksDB-backed warm storage for the latest values cache. If not set, the warm storage is disabled.
+    #[serde(default)]
+    pub latest_values_warm_storage_path: Option<String>,
     /// Enabled JSON RPC API namespaces.
     api_namespaces: Option<Vec<Namespace>>,
     /// Whether to support HTTP methods that install filters and query filter changes.
@@ -353,6 +365,24 @@ pub(crate) struct OptionalENConfig {
     /// (hundreds or thousands RPS).
     #[serde(default = "OptionalENConfig::default_extended_api_tracing")]
     pub extended_rpc_tracing: bool,
+    /// API keys accepted by the JSON-RPC servers together with their compute unit quotas per minute,
+    /// in the `<name>:<key>=<compute_units_per_minute>,...` format. If not set, API keys are not checked.
+    api_keys: Option<ApiKeyQuotas>,
+    /// Costs of RPC methods in compute units, in the `<method_name>=<cost>,...` format. Methods
+    /// not mentioned here cost 1 compute unit per call. Only used if `api_keys` are set.
+    #[serde(default)]
+    api_method_compute_units: MethodComputeUnits,
+    /// Compute unit quota per minute shared by all requests without a recognized API key. If not set,
+    /// such requests are rejected. Only used if `api_keys` are set.
+    api_anonymous_compute_units_per_minute: Option<NonZeroU32>,
+    /// HTTP header to read API keys from.
+    #[serde(default = "OptionalENConfig::default_api_key_header")]
+    api_key_header: String,
+    /// Whether to take the API key from the last segment of the request URL path if the `api_key_header` is missing.
+    /// Disabled by default since keys in URLs are more likely to leak; if disabled, requests without the header
+    /// are treated as anonymous.
+    #[serde(default)]
+    api_key_in_url_path: bool,
 
     // Health checks
     /// Time limit in milliseconds to mark a health check as slow and log the corresponding warning.
@@ -373,6 +403,9 @@ pub(crate) struct OptionalENConfig {
     /// considered experimental.
     #[serde(default)]
     pub estimate_gas_optimize_search: bool,
+    /// Maximum number of recent gas estimates cached by the API server. If set to 0 (the default), the cache is disabled.
+    #[serde(default)]
+    pub estimate_gas_cache_size: usize,
     /// The multiplier to use when suggesting gas price. Should be higher than one,
     /// otherwise if the L1 prices soar, the suggested gas price won't be sufficient to be included in block.
     #[serde(default = "OptionalENConfig::default_gas_price_scale_factor")]
@@ -420,6 +453,14 @@ pub(crate) struct OptionalENConfig {
     /// Enables the stale keys repair task for the Merkle tree.
     #[serde(default)]
     pub merkle_tree_repair_stale_keys: bool,
+    /// Migrates the Merkle tree to the lightweight mode in place on node start, removing tree nodes
+    /// for all tree versions except the latest one.
+    #[serde(default)]
+    pub merkle_tree_migrate_to_lightweight: bool,
+    /// If set, the Merkle tree will be rebuilt in the full mode from Postgres storage logs at the specified path
+    /// in the background. The main tree continues operating normally during the rebuild.
+    #[serde(default)]
+    pub merkle_tree_rebuild_path: Option<String>,
 
     // Postgres config (new parameters)
     /// Threshold in milliseconds for the DB connection lifetime to denote it as long-living and log its details.
@@ -450,6 +491,11 @@ pub(crate) struct OptionalENConfig {
     /// Number of requests per second allocated for the main node HTTP client. Default is 100 requests.
     #[serde(default = "OptionalENConfig::default_main_node_rate_limit_rps")]
     pub main_node_rate_limit_rps: NonZeroUsize,
+    /// Fallback L2 RPC endpoints (e.g., trusted external nodes) used if the main node is unavailable. Requests are sent
+    /// to a single healthy upstream at a time, starting from the main node. Each endpoint is rate-limited
+    /// according to `main_node_rate_limit_rps`, and is checked to have the same L1 and L2 chain IDs as the node.
+    #[serde(default)]
+    pub main_node_fallback_urls: Vec<SensitiveUrl>,
 
     #[serde(default)]
     pub l1_batch_commit_data_generator_mode: L1BatchCommitmentMode,
@@ -487,6 +533,19 @@ pub(crate) struct OptionalENConfig {
     /// If set to 0, L1 batches will not be retained based on their timestamp. The default value is 7 days.
     #[serde(default = "OptionalENConfig::default_pruning_data_retention_sec")]
     pruning_data_retention_sec: u64,
+    /// Addresses of contracts whose events are retained by the pruner.
+    #[serde(default)]
+    pub pruning_retained_contract_addresses: Vec<Address>,
+    /// Event topics (i.e., `topic1` values) of events retained by the pruner.
+    #[serde(default)]
+    pub pruning_retained_event_topics: Vec<H256>,
+    /// Initiator addresses of transactions whose events are retained by the pruner.
+    #[serde(default)]
+    pub pruning_retained_tx_initiators: Vec<Address>,
+    /// Object store to export data to before it is hard-pruned. If not set, pruned data is not exported.
+    /// Configured using env variables with the `EN_PRUNING_EXPORT_OBJECT_STORE_` prefix.
+    #[serde(default)]
+    pub pruning_export_object_store: Option<ObjectStoreConfig>,
     /// Gateway RPC URL, needed for operating during migration.
     pub gateway_url: Option<SensitiveUrl>,
     /// Interval for bridge addresses refreshing in seconds.
@@ -531,6 +590,14 @@ impl OptionalENConfig {
                 general_config.api_config,
                 web3_json_rpc.vm_execution_cache_misses_limit
             ),
+            vm_execution_timeout_ms: load_config!(
+                general_config.api_config,
+                web3_json_rpc.vm_execution_timeout_ms
+            ),
+            vm_execution_cycles_limit: load_config!(
+                general_config.api_config,
+                web3_json_rpc.vm_execution_cycles_limit
+            ),
             fee_history_limit: load_optional_config_or_default!(
                 general_config.api_config,
                 web3_json_rpc.fee_history_limit,
@@ -581,6 +648,10 @@ impl OptionalENConfig {
                 web3_json_rpc.latest_values_cache_size_mb,
                 default_latest_values_cache_size_mb
             ),
+            latest_values_warm_storage_path: general_config
+                .api_config
+                .as_ref()
+                .and_then(|a| a.web3_json_rpc.latest_values_warm_storage_path.clone()),
             filters_disabled: general_config
                 .api_config
                 .as_ref()
@@ -620,6 +691,11 @@ impl OptionalENConfig {
                 .as_ref()
                 .map(|a| a.web3_json_rpc.estimate_gas_optimize_search)
                 .unwrap_or_default(),
+            estimate_gas_cache_size: general_config
+                .api_config
+                .as_ref()
+                .map(|a| a.web3_json_rpc.estimate_gas_cache_size())
+                .unwrap_or_default(),
             gas_price_scale_factor: load_config_or_default!(
                 general_config.api_config,
                 web3_json_rpc.gas_price_scale_factor,
@@ -660,6 +736,16 @@ impl OptionalENConfig {
                 .map_or(false, |config| {
                     config.experimental.merkle_tree_repair_stale_keys
                 }),
+            merkle_tree_migrate_to_lightweight: general_config
+                .db_config
+                .as_ref()
+                .map_or(false, |config| {
+                    config.experimental.merkle_tree_migrate_to_lightweight
+                }),
+            merkle_tree_rebuild_path: general_config
+                .db_config
+                .as_ref()
+                .and_then(|config| config.experimental.merkle_tree_rebuild_path.clone()),
             database_long_connection_threshold_ms: load_config!(
                 general_config.postgres_config,
                 long_connection_threshold_ms
@@ -708,6 +794,22 @@ impl OptionalENConfig {
                 data_retention_sec,
                 default_pruning_data_retention_sec
             ),
+            pruning_retained_contract_addresses: general_config
+                .pruning
+                .as_ref()
+                .map(|a| a.retained_contract_addresses.clone())
+                .unwrap_or_default(),
+            pruning_retained_event_topics: general_config
+                .pruning
+                .as_ref()
+                .map(|a| a.retained_event_topics.clone())
+                .unwrap_or_default(),
+            pruning_retained_tx_initiators: general_config
+                .pruning
+                .as_ref()
+                .map(|a| a.retained_tx_initiators.clone())
+                .unwrap_or_default(),
+            pruning_export_object_store: load_config!(general_config.pruning, export_object_store),
             protective_reads_persistence_enabled: general_config
                 .db_config
                 .as_ref()
@@ -728,9 +830,15 @@ impl OptionalENConfig {
                 web3_json_rpc.extended_api_tracing,
                 default_extended_api_tracing
             ),
+            api_keys: None,
+            api_method_compute_units: MethodComputeUnits::default(),
+            api_anonymous_compute_units_per_minute: None,
+            api_key_header: Self::default_api_key_header(),
+            api_key_in_url_path: false,
             main_node_rate_limit_rps: enconfig
                 .main_node_rate_limit_rps
                 .unwrap_or_else(Self::default_main_node_rate_limit_rps),
+            main_node_fallback_urls: enconfig.main_node_fallback_urls.clone(),
             api_namespaces,
             contracts_diamond_proxy_addr: None,
             gateway_url: secrets
@@ -857,6 +965,10 @@ impl OptionalENConfig {
         true
     }
 
+    fn default_api_key_header() -> String {
+        "x-api-key".to_owned()
+    }
+
     fn default_main_node_rate_limit_rps() -> NonZeroUsize {
         NonZeroUsize::new(100).unwrap()
     }
@@ -886,6 +998,7 @@ impl OptionalENConfig {
             .from_env()
             .context("could not load external node config")?;
         result.snapshots_recovery_object_store = snapshot_recovery_object_store_config().ok();
+        result.pruning_export_object_store = pruning_export_object_store_config().ok();
         Ok(result)
     }
 
@@ -951,6 +1064,20 @@ impl OptionalENConfig {
         }
     }
 
+    pub fn api_keys_config(&self) -> Option<ApiKeysConfig> {
+        Some(ApiKeysConfig {
+            keys: self.api_keys.clone()?,
+            method_costs: self.api_method_compute_units.clone(),
+            anonymous_compute_units_per_minute: self.api_anonymous_compute_units_per_minute,
+            header_name: self.api_key_header.clone(),
+            key_in_url_path: self.api_key_in_url_path,
+        })
+    }
+
+    pub fn vm_execution_timeout(&self) -> Option<Duration> {
+        self.vm_execution_timeout_ms.map(Duration::from_millis)
+    }
+
     pub fn healthcheck_slow_time_limit(&self) -> Option<Duration> {
         self.healthcheck_slow_time_limit_ms
             .map(Duration::from_millis)
@@ -1123,6 +1250,9 @@ pub(crate) struct ExperimentalENConfig {
     /// Maximum number of files concurrently opened by state keeper cache RocksDB. Useful to fit into OS limits; can be used
     /// as a rudimentary way to control RAM usage of the cache.
     pub state_keeper_db_max_open_files: Option<NonZeroU32>,
+    /// Number of L1 batches processed by the state keeper RocksDB cache after which the cache is fully compacted,
+    /// which physically removes overwritten values. If not set, the cache is never compacted explicitly.
+    pub state_keeper_db_compaction_interval: Option<NonZeroU32>,
 
     // Snapshot recovery
     /// L1 batch number of the snapshot to use during recovery. Specifying this parameter is mostly useful for testing.
@@ -1145,10 +1275,47 @@ pub(crate) struct ExperimentalENConfig {
     #[serde(default)] // Temporarily use a conservative option (sequential recovery) as default
     pub snapshots_recovery_tree_parallel_persistence_buffer: Option<NonZeroUsize>,
 
+    // Recovery from L1
+    /// Enables recovering node storage from pubdata published on L1 instead of genesis or snapshot recovery.
+    /// Mutually exclusive with snapshot recovery.
+    #[serde(default)]
+    pub l1_recovery_enabled: bool,
+    /// L1 block to start searching for L1 batch commitments from. Should be set to a block before the first
+    /// L1 batch commitment of the chain; setting it to a later block will make recovery fail.
+    #[serde(default)]
+    pub l1_recovery_from_block: u64,
+
     // Commitment generator
     /// Maximum degree of parallelism during commitment generation, i.e., the maximum number of L1 batches being processed in parallel.
     /// If not specified, commitment generator will use a value roughly equal to the number of CPU cores with some clamping applied.
     pub commitment_generator_max_parallelism: Option<NonZeroU32>,
+
+    // API
+    /// Whether to persist submitted transactions in Postgres and forward them to the main node in the background
+    /// (with retries) instead of proxying them synchronously.
+    #[serde(default)]
+    pub tx_forwarding_queue_enabled: bool,
+    /// Interval between polling the forwarding queue if there are no transactions to forward.
+    #[serde(default = "ExperimentalENConfig::default_tx_forwarding_poll_interval_ms")]
+    tx_forwarding_poll_interval_ms: u64,
+    /// Maximum number of transactions forwarded to the main node in a single iteration.
+    #[serde(default = "ExperimentalENConfig::default_tx_forwarding_batch_size")]
+    pub tx_forwarding_batch_size: NonZeroUsize,
+    /// Delay before the first retry after a failed delivery attempt. Subsequent delays grow exponentially.
+    #[serde(default = "ExperimentalENConfig::default_tx_forwarding_initial_retry_delay_ms")]
+    tx_forwarding_initial_retry_delay_ms: u64,
+    /// Upper bound for the delay between delivery attempts.
+    #[serde(default = "ExperimentalENConfig::default_tx_forwarding_max_retry_delay_ms")]
+    tx_forwarding_max_retry_delay_ms: u64,
+    /// How long transactions rejected by the main node are retained so that their status can be queried.
+    #[serde(default = "ExperimentalENConfig::default_tx_forwarding_rejected_tx_retention_sec")]
+    tx_forwarding_rejected_tx_retention_sec: u64,
+
+    // Reorg detector
+    /// Whether to automatically remediate detected reorgs by reverting the node storage to the last correct L1 batch
+    /// and restarting the node in-process. If disabled, the node exits with an error once a reorg is detected.
+    #[serde(default)]
+    pub reorg_auto_remediation_enabled: bool,
 }
 
 impl ExperimentalENConfig {
@@ -1160,17 +1327,53 @@ impl ExperimentalENConfig {
         MetadataCalculatorRecoveryConfig::default().desired_chunk_size
     }
 
+    fn default_tx_forwarding_poll_interval_ms() -> u64 {
+        TxForwardingConfig::default().poll_interval.as_millis() as u64
+    }
+
+    fn default_tx_forwarding_batch_size() -> NonZeroUsize {
+        NonZeroUsize::new(TxForwardingConfig::default().batch_size).unwrap()
+    }
+
+    fn default_tx_forwarding_initial_retry_delay_ms() -> u64 {
+        TxForwardingConfig::default()
+            .initial_retry_delay
+            .as_millis() as u64
+    }
+
+    fn default_tx_forwarding_max_retry_delay_ms() -> u64 {
+        TxForwardingConfig::default().max_retry_delay.as_millis() as u64
+    }
+
+    fn default_tx_forwarding_rejected_tx_retention_sec() -> u64 {
+        TxForwardingConfig::default()
+            .rejected_tx_retention
+            .as_secs()
+    }
+
     #[cfg(test)]
     fn mock() -> Self {
         Self {
             state_keeper_db_block_cache_capacity_mb:
                 Self::default_state_keeper_db_block_cache_capacity_mb(),
             state_keeper_db_max_open_files: None,
+            state_keeper_db_compaction_interval: None,
             snapshots_recovery_l1_batch: None,
             snapshots_recovery_drop_storage_key_preimages: false,
             snapshots_recovery_tree_chunk_size: Self::default_snapshots_recovery_tree_chunk_size(),
             snapshots_recovery_tree_parallel_persistence_buffer: None,
+            l1_recovery_enabled: false,
+            l1_recovery_from_block: 0,
             commitment_generator_max_parallelism: None,
+            tx_forwarding_queue_enabled: false,
+            tx_forwarding_poll_interval_ms: Self::default_tx_forwarding_poll_interval_ms(),
+            tx_forwarding_batch_size: Self::default_tx_forwarding_batch_size(),
+            tx_forwarding_initial_retry_delay_ms:
+                Self::default_tx_forwarding_initial_retry_delay_ms(),
+            tx_forwarding_max_retry_delay_ms: Self::default_tx_forwarding_max_retry_delay_ms(),
+            tx_forwarding_rejected_tx_retention_sec:
+                Self::default_tx_forwarding_rejected_tx_retention_sec(),
+            reorg_auto_remediation_enabled: false,
         }
     }
 
@@ -1179,6 +1382,19 @@ impl ExperimentalENConfig {
         self.state_keeper_db_block_cache_capacity_mb * BYTES_IN_MEGABYTE
     }
 
+    /// Returns the configuration of the transaction forwarding queue.
+    pub fn tx_forwarding_config(&self) -> TxForwardingConfig {
+        TxForwardingConfig {
+            poll_interval: Duration::from_millis(self.tx_forwarding_poll_interval_ms),
+            batch_size: self.tx_forwarding_batch_size.get(),
+            initial_retry_delay: Duration::from_millis(self.tx_forwarding_initial_retry_delay_ms),
+            max_retry_delay: Duration::from_millis(self.tx_forwarding_max_retry_delay_ms),
+            rejected_tx_retention: Duration::from_secs(
+                self.tx_forwarding_rejected_tx_retention_sec,
+            ),
+        }
+    }
+
     pub fn from_configs(general_config: &GeneralConfig) -> anyhow::Result<Self> {
         Ok(Self {
             state_keeper_db_block_cache_capacity_mb: load_config_or_default!(
@@ -1190,6 +1406,10 @@ impl ExperimentalENConfig {
                 general_config.db_config,
                 experimental.state_keeper_db_max_open_files
             ),
+            state_keeper_db_compaction_interval: load_config!(
+                general_config.db_config,
+                experimental.state_keeper_db_compaction_interval
+            ),
             snapshots_recovery_l1_batch: load_config!(general_config.snapshot_recovery, l1_batch),
             snapshots_recovery_tree_chunk_size: load_optional_config_or_default!(
                 general_config.snapshot_recovery,
@@ -1204,10 +1424,23 @@ impl ExperimentalENConfig {
                 .snapshot_recovery
                 .as_ref()
                 .map_or(false, |config| config.drop_storage_key_preimages),
+            // Recovery from L1 can only be configured via env vars.
+            l1_recovery_enabled: false,
+            l1_recovery_from_block: 0,
             commitment_generator_max_parallelism: general_config
                 .commitment_generator
                 .as_ref()
                 .map(|a| a.max_parallelism),
+            // Forwarding queue can only be configured via env vars.
+            tx_forwarding_queue_enabled: false,
+            tx_forwarding_poll_interval_ms: Self::default_tx_forwarding_poll_interval_ms(),
+            tx_forwarding_batch_size: Self::default_tx_forwarding_batch_size(),
+            tx_forwarding_initial_retry_delay_ms:
+                Self::default_tx_forwarding_initial_retry_delay_ms(),
+            tx_forwarding_max_retry_delay_ms: Self::default_tx_forwarding_max_retry_delay_ms(),
+            tx_forwarding_rejected_tx_retention_sec:
+                Self::default_tx_forwarding_rejected_tx_retention_sec(),
+            reorg_auto_remediation_enabled: false,
         })
     }
 }
@@ -1253,6 +1486,13 @@ pub(crate) fn snapshot_recovery_object_store_config() -> anyhow::Result<ObjectSt
         .context("failed loading snapshot object store config from env variables")
 }
 
+/// Configuration of the object store used to export pruned data. Should be loaded optionally, only if pruning is enabled.
+pub(crate) fn pruning_export_object_store_config() -> anyhow::Result<ObjectStoreConfig> {
+    envy::prefixed("EN_PRUNING_EXPORT_OBJECT_STORE_")
+        .from_env::<ObjectStoreConfig>()
+        .context("failed loading pruning export object store config from env variables")
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ApiComponentConfig {
     /// Address of the tree API used by this EN in case it does not have a
@@ -1504,6 +1744,9 @@ impl From<&ExternalNodeConfig> for TxSenderConfig {
             gas_price_scale_factor: config.optional.gas_price_scale_factor,
             max_nonce_ahead: config.optional.max_nonce_ahead,
             vm_execution_cache_misses_limit: config.optional.vm_execution_cache_misses_limit,
+            vm_execution_timeout: config.optional.vm_execution_timeout(),
+            vm_execution_cycles_limit: config.optional.vm_execution_cycles_limit,
+            estimate_gas_cache_size: config.optional.estimate_gas_cache_size,
             // We set these values to the maximum since we don't know the actual values
             // and they will be enforced by the main node anyway.
             max_allowed_l2_tx_gas_limit: u64::MAX,
@@ -76,6 +76,7 @@ fn parsing_optional_config_from_empty_env() {
     assert_eq!(config.subscriptions_limit, 10_000);
     assert_eq!(config.fee_history_limit, 1_024);
     assert_eq!(config.polling_interval(), Duration::from_millis(200));
+    assert!(config.api_keys_config().is_none());
     assert_eq!(config.max_tx_size_bytes, 1_000_000);
     assert_eq!(
         config.merkle_tree_processing_delay(),
@@ -129,6 +130,9 @@ fn parsing_optional_config_from_env() {
         ),
         ("EN_L1_BATCH_COMMIT_DATA_GENERATOR_MODE", "Validium"),
         ("EN_TIMESTAMP_ASSERTER_MIN_TIME_TILL_END_SEC", "2"),
+        ("EN_API_KEYS", "alice:0123abcd=1000"),
+        ("EN_API_METHOD_COMPUTE_UNITS", "eth_call=10"),
+        ("EN_API_KEY_IN_URL_PATH", "true"),
     ];
     let env_vars = env_vars
         .into_iter()
@@ -174,6 +178,12 @@ fn parsing_optional_config_from_env() {
         config.l1_batch_commit_data_generator_mode,
         L1BatchCommitmentMode::Validium
     );
+    let api_keys = config.api_keys_config().unwrap();
+    assert_eq!(api_keys.keys.iter().len(), 1);
+    assert_eq!(api_keys.method_costs.get("eth_call").get(), 10);
+    assert_eq!(api_keys.anonymous_compute_units_per_minute, None);
+    assert_eq!(api_keys.header_name, "x-api-key");
+    assert!(api_keys.key_in_url_path);
 }
 
 #[test]
@@ -200,6 +210,9 @@ fn parsing_experimental_config_from_empty_env() {
     let config: ExperimentalENConfig = envy::prefixed("EN_EXPERIMENTAL_").from_iter([]).unwrap();
     assert_eq!(config.state_keeper_db_block_cache_capacity(), 128 << 20);
     assert_eq!(config.state_keeper_db_max_open_files, None);
+    let forwarding_config = config.tx_forwarding_config();
+    assert_eq!(forwarding_config.poll_interval, Duration::from_millis(100));
+    assert_eq!(forwarding_config.batch_size, 100);
 }
 
 #[test]
@@ -210,6 +223,17 @@ fn parsing_experimental_config_from_env() {
             "64",
         ),
         ("EN_EXPERIMENTAL_STATE_KEEPER_DB_MAX_OPEN_FILES", "100"),
+        ("EN_EXPERIMENTAL_TX_FORWARDING_POLL_INTERVAL_MS", "50"),
+        ("EN_EXPERIMENTAL_TX_FORWARDING_BATCH_SIZE", "10"),
+        (
+            "EN_EXPERIMENTAL_TX_FORWARDING_INITIAL_RETRY_DELAY_MS",
+            "200",
+        ),
+        ("EN_EXPERIMENTAL_TX_FORWARDING_MAX_RETRY_DELAY_MS", "10000"),
+        (
+            "EN_EXPERIMENTAL_TX_FORWARDING_REJECTED_TX_RETENTION_SEC",
+            "60",
+        ),
     ];
     let env_vars = env_vars
         .into_iter()
@@ -220,4 +244,16 @@ fn parsing_experimental_config_from_env() {
         .unwrap();
     assert_eq!(config.state_keeper_db_block_cache_capacity(), 64 << 20);
     assert_eq!(config.state_keeper_db_max_open_files, NonZeroU32::new(100));
+    let forwarding_config = config.tx_forwarding_config();
+    assert_eq!(forwarding_config.poll_interval, Duration::from_millis(50));
+    assert_eq!(forwarding_config.batch_size, 10);
+    assert_eq!(
+        forwarding_config.initial_retry_delay,
+        Duration::from_millis(200)
+    );
+    assert_eq!(forwarding_config.max_retry_delay, Duration::from_secs(10));
+    assert_eq!(
+        forwarding_config.rejected_tx_retention,
+        Duration::from_secs(60)
+    );
 }
@@ -3,6 +3,7 @@ use std::{collections::HashSet, str::FromStr};
 use anyhow::Context as _;
 use clap::Parser;
 use node_builder::ExternalNodeBuilder;
+use zksync_reorg_detector::ReorgIncidents;
 use zksync_web3_decl::client::{Client, DynClient, L2};
 
 use crate::config::{generate_consensus_secrets, ExternalNodeConfig};
@@ -115,6 +116,35 @@ fn tokio_runtime() -> anyhow::Result<tokio::runtime::Runtime> {
         .build()?)
 }
 
+impl Cli {
+    /// Loads the local part of the node configuration.
+    fn load_config(&self) -> anyhow::Result<ExternalNodeConfig<()>> {
+        let mut config = if let Some(config_path) = self.config_path.clone() {
+            let secrets_path = self.secrets_path.clone().unwrap();
+            let external_node_config_path = self.external_node_config_path.clone().unwrap();
+            if self.enable_consensus {
+                anyhow::ensure!(
+                    self.consensus_path.is_some(),
+                    "if --config-path and --enable-consensus are specified, then --consensus-path should be used to specify the location of the consensus config"
+                );
+            }
+            ExternalNodeConfig::from_files(
+                config_path,
+                external_node_config_path,
+                secrets_path,
+                self.consensus_path.clone(),
+            )?
+        } else {
+            ExternalNodeConfig::new().context("Failed to load node configuration")?
+        };
+
+        if !self.enable_consensus {
+            config.consensus = None;
+        }
+        Ok(config)
+    }
+}
+
 fn main() -> anyhow::Result<()> {
     let runtime = tokio_runtime()?;
 
@@ -128,28 +158,7 @@ fn main() -> anyhow::Result<()> {
         return Ok(());
     }
 
-    let mut config = if let Some(config_path) = opt.config_path.clone() {
-        let secrets_path = opt.secrets_path.clone().unwrap();
-        let external_node_config_path = opt.external_node_config_path.clone().unwrap();
-        if opt.enable_consensus {
-            anyhow::ensure!(
-                opt.consensus_path.is_some(),
-                "if --config-path and --enable-consensus are specified, then --consensus-path should be used to specify the location of the consensus config"
-            );
-        }
-        ExternalNodeConfig::from_files(
-            config_path,
-            external_node_config_path,
-            secrets_path,
-            opt.consensus_path.clone(),
-        )?
-    } else {
-        ExternalNodeConfig::new().context("Failed to load node configuration")?
-    };
-
-    if !opt.enable_consensus {
-        config.consensus = None;
-    }
+    let config = opt.load_config()?;
     let guard = {
         // Observability stack implicitly spawns several tokio tasks, so we need to call this method
         // from within tokio context.
@@ -157,22 +166,48 @@ fn main() -> anyhow::Result<()> {
         config.observability.build_observability()?
     };
 
-    // Build L1 and L2 clients.
-    let main_node_url = &config.required.main_node_url;
-    tracing::info!("Main node URL is: {main_node_url:?}");
-    let main_node_client = Client::http(main_node_url.clone())
-        .context("failed creating JSON-RPC client for main node")?
-        .for_network(config.required.l2_chain_id.into())
-        .with_allowed_requests_per_second(config.optional.main_node_rate_limit_rps)
-        .build();
-    let main_node_client = Box::new(main_node_client) as Box<DynClient<L2>>;
-
-    let config = runtime
-        .block_on(config.fetch_remote(main_node_client.as_ref()))
-        .context("failed fetching remote part of node config from main node")?;
-
-    let node = ExternalNodeBuilder::on_runtime(runtime, config)
-        .build(opt.components.0.into_iter().collect())?;
-    node.run(guard)?;
+    let components: Vec<_> = opt.components.0.iter().copied().collect();
+    // Incidents are shared across in-process restarts of the node performed to remediate reorgs.
+    let reorg_incidents = ReorgIncidents::default();
+    let mut local_config = Some(config);
+    loop {
+        let config = match local_config.take() {
+            Some(config) => config,
+            None => opt.load_config()?,
+        };
+        // Each node run gets a dedicated runtime, while `runtime` is kept alive for the observability stack.
+        let node_runtime = tokio_runtime()?;
+
+        // Build L1 and L2 clients.
+        let main_node_url = &config.required.main_node_url;
+        tracing::info!("Main node URL is: {main_node_url:?}");
+        let main_node_client = Client::http(main_node_url.clone())
+            .context("failed creating JSON-RPC client for main node")?
+            .for_network(config.required.l2_chain_id.into())
+            .with_allowed_requests_per_second(config.optional.main_node_rate_limit_rps)
+            .build();
+        let main_node_client = Box::new(main_node_client) as Box<DynClient<L2>>;
+
+        let config = node_runtime
+            .block_on(config.fetch_remote(main_node_client.as_ref()))
+            .context("failed fetching remote part of node config from main node")?;
+
+        let node = ExternalNodeBuilder::on_runtime(node_runtime, config)
+            .with_reorg_incidents(reorg_incidents.clone())
+            .build(components.clone())?;
+        node.run(None)?;
+
+        // The reorg detector only finishes successfully with a pending incident in the auto-remediation mode.
+        let Some(last_correct_l1_batch) = reorg_incidents.pending_revert() else {
+            break;
+        };
+        tracing::warn!(
+            "Restarting the node to revert its storage to the last correct L1 batch #{last_correct_l1_batch}"
+        );
+    }
+
+    // Make sure that the shutdown happens in the `tokio` context.
+    let _rt_guard = runtime.enter();
+    drop(guard);
     anyhow::Ok(())
 }
@@ -15,6 +15,7 @@ use zksync_metadata_calculator::{
     MerkleTreeReaderConfig, MetadataCalculatorConfig, MetadataCalculatorRecoveryConfig,
 };
 use zksync_node_api_server::web3::Namespace;
+use zksync_node_db_pruner::RetentionRules;
 use zksync_node_framework::{
     implementations::layers::{
         batch_status_updater::BatchStatusUpdaterLayer,
@@ -29,7 +30,9 @@ use zksync_node_framework::{
         main_node_fee_params_fetcher::MainNodeFeeParamsFetcherLayer,
         metadata_calculator::{MetadataCalculatorLayer, TreeApiServerLayer},
         node_storage_init::{
-            external_node_strategy::{ExternalNodeInitStrategyLayer, SnapshotRecoveryConfig},
+            external_node_strategy::{
+                ExternalNodeInitStrategyLayer, L1RecoveryConfig, SnapshotRecoveryConfig,
+            },
             NodeStorageInitializerLayer,
         },
         pools_layer::PoolsLayerBuilder,
@@ -51,11 +54,12 @@ use zksync_node_framework::{
             server::{Web3ServerLayer, Web3ServerOptionalConfig},
             tree_api_client::TreeApiClientLayer,
             tx_sender::{PostgresStorageCachesConfig, TxSenderLayer},
-            tx_sink::ProxySinkLayer,
+            tx_sink::{ForwardingQueueSinkLayer, ProxySinkLayer},
         },
     },
     service::{ZkStackService, ZkStackServiceBuilder},
 };
+use zksync_reorg_detector::ReorgIncidents;
 use zksync_state::RocksdbStorageOptions;
 use zksync_types::L2_ASSET_ROUTER_ADDRESS;
 
@@ -66,6 +70,7 @@ use crate::{config::ExternalNodeConfig, metrics::framework::ExternalNodeMetricsL
 pub(crate) struct ExternalNodeBuilder {
     pub(crate) node: ZkStackServiceBuilder,
     config: ExternalNodeConfig,
+    reorg_incidents: ReorgIncidents,
 }
 
 impl ExternalNodeBuilder {
@@ -74,6 +79,7 @@ impl ExternalNodeBuilder {
         Ok(Self {
             node: ZkStackServiceBuilder::new().context("Cannot create ZkStackServiceBuilder")?,
             config,
+            reorg_incidents: ReorgIncidents::default(),
         })
     }
 
@@ -81,9 +87,16 @@ impl ExternalNodeBuilder {
         Self {
             node: ZkStackServiceBuilder::on_runtime(runtime),
             config,
+            reorg_incidents: ReorgIncidents::default(),
         }
     }
 
+    /// Sets the reorg incident history shared across in-process node restarts.
+    pub fn with_reorg_incidents(mut self, incidents: ReorgIncidents) -> Self {
+        self.reorg_incidents = incidents;
+        self
+    }
+
     fn add_sigint_handler_layer(mut self) -> anyhow::Result<Self> {
         self.node.add_layer(SigintHandlerLayer);
         Ok(self)
@@ -149,7 +162,8 @@ impl ExternalNodeBuilder {
             self.config.required.main_node_url.clone(),
             self.config.optional.main_node_rate_limit_rps,
             self.config.required.l2_chain_id,
-        );
+        )
+        .with_fallback_urls(self.config.optional.main_node_fallback_urls.clone());
         self.node.add_layer(layer);
         Ok(self)
     }
@@ -239,10 +253,13 @@ impl ExternalNodeBuilder {
                 .state_keeper_db_block_cache_capacity(),
             max_open_files: self.config.experimental.state_keeper_db_max_open_files,
         };
-        let state_keeper_layer = StateKeeperLayer::new(
+        let mut state_keeper_layer = StateKeeperLayer::new(
             self.config.required.state_cache_path.clone(),
             rocksdb_options,
         );
+        if let Some(interval) = self.config.experimental.state_keeper_db_compaction_interval {
+            state_keeper_layer = state_keeper_layer.with_cache_compaction(interval);
+        }
         self.node
             .add_layer(io_layer)
             .add_layer(persistence_layer)
@@ -267,11 +284,23 @@ impl ExternalNodeBuilder {
 
     fn add_pruning_layer(mut self) -> anyhow::Result<Self> {
         if self.config.optional.pruning_enabled {
-            let layer = PruningLayer::new(
+            let mut layer = PruningLayer::new(
                 self.config.optional.pruning_removal_delay(),
                 self.config.optional.pruning_chunk_size,
                 self.config.optional.pruning_data_retention(),
-            );
+            )
+            .with_retention_rules(RetentionRules {
+                contract_addresses: self
+                    .config
+                    .optional
+                    .pruning_retained_contract_addresses
+                    .clone(),
+                event_topics: self.config.optional.pruning_retained_event_topics.clone(),
+                tx_initiators: self.config.optional.pruning_retained_tx_initiators.clone(),
+            });
+            if let Some(config) = self.config.optional.pruning_export_object_store.clone() {
+                layer = layer.with_export_object_store(config);
+            }
             self.node.add_layer(layer);
         } else {
             tracing::info!("Pruning is disabled");
@@ -391,6 +420,14 @@ impl ExternalNodeBuilder {
             layer = layer.with_stale_keys_repair();
         }
 
+        // Add Merkle tree migration between modes if requested.
+        if self.config.optional.merkle_tree_migrate_to_lightweight {
+            layer = layer.with_lightweight_migration();
+        }
+        if let Some(rebuild_path) = &self.config.optional.merkle_tree_rebuild_path {
+            layer = layer.with_rebuild(rebuild_path.clone());
+        }
+
         // Add tree pruning if needed.
         if self.config.optional.pruning_enabled {
             layer = layer.with_pruning_config(self.config.optional.pruning_removal_delay());
@@ -429,6 +466,12 @@ impl ExternalNodeBuilder {
             initial_writes_cache_size: self.config.optional.initial_writes_cache_size() as u64,
             latest_values_cache_size: self.config.optional.latest_values_cache_size() as u64,
             latest_values_max_block_lag: 20, // reasonable default
+            latest_values_warm_storage_path: self
+                .config
+                .optional
+                .latest_values_warm_storage_path
+                .clone()
+                .map(Into::into),
         };
         let max_vm_concurrency = self.config.optional.vm_concurrency_limit;
         let tx_sender_layer = TxSenderLayer::new(
@@ -438,7 +481,13 @@ impl ExternalNodeBuilder {
         )
         .with_whitelisted_tokens_for_aa_cache(true);
 
-        self.node.add_layer(ProxySinkLayer);
+        if self.config.experimental.tx_forwarding_queue_enabled {
+            self.node.add_layer(ForwardingQueueSinkLayer::new(
+                self.config.experimental.tx_forwarding_config(),
+            ));
+        } else {
+            self.node.add_layer(ProxySinkLayer);
+        }
         self.node.add_layer(tx_sender_layer);
         Ok(self)
     }
@@ -480,6 +529,8 @@ impl ExternalNodeBuilder {
             batch_request_size_limit: Some(self.config.optional.max_batch_request_size),
             response_body_size_limit: Some(self.config.optional.max_response_body_size()),
             with_extended_tracing: self.config.optional.extended_rpc_tracing,
+            api_keys: self.config.optional.api_keys_config(),
+            read_your_writes_max_wait: None,
             pruning_info_refresh_interval: Some(pruning_info_refresh_interval),
             bridge_addresses_refresh_interval: self
                 .config
@@ -515,7 +566,11 @@ impl ExternalNodeBuilder {
     }
 
     fn add_reorg_detector_layer(mut self) -> anyhow::Result<Self> {
-        self.node.add_layer(ReorgDetectorLayer);
+        let mut layer = ReorgDetectorLayer::new();
+        if self.config.experimental.reorg_auto_remediation_enabled {
+            layer = layer.with_auto_remediation(self.reorg_incidents.clone());
+        }
+        self.node.add_layer(layer);
         Ok(self)
     }
 
@@ -557,6 +612,15 @@ impl ExternalNodeBuilder {
                         .snapshots_recovery_drop_storage_key_preimages,
                     object_store_config: config.optional.snapshots_recovery_object_store.clone(),
                 });
+        let l1_recovery_config =
+            config
+                .experimental
+                .l1_recovery_enabled
+                .then(|| L1RecoveryConfig {
+                    from_l1_block: config.experimental.l1_recovery_from_block,
+                    commitment_mode: config.remote.l1_batch_commit_data_generator_mode,
+                    ..L1RecoveryConfig::new(config.l1_diamond_proxy_address())
+                });
         self.node.add_layer(ExternalNodeInitStrategyLayer {
             l2_chain_id: self.config.required.l2_chain_id,
             max_postgres_concurrency: self
@@ -564,6 +628,11 @@ impl ExternalNodeBuilder {
                 .optional
                 .snapshots_recovery_postgres_max_concurrency,
             snapshot_recovery_config,
+            l1_recovery_config,
+            reorg_incidents: config
+                .experimental
+                .reorg_auto_remediation_enabled
+                .then(|| self.reorg_incidents.clone()),
         });
         let mut layer = NodeStorageInitializerLayer::new();
         if matches!(kind, LayerKind::Precondition) {
@@ -10,8 +10,8 @@ use zksync_object_store::{ObjectStore, StoredObject};
 use zksync_types::{
     snapshots::{
         uniform_hashed_keys_chunk, SnapshotFactoryDependencies, SnapshotFactoryDependency,
-        SnapshotMetadata, SnapshotStorageLog, SnapshotStorageLogsChunk,
-        SnapshotStorageLogsStorageKey, SnapshotVersion,
+        SnapshotHeader, SnapshotMetadata, SnapshotStorageLog, SnapshotStorageLogsChunk,
+        SnapshotStorageLogsChunkMetadata, SnapshotStorageLogsStorageKey, SnapshotVersion,
     },
     L1BatchNumber, L2BlockNumber,
 };
@@ -232,6 +232,59 @@ impl SnapshotCreator {
         Ok(output_filepath)
     }
 
+    /// Stores the header of a completed snapshot in the object store, so that the snapshot can be verified
+    /// without access to Postgres.
+    async fn store_snapshot_header(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        l2_block_number: L2BlockNumber,
+    ) -> anyhow::Result<()> {
+        let mut conn = self
+            .master_pool
+            .connection_tagged("snapshots_creator")
+            .await?;
+        let snapshot = conn
+            .snapshots_dal()
+            .get_snapshot_metadata(l1_batch_number)
+            .await?
+            .with_context(|| format!("snapshot for L1 batch #{l1_batch_number} disappeared"))?;
+        let tree_data = conn
+            .blocks_dal()
+            .get_l1_batch_tree_data(l1_batch_number)
+            .await?
+            .with_context(|| format!("L1 batch #{l1_batch_number} doesn't have tree data"))?;
+        drop(conn);
+
+        let storage_logs_chunks = snapshot
+            .storage_logs_filepaths
+            .into_iter()
+            .enumerate()
+            .map(|(chunk_id, filepath)| {
+                let filepath = filepath
+                    .with_context(|| format!("storage logs chunk {chunk_id} is missing"))?;
+                Ok(SnapshotStorageLogsChunkMetadata {
+                    chunk_id: chunk_id as u64,
+                    filepath,
+                })
+            })
+            .collect::<anyhow::Result<_>>()?;
+        let header = SnapshotHeader {
+            version: snapshot.version.into(),
+            l1_batch_number,
+            l2_block_number,
+            storage_logs_chunks,
+            factory_deps_filepath: snapshot.factory_deps_filepath,
+            l1_batch_root_hash: Some(tree_data.hash),
+        };
+        let filename = self
+            .blob_store
+            .put(l1_batch_number, &header)
+            .await
+            .context("Error storing snapshot header in blob store")?;
+        tracing::info!("Saved snapshot header to {filename}");
+        Ok(())
+    }
+
     /// Returns `Ok(None)` if the created snapshot would coincide with `latest_snapshot`.
     async fn initialize_snapshot_progress(
         config: &SnapshotsCreatorConfig,
@@ -414,6 +467,8 @@ impl SnapshotCreator {
                 )
             });
         futures::future::try_join_all(tasks).await?;
+        self.store_snapshot_header(progress.l1_batch_number, last_l2_block_number_in_batch)
+            .await?;
 
         METRICS
             .snapshot_l1_batch
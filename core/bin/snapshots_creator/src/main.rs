@@ -16,14 +16,16 @@ use zksync_config::configs::PrometheusConfig;
 use zksync_core_leftovers::temp_config_store::{load_database_secrets, load_general_config};
 use zksync_dal::{ConnectionPool, Core};
 use zksync_object_store::ObjectStoreFactory;
+use zksync_types::L1BatchNumber;
 use zksync_vlog::prometheus::PrometheusExporterConfig;
 
-use crate::creator::SnapshotCreator;
+use crate::{creator::SnapshotCreator, verifier::SnapshotVerifier};
 
 mod creator;
 mod metrics;
 #[cfg(test)]
 mod tests;
+mod verifier;
 
 async fn maybe_enable_prometheus_metrics(
     prometheus_config: Option<PrometheusConfig>,
@@ -57,6 +59,21 @@ struct Opt {
     /// Path to the secrets file.
     #[structopt(long)]
     secrets_path: Option<std::path::PathBuf>,
+
+    #[structopt(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(StructOpt)]
+enum Command {
+    /// Verifies a previously created snapshot by recovering a Merkle tree from its storage logs
+    /// and comparing the tree root hash with the one specified in the snapshot header.
+    /// Only the object store is accessed; Postgres is not used.
+    Verify {
+        /// L1 batch number of the snapshot to verify.
+        #[structopt(long)]
+        l1_batch_number: u32,
+    },
 }
 
 #[tokio::main]
@@ -99,14 +116,25 @@ async fn main() -> anyhow::Result<()> {
         .build()
         .await?;
 
-    let creator = SnapshotCreator {
-        blob_store,
-        master_pool,
-        replica_pool,
-        #[cfg(test)]
-        event_listener: Box::new(()),
-    };
-    creator.run(creator_config, MIN_CHUNK_COUNT).await?;
+    match opt.command {
+        Some(Command::Verify { l1_batch_number }) => {
+            let verifier = SnapshotVerifier { blob_store };
+            let report = verifier.verify(L1BatchNumber(l1_batch_number)).await?;
+            tracing::info!("Snapshot verification report: {report:?}");
+            anyhow::ensure!(report.is_ok(), "{report}");
+            tracing::info!("{report}");
+        }
+        None => {
+            let creator = SnapshotCreator {
+                blob_store,
+                master_pool,
+                replica_pool,
+                #[cfg(test)]
+                event_listener: Box::new(()),
+            };
+            creator.run(creator_config, MIN_CHUNK_COUNT).await?;
+        }
+    }
 
     tracing::info!("Finished running snapshot creator!");
     stop_sender.send(true).ok();
@@ -18,6 +18,13 @@ pub(crate) enum StorageChunkStage {
     SaveToGcs,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "stage", rename_all = "snake_case")]
+pub(crate) enum VerificationStage {
+    Load,
+    ExtendTree,
+}
+
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "snapshots_creator")]
 pub(crate) struct SnapshotsCreatorMetrics {
@@ -37,6 +44,12 @@ pub(crate) struct SnapshotsCreatorMetrics {
     /// Latency of factory deps processing split by stage.
     #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
     pub factory_deps_processing_duration: Family<FactoryDepsStage, Histogram<Duration>>,
+    /// Total latency of snapshot verification.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub verification_duration: Histogram<Duration>,
+    /// Latency of processing a storage log chunk during snapshot verification split by stage.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub verification_chunk_duration: Family<VerificationStage, Histogram<Duration>>,
 }
 
 #[vise::register]
@@ -13,15 +13,16 @@ use rand::{thread_rng, Rng};
 use test_casing::test_casing;
 use zksync_config::SnapshotsCreatorConfig;
 use zksync_dal::{Connection, CoreDal};
+use zksync_merkle_tree::{MerkleTree, PatchSet, TreeEntry};
 use zksync_object_store::{MockObjectStore, ObjectStore};
 use zksync_types::{
     block::{L1BatchHeader, L1BatchTreeData, L2BlockHeader},
     snapshots::{
-        SnapshotFactoryDependencies, SnapshotFactoryDependency, SnapshotStorageLog,
+        SnapshotFactoryDependencies, SnapshotFactoryDependency, SnapshotHeader, SnapshotStorageLog,
         SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
     },
     AccountTreeId, Address, L1BatchNumber, L2BlockNumber, ProtocolVersion, StorageKey, StorageLog,
-    H256,
+    H256, U256,
 };
 
 use super::*;
@@ -628,3 +629,177 @@ async fn creator_fails_if_specified_l1_batch_is_missing() {
         .await
         .unwrap_err();
 }
+
+/// Computes the reference root hash for the snapshot using a conventional Merkle tree.
+fn compute_root_hash(
+    snapshot_l1_batch_number: L1BatchNumber,
+    expected_outputs: &ExpectedOutputs,
+) -> H256 {
+    let entries: Vec<_> = expected_outputs
+        .storage_logs
+        .iter()
+        .filter(|log| log.l1_batch_number_of_initial_write <= snapshot_l1_batch_number)
+        .map(|log| {
+            TreeEntry::new(
+                U256::from_little_endian(&log.key.0),
+                log.enumeration_index,
+                log.value,
+            )
+        })
+        .collect();
+    let leaf_count = entries.len() as u64;
+    let mut tree = MerkleTree::new(PatchSet::default()).unwrap();
+    let output = tree.extend(entries).unwrap();
+    assert_eq!(output.leaf_count, leaf_count);
+    output.root_hash
+}
+
+/// Creates a snapshot and returns its header together with the reference root hash.
+/// Tree data in Postgres is mocked, so the root hash in the stored header is replaced with the reference one.
+async fn create_and_load_snapshot(
+    object_store: &Arc<dyn ObjectStore>,
+    pool: &ConnectionPool<Core>,
+) -> (SnapshotHeader, H256) {
+    let mut rng = thread_rng();
+    let mut conn = pool.connection().await.unwrap();
+    let expected_outputs = prepare_postgres(&mut rng, &mut conn, 10).await;
+
+    SnapshotCreator::for_tests(object_store.clone(), pool.clone())
+        .run(TEST_CONFIG, MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+    let snapshot_l1_batch_number = L1BatchNumber(8);
+    let mut header: SnapshotHeader = object_store.get(snapshot_l1_batch_number).await.unwrap();
+    assert_eq!(header.l1_batch_number, snapshot_l1_batch_number);
+    assert_eq!(header.l1_batch_root_hash, Some(H256::zero()));
+
+    let root_hash = compute_root_hash(snapshot_l1_batch_number, &expected_outputs);
+    header.l1_batch_root_hash = Some(root_hash);
+    object_store
+        .put(snapshot_l1_batch_number, &header)
+        .await
+        .unwrap();
+    (header, root_hash)
+}
+
+#[tokio::test]
+async fn verifying_correct_snapshot() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let object_store = MockObjectStore::arc();
+    let (mut header, root_hash) = create_and_load_snapshot(&object_store, &pool).await;
+
+    let verifier = SnapshotVerifier {
+        blob_store: object_store,
+    };
+    let report = verifier.verify(L1BatchNumber(8)).await.unwrap();
+    assert!(report.is_ok(), "{report:#?}");
+    assert_eq!(report.l1_batch_number, L1BatchNumber(8));
+    assert_eq!(report.expected_root_hash, root_hash);
+    assert_eq!(report.actual_root_hash, root_hash);
+    assert_eq!(report.actual_leaf_count, report.max_enumeration_index);
+
+    header.l1_batch_root_hash = Some(H256::zero());
+    let report = verifier.verify_snapshot(&header).await.unwrap();
+    assert!(!report.is_ok(), "{report:#?}");
+    assert_eq!(report.expected_root_hash, H256::zero());
+    assert_eq!(report.actual_root_hash, root_hash);
+
+    // Headers produced by older snapshot creators cannot be verified.
+    header.l1_batch_root_hash = None;
+    let err = verifier.verify_snapshot(&header).await.unwrap_err();
+    assert!(format!("{err:#}").contains("root hash"), "{err:#}");
+}
+
+#[tokio::test]
+async fn verifying_snapshot_with_missing_chunk() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let object_store = MockObjectStore::arc();
+    let (header, root_hash) = create_and_load_snapshot(&object_store, &pool).await;
+
+    let missing_key = SnapshotStorageLogsStorageKey {
+        l1_batch_number: header.l1_batch_number,
+        chunk_id: 3,
+    };
+    object_store
+        .remove::<SnapshotStorageLogsChunk>(missing_key)
+        .await
+        .unwrap();
+
+    let verifier = SnapshotVerifier {
+        blob_store: object_store,
+    };
+    let report = verifier.verify(header.l1_batch_number).await.unwrap();
+    assert!(!report.is_ok());
+    assert_eq!(report.missing_chunks, [3]);
+    assert_ne!(report.actual_root_hash, root_hash);
+}
+
+#[tokio::test]
+async fn verifying_snapshot_with_chunk_missing_from_header() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let object_store = MockObjectStore::arc();
+    let (mut header, root_hash) = create_and_load_snapshot(&object_store, &pool).await;
+    header
+        .storage_logs_chunks
+        .retain(|chunk| chunk.chunk_id != 2);
+
+    let verifier = SnapshotVerifier {
+        blob_store: object_store,
+    };
+    let report = verifier.verify_snapshot(&header).await.unwrap();
+    assert!(!report.is_ok());
+    assert_eq!(report.missing_chunks, [2]);
+    assert_ne!(report.actual_root_hash, root_hash);
+}
+
+#[tokio::test]
+async fn verifying_snapshot_with_corrupted_chunk() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let object_store = MockObjectStore::arc();
+    let (header, root_hash) = create_and_load_snapshot(&object_store, &pool).await;
+
+    let l1_batch_number = header.l1_batch_number;
+    let first_chunk: SnapshotStorageLogsChunk = object_store
+        .get(SnapshotStorageLogsStorageKey {
+            l1_batch_number,
+            chunk_id: 0,
+        })
+        .await
+        .unwrap();
+    let corrupted_key = SnapshotStorageLogsStorageKey {
+        l1_batch_number,
+        chunk_id: 1,
+    };
+    let mut corrupted_chunk: SnapshotStorageLogsChunk =
+        object_store.get(corrupted_key).await.unwrap();
+    // Duplicate a key from another chunk, and reuse an enumeration index from the same chunk.
+    corrupted_chunk
+        .storage_logs
+        .push(first_chunk.storage_logs[0].clone());
+    let mut log_with_reused_index = corrupted_chunk.storage_logs[0].clone();
+    log_with_reused_index.key = H256::repeat_byte(0xff);
+    corrupted_chunk.storage_logs.push(log_with_reused_index);
+    object_store
+        .put(corrupted_key, &corrupted_chunk)
+        .await
+        .unwrap();
+
+    let verifier = SnapshotVerifier {
+        blob_store: object_store,
+    };
+    let report = verifier.verify(l1_batch_number).await.unwrap();
+    assert!(!report.is_ok());
+    assert!(report.missing_chunks.is_empty());
+    assert_eq!(report.duplicate_key_count, 1);
+    assert_eq!(report.duplicate_keys[0].chunk_id, 1);
+    assert_eq!(
+        report.duplicate_keys[0].hashed_key,
+        first_chunk.storage_logs[0].key
+    );
+    assert_eq!(report.bad_enumeration_index_count, 1);
+    assert_eq!(
+        report.bad_enumeration_indices[0].hashed_key,
+        H256::repeat_byte(0xff)
+    );
+    assert_ne!(report.actual_root_hash, root_hash);
+}
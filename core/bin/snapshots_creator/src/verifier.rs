@@ -0,0 +1,397 @@
+//! [`SnapshotVerifier`] checking a created snapshot against the Merkle tree root hash in its header.
+
+use std::{collections::HashSet, fmt, path::Path, sync::Arc};
+
+use anyhow::Context as _;
+use zksync_merkle_tree::{recovery::MerkleTreeRecovery, RocksDBWrapper, TreeEntry};
+use zksync_object_store::{ObjectStore, ObjectStoreError};
+use zksync_storage::{db::NamedColumnFamily, RocksDB};
+use zksync_types::{
+    snapshots::{
+        SnapshotHeader, SnapshotStorageLog, SnapshotStorageLogsChunk,
+        SnapshotStorageLogsStorageKey, SnapshotVersion,
+    },
+    L1BatchNumber, StorageKey, H256, U256,
+};
+
+use crate::metrics::{VerificationStage, METRICS};
+
+/// Maximum number of problematic entries of each kind recorded in a [`VerificationReport`].
+/// Further entries are only counted.
+const MAX_REPORTED_ENTRIES: usize = 100;
+
+/// Problem with a storage log encountered during snapshot verification.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct StorageLogIssue {
+    pub chunk_id: u64,
+    pub hashed_key: H256,
+    pub enumeration_index: u64,
+}
+
+/// Report produced by [`SnapshotVerifier`].
+#[derive(Debug, Default)]
+pub(crate) struct VerificationReport {
+    pub l1_batch_number: L1BatchNumber,
+    /// Root hash of the L1 batch as specified in the snapshot header.
+    pub expected_root_hash: H256,
+    /// Root hash of the tree recovered from the snapshot storage logs.
+    pub actual_root_hash: H256,
+    /// Number of distinct keys in the snapshot storage logs.
+    pub actual_leaf_count: u64,
+    /// Maximum enumeration index in the snapshot storage logs. For a correct snapshot, enumeration indices
+    /// are distinct and non-zero, so this is equal to the leaf count.
+    pub max_enumeration_index: u64,
+    /// IDs of chunks that are not listed in the header or are missing from the object store.
+    pub missing_chunks: Vec<u64>,
+    /// Storage logs with a hashed key that was already encountered in the snapshot.
+    pub duplicate_keys: Vec<StorageLogIssue>,
+    pub duplicate_key_count: usize,
+    /// Storage logs with an enumeration index that is zero or was already encountered in the snapshot.
+    pub bad_enumeration_indices: Vec<StorageLogIssue>,
+    pub bad_enumeration_index_count: usize,
+    /// Storage logs with the initial write L1 batch after the snapshot L1 batch.
+    pub bad_initial_writes: Vec<StorageLogIssue>,
+    pub bad_initial_write_count: usize,
+}
+
+impl VerificationReport {
+    /// Checks whether the snapshot is correct.
+    pub fn is_ok(&self) -> bool {
+        self.missing_chunks.is_empty()
+            && self.duplicate_key_count == 0
+            && self.bad_enumeration_index_count == 0
+            && self.bad_initial_write_count == 0
+            && self.max_enumeration_index == self.actual_leaf_count
+            && self.expected_root_hash == self.actual_root_hash
+    }
+
+    fn record(issues: &mut Vec<StorageLogIssue>, count: &mut usize, issue: StorageLogIssue) {
+        *count += 1;
+        if issues.len() < MAX_REPORTED_ENTRIES {
+            issues.push(issue);
+        }
+    }
+}
+
+impl fmt::Display for VerificationReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let l1_batch_number = self.l1_batch_number;
+        if self.is_ok() {
+            return write!(
+                formatter,
+                "snapshot for L1 batch #{l1_batch_number} is correct (root hash: {:?}, leaf count: {})",
+                self.actual_root_hash, self.actual_leaf_count
+            );
+        }
+
+        write!(
+            formatter,
+            "snapshot for L1 batch #{l1_batch_number} is incorrect: "
+        )?;
+        let mut problems = vec![];
+        if !self.missing_chunks.is_empty() {
+            problems.push(format!("missing chunks {:?}", self.missing_chunks));
+        }
+        if self.duplicate_key_count > 0 {
+            problems.push(format!(
+                "{} duplicate keys (first: {:?})",
+                self.duplicate_key_count, self.duplicate_keys[0]
+            ));
+        }
+        if self.bad_enumeration_index_count > 0 {
+            problems.push(format!(
+                "{} bad enumeration indices (first: {:?})",
+                self.bad_enumeration_index_count, self.bad_enumeration_indices[0]
+            ));
+        }
+        if self.bad_initial_write_count > 0 {
+            problems.push(format!(
+                "{} initial writes after the snapshot L1 batch (first: {:?})",
+                self.bad_initial_write_count, self.bad_initial_writes[0]
+            ));
+        }
+        if self.max_enumeration_index != self.actual_leaf_count {
+            problems.push(format!(
+                "max enumeration index {} differs from leaf count {}",
+                self.max_enumeration_index, self.actual_leaf_count
+            ));
+        }
+        if self.expected_root_hash != self.actual_root_hash {
+            problems.push(format!(
+                "root hash mismatch (expected: {:?}, actual: {:?})",
+                self.expected_root_hash, self.actual_root_hash
+            ));
+        }
+        write!(formatter, "{}", problems.join(", "))
+    }
+}
+
+/// Column families of [`SeenEntries`].
+#[derive(Debug, Clone, Copy)]
+enum SeenEntriesColumnFamily {
+    /// Hashed keys encountered in the snapshot.
+    Keys,
+    /// Enumeration indices (big-endian) encountered in the snapshot.
+    Indices,
+}
+
+impl NamedColumnFamily for SeenEntriesColumnFamily {
+    const DB_NAME: &'static str = "snapshot_verifier_seen_entries";
+    const ALL: &'static [Self] = &[Self::Keys, Self::Indices];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Keys => "keys",
+            Self::Indices => "indices",
+        }
+    }
+}
+
+/// Disk-backed sets of keys and enumeration indices encountered in the snapshot, so that the verifier
+/// doesn't need to hold the entire snapshot state in memory.
+#[derive(Debug)]
+struct SeenEntries {
+    db: RocksDB<SeenEntriesColumnFamily>,
+}
+
+impl SeenEntries {
+    fn new(path: &Path) -> anyhow::Result<Self> {
+        let db = RocksDB::new(path).context("failed opening RocksDB for seen entries")?;
+        Ok(Self { db })
+    }
+
+    /// Checks which of `raw_keys` are already present in `cf`.
+    fn contains(
+        &self,
+        cf: SeenEntriesColumnFamily,
+        raw_keys: impl Iterator<Item = Vec<u8>>,
+    ) -> anyhow::Result<Vec<bool>> {
+        self.db
+            .multi_get_cf(cf, raw_keys)
+            .into_iter()
+            .map(|value| Ok(value?.is_some()))
+            .collect::<Result<_, zksync_storage::rocksdb::Error>>()
+            .context("failed reading seen entries")
+    }
+
+    fn insert(&self, keys: &[H256], indices: &[u64]) -> anyhow::Result<()> {
+        let mut batch = self.db.new_write_batch();
+        for key in keys {
+            batch.put_cf(SeenEntriesColumnFamily::Keys, key.as_bytes(), &[]);
+        }
+        for index in indices {
+            batch.put_cf(SeenEntriesColumnFamily::Indices, &index.to_be_bytes(), &[]);
+        }
+        self.db.write(batch).context("failed writing seen entries")
+    }
+}
+
+/// Verifier of a single storage snapshot.
+///
+/// Loads the snapshot header from the object store, streams all storage log chunks listed in it, recovers a Merkle tree
+/// from them in a temporary RocksDB instance and compares its root hash with the one specified in the header.
+/// Postgres is not accessed, so a snapshot can be verified by anyone with access to the object store.
+#[derive(Debug)]
+pub(crate) struct SnapshotVerifier {
+    pub blob_store: Arc<dyn ObjectStore>,
+}
+
+impl SnapshotVerifier {
+    /// Verifies the snapshot for the specified L1 batch.
+    pub async fn verify(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<VerificationReport> {
+        let header: SnapshotHeader =
+            self.blob_store
+                .get(l1_batch_number)
+                .await
+                .with_context(|| {
+                    format!("failed loading header for snapshot at L1 batch #{l1_batch_number}")
+                })?;
+        tracing::info!(
+            "Verifying snapshot for L1 batch #{l1_batch_number} against root hash {:?}",
+            header.l1_batch_root_hash
+        );
+        self.verify_snapshot(&header).await
+    }
+
+    pub(crate) async fn verify_snapshot(
+        &self,
+        header: &SnapshotHeader,
+    ) -> anyhow::Result<VerificationReport> {
+        let latency = METRICS.verification_duration.start();
+        let l1_batch_number = header.l1_batch_number;
+        let version = SnapshotVersion::try_from(header.version)
+            .with_context(|| format!("unsupported snapshot version: {}", header.version))?;
+        let expected_root_hash = header
+            .l1_batch_root_hash
+            .context("snapshot header doesn't specify the L1 batch root hash; it was created by an outdated snapshot creator")?;
+        let mut report = VerificationReport {
+            l1_batch_number,
+            expected_root_hash,
+            ..VerificationReport::default()
+        };
+
+        let temp_dir = tempfile::TempDir::new().context("failed creating temporary directory")?;
+        let tree_db = RocksDBWrapper::new(&temp_dir.path().join("tree"))
+            .context("failed opening RocksDB for Merkle tree")?;
+        let mut tree = MerkleTreeRecovery::new(tree_db, l1_batch_number.0.into())?;
+        let seen_entries = SeenEntries::new(&temp_dir.path().join("seen_entries"))?;
+
+        let listed_chunk_ids: HashSet<_> = header
+            .storage_logs_chunks
+            .iter()
+            .map(|chunk| chunk.chunk_id)
+            .collect();
+        // Chunk IDs are expected to form a contiguous range starting from 0; gaps are reported as missing chunks.
+        let chunk_count = header
+            .storage_logs_chunks
+            .iter()
+            .map(|chunk| chunk.chunk_id + 1)
+            .max()
+            .unwrap_or(0);
+        for chunk_id in 0..chunk_count {
+            if !listed_chunk_ids.contains(&chunk_id) {
+                tracing::warn!(
+                    "Storage logs chunk {chunk_id} is not listed in the snapshot header"
+                );
+                report.missing_chunks.push(chunk_id);
+                continue;
+            }
+
+            let load_latency =
+                METRICS.verification_chunk_duration[&VerificationStage::Load].start();
+            let Some(storage_logs) = self.load_chunk(version, l1_batch_number, chunk_id).await?
+            else {
+                tracing::warn!("Storage logs chunk {chunk_id} is missing from the object store");
+                report.missing_chunks.push(chunk_id);
+                continue;
+            };
+            let load_latency = load_latency.observe();
+            tracing::info!(
+                "Loaded storage logs chunk {chunk_id} ({} logs) in {load_latency:?}",
+                storage_logs.len()
+            );
+
+            let extend_latency =
+                METRICS.verification_chunk_duration[&VerificationStage::ExtendTree].start();
+            let entries = Self::check_chunk(&mut report, &seen_entries, chunk_id, storage_logs)?;
+            tree.extend_random(entries)?;
+            let extend_latency = extend_latency.observe();
+            tracing::info!("Extended tree with chunk {chunk_id} in {extend_latency:?}");
+        }
+
+        report.actual_root_hash = tree.root_hash();
+        let latency = latency.observe();
+        tracing::info!("Verified snapshot for L1 batch #{l1_batch_number} in {latency:?}");
+        Ok(report)
+    }
+
+    /// Checks storage logs in a chunk and returns tree entries for logs with previously unseen keys.
+    fn check_chunk(
+        report: &mut VerificationReport,
+        seen_entries: &SeenEntries,
+        chunk_id: u64,
+        storage_logs: Vec<SnapshotStorageLog>,
+    ) -> anyhow::Result<Vec<TreeEntry>> {
+        let key_is_seen = seen_entries.contains(
+            SeenEntriesColumnFamily::Keys,
+            storage_logs.iter().map(|log| log.key.as_bytes().to_vec()),
+        )?;
+        let index_is_seen = seen_entries.contains(
+            SeenEntriesColumnFamily::Indices,
+            storage_logs
+                .iter()
+                .map(|log| log.enumeration_index.to_be_bytes().to_vec()),
+        )?;
+
+        // Only entries within the chunk are held in memory.
+        let mut chunk_keys = HashSet::with_capacity(storage_logs.len());
+        let mut chunk_indices = HashSet::with_capacity(storage_logs.len());
+        let mut entries = Vec::with_capacity(storage_logs.len());
+        for ((log, key_is_seen), index_is_seen) in
+            storage_logs.into_iter().zip(key_is_seen).zip(index_is_seen)
+        {
+            let issue = StorageLogIssue {
+                chunk_id,
+                hashed_key: log.key,
+                enumeration_index: log.enumeration_index,
+            };
+            if log.l1_batch_number_of_initial_write > report.l1_batch_number {
+                VerificationReport::record(
+                    &mut report.bad_initial_writes,
+                    &mut report.bad_initial_write_count,
+                    issue.clone(),
+                );
+            }
+            if key_is_seen || !chunk_keys.insert(log.key) {
+                // Do not insert the duplicate entry into the tree; it would overwrite the previous value.
+                VerificationReport::record(
+                    &mut report.duplicate_keys,
+                    &mut report.duplicate_key_count,
+                    issue,
+                );
+                continue;
+            }
+
+            let index = log.enumeration_index;
+            if index == 0 || index_is_seen || !chunk_indices.insert(index) {
+                VerificationReport::record(
+                    &mut report.bad_enumeration_indices,
+                    &mut report.bad_enumeration_index_count,
+                    issue,
+                );
+            }
+            report.actual_leaf_count += 1;
+            report.max_enumeration_index = report.max_enumeration_index.max(index);
+            entries.push(TreeEntry::new(
+                U256::from_little_endian(&log.key.0),
+                index,
+                log.value,
+            ));
+        }
+
+        let chunk_keys: Vec<_> = chunk_keys.into_iter().collect();
+        let chunk_indices: Vec<_> = chunk_indices.into_iter().collect();
+        seen_entries.insert(&chunk_keys, &chunk_indices)?;
+        Ok(entries)
+    }
+
+    /// Returns `Ok(None)` if the chunk is missing.
+    async fn load_chunk(
+        &self,
+        version: SnapshotVersion,
+        l1_batch_number: L1BatchNumber,
+        chunk_id: u64,
+    ) -> anyhow::Result<Option<Vec<SnapshotStorageLog>>> {
+        let key = SnapshotStorageLogsStorageKey {
+            l1_batch_number,
+            chunk_id,
+        };
+        let storage_logs = match version {
+            SnapshotVersion::Version0 => {
+                let chunk: Result<SnapshotStorageLogsChunk<StorageKey>, _> =
+                    self.blob_store.get(key).await;
+                chunk.map(|chunk| {
+                    chunk
+                        .storage_logs
+                        .into_iter()
+                        .map(SnapshotStorageLog::drop_key_preimage)
+                        .collect()
+                })
+            }
+            SnapshotVersion::Version1 => {
+                let chunk: Result<SnapshotStorageLogsChunk, _> = self.blob_store.get(key).await;
+                chunk.map(|chunk| chunk.storage_logs)
+            }
+        };
+
+        match storage_logs {
+            Ok(logs) => Ok(Some(logs)),
+            Err(ObjectStoreError::KeyNotFound(_)) => Ok(None),
+            Err(err) => Err(anyhow::Error::from(err))
+                .with_context(|| format!("failed loading storage logs chunk {chunk_id}")),
+        }
+    }
+}
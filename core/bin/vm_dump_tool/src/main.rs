@@ -0,0 +1,192 @@
+//! Tool to process VM dumps produced by the VM playground and shadow mode.
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use anyhow::Context as _;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use zksync_config::ObjectStoreConfig;
+use zksync_env_config::FromEnv;
+use zksync_multivm::{interface::utils::VmDump, utils::minimize_divergence, VmVersion};
+use zksync_object_store::{Bucket, ObjectStoreFactory};
+use zksync_types::ProtocolVersionId;
+
+use crate::replay::ReplayVm;
+
+mod replay;
+
+/// Source of a VM dump.
+#[derive(Debug, Args)]
+#[group(required = true, multiple = false)]
+struct DumpSource {
+    /// Path to the JSON-serialized VM dump.
+    path: Option<PathBuf>,
+    /// Key of the VM dump in the `vm_dumps` object store bucket. The object store is configured using
+    /// `OBJECT_STORE_*` env variables.
+    #[arg(long)]
+    key: Option<String>,
+}
+
+impl DumpSource {
+    fn read(self) -> anyhow::Result<VmDump> {
+        let (raw_dump, source) = match (self.path, self.key) {
+            (Some(path), _) => (read_file(&path)?, path.display().to_string()),
+            (None, Some(key)) => {
+                let runtime = tokio::runtime::Runtime::new()?;
+                let raw_dump = runtime.block_on(read_from_object_store(&key))?;
+                (raw_dump, format!("object store key `{key}`"))
+            }
+            (None, None) => unreachable!("ensured by `clap`"),
+        };
+        serde_json::from_slice(&raw_dump).with_context(|| format!("failed parsing {source}"))
+    }
+}
+
+fn read_file(path: &Path) -> anyhow::Result<Vec<u8>> {
+    fs::read(path).with_context(|| format!("failed reading {}", path.display()))
+}
+
+async fn read_from_object_store(key: &str) -> anyhow::Result<Vec<u8>> {
+    let config = ObjectStoreConfig::from_env().context("ObjectStoreConfig::from_env()")?;
+    let store = ObjectStoreFactory::new(config).create_store().await?;
+    store
+        .get_raw(Bucket::VmDumps, key)
+        .await
+        .with_context(|| format!("failed getting VM dump `{key}` from object store"))
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum VmKind {
+    /// Legacy VM.
+    Legacy,
+    /// Fast VM.
+    Fast,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Replays a VM dump and outputs execution results, emitted events, storage diffs and call traces
+    /// for each transaction.
+    Replay {
+        #[command(flatten)]
+        source: DumpSource,
+        /// VM to replay the dump on.
+        #[arg(long, value_enum, default_value_t = VmKind::Legacy)]
+        vm: VmKind,
+        /// Protocol version determining the legacy VM version. If not specified, the protocol version
+        /// from the dump is used.
+        #[arg(long)]
+        protocol_version: Option<u16>,
+        /// Outputs results as JSON, e.g. for diffing between VM versions.
+        #[arg(long)]
+        json: bool,
+        /// Path to write results to. If not specified, results are written to stdout.
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Minimizes a VM dump exhibiting a divergence between the legacy and fast VMs, and writes a compact reproducer
+    /// which can be added to the divergence corpus of the `zksync_multivm` crate.
+    Minimize {
+        #[command(flatten)]
+        source: DumpSource,
+        /// Path to write the reproducer to.
+        #[arg(long, short)]
+        output: PathBuf,
+    },
+}
+
+#[derive(Debug, Parser)]
+#[command(author = "Matter Labs", version, about = "VM dump tool", long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+impl Cli {
+    fn run(self) -> anyhow::Result<()> {
+        match self.command {
+            Command::Replay {
+                source,
+                vm,
+                protocol_version,
+                json,
+                output,
+            } => {
+                let dump = source.read()?;
+                let replay_vm = match (vm, protocol_version) {
+                    (VmKind::Fast, None) => ReplayVm::Fast,
+                    (VmKind::Fast, Some(_)) => {
+                        anyhow::bail!("protocol version can only be specified for the legacy VM");
+                    }
+                    (VmKind::Legacy, Some(version)) => {
+                        let version = ProtocolVersionId::try_from(version)
+                            .map_err(|err| anyhow::anyhow!("invalid protocol version: {err}"))?;
+                        ReplayVm::Legacy(version.into())
+                    }
+                    (VmKind::Legacy, None) => {
+                        ReplayVm::Legacy(VmVersion::from(dump.system_env.version))
+                    }
+                };
+
+                tracing::info!(
+                    "Replaying dump for L1 batch #{} on {replay_vm:?}",
+                    dump.l1_batch_number()
+                );
+                let started_at = Instant::now();
+                let reports = replay_vm.replay(dump)?;
+                tracing::info!(
+                    "Replayed {} transactions in {:?}",
+                    reports.len(),
+                    started_at.elapsed()
+                );
+
+                let mut writer: Box<dyn Write> = if let Some(output) = &output {
+                    let file = fs::File::create(output)
+                        .with_context(|| format!("failed creating {}", output.display()))?;
+                    Box::new(io::BufWriter::new(file))
+                } else {
+                    Box::new(io::stdout().lock())
+                };
+                if json {
+                    serde_json::to_writer_pretty(&mut writer, &reports)
+                        .context("failed outputting reports")?;
+                    writeln!(writer)?;
+                } else {
+                    for report in &reports {
+                        writeln!(writer, "{report}")?;
+                    }
+                }
+                writer.flush()?;
+            }
+            Command::Minimize { source, output } => {
+                let dump = source.read()?;
+                let started_at = Instant::now();
+                let reproducer = minimize_divergence(dump)?;
+                tracing::info!(
+                    "Minimized divergence for transaction {:?} in {:?}",
+                    reproducer.tx_hash,
+                    started_at.elapsed()
+                );
+
+                let reproducer =
+                    serde_json::to_vec(&reproducer).context("failed serializing reproducer")?;
+                fs::write(&output, reproducer).with_context(|| {
+                    format!("failed writing reproducer to {}", output.display())
+                })?;
+                tracing::info!("Written reproducer to {}", output.display());
+            }
+        }
+        Ok(())
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let _guard = zksync_vlog::ObservabilityBuilder::new()
+        .with_logs(Some(zksync_vlog::Logs::default()))
+        .try_build()?;
+    Cli::parse().run()
+}
@@ -0,0 +1,331 @@
+//! Replaying VM dumps with call tracing.
+
+use std::{collections::BTreeMap, fmt, sync::Arc};
+
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use zksync_multivm::{
+    interface::{
+        storage::{ImmutableStorageView, StorageSnapshot, StorageView},
+        utils::VmDump,
+        Call, CallType, ExecutionResult, L2BlockEnv, VmExecutionResultAndLogs, VmFactory,
+        VmInterface,
+    },
+    is_supported_by_fast_vm,
+    tracers::CallTracer,
+    vm_fast,
+    vm_latest::HistoryDisabled,
+    LegacyVmInstance, MultiVmTracer, VmVersion,
+};
+use zksync_types::{web3::Bytes, Address, StorageKey, Transaction, H256, U256};
+
+/// VM used to replay a dump.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum ReplayVm {
+    /// Legacy VM of the specified version.
+    Legacy(VmVersion),
+    /// Fast VM.
+    Fast,
+}
+
+impl ReplayVm {
+    pub(crate) fn replay(self, dump: VmDump) -> anyhow::Result<Vec<TransactionReport>> {
+        let storage = StorageView::new(dump.storage).to_rc_ptr();
+        let (l1_batch_env, system_env) = (dump.l1_batch_env, dump.system_env);
+        let mut vm: Box<dyn TracingVm> = match self {
+            Self::Legacy(vm_version) => Box::new(LegacyVmInstance::new_with_specific_version(
+                l1_batch_env,
+                system_env,
+                storage,
+                vm_version,
+            )),
+            Self::Fast => {
+                anyhow::ensure!(
+                    is_supported_by_fast_vm(system_env.version),
+                    "protocol version {} is not supported by the fast VM",
+                    system_env.version
+                );
+                Box::new(FastVm::new(l1_batch_env, system_env, storage))
+            }
+        };
+
+        let mut reports = vec![];
+        for (i, l2_block) in dump.l2_blocks.into_iter().enumerate() {
+            if i > 0 {
+                // First block is already set.
+                vm.start_new_l2_block(L2BlockEnv {
+                    number: l2_block.number.0,
+                    timestamp: l2_block.timestamp,
+                    prev_block_hash: l2_block.prev_block_hash,
+                    max_virtual_blocks_to_create: l2_block.virtual_blocks,
+                });
+            }
+
+            for tx in l2_block.txs {
+                let tx_hash = tx.hash();
+                tracing::debug!(
+                    "Executing transaction {tx_hash:?} in L2 block #{}",
+                    l2_block.number
+                );
+                let (tx_result, call_traces) = vm.execute_with_call_traces(tx)?;
+                reports.push(TransactionReport::new(
+                    l2_block.number.0,
+                    tx_hash,
+                    tx_result,
+                    call_traces,
+                ));
+            }
+        }
+        Ok(reports)
+    }
+}
+
+type FastVm = vm_fast::Vm<ImmutableStorageView<StorageSnapshot>, CallTracer, ()>;
+
+/// Object-safe VM interface used for replaying.
+trait TracingVm {
+    fn start_new_l2_block(&mut self, l2_block_env: L2BlockEnv);
+
+    fn execute_with_call_traces(
+        &mut self,
+        tx: Transaction,
+    ) -> anyhow::Result<(VmExecutionResultAndLogs, Vec<Call>)>;
+}
+
+impl TracingVm for LegacyVmInstance<StorageSnapshot, HistoryDisabled> {
+    fn start_new_l2_block(&mut self, l2_block_env: L2BlockEnv) {
+        VmInterface::start_new_l2_block(self, l2_block_env);
+    }
+
+    fn execute_with_call_traces(
+        &mut self,
+        tx: Transaction,
+    ) -> anyhow::Result<(VmExecutionResultAndLogs, Vec<Call>)> {
+        let call_traces = Arc::new(OnceCell::new());
+        let mut tracer = vec![CallTracer::new(call_traces.clone()).into_tracer_pointer()].into();
+        let (compression_result, tx_result) =
+            self.inspect_transaction_with_bytecode_compression(&mut tracer, tx, true);
+        compression_result.map_err(|err| anyhow::anyhow!("failed compressing bytecodes: {err}"))?;
+        drop(tracer);
+
+        let call_traces = Arc::try_unwrap(call_traces)
+            .map_err(|_| anyhow::anyhow!("failed extracting call traces"))?
+            .take()
+            .unwrap_or_default();
+        Ok((tx_result, call_traces))
+    }
+}
+
+impl TracingVm for FastVm {
+    fn start_new_l2_block(&mut self, l2_block_env: L2BlockEnv) {
+        VmInterface::start_new_l2_block(self, l2_block_env);
+    }
+
+    fn execute_with_call_traces(
+        &mut self,
+        tx: Transaction,
+    ) -> anyhow::Result<(VmExecutionResultAndLogs, Vec<Call>)> {
+        let mut tracer = (CallTracer::default(), ());
+        let (compression_result, tx_result) =
+            self.inspect_transaction_with_bytecode_compression(&mut tracer, tx, true);
+        compression_result.map_err(|err| anyhow::anyhow!("failed compressing bytecodes: {err}"))?;
+        Ok((tx_result, tracer.0.into_result()))
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum TransactionStatus {
+    Success,
+    Revert,
+    Halt,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct EventReport {
+    address: Address,
+    topics: Vec<H256>,
+    data: Bytes,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct StorageDiff {
+    address: Address,
+    key: H256,
+    previous_value: H256,
+    value: H256,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct CallReport {
+    r#type: String,
+    from: Address,
+    to: Address,
+    value: U256,
+    gas: u64,
+    gas_used: u64,
+    input: Bytes,
+    output: Bytes,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    revert_reason: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    calls: Vec<CallReport>,
+}
+
+impl From<Call> for CallReport {
+    fn from(call: Call) -> Self {
+        let r#type = match call.r#type {
+            CallType::Call(opcode) => format!("{opcode:?}").to_lowercase(),
+            CallType::Create => "create".to_owned(),
+            CallType::NearCall => "near_call".to_owned(),
+        };
+        Self {
+            r#type,
+            from: call.from,
+            to: call.to,
+            value: call.value,
+            gas: call.gas,
+            gas_used: call.gas_used,
+            input: call.input.into(),
+            output: call.output.into(),
+            error: call.error,
+            revert_reason: call.revert_reason,
+            calls: call.calls.into_iter().map(Self::from).collect(),
+        }
+    }
+}
+
+impl CallReport {
+    fn fmt_indented(&self, formatter: &mut fmt::Formatter<'_>, indent: usize) -> fmt::Result {
+        write!(
+            formatter,
+            "{:indent$}{} {:?} -> {:?}, value: {}, gas used: {}",
+            "", self.r#type, self.from, self.to, self.value, self.gas_used
+        )?;
+        if let Some(error) = &self.error {
+            write!(formatter, ", error: {error}")?;
+        }
+        if let Some(reason) = &self.revert_reason {
+            write!(formatter, ", revert reason: {reason}")?;
+        }
+        writeln!(formatter)?;
+        for call in &self.calls {
+            call.fmt_indented(formatter, indent + 2)?;
+        }
+        Ok(())
+    }
+}
+
+/// Execution report for a single transaction.
+#[derive(Debug, Serialize)]
+pub(crate) struct TransactionReport {
+    l2_block_number: u32,
+    tx_hash: H256,
+    status: TransactionStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    gas_used: u64,
+    events: Vec<EventReport>,
+    storage_diffs: Vec<StorageDiff>,
+    call_traces: Vec<CallReport>,
+}
+
+impl TransactionReport {
+    fn new(
+        l2_block_number: u32,
+        tx_hash: H256,
+        tx_result: VmExecutionResultAndLogs,
+        call_traces: Vec<Call>,
+    ) -> Self {
+        let (status, error) = match &tx_result.result {
+            ExecutionResult::Success { .. } => (TransactionStatus::Success, None),
+            ExecutionResult::Revert { output } => {
+                (TransactionStatus::Revert, Some(output.to_string()))
+            }
+            ExecutionResult::Halt { reason } => (TransactionStatus::Halt, Some(reason.to_string())),
+        };
+        let events = tx_result
+            .logs
+            .events
+            .into_iter()
+            .map(|event| EventReport {
+                address: event.address,
+                topics: event.indexed_topics,
+                data: event.value.into(),
+            })
+            .collect();
+
+        // Deduplicate writes and remove no-op ones.
+        let mut storage_diffs = BTreeMap::<StorageKey, (H256, H256)>::new();
+        for log in &tx_result.logs.storage_logs {
+            if !log.log.is_write() {
+                continue;
+            }
+            storage_diffs
+                .entry(log.log.key)
+                .and_modify(|(_, value)| *value = log.log.value)
+                .or_insert((log.previous_value, log.log.value));
+        }
+        let storage_diffs = storage_diffs
+            .into_iter()
+            .filter(|(_, (previous_value, value))| previous_value != value)
+            .map(|(key, (previous_value, value))| StorageDiff {
+                address: *key.address(),
+                key: *key.key(),
+                previous_value,
+                value,
+            })
+            .collect();
+
+        Self {
+            l2_block_number,
+            tx_hash,
+            status,
+            error,
+            gas_used: tx_result.statistics.gas_used,
+            events,
+            storage_diffs,
+            call_traces: call_traces.into_iter().map(CallReport::from).collect(),
+        }
+    }
+}
+
+impl fmt::Display for TransactionReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "Transaction {:?} (L2 block #{}): {:?}, gas used: {}",
+            self.tx_hash, self.l2_block_number, self.status, self.gas_used
+        )?;
+        if let Some(error) = &self.error {
+            write!(formatter, ", error: {error}")?;
+        }
+        writeln!(formatter)?;
+
+        writeln!(formatter, "  Events ({}):", self.events.len())?;
+        for event in &self.events {
+            writeln!(
+                formatter,
+                "    {:?}, topics: {:?}, data: 0x{}",
+                event.address,
+                event.topics,
+                hex::encode(&event.data.0)
+            )?;
+        }
+        writeln!(formatter, "  Storage diffs ({}):", self.storage_diffs.len())?;
+        for diff in &self.storage_diffs {
+            writeln!(
+                formatter,
+                "    {:?}:{:?}: {:?} -> {:?}",
+                diff.address, diff.key, diff.previous_value, diff.value
+            )?;
+        }
+        writeln!(formatter, "  Call traces:")?;
+        for call in &self.call_traces {
+            call.fmt_indented(formatter, 4)?;
+        }
+        Ok(())
+    }
+}
@@ -270,8 +270,11 @@ impl MainNodeBuilder {
                 .state_keeper_db_block_cache_capacity(),
             max_open_files: db_config.experimental.state_keeper_db_max_open_files,
         };
-        let state_keeper_layer =
+        let mut state_keeper_layer =
             StateKeeperLayer::new(db_config.state_keeper_db_path, rocksdb_options);
+        if let Some(interval) = db_config.experimental.state_keeper_db_compaction_interval {
+            state_keeper_layer = state_keeper_layer.with_cache_compaction(interval);
+        }
         self.node
             .add_layer(persistence_layer)
             .add_layer(mempool_io_layer)
@@ -333,6 +336,10 @@ impl MainNodeBuilder {
             initial_writes_cache_size: rpc_config.initial_writes_cache_size() as u64,
             latest_values_cache_size: rpc_config.latest_values_cache_size() as u64,
             latest_values_max_block_lag: rpc_config.latest_values_max_block_lag(),
+            latest_values_warm_storage_path: rpc_config
+                .latest_values_warm_storage_path
+                .clone()
+                .map(Into::into),
         };
         let vm_config = self
             .configs
@@ -402,6 +409,7 @@ impl MainNodeBuilder {
             batch_request_size_limit: Some(rpc_config.max_batch_request_size()),
             response_body_size_limit: Some(rpc_config.max_response_body_size()),
             with_extended_tracing: rpc_config.extended_api_tracing,
+            read_your_writes_max_wait: rpc_config.read_your_writes_max_wait(),
             ..Default::default()
         };
         self.node.add_layer(Web3ServerLayer::http(
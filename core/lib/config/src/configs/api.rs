@@ -132,6 +132,197 @@ pub struct MaxResponseSize {
     pub overrides: MaxResponseSizeOverrides,
 }
 
+/// Compute-unit quota for a single API key.
+#[derive(Clone, PartialEq)]
+pub struct ApiKeyQuota {
+    /// Human-readable key name used in metrics and logs. The key itself is never logged.
+    pub name: String,
+    pub key: String,
+    pub compute_units_per_minute: NonZeroU32,
+}
+
+impl fmt::Debug for ApiKeyQuota {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ApiKeyQuota")
+            .field("name", &self.name)
+            .field("compute_units_per_minute", &self.compute_units_per_minute)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Quotas for API keys accepted by the JSON-RPC servers.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ApiKeyQuotas(Vec<ApiKeyQuota>);
+
+impl FromStr for ApiKeyQuotas {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut quotas = Vec::<ApiKeyQuota>::new();
+        for part in s.split(',') {
+            let (name_and_key, limit) = part.rsplit_once('=').with_context(|| {
+                format!("Part `{part}` doesn't have form <name>:<key>=<compute_units_per_minute>")
+            })?;
+            let (name, key) = name_and_key.split_once(':').with_context(|| {
+                format!("Part `{part}` doesn't have form <name>:<key>=<compute_units_per_minute>")
+            })?;
+            let (name, key, limit) = (name.trim(), key.trim(), limit.trim());
+            let compute_units_per_minute = limit.parse().with_context(|| {
+                format!(
+                    "`{limit}` specified for API key `{name}` is not a valid compute unit limit"
+                )
+            })?;
+
+            if let Some(prev) = quotas.iter().find(|q| q.name == name || q.key == key) {
+                anyhow::bail!(
+                    "API key `{name}` has a duplicate name or key with API key `{}`",
+                    prev.name
+                );
+            }
+            quotas.push(ApiKeyQuota {
+                name: name.to_owned(),
+                key: key.to_owned(),
+                compute_units_per_minute,
+            });
+        }
+        Ok(Self(quotas))
+    }
+}
+
+impl FromIterator<ApiKeyQuota> for ApiKeyQuotas {
+    fn from_iter<I: IntoIterator<Item = ApiKeyQuota>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl ApiKeyQuotas {
+    /// Iterates over all quotas.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &ApiKeyQuota> + '_ {
+        self.0.iter()
+    }
+}
+
+impl<'de> Deserialize<'de> for ApiKeyQuotas {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ParseVisitor;
+
+        impl<'v> de::Visitor<'v> for ParseVisitor {
+            type Value = ApiKeyQuotas;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str(
+                    "comma-separated list of <name>:<key>=<compute_units_per_minute> tuples, \
+                     such as: alice:0123abcd=1000,bob:4567ef00=500",
+                )
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                value.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(ParseVisitor)
+    }
+}
+
+/// Costs of RPC methods in compute units. Methods not mentioned here cost 1 compute unit per call.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MethodComputeUnits(HashMap<String, NonZeroU32>);
+
+impl<S: Into<String>> FromIterator<(S, NonZeroU32)> for MethodComputeUnits {
+    fn from_iter<I: IntoIterator<Item = (S, NonZeroU32)>>(iter: I) -> Self {
+        Self(
+            iter.into_iter()
+                .map(|(method_name, cost)| (method_name.into(), cost))
+                .collect(),
+        )
+    }
+}
+
+impl FromStr for MethodComputeUnits {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut costs = HashMap::new();
+        for part in s.split(',') {
+            let (method_name, cost) = part
+                .split_once('=')
+                .with_context(|| format!("Part `{part}` doesn't have form <method_name>=<int>"))?;
+            let (method_name, cost) = (method_name.trim(), cost.trim());
+            let cost = cost.parse().with_context(|| {
+                format!("`{cost}` specified for method `{method_name}` is not a valid cost")
+            })?;
+
+            if let Some(prev_cost) = costs.insert(method_name.to_owned(), cost) {
+                anyhow::bail!("Cost for `{method_name}` is redefined from {prev_cost} to {cost}");
+            }
+        }
+        Ok(Self(costs))
+    }
+}
+
+impl MethodComputeUnits {
+    /// Default cost of a method call in compute units.
+    pub const DEFAULT_COST: NonZeroU32 = NonZeroU32::MIN;
+
+    /// Gets the cost of calling the specified method.
+    pub fn get(&self, method_name: &str) -> NonZeroU32 {
+        self.0
+            .get(method_name)
+            .copied()
+            .unwrap_or(Self::DEFAULT_COST)
+    }
+}
+
+impl<'de> Deserialize<'de> for MethodComputeUnits {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ParseVisitor;
+
+        impl<'v> de::Visitor<'v> for ParseVisitor {
+            type Value = MethodComputeUnits;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str(
+                    "comma-separated list of <method_name>=<cost> tuples, such as: eth_call=10,debug_traceCall=100",
+                )
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                value.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(ParseVisitor)
+    }
+}
+
+/// Configuration of API key identification and per-key quotas for JSON-RPC servers.
+///
+/// For WebSocket servers, the API key is taken from the upgrade request and applies to all calls over the connection.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiKeysConfig {
+    /// Accepted API keys together with their quotas.
+    pub keys: ApiKeyQuotas,
+    /// Costs of RPC methods in compute units.
+    pub method_costs: MethodComputeUnits,
+    /// Quota shared by all requests without a recognized API key. If not set, such requests are rejected.
+    pub anonymous_compute_units_per_minute: Option<NonZeroU32>,
+    /// HTTP header to read the API key from.
+    pub header_name: String,
+    /// Whether to take the API key from the last segment of the request URL path (e.g., `https://rpc.example.com/<key>`)
+    /// if the header is missing. This is useful for clients that cannot set headers (e.g., WebSocket clients in browsers),
+    /// but keys in URLs are more likely to leak, e.g. into proxy logs. If disabled, requests without the header
+    /// are treated as anonymous.
+    pub key_in_url_path: bool,
+}
+
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct Web3JsonRpcConfig {
     /// Port to which the HTTP RPC server is listening.
@@ -173,11 +364,21 @@ pub struct Web3JsonRpcConfig {
     /// considered experimental.
     #[serde(default)]
     pub estimate_gas_optimize_search: bool,
+    /// Maximum number of recent gas estimates cached by the API server. Estimates are cached per transaction, block
+    /// and fee input; estimates with state overrides are never cached. If not set or set to 0, the cache is disabled.
+    pub estimate_gas_cache_size: Option<usize>,
     ///  Max possible size of an ABI encoded tx (in bytes).
     pub max_tx_size: usize,
     /// Max number of cache misses during one VM execution. If the number of cache misses exceeds this value, the API server panics.
     /// This is a temporary solution to mitigate API request resulting in thousands of DB queries.
     pub vm_execution_cache_misses_limit: Option<usize>,
+    /// Max wall-clock duration (in ms) of a single VM execution for `eth_call`-like methods and gas estimation.
+    /// If execution takes longer, it's halted and the API returns an error. If not set, the duration is not limited.
+    pub vm_execution_timeout_ms: Option<u64>,
+    /// Max number of VM cycles (for the fast VM, executed instructions) in a single VM execution for `eth_call`-like
+    /// methods and gas estimation. Unlike the gas limit, this limit cannot be changed by the caller. If not set, the number
+    /// of cycles is only limited by the gas limit.
+    pub vm_execution_cycles_limit: Option<u64>,
     /// Max number of VM instances to be concurrently spawned by the API server.
     /// This option can be tweaked down if the API server is running out of memory.
     /// If not set, the VM concurrency limit will be efficiently disabled.
@@ -193,6 +394,12 @@ pub struct Web3JsonRpcConfig {
     /// lead to increased the cache update latency, i.e., less storage queries being processed by the cache. OTOH, smaller values
     /// can lead to spurious resets when Postgres lags for whatever reason (e.g., when sealing L1 batches).
     pub latest_values_max_block_lag: Option<NonZeroU32>,
+    /// Path to the RocksDB-backed warm storage for the latest values cache. If set, storage values read by the API
+    /// sandbox are persisted on disk and kept up to date with the latest sealed L2 block, so that most reads
+    /// for hot contracts don't reach Postgres. Requires the latest values cache to be enabled. Unlike the in-memory cache,
+    /// the warm storage isn't reset if it lags behind by more than `latest_values_max_block_lag` blocks (e.g., after a restart);
+    /// instead, it's caught up in chunks of this size.
+    pub latest_values_warm_storage_path: Option<String>,
     /// Limit for fee history block range.
     pub fee_history_limit: Option<u64>,
     /// Maximum number of requests in a single batch JSON RPC request. Default is 500.
@@ -224,6 +431,11 @@ pub struct Web3JsonRpcConfig {
     /// (hundreds or thousands RPS).
     #[serde(default)]
     pub extended_api_tracing: bool,
+    /// Maximum time in milliseconds to wait for the replica DB to catch up with the consistency watermark
+    /// provided by an HTTP client. If the replica doesn't catch up in time, the request is served from the master DB.
+    /// If not set, read-your-writes consistency is disabled. Only applies to the HTTP server; WebSocket subscriptions
+    /// and data served from in-memory caches (e.g., pending transaction filters) are always based on the replica DB.
+    pub read_your_writes_max_wait_ms: Option<u64>,
 }
 
 impl Web3JsonRpcConfig {
@@ -246,13 +458,17 @@ impl Web3JsonRpcConfig {
             estimate_gas_scale_factor: 1.5,
             estimate_gas_acceptable_overestimation: 1000,
             estimate_gas_optimize_search: false,
+            estimate_gas_cache_size: None,
             max_tx_size: 1000000,
             vm_execution_cache_misses_limit: None,
+            vm_execution_timeout_ms: None,
+            vm_execution_cycles_limit: None,
             vm_concurrency_limit: None,
             factory_deps_cache_size_mb: None,
             initial_writes_cache_size_mb: None,
             latest_values_cache_size_mb: None,
             latest_values_max_block_lag: None,
+            latest_values_warm_storage_path: None,
             fee_history_limit: None,
             max_batch_request_size: None,
             max_response_body_size_mb: None,
@@ -264,6 +480,7 @@ impl Web3JsonRpcConfig {
             whitelisted_tokens_for_aa: vec![],
             api_namespaces: None,
             extended_api_tracing: false,
+            read_your_writes_max_wait_ms: None,
         }
     }
 
@@ -318,6 +535,10 @@ impl Web3JsonRpcConfig {
         self.latest_values_max_block_lag.map_or(20, NonZeroU32::get)
     }
 
+    pub fn estimate_gas_cache_size(&self) -> usize {
+        self.estimate_gas_cache_size.unwrap_or(0)
+    }
+
     pub fn fee_history_limit(&self) -> u64 {
         self.fee_history_limit.unwrap_or(1024)
     }
@@ -352,6 +573,14 @@ impl Web3JsonRpcConfig {
     pub fn mempool_cache_size(&self) -> usize {
         self.mempool_cache_size.unwrap_or(10_000)
     }
+
+    pub fn read_your_writes_max_wait(&self) -> Option<Duration> {
+        self.read_your_writes_max_wait_ms.map(Duration::from_millis)
+    }
+
+    pub fn vm_execution_timeout(&self) -> Option<Duration> {
+        self.vm_execution_timeout_ms.map(Duration::from_millis)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -431,4 +660,32 @@ mod tests {
         assert_eq!(scaled.get("zks_getProof"), Some(32_000));
         assert_eq!(scaled.get("eth_blockNumber"), None);
     }
+
+    #[test]
+    fn parsing_api_key_quotas() {
+        let quotas: ApiKeyQuotas = "alice:0123abcd=1000, bob : 4567ef00 = 500".parse().unwrap();
+        let quotas: Vec<_> = quotas.iter().collect();
+        assert_eq!(quotas.len(), 2);
+        assert_eq!(quotas[0].name, "alice");
+        assert_eq!(quotas[0].key, "0123abcd");
+        assert_eq!(quotas[0].compute_units_per_minute.get(), 1_000);
+        assert_eq!(quotas[1].name, "bob");
+        assert_eq!(quotas[1].key, "4567ef00");
+        assert_eq!(quotas[1].compute_units_per_minute.get(), 500);
+        assert!(!format!("{quotas:?}").contains("0123abcd"));
+
+        "alice:0123abcd=1000,alice:4567ef00=500"
+            .parse::<ApiKeyQuotas>()
+            .unwrap_err();
+        "alice=1000".parse::<ApiKeyQuotas>().unwrap_err();
+        "alice:0123abcd=0".parse::<ApiKeyQuotas>().unwrap_err();
+
+        let costs: MethodComputeUnits = "eth_call=10, debug_traceCall = 100".parse().unwrap();
+        assert_eq!(costs.get("eth_call").get(), 10);
+        assert_eq!(costs.get("debug_traceCall").get(), 100);
+        assert_eq!(
+            costs.get("eth_blockNumber"),
+            MethodComputeUnits::DEFAULT_COST
+        );
+    }
 }
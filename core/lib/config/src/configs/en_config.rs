@@ -15,6 +15,9 @@ pub struct ENConfig {
 
     // Main node configuration
     pub main_node_url: SensitiveUrl,
+    /// Fallback L2 RPC endpoints (e.g., trusted external nodes) used if the main node is unavailable.
+    #[serde(default)]
+    pub main_node_fallback_urls: Vec<SensitiveUrl>,
     pub main_node_rate_limit_rps: Option<NonZeroUsize>,
 
     pub bridge_addresses_refresh_interval_sec: Option<NonZeroU64>,
@@ -13,6 +13,10 @@ pub struct ExperimentalDBConfig {
     /// Maximum number of files concurrently opened by state keeper cache RocksDB. Useful to fit into OS limits; can be used
     /// as a rudimentary way to control RAM usage of the cache.
     pub state_keeper_db_max_open_files: Option<NonZeroU32>,
+    /// Number of L1 batches processed by the state keeper RocksDB cache after which the cache is fully compacted,
+    /// which physically removes overwritten values. If not set, the cache is never compacted explicitly.
+    #[serde(default)]
+    pub state_keeper_db_compaction_interval: Option<NonZeroU32>,
     /// Configures whether to persist protective reads when persisting L1 batches in the state keeper.
     /// Protective reads are never required by full nodes so far, not until such a node runs a full Merkle tree
     /// (presumably, to participate in L1 batch proving).
@@ -32,6 +36,14 @@ pub struct ExperimentalDBConfig {
     /// Enables the stale keys repair task for the Merkle tree.
     #[serde(default)]
     pub merkle_tree_repair_stale_keys: bool,
+    /// Migrates the Merkle tree to the lightweight mode in place on node start. Only has an effect if the tree
+    /// is configured to run in the lightweight mode.
+    #[serde(default)]
+    pub merkle_tree_migrate_to_lightweight: bool,
+    /// If set, the Merkle tree will be rebuilt in the full mode from Postgres storage logs at the specified path
+    /// in the background. The main tree continues operating normally during the rebuild.
+    #[serde(default)]
+    pub merkle_tree_rebuild_path: Option<String>,
 }
 
 impl Default for ExperimentalDBConfig {
@@ -40,10 +52,13 @@ impl Default for ExperimentalDBConfig {
             state_keeper_db_block_cache_capacity_mb:
                 Self::default_state_keeper_db_block_cache_capacity_mb(),
             state_keeper_db_max_open_files: None,
+            state_keeper_db_compaction_interval: None,
             protective_reads_persistence_enabled: false,
             processing_delay_ms: Self::default_merkle_tree_processing_delay_ms(),
             include_indices_and_filters_in_block_cache: false,
             merkle_tree_repair_stale_keys: false,
+            merkle_tree_migrate_to_lightweight: false,
+            merkle_tree_rebuild_path: None,
         }
     }
 }
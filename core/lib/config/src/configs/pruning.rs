@@ -1,6 +1,9 @@
 use std::num::NonZeroU64;
 
 use serde::Deserialize;
+use zksync_basic_types::{Address, H256};
+
+use crate::ObjectStoreConfig;
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct PruningConfig {
@@ -16,4 +19,17 @@ pub struct PruningConfig {
     /// the retention period greater than that implicitly imposed by other criteria (e.g., 7 or 30 days).
     /// If set to 0, L1 batches will not be retained based on their timestamp. The default value is 1 hour.
     pub data_retention_sec: Option<u64>,
+    /// Events emitted by these contracts will be retained in Postgres indefinitely, even after the containing
+    /// L1 batches are pruned. Only events are retained; transactions, receipts and call traces are pruned as usual.
+    #[serde(default)]
+    pub retained_contract_addresses: Vec<Address>,
+    /// Events with the first topic (i.e., the event signature) from this list will be retained in Postgres indefinitely.
+    #[serde(default)]
+    pub retained_event_topics: Vec<H256>,
+    /// Events emitted by transactions initiated by these accounts will be retained in Postgres indefinitely.
+    #[serde(default)]
+    pub retained_tx_initiators: Vec<Address>,
+    /// If set, data in each chunk of L1 batches is exported to this object store before it is hard-pruned
+    /// from Postgres, so that it can be reloaded or queried offline.
+    pub export_object_store: Option<ObjectStoreConfig>,
 }
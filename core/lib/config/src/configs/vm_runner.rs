@@ -10,6 +10,10 @@ pub struct ProtectiveReadsWriterConfig {
     pub window_size: u32,
     /// All batches before this one (inclusive) are always considered to be processed.
     pub first_processed_batch: L1BatchNumber,
+    /// Whether to load batches using storage snapshots from Postgres instead of the RocksDB cache. In this mode,
+    /// up to `window_size` batches are executed in parallel, which is useful to re-execute historical batches.
+    #[serde(default)]
+    pub use_snapshots: bool,
 }
 
 impl ProtectiveReadsWriterConfig {
@@ -27,6 +31,10 @@ pub struct BasicWitnessInputProducerConfig {
     pub window_size: u32,
     /// All batches before this one (inclusive) are always considered to be processed.
     pub first_processed_batch: L1BatchNumber,
+    /// Whether to load batches using storage snapshots from Postgres instead of the RocksDB cache. In this mode,
+    /// up to `window_size` batches are executed in parallel, which is useful to re-execute historical batches.
+    #[serde(default)]
+    pub use_snapshots: bool,
 }
 
 impl BasicWitnessInputProducerConfig {
@@ -86,13 +86,17 @@ impl Distribution<configs::api::Web3JsonRpcConfig> for EncodeDist {
             estimate_gas_scale_factor: self.sample(rng),
             estimate_gas_acceptable_overestimation: self.sample(rng),
             estimate_gas_optimize_search: self.sample(rng),
+            estimate_gas_cache_size: self.sample(rng),
             max_tx_size: self.sample(rng),
             vm_execution_cache_misses_limit: self.sample(rng),
+            vm_execution_timeout_ms: self.sample(rng),
+            vm_execution_cycles_limit: self.sample(rng),
             vm_concurrency_limit: self.sample(rng),
             factory_deps_cache_size_mb: self.sample(rng),
             initial_writes_cache_size_mb: self.sample(rng),
             latest_values_cache_size_mb: self.sample(rng),
             latest_values_max_block_lag: self.sample(rng),
+            latest_values_warm_storage_path: self.sample(rng),
             fee_history_limit: self.sample(rng),
             max_batch_request_size: self.sample(rng),
             max_response_body_size_mb: self.sample(rng),
@@ -116,6 +120,7 @@ impl Distribution<configs::api::Web3JsonRpcConfig> for EncodeDist {
             api_namespaces: self
                 .sample_opt(|| self.sample_range(rng).map(|_| self.sample(rng)).collect()),
             extended_api_tracing: self.sample(rng),
+            read_your_writes_max_wait_ms: self.sample(rng),
         }
     }
 }
@@ -304,10 +309,13 @@ impl Distribution<configs::ExperimentalDBConfig> for EncodeDist {
         configs::ExperimentalDBConfig {
             state_keeper_db_block_cache_capacity_mb: self.sample(rng),
             state_keeper_db_max_open_files: self.sample(rng),
+            state_keeper_db_compaction_interval: self.sample(rng),
             protective_reads_persistence_enabled: self.sample(rng),
             processing_delay_ms: self.sample(rng),
             include_indices_and_filters_in_block_cache: self.sample(rng),
             merkle_tree_repair_stale_keys: self.sample(rng),
+            merkle_tree_migrate_to_lightweight: self.sample(rng),
+            merkle_tree_rebuild_path: self.sample(rng),
         }
     }
 }
@@ -937,6 +945,9 @@ impl Distribution<configs::en_config::ENConfig> for EncodeDist {
             l2_chain_id: L2ChainId::default(),
             l1_chain_id: L1ChainId(rng.gen()),
             main_node_url: format!("localhost:{}", rng.gen::<u16>()).parse().unwrap(),
+            main_node_fallback_urls: (0..rng.gen_range(0..3))
+                .map(|_| format!("localhost:{}", rng.gen::<u16>()).parse().unwrap())
+                .collect(),
             l1_batch_commit_data_generator_mode: match rng.gen_range(0..2) {
                 0 => L1BatchCommitmentMode::Rollup,
                 _ => L1BatchCommitmentMode::Validium,
@@ -990,6 +1001,7 @@ impl Distribution<configs::vm_runner::ProtectiveReadsWriterConfig> for EncodeDis
             db_path: self.sample(rng),
             window_size: self.sample(rng),
             first_processed_batch: L1BatchNumber(rng.gen()),
+            use_snapshots: self.sample(rng),
         }
     }
 }
@@ -1003,6 +1015,7 @@ impl Distribution<configs::vm_runner::BasicWitnessInputProducerConfig> for Encod
             db_path: self.sample(rng),
             window_size: self.sample(rng),
             first_processed_batch: L1BatchNumber(rng.gen()),
+            use_snapshots: self.sample(rng),
         }
     }
 }
@@ -1063,6 +1076,10 @@ impl Distribution<configs::pruning::PruningConfig> for EncodeDist {
             chunk_size: self.sample(rng),
             removal_delay_sec: self.sample_opt(|| rng.gen()),
             data_retention_sec: self.sample(rng),
+            retained_contract_addresses: self.sample_range(rng).map(|_| rng.gen()).collect(),
+            retained_event_topics: self.sample_range(rng).map(|_| rng.gen()).collect(),
+            retained_tx_initiators: self.sample_range(rng).map(|_| rng.gen()).collect(),
+            export_object_store: self.sample(rng),
         }
     }
 }
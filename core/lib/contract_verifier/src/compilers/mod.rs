@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use anyhow::Context as _;
 use serde::{Deserialize, Serialize};
-use zksync_types::contract_verification_api::CompilationArtifacts;
+use zksync_types::contract_verification_api::{CompilationArtifacts, ImmutableReference};
 
 pub(crate) use self::{
     solc::{Solc, SolcInput},
@@ -121,6 +121,16 @@ fn parse_standard_json_output(
     } else {
         None
     };
+    let immutable_refs = if get_deployed_bytecode {
+        parse_immutable_refs(contract)?
+    } else {
+        vec![]
+    };
+    let immutables_len = if get_deployed_bytecode {
+        parse_immutables_len(contract)?
+    } else {
+        None
+    };
 
     let mut abi = contract["abi"].clone();
     if abi.is_null() {
@@ -139,5 +149,57 @@ fn parse_standard_json_output(
         bytecode,
         deployed_bytecode,
         abi,
+        immutable_refs,
+        immutables_len,
     })
 }
+
+/// Parses `/evm/deployedBytecode/immutableReferences` from the contract output. This is a map from AST IDs
+/// of immutable variables to the list of their locations in the deployed bytecode.
+fn parse_immutable_refs(
+    contract: &serde_json::Value,
+) -> Result<Vec<ImmutableReference>, ContractVerifierError> {
+    let Some(refs) = contract.pointer("/evm/deployedBytecode/immutableReferences") else {
+        return Ok(vec![]);
+    };
+    let refs: HashMap<String, Vec<ImmutableReference>> = serde_json::from_value(refs.clone())
+        .context("unexpected `/evm/deployedBytecode/immutableReferences` value")?;
+    let mut refs: Vec<_> = refs.into_values().flatten().collect();
+    refs.sort_unstable_by_key(|r| r.start);
+    Ok(refs)
+}
+
+/// Parses the total length of immutables appended to the deployed bytecode from `/layout/code_layout`
+/// in `vyper` output. The layout is a (potentially nested by module) map of immutables to their `offset`
+/// and `length` in the immutables section. Returns `None` if the layout is not output by the compiler.
+fn parse_immutables_len(
+    contract: &serde_json::Value,
+) -> Result<Option<usize>, ContractVerifierError> {
+    fn section_end(layout: &serde_json::Value) -> anyhow::Result<usize> {
+        let Some(layout) = layout.as_object() else {
+            anyhow::bail!("unexpected code layout entry: {layout}");
+        };
+        if let (Some(offset), Some(length)) = (layout.get("offset"), layout.get("length")) {
+            let (Some(offset), Some(length)) = (offset.as_u64(), length.as_u64()) else {
+                anyhow::bail!("unexpected immutable location: {offset}, {length}");
+            };
+            let end = offset
+                .checked_add(length)
+                .context("immutable location overflow")?;
+            return usize::try_from(end).context("immutable location overflow");
+        }
+        layout
+            .values()
+            .try_fold(0, |max_end, entry| Ok(max_end.max(section_end(entry)?)))
+    }
+
+    let Some(layout) = contract.get("layout") else {
+        return Ok(None);
+    };
+    let Some(code_layout) = layout.get("code_layout") else {
+        // The contract has no immutables.
+        return Ok(Some(0));
+    };
+    let len = section_end(code_layout).context("unexpected `/layout/code_layout` value")?;
+    Ok(Some(len))
+}
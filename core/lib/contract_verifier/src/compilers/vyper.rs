@@ -50,7 +50,7 @@ impl VyperInput {
             sources: sources.collect(),
             settings: Settings {
                 output_selection: Some(serde_json::json!({
-                    "*": [ "abi", "evm.bytecode", "evm.deployedBytecode" ],
+                    "*": [ "abi", "evm.bytecode", "evm.deployedBytecode", "layout" ],
                 })),
                 other: serde_json::json!({
                     "optimize": self.optimizer_mode.as_deref(),
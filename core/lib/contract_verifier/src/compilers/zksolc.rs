@@ -154,6 +154,8 @@ impl ZkSolc {
             bytecode,
             deployed_bytecode: None,
             abi: serde_json::Value::Array(Vec::new()),
+            immutable_refs: vec![],
+            immutables_len: None,
         })
     }
 
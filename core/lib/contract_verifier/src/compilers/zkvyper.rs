@@ -83,6 +83,8 @@ impl ZkVyper {
                     abi: artifact["abi"].clone(),
                     bytecode,
                     deployed_bytecode: None,
+                    immutable_refs: vec![],
+                    immutables_len: None,
                 });
             }
         }
@@ -92,6 +92,38 @@ impl VersionedCompiler {
             Self::ZkSolc(_) | Self::ZkVyper(_) => BytecodeMarker::EraVm,
         }
     }
+
+    /// Compares the compiled deployed bytecode with the actual one. For EVM contracts, values of immutable variables
+    /// (which are only known after the constructor is executed) are ignored:
+    ///
+    /// - `solc` reports locations of immutables in the deployed bytecode; these locations are zeroed in the compiled bytecode.
+    /// - `vyper` appends immutables to the end of the deployed bytecode; their total length is reported by the compiler.
+    fn deployed_bytecode_matches(&self, artifacts: &CompilationArtifacts, deployed: &[u8]) -> bool {
+        let compiled = artifacts.deployed_bytecode();
+        match self {
+            Self::Solc(_) if !artifacts.immutable_refs.is_empty() => {
+                if compiled.len() != deployed.len() {
+                    return false;
+                }
+                let mut masked = deployed.to_vec();
+                for immutable_ref in &artifacts.immutable_refs {
+                    let end = immutable_ref.start.saturating_add(immutable_ref.length);
+                    let Some(immutable_value) = masked.get_mut(immutable_ref.start..end) else {
+                        return false;
+                    };
+                    immutable_value.fill(0);
+                }
+                masked == compiled
+            }
+            Self::Vyper(_) => {
+                // If the compiler didn't report immutables layout, require an exact match.
+                let immutables_len = artifacts.immutables_len.unwrap_or(0);
+                deployed.len() == compiled.len().saturating_add(immutables_len)
+                    && deployed.starts_with(compiled)
+            }
+            _ => compiled == deployed,
+        }
+    }
 }
 
 enum ConstructorArgs {
@@ -265,7 +297,8 @@ impl ContractVerifier {
             .context("invalid stored EVM bytecode")?,
         };
 
-        if artifacts.deployed_bytecode() != deployed_bytecode {
+        let compiler = VersionedCompiler::from(request.req.compiler_versions.clone());
+        if !compiler.deployed_bytecode_matches(&artifacts, deployed_bytecode) {
             tracing::info!(
                 request_id = request.id,
                 deployed = hex::encode(deployed_bytecode),
@@ -9,7 +9,9 @@ use zksync_node_test_utils::{create_l1_batch, create_l2_block};
 use zksync_types::{
     address_to_h256,
     bytecode::{pad_evm_bytecode, BytecodeHash},
-    contract_verification_api::{CompilerVersions, SourceCodeData, VerificationIncomingRequest},
+    contract_verification_api::{
+        CompilerVersions, ImmutableReference, SourceCodeData, VerificationIncomingRequest,
+    },
     get_code_key, get_known_code_key,
     l2::L2Tx,
     tx::IncludedTxLocation,
@@ -28,6 +30,7 @@ mod real;
 
 const SOLC_VERSION: &str = "0.8.27";
 const ZKSOLC_VERSION: &str = "1.5.4";
+const VYPER_VERSION: &str = "0.3.10";
 
 const BYTECODE_KINDS: [BytecodeMarker; 2] = [BytecodeMarker::EraVm, BytecodeMarker::Evm];
 
@@ -215,6 +218,7 @@ type SharedMockFn<In> =
 struct MockCompilerResolver {
     zksolc: SharedMockFn<ZkSolcInput>,
     solc: SharedMockFn<SolcInput>,
+    vyper: SharedMockFn<VyperInput>,
 }
 
 impl fmt::Debug for MockCompilerResolver {
@@ -232,6 +236,7 @@ impl MockCompilerResolver {
         Self {
             zksolc: Arc::new(move |input| Ok(zksolc(input))),
             solc: Arc::new(|input| panic!("unexpected solc call: {input:?}")),
+            vyper: Arc::new(|input| panic!("unexpected vyper call: {input:?}")),
         }
     }
 
@@ -239,6 +244,15 @@ impl MockCompilerResolver {
         Self {
             solc: Arc::new(move |input| Ok(solc(input))),
             zksolc: Arc::new(|input| panic!("unexpected zksolc call: {input:?}")),
+            vyper: Arc::new(|input| panic!("unexpected vyper call: {input:?}")),
+        }
+    }
+
+    fn vyper(vyper: impl Fn(VyperInput) -> CompilationArtifacts + 'static + Send + Sync) -> Self {
+        Self {
+            vyper: Arc::new(move |input| Ok(vyper(input))),
+            zksolc: Arc::new(|input| panic!("unexpected zksolc call: {input:?}")),
+            solc: Arc::new(|input| panic!("unexpected solc call: {input:?}")),
         }
     }
 }
@@ -263,13 +277,23 @@ impl Compiler<SolcInput> for MockCompilerResolver {
     }
 }
 
+#[async_trait]
+impl Compiler<VyperInput> for MockCompilerResolver {
+    async fn compile(
+        self: Box<Self>,
+        input: VyperInput,
+    ) -> Result<CompilationArtifacts, ContractVerifierError> {
+        (self.vyper)(input)
+    }
+}
+
 #[async_trait]
 impl CompilerResolver for MockCompilerResolver {
     async fn supported_versions(&self) -> anyhow::Result<SupportedCompilerVersions> {
         Ok(SupportedCompilerVersions {
             solc: [SOLC_VERSION.to_owned()].into_iter().collect(),
             zksolc: [ZKSOLC_VERSION.to_owned()].into_iter().collect(),
-            vyper: HashSet::default(),
+            vyper: [VYPER_VERSION.to_owned()].into_iter().collect(),
             zkvyper: HashSet::default(),
         })
     }
@@ -308,9 +332,15 @@ impl CompilerResolver for MockCompilerResolver {
 
     async fn resolve_vyper(
         &self,
-        _version: &str,
+        version: &str,
     ) -> Result<Box<dyn Compiler<VyperInput>>, ContractVerifierError> {
-        unreachable!("not tested")
+        if version != VYPER_VERSION {
+            return Err(ContractVerifierError::UnknownCompilerVersion(
+                "vyper",
+                version.to_owned(),
+            ));
+        }
+        Ok(Box::new(self.clone()))
     }
 
     async fn resolve_zkvyper(
@@ -408,6 +438,8 @@ async fn contract_verifier_basics(contract: TestContract) {
             bytecode: vec![0; 32],
             deployed_bytecode: None,
             abi: counter_contract_abi(),
+            immutable_refs: vec![],
+            immutables_len: None,
         }
     });
     let verifier = ContractVerifier::with_resolver(
@@ -521,6 +553,8 @@ async fn verifying_evm_bytecode(contract: TestContract) {
         bytecode: creation_bytecode.clone(),
         deployed_bytecode: Some(deployed_bytecode),
         abi: counter_contract_abi(),
+        immutable_refs: vec![],
+        immutables_len: None,
     };
     let mock_resolver = MockCompilerResolver::solc(move |input| {
         assert_eq!(input.standard_json.language, "Solidity");
@@ -544,6 +578,139 @@ async fn verifying_evm_bytecode(contract: TestContract) {
     assert_request_success(&mut storage, request_id, address, &creation_bytecode).await;
 }
 
+#[test_casing(4, Product(([false, true], [false, true])))]
+#[tokio::test]
+async fn verifying_evm_bytecode_with_immutables(mismatch_outside_immutables: bool, vyper: bool) {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    let creation_bytecode = vec![3_u8; 20];
+    let mut deployed_bytecode = vec![5_u8; 16];
+    if vyper {
+        // `vyper` appends immutable values to the end of the deployed bytecode.
+        deployed_bytecode.extend_from_slice(&[1; 32]);
+    } else {
+        // Immutable values are only known after the contract is deployed.
+        deployed_bytecode[2..6].copy_from_slice(&[1, 2, 3, 4]);
+        deployed_bytecode[10..12].copy_from_slice(&[0xff; 2]);
+    }
+    if mismatch_outside_immutables {
+        deployed_bytecode[7] = 0xaa;
+    }
+
+    prepare_storage(&mut storage).await;
+    let address = Address::repeat_byte(1);
+    mock_evm_deployment(
+        &mut storage,
+        address,
+        creation_bytecode.clone(),
+        &deployed_bytecode,
+        &[],
+    )
+    .await;
+    let mut req = test_request(address, COUNTER_CONTRACT);
+    if vyper {
+        req.source_code_data = SourceCodeData::VyperMultiFile(HashMap::from([(
+            "Counter.vy".to_owned(),
+            COUNTER_VYPER_CONTRACT.to_owned(),
+        )]));
+        req.compiler_versions = CompilerVersions::Vyper {
+            compiler_vyper_version: VYPER_VERSION.to_owned(),
+            compiler_zkvyper_version: None,
+        };
+    } else {
+        req.compiler_versions = CompilerVersions::Solc {
+            compiler_solc_version: SOLC_VERSION.to_owned(),
+            compiler_zksolc_version: None,
+        };
+    }
+    let request_id = storage
+        .contract_verification_dal()
+        .add_contract_verification_request(&req)
+        .await
+        .unwrap();
+
+    let mock_resolver = if vyper {
+        let artifacts = CompilationArtifacts {
+            bytecode: creation_bytecode.clone(),
+            deployed_bytecode: Some(vec![5_u8; 16]),
+            abi: counter_contract_abi(),
+            immutable_refs: vec![],
+            immutables_len: Some(32),
+        };
+        MockCompilerResolver::vyper(move |_| artifacts.clone())
+    } else {
+        let mut compiled_deployed_bytecode = vec![5_u8; 16];
+        compiled_deployed_bytecode[2..6].fill(0);
+        compiled_deployed_bytecode[10..12].fill(0);
+        let artifacts = CompilationArtifacts {
+            bytecode: creation_bytecode.clone(),
+            deployed_bytecode: Some(compiled_deployed_bytecode),
+            abi: counter_contract_abi(),
+            immutable_refs: vec![
+                ImmutableReference {
+                    start: 2,
+                    length: 4,
+                },
+                ImmutableReference {
+                    start: 10,
+                    length: 2,
+                },
+            ],
+            immutables_len: None,
+        };
+        MockCompilerResolver::solc(move |_| artifacts.clone())
+    };
+    let verifier = ContractVerifier::with_resolver(
+        Duration::from_secs(60),
+        pool.clone(),
+        Arc::new(mock_resolver),
+    )
+    .await
+    .unwrap();
+
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    verifier.run(stop_receiver, Some(1)).await.unwrap();
+
+    if !mismatch_outside_immutables {
+        assert_request_success(&mut storage, request_id, address, &creation_bytecode).await;
+        return;
+    }
+    let status = storage
+        .contract_verification_dal()
+        .get_verification_request_status(request_id)
+        .await
+        .unwrap()
+        .expect("no status");
+    assert_eq!(status.status, "failed");
+    let err = status.error.unwrap();
+    assert_eq!(err, ContractVerifierError::BytecodeMismatch.to_string());
+}
+
+#[test]
+fn vyper_immutables_are_bounded_by_reported_len() {
+    let compiler = VersionedCompiler::Vyper(VYPER_VERSION.to_owned());
+    let artifacts = CompilationArtifacts {
+        bytecode: vec![3_u8; 20],
+        deployed_bytecode: Some(vec![5_u8; 16]),
+        abi: counter_contract_abi(),
+        immutable_refs: vec![],
+        immutables_len: Some(32),
+    };
+
+    let mut deployed_bytecode = vec![5_u8; 48];
+    assert!(compiler.deployed_bytecode_matches(&artifacts, &deployed_bytecode));
+    deployed_bytecode.push(0);
+    assert!(!compiler.deployed_bytecode_matches(&artifacts, &deployed_bytecode));
+    assert!(!compiler.deployed_bytecode_matches(&artifacts, &[5_u8; 32]));
+
+    let artifacts = CompilationArtifacts {
+        immutables_len: None,
+        ..artifacts
+    };
+    assert!(compiler.deployed_bytecode_matches(&artifacts, &[5_u8; 16]));
+    assert!(!compiler.deployed_bytecode_matches(&artifacts, &[5_u8; 48]));
+}
+
 #[tokio::test]
 async fn bytecode_mismatch_error() {
     let pool = ConnectionPool::test_pool().await;
@@ -563,6 +730,8 @@ async fn bytecode_mismatch_error() {
         bytecode: vec![0; 32],
         deployed_bytecode: None,
         abi: counter_contract_abi(),
+        immutable_refs: vec![],
+        immutables_len: None,
     });
     let verifier = ContractVerifier::with_resolver(
         Duration::from_secs(60),
@@ -643,11 +812,15 @@ async fn args_mismatch_error(contract: TestContract, bytecode_kind: BytecodeMark
             bytecode: bytecode.clone(),
             deployed_bytecode: None,
             abi: counter_contract_abi(),
+            immutable_refs: vec![],
+            immutables_len: None,
         }),
         BytecodeMarker::Evm => MockCompilerResolver::solc(move |_| CompilationArtifacts {
             bytecode: vec![3_u8; 48],
             deployed_bytecode: Some(bytecode.clone()),
             abi: counter_contract_abi(),
+            immutable_refs: vec![],
+            immutables_len: None,
         }),
     };
     let verifier = ContractVerifier::with_resolver(
@@ -712,6 +885,8 @@ async fn creation_bytecode_mismatch() {
         bytecode: vec![4; 20], // differs from `creation_bytecode`
         deployed_bytecode: Some(deployed_bytecode.clone()),
         abi: counter_contract_abi(),
+        immutable_refs: vec![],
+        immutables_len: None,
     });
     let verifier = ContractVerifier::with_resolver(
         Duration::from_secs(60),
@@ -349,6 +349,8 @@ async fn using_standalone_vyper(specify_contract_file: bool) {
     let output = compiler.compile(input).await.unwrap();
 
     assert!(output.deployed_bytecode.is_some());
+    // The counter contract has no immutables.
+    assert_eq!(output.immutables_len, Some(0));
     assert_eq!(output.abi, without_internal_types(counter_contract_abi()));
 }
 
@@ -0,0 +1,12 @@
+-- Headers of hard-pruned L2 blocks containing events retained according to pruning retention rules.
+-- Used to return block information for retained events (e.g., in `eth_getLogs`).
+CREATE TABLE IF NOT EXISTS pruning_retained_miniblocks
+(
+    number          BIGINT NOT NULL PRIMARY KEY,
+    hash            BYTEA  NOT NULL,
+    l1_batch_number BIGINT,
+    timestamp       BIGINT NOT NULL,
+
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1,26 @@
+-- Queue of L2 transactions accepted by an external node and forwarded to the main node.
+CREATE TABLE IF NOT EXISTS forwarded_transactions
+(
+    hash              BYTEA     NOT NULL PRIMARY KEY,
+    initiator_address BYTEA     NOT NULL,
+    nonce             BIGINT    NOT NULL,
+    raw_tx            BYTEA     NOT NULL,
+    received_at       TIMESTAMP NOT NULL,
+    -- Number of failed delivery attempts.
+    attempts          INT       NOT NULL DEFAULT 0,
+    next_attempt_at   TIMESTAMP NOT NULL,
+    last_error        TEXT,
+    -- Set once the main node accepts the transaction.
+    forwarded_at      TIMESTAMP,
+    -- Set if the main node has rejected the transaction; such transactions are never retried.
+    rejection_reason  TEXT,
+
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS forwarded_transactions_initiator_address_nonce_idx
+    ON forwarded_transactions (initiator_address, nonce);
+CREATE INDEX IF NOT EXISTS forwarded_transactions_next_attempt_at_idx
+    ON forwarded_transactions (next_attempt_at)
+    WHERE forwarded_at IS NULL AND rejection_reason IS NULL;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS erc20_transfers;
+DROP TABLE IF EXISTS vm_runner_workloads;
@@ -0,0 +1,32 @@
+-- Progress of custom VM runner workloads. Each workload has its own cursor identified by the workload name.
+CREATE TABLE IF NOT EXISTS vm_runner_workloads
+(
+    workload              TEXT      NOT NULL,
+    l1_batch_number       BIGINT    NOT NULL,
+    created_at            TIMESTAMP NOT NULL,
+    updated_at            TIMESTAMP NOT NULL,
+    processing_started_at TIMESTAMP,
+    time_taken            TIME,
+    PRIMARY KEY (workload, l1_batch_number)
+);
+
+-- ERC-20 transfers indexed by the reference VM runner workload.
+CREATE TABLE IF NOT EXISTS erc20_transfers
+(
+    tx_hash           BYTEA       NOT NULL,
+    -- Index of the `Transfer` event among all events emitted by the transaction.
+    event_index_in_tx INT         NOT NULL,
+    l1_batch_number   BIGINT      NOT NULL,
+    l2_block_number   BIGINT      NOT NULL,
+    token_address     BYTEA       NOT NULL,
+    from_address      BYTEA       NOT NULL,
+    to_address        BYTEA       NOT NULL,
+    value             NUMERIC(80) NOT NULL,
+
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (tx_hash, event_index_in_tx)
+);
+
+CREATE INDEX IF NOT EXISTS erc20_transfers_l1_batch_number_idx ON erc20_transfers (l1_batch_number);
+CREATE INDEX IF NOT EXISTS erc20_transfers_token_address_idx ON erc20_transfers (token_address);
@@ -1,3 +1,5 @@
+use std::ops;
+
 use zksync_db_connection::{
     connection::Connection, error::DalResult, instrument::InstrumentExt, interpolate_query,
     match_query_as,
@@ -8,7 +10,7 @@ use zksync_types::{
     debug_flat_call::CallTraceMeta,
     fee_model::BatchFeeInput,
     l2_to_l1_log::L2ToL1Log,
-    web3::{BlockHeader, Bytes},
+    web3::{keccak256_concat, BlockHeader, Bytes},
     Bloom, L1BatchNumber, L2BlockNumber, ProtocolVersionId, H160, H256, U256, U64,
 };
 use zksync_vm_interface::Call;
@@ -35,6 +37,15 @@ impl BlocksWeb3Dal<'_, '_> {
         &mut self,
         block_number: L2BlockNumber,
     ) -> DalResult<Option<api::Block<H256>>> {
+        let mut blocks = self.get_api_blocks(block_number..=block_number).await?;
+        Ok(blocks.pop())
+    }
+
+    /// Returns API blocks in the specified range ordered by their number. Missing L2 blocks are skipped.
+    pub async fn get_api_blocks(
+        &mut self,
+        block_numbers: ops::RangeInclusive<L2BlockNumber>,
+    ) -> DalResult<Vec<api::Block<H256>>> {
         let rows = sqlx::query!(
             r#"
             SELECT
@@ -58,29 +69,33 @@ impl BlocksWeb3Dal<'_, '_> {
             LEFT JOIN l1_batches ON l1_batches.number = miniblocks.l1_batch_number
             LEFT JOIN transactions ON transactions.miniblock_number = miniblocks.number
             WHERE
-                miniblocks.number = $1
+                miniblocks.number BETWEEN $1 AND $2
             ORDER BY
+                miniblocks.number ASC,
                 transactions.index_in_block ASC
             "#,
-            i64::from(block_number.0)
+            i64::from(block_numbers.start().0),
+            i64::from(block_numbers.end().0)
         )
-        .instrument("get_api_block")
-        .with_arg("block_number", &block_number)
+        .instrument("get_api_blocks")
+        .with_arg("block_numbers", &block_numbers)
         .fetch_all(self.storage)
         .await?;
 
-        let block = rows.into_iter().fold(None, |prev_block, row| {
-            let mut block = prev_block.unwrap_or_else(|| {
-                // This code will be only executed for the first row in the DB response.
+        let mut blocks: Vec<api::Block<H256>> = vec![];
+        for row in rows {
+            let number = U64::from(row.number as u64);
+            if blocks.last().map_or(true, |block| block.number != number) {
+                // This code will be only executed for the first row of each block in the DB response.
                 // All other rows will only be used to extract relevant transactions.
-                api::Block {
+                blocks.push(api::Block {
                     hash: H256::from_slice(&row.block_hash),
                     parent_hash: row
                         .parent_hash
                         .as_deref()
                         .map_or_else(H256::zero, H256::from_slice),
                     uncles_hash: EMPTY_UNCLES_HASH,
-                    number: (row.number as u64).into(),
+                    number,
                     l1_batch_number: row.l1_batch_number.map(|number| (number as u64).into()),
                     base_fee_per_gas: bigdecimal_to_u256(row.base_fee_per_gas),
                     timestamp: (row.timestamp as u64).into(),
@@ -95,8 +110,9 @@ impl BlocksWeb3Dal<'_, '_> {
                         .map(|b| Bloom::from_slice(&b))
                         .unwrap_or_default(),
                     ..api::Block::default()
-                }
-            });
+                });
+            }
+            let block = blocks.last_mut().unwrap();
 
             if let (Some(gas_limit), Some(refunded_gas)) =
                 (row.transaction_gas_limit, row.refunded_gas)
@@ -106,11 +122,8 @@ impl BlocksWeb3Dal<'_, '_> {
             if let Some(tx_hash) = &row.tx_hash {
                 block.transactions.push(H256::from_slice(tx_hash));
             }
-
-            Some(block)
-        });
-
-        Ok(block)
+        }
+        Ok(blocks)
     }
 
     pub async fn get_block_tx_count(
@@ -496,6 +509,57 @@ impl BlocksWeb3Dal<'_, '_> {
         })
     }
 
+    /// Returns hash preimages for L2 blocks in the specified range ordered by the block number.
+    /// Blocks missing from the storage are skipped.
+    pub async fn get_l2_block_hash_preimages(
+        &mut self,
+        blocks: ops::RangeInclusive<L2BlockNumber>,
+    ) -> DalResult<Vec<api::L2BlockHashPreimage>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                miniblocks.number,
+                miniblocks.timestamp,
+                transactions.hash AS "tx_hash?"
+            FROM
+                miniblocks
+            LEFT JOIN transactions ON transactions.miniblock_number = miniblocks.number
+            WHERE
+                miniblocks.number BETWEEN $1 AND $2
+            ORDER BY
+                miniblocks.number,
+                transactions.index_in_block
+            "#,
+            i64::from(blocks.start().0),
+            i64::from(blocks.end().0)
+        )
+        .instrument("get_l2_block_hash_preimages")
+        .with_arg("blocks", &blocks)
+        .fetch_all(self.storage)
+        .await?;
+
+        let mut preimages: Vec<api::L2BlockHashPreimage> = vec![];
+        for row in rows {
+            let number = L2BlockNumber(row.number as u32);
+            if preimages
+                .last()
+                .map_or(true, |block| block.number != number)
+            {
+                preimages.push(api::L2BlockHashPreimage {
+                    number,
+                    timestamp: row.timestamp as u64,
+                    txs_rolling_hash: H256::zero(),
+                });
+            }
+            if let Some(tx_hash) = row.tx_hash {
+                let block = preimages.last_mut().unwrap();
+                block.txs_rolling_hash =
+                    keccak256_concat(block.txs_rolling_hash, H256::from_slice(&tx_hash));
+            }
+        }
+        Ok(preimages)
+    }
+
     pub async fn get_l1_batch_info_for_tx(
         &mut self,
         tx_hash: H256,
@@ -597,6 +661,59 @@ impl BlocksWeb3Dal<'_, '_> {
         .collect())
     }
 
+    /// Returns call traces for all transactions in the specified L2 blocks ordered by the block number
+    /// and the transaction index in the block.
+    pub async fn get_traces_for_l2_blocks(
+        &mut self,
+        block_numbers: ops::RangeInclusive<L2BlockNumber>,
+    ) -> DalResult<Vec<(Call, CallTraceMeta)>> {
+        sqlx::query!(
+            r#"
+            SELECT
+                transactions.hash AS tx_hash,
+                transactions.index_in_block AS tx_index_in_block,
+                miniblocks.number AS block_number,
+                miniblocks.hash AS block_hash,
+                miniblocks.protocol_version,
+                call_trace
+            FROM
+                call_traces
+            INNER JOIN transactions ON tx_hash = transactions.hash
+            INNER JOIN miniblocks ON miniblocks.number = transactions.miniblock_number
+            WHERE
+                transactions.miniblock_number BETWEEN $1 AND $2
+            ORDER BY
+                transactions.miniblock_number,
+                transactions.index_in_block
+            "#,
+            i64::from(block_numbers.start().0),
+            i64::from(block_numbers.end().0)
+        )
+        .try_map(|row| {
+            let protocol_version = row
+                .protocol_version
+                .map(parse_protocol_version)
+                .transpose()?
+                .unwrap_or_else(ProtocolVersionId::last_potentially_undefined);
+            let meta = CallTraceMeta {
+                index_in_block: row.tx_index_in_block.unwrap_or_default() as usize,
+                tx_hash: H256::from_slice(&row.tx_hash),
+                block_number: row.block_number as u32,
+                block_hash: H256::from_slice(&row.block_hash),
+            };
+            let call_trace = CallTrace {
+                call_trace: row.call_trace,
+                tx_hash: row.tx_hash,
+                tx_index_in_block: row.tx_index_in_block,
+            };
+            Ok((call_trace.into_call(protocol_version), meta))
+        })
+        .instrument("get_traces_for_l2_blocks")
+        .with_arg("block_numbers", &block_numbers)
+        .fetch_all(self.storage)
+        .await
+    }
+
     /// Returns `base_fee_per_gas` and `fair_pubdata_price` for L2 block range [min(newest_block - block_count + 1, 0), newest_block]
     /// in descending order of L2 block numbers.
     pub async fn get_fee_history(
@@ -885,6 +1002,14 @@ mod tests {
         let block = conn.blocks_web3_dal().get_api_block(L2BlockNumber(1)).await;
         assert!(block.unwrap().is_none());
 
+        let blocks = conn
+            .blocks_web3_dal()
+            .get_api_blocks(L2BlockNumber(0)..=L2BlockNumber(5))
+            .await
+            .unwrap();
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].hash, block_hash);
+
         let tx_count = conn
             .blocks_web3_dal()
             .get_block_tx_count(L2BlockNumber(1))
@@ -1163,5 +1288,71 @@ mod tests {
             assert_eq!(tx_result.hash, meta.tx_hash);
             assert_eq!(*trace, expected_trace);
         }
+
+        let range_traces = conn
+            .blocks_web3_dal()
+            .get_traces_for_l2_blocks(L2BlockNumber(0)..=L2BlockNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(range_traces, traces);
+    }
+
+    #[tokio::test]
+    async fn getting_l2_block_hash_preimages() {
+        let connection_pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = connection_pool.connection().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(&ProtocolVersion::default())
+            .await
+            .unwrap();
+        for number in 1..=2 {
+            conn.blocks_dal()
+                .insert_l2_block(&create_l2_block_header(number))
+                .await
+                .unwrap();
+        }
+
+        let transactions = [mock_l2_transaction(), mock_l2_transaction()];
+        let mut tx_results = vec![];
+        for tx in transactions {
+            conn.transactions_dal()
+                .insert_transaction_l2(
+                    &tx,
+                    TransactionExecutionMetrics::default(),
+                    ValidationTraces::default(),
+                )
+                .await
+                .unwrap();
+            tx_results.push(mock_execution_result(tx));
+        }
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_l2_block(
+                L2BlockNumber(1),
+                &tx_results,
+                1.into(),
+                ProtocolVersionId::latest(),
+                false,
+            )
+            .await
+            .unwrap();
+
+        let preimages = conn
+            .blocks_web3_dal()
+            .get_l2_block_hash_preimages(L2BlockNumber(1)..=L2BlockNumber(3))
+            .await
+            .unwrap();
+        assert_eq!(preimages.len(), 2);
+
+        let mut hasher = L2BlockHasher::new(L2BlockNumber(1), 1, H256::zero());
+        for tx_result in &tx_results {
+            hasher.push_tx_hash(tx_result.hash);
+        }
+        assert_eq!(preimages[0].number, L2BlockNumber(1));
+        assert_eq!(
+            preimages[0].hash(H256::zero(), ProtocolVersionId::latest()),
+            hasher.finalize(ProtocolVersionId::latest())
+        );
+        assert_eq!(preimages[1].number, L2BlockNumber(2));
+        assert_eq!(preimages[1].txs_rolling_hash, H256::zero());
     }
 }
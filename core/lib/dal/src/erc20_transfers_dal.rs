@@ -0,0 +1,194 @@
+//! DAL for ERC-20 transfers indexed by the reference custom VM runner workload.
+
+use bigdecimal::BigDecimal;
+use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
+use zksync_types::{Address, L1BatchNumber, L2BlockNumber, H256, U256};
+
+use crate::{
+    models::{bigdecimal_to_u256, u256_to_big_decimal},
+    Core,
+};
+
+/// ERC-20 transfer, i.e., a `Transfer(address,address,uint256)` event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Erc20Transfer {
+    pub tx_hash: H256,
+    /// Index of the event among all events emitted by the transaction.
+    pub event_index_in_tx: u32,
+    pub l2_block_number: L2BlockNumber,
+    pub token_address: Address,
+    pub from: Address,
+    pub to: Address,
+    pub value: U256,
+}
+
+#[derive(Debug)]
+struct StorageErc20Transfer {
+    tx_hash: Vec<u8>,
+    event_index_in_tx: i32,
+    l2_block_number: i64,
+    token_address: Vec<u8>,
+    from_address: Vec<u8>,
+    to_address: Vec<u8>,
+    value: BigDecimal,
+}
+
+impl From<StorageErc20Transfer> for Erc20Transfer {
+    fn from(row: StorageErc20Transfer) -> Self {
+        Self {
+            tx_hash: H256::from_slice(&row.tx_hash),
+            event_index_in_tx: row.event_index_in_tx as u32,
+            l2_block_number: L2BlockNumber(row.l2_block_number as u32),
+            token_address: Address::from_slice(&row.token_address),
+            from: Address::from_slice(&row.from_address),
+            to: Address::from_slice(&row.to_address),
+            value: bigdecimal_to_u256(row.value),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Erc20TransfersDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl Erc20TransfersDal<'_, '_> {
+    /// Inserts transfers for the specified L1 batch. Transfers that are already present are skipped,
+    /// so that re-processing a batch is idempotent.
+    pub async fn insert_transfers(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        transfers: &[Erc20Transfer],
+    ) -> DalResult<()> {
+        let mut tx_hashes = Vec::with_capacity(transfers.len());
+        let mut event_indices = Vec::with_capacity(transfers.len());
+        let mut l2_block_numbers = Vec::with_capacity(transfers.len());
+        let mut token_addresses = Vec::with_capacity(transfers.len());
+        let mut from_addresses = Vec::with_capacity(transfers.len());
+        let mut to_addresses = Vec::with_capacity(transfers.len());
+        let mut values = Vec::with_capacity(transfers.len());
+        for transfer in transfers {
+            tx_hashes.push(transfer.tx_hash.as_bytes());
+            event_indices.push(transfer.event_index_in_tx as i32);
+            l2_block_numbers.push(i64::from(transfer.l2_block_number.0));
+            token_addresses.push(transfer.token_address.as_bytes());
+            from_addresses.push(transfer.from.as_bytes());
+            to_addresses.push(transfer.to.as_bytes());
+            values.push(u256_to_big_decimal(transfer.value));
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+            erc20_transfers (
+                tx_hash,
+                event_index_in_tx,
+                l1_batch_number,
+                l2_block_number,
+                token_address,
+                from_address,
+                to_address,
+                value,
+                created_at,
+                updated_at
+            )
+            SELECT
+                u.tx_hash,
+                u.event_index_in_tx,
+                $8,
+                u.l2_block_number,
+                u.token_address,
+                u.from_address,
+                u.to_address,
+                u.value,
+                NOW(),
+                NOW()
+            FROM
+                UNNEST(
+                    $1::bytea [],
+                    $2::int [],
+                    $3::bigint [],
+                    $4::bytea [],
+                    $5::bytea [],
+                    $6::bytea [],
+                    $7::numeric []
+                ) AS u (
+                    tx_hash,
+                    event_index_in_tx,
+                    l2_block_number,
+                    token_address,
+                    from_address,
+                    to_address,
+                    value
+                )
+            ON CONFLICT (tx_hash, event_index_in_tx) DO NOTHING
+            "#,
+            &tx_hashes as &[&[u8]],
+            &event_indices,
+            &l2_block_numbers,
+            &token_addresses as &[&[u8]],
+            &from_addresses as &[&[u8]],
+            &to_addresses as &[&[u8]],
+            &values,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("insert_erc20_transfers")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("transfers.len", &transfers.len())
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns transfers for the specified L1 batch ordered by L2 block number and transaction.
+    pub async fn get_transfers_for_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> DalResult<Vec<Erc20Transfer>> {
+        let rows = sqlx::query_as!(
+            StorageErc20Transfer,
+            r#"
+            SELECT
+                erc20_transfers.tx_hash,
+                erc20_transfers.event_index_in_tx,
+                erc20_transfers.l2_block_number,
+                erc20_transfers.token_address,
+                erc20_transfers.from_address,
+                erc20_transfers.to_address,
+                erc20_transfers.value
+            FROM
+                erc20_transfers
+            LEFT JOIN transactions ON erc20_transfers.tx_hash = transactions.hash
+            WHERE
+                erc20_transfers.l1_batch_number = $1
+            ORDER BY
+                erc20_transfers.l2_block_number,
+                transactions.index_in_block,
+                erc20_transfers.event_index_in_tx
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("get_erc20_transfers_for_l1_batch")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_all(self.storage)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Removes transfers for all L1 batches after the specified one.
+    pub async fn delete_transfers(&mut self, last_batch_to_keep: L1BatchNumber) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM erc20_transfers
+            WHERE
+                l1_batch_number > $1
+            "#,
+            i64::from(last_batch_to_keep.0)
+        )
+        .instrument("delete_erc20_transfers")
+        .with_arg("last_batch_to_keep", &last_batch_to_keep)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+}
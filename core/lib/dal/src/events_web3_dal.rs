@@ -79,10 +79,15 @@ impl EventsWeb3Dal<'_, '_> {
                 ORDER BY miniblock_number ASC, event_index_in_block ASC
                 LIMIT ${}
             )
-            SELECT miniblocks.hash as "block_hash", miniblocks.l1_batch_number as "l1_batch_number",
-                miniblocks.timestamp as block_timestamp, events_select.*
+            SELECT COALESCE(miniblocks.hash, retained.hash) as "block_hash",
+                COALESCE(miniblocks.l1_batch_number, retained.l1_batch_number) as "l1_batch_number",
+                COALESCE(miniblocks.timestamp, retained.timestamp) as block_timestamp, events_select.*
             FROM events_select
-            INNER JOIN miniblocks ON events_select.miniblock_number = miniblocks.number
+            LEFT JOIN miniblocks ON events_select.miniblock_number = miniblocks.number
+            -- Events in hard-pruned L2 blocks can be retained according to pruning retention rules
+            LEFT JOIN pruning_retained_miniblocks AS retained
+                ON events_select.miniblock_number = retained.number
+            WHERE miniblocks.number IS NOT NULL OR retained.number IS NOT NULL
             ORDER BY miniblock_number ASC, event_index_in_block ASC
             "#,
             where_sql, arg_index
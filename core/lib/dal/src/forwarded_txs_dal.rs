@@ -0,0 +1,409 @@
+//! DAL for the queue of transactions forwarded by an external node to the main node.
+
+use std::{collections::HashMap, time::Duration};
+
+use zksync_db_connection::{
+    connection::Connection, error::DalResult, instrument::InstrumentExt,
+    utils::pg_interval_from_duration,
+};
+use zksync_types::{Address, Nonce, H256};
+
+use crate::Core;
+
+/// Delivery status of a forwarded transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ForwardedTxStatus {
+    /// Transaction is waiting to be delivered to the main node.
+    Queued { attempts: u32 },
+    /// Transaction was accepted by the main node.
+    Forwarded,
+    /// Transaction was rejected by the main node; it will not be retried.
+    Rejected { reason: String },
+}
+
+/// Transaction stored in the forwarding queue.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForwardedTx {
+    pub hash: H256,
+    pub initiator_address: Address,
+    pub nonce: Nonce,
+    /// Raw transaction bytes as submitted via `eth_sendRawTransaction`.
+    pub raw_tx: Vec<u8>,
+    pub received_at: chrono::NaiveDateTime,
+    pub status: ForwardedTxStatus,
+}
+
+#[derive(Debug)]
+struct StorageForwardedTx {
+    hash: Vec<u8>,
+    initiator_address: Vec<u8>,
+    nonce: i64,
+    raw_tx: Vec<u8>,
+    received_at: chrono::NaiveDateTime,
+    attempts: i32,
+    forwarded_at: Option<chrono::NaiveDateTime>,
+    rejection_reason: Option<String>,
+}
+
+impl From<StorageForwardedTx> for ForwardedTx {
+    fn from(row: StorageForwardedTx) -> Self {
+        let status = if let Some(reason) = row.rejection_reason {
+            ForwardedTxStatus::Rejected { reason }
+        } else if row.forwarded_at.is_some() {
+            ForwardedTxStatus::Forwarded
+        } else {
+            ForwardedTxStatus::Queued {
+                attempts: row.attempts as u32,
+            }
+        };
+        Self {
+            hash: H256::from_slice(&row.hash),
+            initiator_address: Address::from_slice(&row.initiator_address),
+            nonce: Nonce(row.nonce as u32),
+            raw_tx: row.raw_tx,
+            received_at: row.received_at,
+            status,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ForwardedTxsDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl ForwardedTxsDal<'_, '_> {
+    /// Inserts a new transaction into the queue. Returns `false` if the transaction is already present.
+    pub async fn insert_tx(
+        &mut self,
+        hash: H256,
+        initiator_address: Address,
+        nonce: Nonce,
+        raw_tx: &[u8],
+    ) -> DalResult<bool> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO
+            forwarded_transactions (
+                hash,
+                initiator_address,
+                nonce,
+                raw_tx,
+                received_at,
+                next_attempt_at,
+                created_at,
+                updated_at
+            )
+            VALUES
+            ($1, $2, $3, $4, NOW(), NOW(), NOW(), NOW())
+            ON CONFLICT (hash) DO NOTHING
+            "#,
+            hash.as_bytes(),
+            initiator_address.as_bytes(),
+            i64::from(nonce.0),
+            raw_tx
+        )
+        .instrument("insert_forwarded_tx")
+        .with_arg("hash", &hash)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn get_tx(&mut self, hash: H256) -> DalResult<Option<ForwardedTx>> {
+        let row = sqlx::query_as!(
+            StorageForwardedTx,
+            r#"
+            SELECT
+                hash,
+                initiator_address,
+                nonce,
+                raw_tx,
+                received_at,
+                attempts,
+                forwarded_at,
+                rejection_reason
+            FROM
+                forwarded_transactions
+            WHERE
+                hash = $1
+            "#,
+            hash.as_bytes()
+        )
+        .instrument("get_forwarded_tx")
+        .with_arg("hash", &hash)
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(row.map(Into::into))
+    }
+
+    /// Returns queued transactions that are due for a delivery attempt, oldest first.
+    pub async fn get_txs_to_forward(&mut self, limit: usize) -> DalResult<Vec<ForwardedTx>> {
+        let rows = sqlx::query_as!(
+            StorageForwardedTx,
+            r#"
+            SELECT
+                hash,
+                initiator_address,
+                nonce,
+                raw_tx,
+                received_at,
+                attempts,
+                forwarded_at,
+                rejection_reason
+            FROM
+                forwarded_transactions
+            WHERE
+                forwarded_at IS NULL
+                AND rejection_reason IS NULL
+                AND next_attempt_at <= NOW()
+            ORDER BY
+                received_at
+            LIMIT
+                $1
+            "#,
+            limit as i64
+        )
+        .instrument("get_txs_to_forward")
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    pub async fn mark_tx_as_forwarded(&mut self, hash: H256) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            UPDATE forwarded_transactions
+            SET
+                forwarded_at = NOW(),
+                updated_at = NOW()
+            WHERE
+                hash = $1
+            "#,
+            hash.as_bytes()
+        )
+        .instrument("mark_tx_as_forwarded")
+        .with_arg("hash", &hash)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn mark_tx_as_rejected(&mut self, hash: H256, reason: &str) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            UPDATE forwarded_transactions
+            SET
+                rejection_reason = $2,
+                updated_at = NOW()
+            WHERE
+                hash = $1
+            "#,
+            hash.as_bytes(),
+            reason
+        )
+        .instrument("mark_tx_as_rejected")
+        .with_arg("hash", &hash)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Records a failed delivery attempt; the next attempt will be made no earlier than after `retry_after`.
+    pub async fn record_failed_attempt(
+        &mut self,
+        hash: H256,
+        error: &str,
+        retry_after: Duration,
+    ) -> DalResult<()> {
+        let retry_after = pg_interval_from_duration(retry_after);
+        sqlx::query!(
+            r#"
+            UPDATE forwarded_transactions
+            SET
+                attempts = attempts + 1,
+                last_error = $2,
+                next_attempt_at = NOW() + $3::INTERVAL,
+                updated_at = NOW()
+            WHERE
+                hash = $1
+            "#,
+            hash.as_bytes(),
+            error,
+            retry_after
+        )
+        .instrument("record_failed_attempt")
+        .with_arg("hash", &hash)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns nonces of non-rejected transactions for the specified account, starting from `from_nonce`.
+    pub async fn get_pending_nonces(
+        &mut self,
+        initiator_address: Address,
+        from_nonce: Nonce,
+    ) -> DalResult<Vec<Nonce>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT DISTINCT
+                nonce
+            FROM
+                forwarded_transactions
+            WHERE
+                initiator_address = $1
+                AND nonce >= $2
+                AND rejection_reason IS NULL
+            ORDER BY
+                nonce
+            "#,
+            initiator_address.as_bytes(),
+            i64::from(from_nonce.0)
+        )
+        .instrument("get_pending_nonces")
+        .with_arg("initiator_address", &initiator_address)
+        .fetch_all(self.storage)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| Nonce(row.nonce as u32))
+            .collect())
+    }
+
+    /// Returns all distinct initiator addresses of transactions in the queue.
+    pub async fn get_initiator_addresses(&mut self) -> DalResult<Vec<Address>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT DISTINCT
+                initiator_address
+            FROM
+                forwarded_transactions
+            "#
+        )
+        .instrument("get_initiator_addresses")
+        .fetch_all(self.storage)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| Address::from_slice(&row.initiator_address))
+            .collect())
+    }
+
+    /// Removes transactions with nonces lower than the stored account nonces. Such transactions are either
+    /// included into synced L2 blocks or replaced. Returns the number of removed transactions.
+    pub async fn remove_stale_txs(
+        &mut self,
+        stored_nonces: &HashMap<Address, Nonce>,
+    ) -> DalResult<usize> {
+        let (addresses, nonces): (Vec<_>, Vec<_>) = stored_nonces
+            .iter()
+            .map(|(address, nonce)| (address.as_bytes(), i64::from(nonce.0)))
+            .unzip();
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM forwarded_transactions
+            USING
+            UNNEST($1::bytea [], $2::bigint []) AS u (initiator_address, nonce)
+            WHERE
+                forwarded_transactions.initiator_address = u.initiator_address
+                AND forwarded_transactions.nonce < u.nonce
+            "#,
+            &addresses as &[&[u8]],
+            &nonces
+        )
+        .instrument("remove_stale_forwarded_txs")
+        .with_arg("stored_nonces.len", &stored_nonces.len())
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected() as usize)
+    }
+
+    /// Removes rejected transactions that were last updated more than `retention` ago.
+    /// Returns the number of removed transactions.
+    pub async fn remove_rejected_txs(&mut self, retention: Duration) -> DalResult<usize> {
+        let retention = pg_interval_from_duration(retention);
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM forwarded_transactions
+            WHERE
+                rejection_reason IS NOT NULL
+                AND updated_at < NOW() - $1::INTERVAL
+            "#,
+            retention
+        )
+        .instrument("remove_rejected_forwarded_txs")
+        .with_arg("retention", &retention)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected() as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConnectionPool, Core, CoreDal};
+
+    #[tokio::test]
+    async fn forwarded_txs_lifecycle() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.forwarded_txs_dal();
+        let address = Address::repeat_byte(1);
+
+        for nonce in [0, 1, 3] {
+            let hash = H256::from_low_u64_be(nonce.into());
+            let inserted = dal
+                .insert_tx(hash, address, Nonce(nonce), &[nonce as u8; 4])
+                .await
+                .unwrap();
+            assert!(inserted);
+        }
+        let inserted = dal
+            .insert_tx(H256::zero(), address, Nonce(0), &[0; 4])
+            .await
+            .unwrap();
+        assert!(!inserted);
+
+        let txs = dal.get_txs_to_forward(10).await.unwrap();
+        assert_eq!(txs.len(), 3);
+        assert_eq!(txs[0].status, ForwardedTxStatus::Queued { attempts: 0 });
+        assert_eq!(txs[1].raw_tx, [1; 4]);
+
+        dal.mark_tx_as_forwarded(H256::zero()).await.unwrap();
+        dal.record_failed_attempt(H256::from_low_u64_be(1), "oops", Duration::from_secs(3_600))
+            .await
+            .unwrap();
+        dal.mark_tx_as_rejected(H256::from_low_u64_be(3), "invalid nonce")
+            .await
+            .unwrap();
+        let txs = dal.get_txs_to_forward(10).await.unwrap();
+        assert!(txs.is_empty(), "{txs:?}");
+
+        let tx = dal.get_tx(H256::zero()).await.unwrap().unwrap();
+        assert_eq!(tx.status, ForwardedTxStatus::Forwarded);
+        let tx = dal.get_tx(H256::from_low_u64_be(1)).await.unwrap().unwrap();
+        assert_eq!(tx.status, ForwardedTxStatus::Queued { attempts: 1 });
+        let tx = dal.get_tx(H256::from_low_u64_be(3)).await.unwrap().unwrap();
+        assert!(matches!(tx.status, ForwardedTxStatus::Rejected { .. }));
+
+        let nonces = dal.get_pending_nonces(address, Nonce(0)).await.unwrap();
+        assert_eq!(nonces, [Nonce(0), Nonce(1)]);
+        assert_eq!(dal.get_initiator_addresses().await.unwrap(), [address]);
+
+        let stored_nonces = HashMap::from([(address, Nonce(1))]);
+        let removed = dal.remove_stale_txs(&stored_nonces).await.unwrap();
+        assert_eq!(removed, 1);
+        assert!(dal.get_tx(H256::zero()).await.unwrap().is_none());
+
+        let removed = dal.remove_rejected_txs(Duration::ZERO).await.unwrap();
+        assert_eq!(removed, 1);
+        assert!(dal
+            .get_tx(H256::from_low_u64_be(3))
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
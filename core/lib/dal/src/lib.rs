@@ -15,8 +15,9 @@ use crate::{
     base_token_dal::BaseTokenDal, blocks_dal::BlocksDal, blocks_web3_dal::BlocksWeb3Dal,
     consensus_dal::ConsensusDal, contract_verification_dal::ContractVerificationDal,
     custom_genesis_export_dal::CustomGenesisExportDal, data_availability_dal::DataAvailabilityDal,
-    eth_sender_dal::EthSenderDal, eth_watcher_dal::EthWatcherDal, events_dal::EventsDal,
-    events_web3_dal::EventsWeb3Dal, factory_deps_dal::FactoryDepsDal,
+    erc20_transfers_dal::Erc20TransfersDal, eth_sender_dal::EthSenderDal,
+    eth_watcher_dal::EthWatcherDal, events_dal::EventsDal, events_web3_dal::EventsWeb3Dal,
+    factory_deps_dal::FactoryDepsDal, forwarded_txs_dal::ForwardedTxsDal,
     proof_generation_dal::ProofGenerationDal, protocol_versions_dal::ProtocolVersionsDal,
     protocol_versions_web3_dal::ProtocolVersionsWeb3Dal, pruning_dal::PruningDal,
     snapshot_recovery_dal::SnapshotRecoveryDal, snapshots_creator_dal::SnapshotsCreatorDal,
@@ -35,11 +36,13 @@ pub mod consensus_dal;
 pub mod contract_verification_dal;
 pub mod custom_genesis_export_dal;
 mod data_availability_dal;
+pub mod erc20_transfers_dal;
 pub mod eth_sender_dal;
 pub mod eth_watcher_dal;
 pub mod events_dal;
 pub mod events_web3_dal;
 pub mod factory_deps_dal;
+pub mod forwarded_txs_dal;
 pub mod helpers;
 pub mod metrics;
 mod models;
@@ -135,6 +138,10 @@ where
     fn eth_watcher_dal(&mut self) -> EthWatcherDal<'_, 'a>;
 
     fn custom_genesis_export_dal(&mut self) -> CustomGenesisExportDal<'_, 'a>;
+
+    fn forwarded_txs_dal(&mut self) -> ForwardedTxsDal<'_, 'a>;
+
+    fn erc20_transfers_dal(&mut self) -> Erc20TransfersDal<'_, 'a>;
 }
 
 #[derive(Clone, Debug)]
@@ -265,4 +272,12 @@ impl<'a> CoreDal<'a> for Connection<'a, Core> {
     fn custom_genesis_export_dal(&mut self) -> CustomGenesisExportDal<'_, 'a> {
         CustomGenesisExportDal { storage: self }
     }
+
+    fn forwarded_txs_dal(&mut self) -> ForwardedTxsDal<'_, 'a> {
+        ForwardedTxsDal { storage: self }
+    }
+
+    fn erc20_transfers_dal(&mut self) -> Erc20TransfersDal<'_, 'a> {
+        Erc20TransfersDal { storage: self }
+    }
 }
@@ -1,7 +1,7 @@
 use std::ops;
 
 use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
-use zksync_types::{L1BatchNumber, L2BlockNumber, H256};
+use zksync_types::{Address, L1BatchNumber, L2BlockNumber, H256};
 
 use crate::Core;
 
@@ -40,6 +40,46 @@ impl PruningInfo {
     }
 }
 
+/// Rules specifying data in hard-pruned L1 batches that should be retained in Postgres.
+///
+/// An event is retained if it matches any of the rules. Headers of L2 blocks containing retained events
+/// are retained as well, so that the events can still be returned by `eth_getLogs`. Only events are retained;
+/// transactions, receipts and call traces in pruned L2 blocks are pruned regardless of the rules, since the API
+/// treats these blocks as pruned.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RetentionRules {
+    /// Events emitted by these contracts are retained.
+    pub contract_addresses: Vec<Address>,
+    /// Events with the first topic (i.e., the event signature) from this list are retained.
+    pub event_topics: Vec<H256>,
+    /// Events emitted by transactions initiated by these accounts are retained.
+    pub tx_initiators: Vec<Address>,
+}
+
+impl RetentionRules {
+    /// Checks whether these rules don't retain any data.
+    pub fn is_empty(&self) -> bool {
+        self.contract_addresses.is_empty()
+            && self.event_topics.is_empty()
+            && self.tx_initiators.is_empty()
+    }
+
+    fn contract_addresses(&self) -> Vec<&[u8]> {
+        self.contract_addresses
+            .iter()
+            .map(Address::as_bytes)
+            .collect()
+    }
+
+    fn event_topics(&self) -> Vec<&[u8]> {
+        self.event_topics.iter().map(H256::as_bytes).collect()
+    }
+
+    fn tx_initiators(&self) -> Vec<&[u8]> {
+        self.tx_initiators.iter().map(Address::as_bytes).collect()
+    }
+}
+
 /// Statistics about a single hard pruning iteration.
 #[derive(Debug, Default)]
 pub struct HardPruningStats {
@@ -49,6 +89,8 @@ pub struct HardPruningStats {
     pub deleted_events: u64,
     pub deleted_call_traces: u64,
     pub deleted_l2_to_l1_logs: u64,
+    /// Number of L2 block headers retained because of [`RetentionRules`].
+    pub retained_l2_blocks: u64,
 }
 
 #[derive(Debug)]
@@ -193,6 +235,22 @@ impl PruningDal<'_, '_> {
         &mut self,
         last_l1_batch_to_prune: L1BatchNumber,
         last_l2_block_to_prune: L2BlockNumber,
+    ) -> DalResult<HardPruningStats> {
+        self.hard_prune_batches_range_with_retention(
+            last_l1_batch_to_prune,
+            last_l2_block_to_prune,
+            &RetentionRules::default(),
+        )
+        .await
+    }
+
+    /// Same as [`Self::hard_prune_batches_range()`], but retains data matching the provided `retention_rules`.
+    /// Does not insert pruning logs; the caller is responsible to do this!
+    pub async fn hard_prune_batches_range_with_retention(
+        &mut self,
+        last_l1_batch_to_prune: L1BatchNumber,
+        last_l2_block_to_prune: L2BlockNumber,
+        retention_rules: &RetentionRules,
     ) -> DalResult<HardPruningStats> {
         let row = sqlx::query!(
             r#"
@@ -217,8 +275,20 @@ impl PruningDal<'_, '_> {
 
         let first_l2_block_to_prune = L2BlockNumber(first_l2_block_to_prune as u32);
 
+        let retained_l2_blocks = if retention_rules.is_empty() {
+            0
+        } else {
+            self.retain_l2_blocks(
+                first_l2_block_to_prune..=last_l2_block_to_prune,
+                retention_rules,
+            )
+            .await?
+        };
         let deleted_events = self
-            .delete_events(first_l2_block_to_prune..=last_l2_block_to_prune)
+            .delete_events(
+                first_l2_block_to_prune..=last_l2_block_to_prune,
+                retention_rules,
+            )
             .await?;
         let deleted_l2_to_l1_logs = self
             .delete_l2_to_l1_logs(first_l2_block_to_prune..=last_l2_block_to_prune)
@@ -242,25 +312,101 @@ impl PruningDal<'_, '_> {
             deleted_l2_to_l1_logs,
             deleted_call_traces,
             deleted_storage_logs,
+            retained_l2_blocks,
         };
         Ok(stats)
     }
 
+    /// Copies headers of L2 blocks containing retained events so that these events can be returned
+    /// by the API after the L2 blocks are pruned.
+    async fn retain_l2_blocks(
+        &mut self,
+        l2_blocks_to_prune: ops::RangeInclusive<L2BlockNumber>,
+        retention_rules: &RetentionRules,
+    ) -> DalResult<u64> {
+        let contract_addresses = retention_rules.contract_addresses();
+        let event_topics = retention_rules.event_topics();
+        let tx_initiators = retention_rules.tx_initiators();
+        let execution_result = sqlx::query!(
+            r#"
+            INSERT INTO
+            pruning_retained_miniblocks (
+                number,
+                hash,
+                l1_batch_number,
+                timestamp,
+                created_at,
+                updated_at
+            )
+            SELECT
+                number,
+                hash,
+                l1_batch_number,
+                timestamp,
+                NOW(),
+                NOW()
+            FROM
+                miniblocks
+            WHERE
+                number BETWEEN $1 AND $2
+                AND EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        events
+                    WHERE
+                        events.miniblock_number = miniblocks.number
+                        AND (
+                            events.address = ANY($3)
+                            OR events.topic1 = ANY($4)
+                            OR events.tx_initiator_address = ANY($5)
+                        )
+                )
+            ON CONFLICT (number) DO NOTHING
+            "#,
+            i64::from(l2_blocks_to_prune.start().0),
+            i64::from(l2_blocks_to_prune.end().0),
+            &contract_addresses as &[&[u8]],
+            &event_topics as &[&[u8]],
+            &tx_initiators as &[&[u8]]
+        )
+        .instrument("hard_prune_batches_range#retain_l2_blocks")
+        .with_arg("l2_blocks_to_prune", &l2_blocks_to_prune)
+        .with_arg("retention_rules", retention_rules)
+        .report_latency()
+        .execute(self.storage)
+        .await?;
+        Ok(execution_result.rows_affected())
+    }
+
     async fn delete_events(
         &mut self,
         l2_blocks_to_prune: ops::RangeInclusive<L2BlockNumber>,
+        retention_rules: &RetentionRules,
     ) -> DalResult<u64> {
+        let contract_addresses = retention_rules.contract_addresses();
+        let event_topics = retention_rules.event_topics();
+        let tx_initiators = retention_rules.tx_initiators();
         let execution_result = sqlx::query!(
             r#"
             DELETE FROM events
             WHERE
                 miniblock_number BETWEEN $1 AND $2
+                AND NOT (
+                    address = ANY($3)
+                    OR topic1 = ANY($4)
+                    OR tx_initiator_address = ANY($5)
+                )
             "#,
             i64::from(l2_blocks_to_prune.start().0),
-            i64::from(l2_blocks_to_prune.end().0)
+            i64::from(l2_blocks_to_prune.end().0),
+            &contract_addresses as &[&[u8]],
+            &event_topics as &[&[u8]],
+            &tx_initiators as &[&[u8]]
         )
         .instrument("hard_prune_batches_range#delete_events")
         .with_arg("l2_blocks_to_prune", &l2_blocks_to_prune)
+        .with_arg("retention_rules", retention_rules)
         .report_latency()
         .execute(self.storage)
         .await?;
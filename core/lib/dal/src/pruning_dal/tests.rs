@@ -2,8 +2,8 @@ use std::ops;
 
 use zksync_db_connection::connection::Connection;
 use zksync_types::{
-    tx::IncludedTxLocation, AccountTreeId, Address, L1BatchNumber, L2BlockNumber, L2ChainId,
-    ProtocolVersion, ProtocolVersionId, StorageKey, StorageLog, H256,
+    api::GetLogsFilter, tx::IncludedTxLocation, AccountTreeId, Address, L1BatchNumber,
+    L2BlockNumber, L2ChainId, ProtocolVersion, ProtocolVersionId, StorageKey, StorageLog, H256,
 };
 use zksync_vm_interface::{tracer::ValidationTraces, TransactionExecutionMetrics};
 
@@ -436,6 +436,93 @@ async fn l1_batches_can_be_hard_pruned() {
     assert_l1_batches_not_exist(&mut transaction, L1BatchNumber(1)..=L1BatchNumber(9)).await;
 }
 
+#[tokio::test]
+async fn events_are_retained_according_to_rules() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    insert_realistic_l1_batches(&mut conn, 10).await;
+
+    // `mock_vm_event(index)` has `Address::repeat_byte(index)` address and `H256::repeat_byte(0)` first topic.
+    let retention_rules = RetentionRules {
+        contract_addresses: vec![Address::repeat_byte(1), Address::repeat_byte(3)],
+        ..RetentionRules::default()
+    };
+    let stats = conn
+        .pruning_dal()
+        .hard_prune_batches_range_with_retention(
+            L1BatchNumber(4),
+            L2BlockNumber(9),
+            &retention_rules,
+        )
+        .await
+        .unwrap();
+    assert_eq!(stats.deleted_l1_batches, 5);
+    assert_eq!(stats.deleted_l2_blocks, 10);
+    assert_eq!(stats.deleted_events, 30);
+    assert_eq!(stats.retained_l2_blocks, 10);
+    assert_l1_batches_not_exist(&mut conn, L1BatchNumber(0)..=L1BatchNumber(4)).await;
+
+    let filter = GetLogsFilter {
+        from_block: L2BlockNumber(0),
+        to_block: L2BlockNumber(19),
+        addresses: vec![Address::repeat_byte(1)],
+        topics: vec![],
+    };
+    let logs = conn.events_web3_dal().get_logs(filter, 100).await.unwrap();
+    assert_eq!(logs.len(), 20);
+    for log in &logs {
+        assert_eq!(log.address, Address::repeat_byte(1));
+        let block_number = log.block_number.unwrap().as_u32();
+        let expected_block_header = create_l2_block_header(block_number);
+        assert_eq!(log.block_hash, Some(expected_block_header.hash));
+        assert_eq!(log.l1_batch_number, Some((block_number / 2).into()));
+        assert_eq!(
+            log.block_timestamp,
+            Some(expected_block_header.timestamp.into())
+        );
+    }
+
+    let filter = GetLogsFilter {
+        from_block: L2BlockNumber(0),
+        to_block: L2BlockNumber(19),
+        addresses: vec![Address::repeat_byte(2)],
+        topics: vec![],
+    };
+    let logs = conn.events_web3_dal().get_logs(filter, 100).await.unwrap();
+    let log_blocks: Vec<_> = logs
+        .iter()
+        .map(|log| log.block_number.unwrap().as_u32())
+        .collect();
+    assert_eq!(log_blocks, (10..20).collect::<Vec<_>>());
+
+    // Retention by the event topic.
+    let retention_rules = RetentionRules {
+        event_topics: vec![H256::repeat_byte(0)],
+        ..RetentionRules::default()
+    };
+    let stats = conn
+        .pruning_dal()
+        .hard_prune_batches_range_with_retention(
+            L1BatchNumber(9),
+            L2BlockNumber(19),
+            &retention_rules,
+        )
+        .await
+        .unwrap();
+    assert_eq!(stats.deleted_events, 0);
+    assert_eq!(stats.retained_l2_blocks, 10);
+
+    let filter = GetLogsFilter {
+        from_block: L2BlockNumber(0),
+        to_block: L2BlockNumber(19),
+        addresses: vec![Address::repeat_byte(2)],
+        topics: vec![],
+    };
+    let logs = conn.events_web3_dal().get_logs(filter, 100).await.unwrap();
+    assert_eq!(logs.len(), 10);
+    assert!(logs.iter().all(|log| log.block_hash.is_some()));
+}
+
 #[tokio::test]
 async fn transactions_are_handled_correctly_after_pruning() {
     let pool = ConnectionPool::<Core>::test_pool().await;
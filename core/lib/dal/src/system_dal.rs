@@ -56,6 +56,29 @@ impl SystemDal<'_, '_> {
         })
     }
 
+    /// Returns the current position in the write-ahead log (WAL) of the database. For replicas, this is
+    /// the last replayed position; for the master DB, the current write position. Positions are comparable
+    /// across the master and its replicas, which allows to check whether a replica has caught up with
+    /// a certain write on the master.
+    pub async fn get_wal_position(&mut self) -> DalResult<u64> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                (
+                    CASE
+                        WHEN PG_IS_IN_RECOVERY() THEN PG_LAST_WAL_REPLAY_LSN()
+                        ELSE PG_CURRENT_WAL_LSN()
+                    END - '0/0'::PG_LSN
+                )::BIGINT AS position
+            "#
+        )
+        .instrument("get_wal_position")
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(row.position.unwrap_or(0) as u64)
+    }
+
     pub(crate) async fn get_table_sizes(&mut self) -> DalResult<HashMap<String, TableSize>> {
         let rows = sqlx::query!(
             r#"
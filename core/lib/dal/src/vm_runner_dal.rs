@@ -286,4 +286,157 @@ impl VmRunnerDal<'_, '_> {
         }
         Ok(())
     }
+
+    /// Returns the latest L1 batch processed by the custom VM runner `workload`.
+    pub async fn get_workload_latest_processed_batch(
+        &mut self,
+        workload: &str,
+    ) -> DalResult<Option<L1BatchNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MAX(l1_batch_number) AS "last_processed_l1_batch"
+            FROM
+                vm_runner_workloads
+            WHERE
+                workload = $1
+                AND time_taken IS NOT NULL
+            "#,
+            workload
+        )
+        .instrument("get_workload_latest_processed_batch")
+        .with_arg("workload", &workload)
+        .report_latency()
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.last_processed_l1_batch.map(|n| L1BatchNumber(n as u32)))
+    }
+
+    pub async fn get_workload_last_ready_batch(
+        &mut self,
+        workload: &str,
+        default_batch: L1BatchNumber,
+        window_size: u32,
+    ) -> DalResult<L1BatchNumber> {
+        let row = sqlx::query!(
+            r#"
+            WITH
+            available_batches AS (
+                SELECT
+                    MAX(number) AS "last_batch"
+                FROM
+                    l1_batches
+                WHERE
+                    is_sealed
+            ),
+            
+            processed_batches AS (
+                SELECT
+                    COALESCE(MAX(l1_batch_number), $2) + $3 AS "last_ready_batch"
+                FROM
+                    vm_runner_workloads
+                WHERE
+                    workload = $1
+                    AND time_taken IS NOT NULL
+            )
+            
+            SELECT
+                LEAST(last_batch, last_ready_batch) AS "last_ready_batch!"
+            FROM
+                available_batches
+            FULL JOIN processed_batches ON TRUE
+            "#,
+            workload,
+            default_batch.0 as i32,
+            window_size as i32
+        )
+        .instrument("get_workload_last_ready_batch")
+        .with_arg("workload", &workload)
+        .report_latency()
+        .fetch_one(self.storage)
+        .await?;
+        Ok(L1BatchNumber(row.last_ready_batch as u32))
+    }
+
+    pub async fn mark_workload_batch_as_processing(
+        &mut self,
+        workload: &str,
+        l1_batch_number: L1BatchNumber,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+            vm_runner_workloads (
+                workload, l1_batch_number, created_at, updated_at, processing_started_at
+            )
+            VALUES
+            ($1, $2, NOW(), NOW(), NOW())
+            ON CONFLICT (workload, l1_batch_number) DO
+            UPDATE
+            SET
+            updated_at = NOW(),
+            processing_started_at = NOW()
+            "#,
+            workload,
+            i64::from(l1_batch_number.0),
+        )
+        .instrument("mark_workload_batch_as_processing")
+        .with_arg("workload", &workload)
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .report_latency()
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn mark_workload_batch_as_completed(
+        &mut self,
+        workload: &str,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        let update_result = sqlx::query!(
+            r#"
+            UPDATE vm_runner_workloads
+            SET
+                time_taken = NOW() - processing_started_at
+            WHERE
+                workload = $1
+                AND l1_batch_number = $2
+            "#,
+            workload,
+            i64::from(l1_batch_number.0),
+        )
+        .instrument("mark_workload_batch_as_completed")
+        .with_arg("workload", &workload)
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .report_latency()
+        .execute(self.storage)
+        .await?;
+        if update_result.rows_affected() == 0 {
+            anyhow::bail!(
+                "Trying to mark an L1 batch as completed while it is not being processed"
+            );
+        }
+        Ok(())
+    }
+
+    /// Removes progress of all custom VM runner workloads for L1 batches after the specified one.
+    pub async fn delete_workloads_data(
+        &mut self,
+        last_batch_to_keep: L1BatchNumber,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM vm_runner_workloads
+            WHERE
+                l1_batch_number > $1
+            "#,
+            i64::from(last_batch_to_keep.0)
+        )
+        .instrument("delete_workloads_data")
+        .with_arg("last_batch_to_keep", &last_batch_to_keep)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
 }
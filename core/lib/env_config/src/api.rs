@@ -70,13 +70,17 @@ mod tests {
                 gas_price_scale_factor: 1.2,
                 estimate_gas_acceptable_overestimation: 1000,
                 estimate_gas_optimize_search: false,
+                estimate_gas_cache_size: Some(500),
                 max_tx_size: 1000000,
                 vm_execution_cache_misses_limit: None,
+                vm_execution_timeout_ms: Some(5_000),
+                vm_execution_cycles_limit: Some(100_000_000),
                 vm_concurrency_limit: Some(512),
                 factory_deps_cache_size_mb: Some(128),
                 initial_writes_cache_size_mb: Some(32),
                 latest_values_cache_size_mb: Some(256),
                 latest_values_max_block_lag: Some(NonZeroU32::new(50).unwrap()),
+                latest_values_warm_storage_path: Some("./db/api_warm_values".into()),
                 fee_history_limit: Some(100),
                 max_batch_request_size: Some(200),
                 max_response_body_size_mb: Some(10),
@@ -97,6 +101,7 @@ mod tests {
                 ],
                 api_namespaces: Some(vec!["debug".to_string()]),
                 extended_api_tracing: true,
+                read_your_writes_max_wait_ms: Some(500),
             },
             prometheus: PrometheusConfig {
                 listener_port: 3312,
@@ -129,15 +134,20 @@ mod tests {
             API_WEB3_JSON_RPC_GAS_PRICE_SCALE_FACTOR=1.2
             API_WEB3_JSON_RPC_API_NAMESPACES=debug
             API_WEB3_JSON_RPC_EXTENDED_API_TRACING=true
+            API_WEB3_JSON_RPC_READ_YOUR_WRITES_MAX_WAIT_MS=500
             API_WEB3_JSON_RPC_WHITELISTED_TOKENS_FOR_AA="0x0000000000000000000000000000000000000001,0x0000000000000000000000000000000000000002"
             API_WEB3_JSON_RPC_ESTIMATE_GAS_SCALE_FACTOR=1.0
             API_WEB3_JSON_RPC_ESTIMATE_GAS_ACCEPTABLE_OVERESTIMATION=1000
+            API_WEB3_JSON_RPC_ESTIMATE_GAS_CACHE_SIZE=500
             API_WEB3_JSON_RPC_MAX_TX_SIZE=1000000
+            API_WEB3_JSON_RPC_VM_EXECUTION_TIMEOUT_MS=5000
+            API_WEB3_JSON_RPC_VM_EXECUTION_CYCLES_LIMIT=100000000
             API_WEB3_JSON_RPC_VM_CONCURRENCY_LIMIT=512
             API_WEB3_JSON_RPC_FACTORY_DEPS_CACHE_SIZE_MB=128
             API_WEB3_JSON_RPC_INITIAL_WRITES_CACHE_SIZE_MB=32
             API_WEB3_JSON_RPC_LATEST_VALUES_CACHE_SIZE_MB=256
             API_WEB3_JSON_RPC_LATEST_VALUES_MAX_BLOCK_LAG=50
+            API_WEB3_JSON_RPC_LATEST_VALUES_WARM_STORAGE_PATH="./db/api_warm_values"
             API_WEB3_JSON_RPC_FEE_HISTORY_LIMIT=100
             API_WEB3_JSON_RPC_MAX_BATCH_REQUEST_SIZE=200
             API_WEB3_JSON_RPC_WEBSOCKET_REQUESTS_PER_MINUTE_LIMIT=10
@@ -41,6 +41,7 @@ mod tests {
             VM_RUNNER_BWIP_DB_PATH=/db/bwip
             VM_RUNNER_BWIP_WINDOW_SIZE=50
             VM_RUNNER_BWIP_FIRST_PROCESSED_BATCH=123
+            VM_RUNNER_BWIP_USE_SNAPSHOTS=true
         "#;
         lock.set_env(config);
 
@@ -48,6 +49,11 @@ mod tests {
         assert_eq!(config.db_path, "/db/bwip");
         assert_eq!(config.window_size, 50);
         assert_eq!(config.first_processed_batch, L1BatchNumber(123));
+        assert!(config.use_snapshots);
+
+        lock.remove_env(&["VM_RUNNER_BWIP_USE_SNAPSHOTS"]);
+        let config = BasicWitnessInputProducerConfig::from_env().unwrap();
+        assert!(!config.use_snapshots);
     }
 
     #[test]
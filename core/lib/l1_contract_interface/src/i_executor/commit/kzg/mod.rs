@@ -1 +1,55 @@
 pub use kzg::{pubdata_to_blob_commitments, KzgInfo, ZK_SYNC_BYTES_PER_BLOB};
+use zksync_types::{
+    blob::num_blobs_required, commitment::BlobHash, web3::keccak256, ProtocolVersionId, H256,
+};
+
+/// Computes linear hashes of the blobs containing the provided pubdata. Blobs not used by the pubdata
+/// have zero hashes.
+pub fn pubdata_to_blob_linear_hashes(
+    blobs_required: usize,
+    mut pubdata_input: Vec<u8>,
+) -> Vec<H256> {
+    // Now, we need to calculate the linear hashes of the blobs.
+    // Firstly, let's pad the pubdata to the size of the blob.
+    if pubdata_input.len() % ZK_SYNC_BYTES_PER_BLOB != 0 {
+        pubdata_input.resize(
+            pubdata_input.len()
+                + (ZK_SYNC_BYTES_PER_BLOB - pubdata_input.len() % ZK_SYNC_BYTES_PER_BLOB),
+            0,
+        );
+    }
+
+    let mut result = vec![H256::zero(); blobs_required];
+
+    pubdata_input
+        .chunks(ZK_SYNC_BYTES_PER_BLOB)
+        .enumerate()
+        .for_each(|(i, chunk)| {
+            result[i] = H256(keccak256(chunk));
+        });
+
+    result
+}
+
+/// Computes blob hashes for the L1 batch pubdata in the form used by the batch commitment.
+/// For protocol versions before 1.4.2, the hashes are zero.
+pub fn pubdata_to_blob_hashes(
+    protocol_version: ProtocolVersionId,
+    pubdata_input: Vec<u8>,
+) -> Vec<BlobHash> {
+    let blobs_required = num_blobs_required(&protocol_version);
+    if !protocol_version.is_post_1_4_2() {
+        return vec![BlobHash::default(); blobs_required];
+    }
+
+    let commitments = pubdata_to_blob_commitments(blobs_required, &pubdata_input);
+    let linear_hashes = pubdata_to_blob_linear_hashes(blobs_required, pubdata_input);
+    commitments
+        .into_iter()
+        .zip(linear_hashes)
+        .map(|(commitment, linear_hash)| BlobHash {
+            commitment,
+            linear_hash,
+        })
+        .collect()
+}
@@ -7,6 +7,7 @@ use zksync_types::{L1BatchNumber, StorageKey};
 
 use crate::{
     consistency::ConsistencyError,
+    migration::{migrate_to_lightweight, LightweightMigrationStats},
     storage::{PatchSet, Patched, RocksDBWrapper},
     types::{
         Key, NodeKey, RawNode, Root, TreeEntry, TreeEntryWithProof, TreeInstruction, TreeLogEntry,
@@ -140,6 +141,26 @@ impl ZkSyncTree {
         MerkleTreePruner::new(db)
     }
 
+    /// Migrates this tree to the lightweight mode in place, removing nodes for all tree versions except the latest one.
+    /// See [`migrate_to_lightweight()`](crate::migration::migrate_to_lightweight) for details.
+    ///
+    /// # Errors
+    ///
+    /// Proxies database I/O errors and consistency check errors.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tree pruner was obtained for this tree; it's logically unsound to run the migration
+    /// concurrently with the pruner.
+    pub fn migrate_to_lightweight(&mut self) -> anyhow::Result<LightweightMigrationStats> {
+        assert!(
+            !self.pruning_enabled,
+            "cannot migrate tree to lightweight mode after obtaining its pruner"
+        );
+        self.mode = TreeMode::Lightweight;
+        migrate_to_lightweight(self.tree.db.inner_mut())
+    }
+
     /// Returns a readonly handle to the tree. The handle **does not** see uncommitted changes to the tree,
     /// only ones flushed to RocksDB.
     pub fn reader(&self) -> ZkSyncTreeReader {
@@ -69,6 +69,7 @@ mod errors;
 mod getters;
 mod hasher;
 mod metrics;
+pub mod migration;
 mod pruning;
 pub mod recovery;
 pub mod repair;
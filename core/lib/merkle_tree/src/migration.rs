@@ -0,0 +1,213 @@
+//! Migration of the Merkle tree between the full and lightweight [`ZkSyncTree`](crate::domain::ZkSyncTree)
+//! processing modes.
+//!
+//! # Overview
+//!
+//! Both processing modes use the same node layout, but they have different data requirements:
+//!
+//! - A full tree produces Merkle paths for all storage logs in each processed L1 batch (including reads),
+//!   and is expected to serve proofs for past L1 batches. Hence, it retains nodes for all tree versions
+//!   that are not explicitly pruned.
+//! - A lightweight tree only computes root hashes and only needs nodes for the latest tree version.
+//!
+//! Accordingly, migration is asymmetric:
+//!
+//! - Migrating from the full to the lightweight mode happens in place using [`migrate_to_lightweight()`].
+//!   It removes all stale nodes (incl. leaves) not reachable from the latest tree version.
+//! - Migrating from the lightweight to the full mode requires rebuilding the tree from the latest storage state
+//!   (e.g., from Postgres storage logs) in a separate database using [`MerkleTreeRecovery`](crate::recovery::MerkleTreeRecovery).
+//!   Since the old tree is not touched during rebuild, it can continue serving requests. Once the rebuild is finished,
+//!   the rebuilt tree should be checked using [`verify_rebuilt_tree()`] before replacing the old tree.
+
+use std::time::Instant;
+
+use anyhow::Context as _;
+
+use crate::{types::ValueHash, Database, MerkleTree, MerkleTreePruner, PruneDatabase};
+
+/// Stats returned by [`migrate_to_lightweight()`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LightweightMigrationStats {
+    /// Tree version retained after the migration (i.e., the latest tree version), or `None` if the tree is empty.
+    pub retained_version: Option<u64>,
+    /// Number of nodes removed from the tree.
+    pub removed_node_count: usize,
+}
+
+/// Migrates a tree from the full to the lightweight mode in place by removing all nodes
+/// that are not reachable from the latest tree version. After the migration, the tree is checked for consistency.
+///
+/// The migration must not run concurrently with a [`MerkleTreePruner`] for the same tree; it can run concurrently
+/// with tree readers and with the tree being extended, though.
+///
+/// # Errors
+///
+/// Propagates database I/O errors and returns an error if the tree is inconsistent after the migration.
+pub fn migrate_to_lightweight<DB: PruneDatabase>(
+    db: &mut DB,
+) -> anyhow::Result<LightweightMigrationStats> {
+    let started_at = Instant::now();
+    let (mut pruner, _handle) = MerkleTreePruner::new(&mut *db);
+    let Some(retained_version) = pruner.last_prunable_version() else {
+        tracing::info!("Merkle tree is empty; nothing to migrate");
+        return Ok(LightweightMigrationStats::default());
+    };
+    tracing::info!(
+        "Migrating Merkle tree to lightweight mode; retaining version {retained_version}"
+    );
+
+    let mut removed_node_count = 0;
+    while let Some(stats) = pruner
+        .prune_up_to(retained_version)
+        .context("failed pruning stale tree nodes")?
+    {
+        stats.report();
+        removed_node_count += stats.pruned_key_count;
+    }
+    drop(pruner);
+
+    MerkleTree::new(&mut *db)?
+        .verify_consistency(retained_version, true)
+        .with_context(|| {
+            format!("Merkle tree is inconsistent at version {retained_version} after migration")
+        })?;
+    let stats = LightweightMigrationStats {
+        retained_version: Some(retained_version),
+        removed_node_count,
+    };
+    tracing::info!(
+        "Migrated Merkle tree to lightweight mode in {:?}: {stats:?}",
+        started_at.elapsed()
+    );
+    Ok(stats)
+}
+
+/// Verifies a tree rebuilt from the storage state (e.g., when migrating it from the lightweight to the full mode).
+///
+/// Checks that the latest version of the tree is `version`, that its root hash matches `expected_root_hash`,
+/// and that the tree is consistent at this version.
+///
+/// # Errors
+///
+/// Returns an error if any of the checks fails.
+pub fn verify_rebuilt_tree<DB: Database>(
+    db: DB,
+    version: u64,
+    expected_root_hash: ValueHash,
+) -> anyhow::Result<()> {
+    let started_at = Instant::now();
+    let tree = MerkleTree::new(db)?;
+    let latest_version = tree.latest_version();
+    anyhow::ensure!(
+        latest_version == Some(version),
+        "Unexpected latest version of the rebuilt tree: expected {version}, got {latest_version:?}"
+    );
+    let root_hash = tree.latest_root_hash();
+    anyhow::ensure!(
+        root_hash == expected_root_hash,
+        "Root hash of the rebuilt tree {root_hash:?} differs from the expected root hash {expected_root_hash:?}"
+    );
+    tree.verify_consistency(version, true)
+        .with_context(|| format!("rebuilt Merkle tree is inconsistent at version {version}"))?;
+    tracing::info!(
+        "Verified rebuilt Merkle tree at version {version} in {:?}",
+        started_at.elapsed()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        recovery::MerkleTreeRecovery, Key, PatchSet, RocksDBWrapper, TreeEntry, TreeInstruction,
+    };
+
+    fn test_entries(version: u64) -> Vec<TreeEntry> {
+        (0_u64..50)
+            .map(|i| TreeEntry::new(Key::from(i), i + 1, ValueHash::from_low_u64_be(version + i)))
+            .collect()
+    }
+
+    #[test]
+    fn migrating_empty_tree_to_lightweight() {
+        let mut db = PatchSet::default();
+        let stats = migrate_to_lightweight(&mut db).unwrap();
+        assert_eq!(stats, LightweightMigrationStats::default());
+    }
+
+    #[test]
+    fn migrating_tree_to_lightweight() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut db = RocksDBWrapper::new(temp_dir.path()).unwrap();
+        let mut tree = MerkleTree::new(&mut db).unwrap();
+        for version in 0..5 {
+            tree.extend(test_entries(version)).unwrap();
+        }
+        let root_hash = tree.latest_root_hash();
+        let expected_entries = tree.entries(4, &[Key::from(1), Key::from(10)]).unwrap();
+
+        let stats = migrate_to_lightweight(&mut db).unwrap();
+        assert_eq!(stats.retained_version, Some(4));
+        assert!(stats.removed_node_count > 0, "{stats:?}");
+        assert_eq!(db.min_stale_key_version(), None);
+
+        let mut tree = MerkleTree::new(&mut db).unwrap();
+        assert_eq!(tree.latest_root_hash(), root_hash);
+        assert!(tree.root_hash(4).is_some());
+        // Past versions should be inaccessible after migration.
+        assert!(tree.entries_with_proofs(3, &[Key::from(1)]).is_err());
+        let entries = tree.entries(4, &[Key::from(1), Key::from(10)]).unwrap();
+        assert_eq!(entries, expected_entries);
+
+        // Check that the tree can be extended after migration.
+        let output = tree
+            .extend_with_proofs(vec![TreeInstruction::Write(TreeEntry::new(
+                Key::from(100),
+                51,
+                ValueHash::repeat_byte(1),
+            ))])
+            .unwrap();
+        assert_eq!(output.leaf_count, 51);
+        tree.verify_consistency(5, true).unwrap();
+
+        // Nodes made stale by the new version should be removed by the repeated migration.
+        let stats = migrate_to_lightweight(&mut db).unwrap();
+        assert_eq!(stats.retained_version, Some(5));
+        assert!(stats.removed_node_count > 0, "{stats:?}");
+        let stats = migrate_to_lightweight(&mut db).unwrap();
+        assert_eq!(stats.retained_version, Some(5));
+        assert_eq!(stats.removed_node_count, 0);
+    }
+
+    #[test]
+    fn verifying_rebuilt_tree() {
+        let mut original_db = PatchSet::default();
+        let mut tree = MerkleTree::new(&mut original_db).unwrap();
+        for version in 0..3 {
+            tree.extend(test_entries(version)).unwrap();
+        }
+        let root_hash = tree.latest_root_hash();
+        let entries = tree
+            .entries(2, &(0_u64..50).map(Key::from).collect::<Vec<_>>())
+            .unwrap();
+
+        let mut recovery = MerkleTreeRecovery::new(PatchSet::default(), 2).unwrap();
+        let recovery_entries = (0_u64..50)
+            .zip(entries)
+            .map(|(i, entry)| TreeEntry::new(Key::from(i), entry.leaf_index, entry.value))
+            .collect();
+        recovery.extend_random(recovery_entries).unwrap();
+        let mut rebuilt_db = recovery.finalize().unwrap();
+
+        verify_rebuilt_tree(&mut rebuilt_db, 2, root_hash).unwrap();
+        let err = verify_rebuilt_tree(&mut rebuilt_db, 2, ValueHash::zero())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("differs from the expected root hash"), "{err}");
+        let err = verify_rebuilt_tree(&mut rebuilt_db, 3, root_hash)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Unexpected latest version"), "{err}");
+    }
+}
@@ -10,6 +10,7 @@ mod metrics;
 pub mod vm_1_4_1;
 pub mod vm_1_4_2;
 pub mod vm_boojum_integration;
+pub mod vm_fast;
 pub mod vm_latest;
 pub mod vm_refunds_enhancement;
 pub mod vm_virtual_blocks;
@@ -37,6 +38,13 @@ impl Drop for CallTracer {
     }
 }
 
+/// Creates a tracer with a new result cell. Useful for the fast VM; see [`Self::into_result()`].
+impl Default for CallTracer {
+    fn default() -> Self {
+        Self::new(Arc::default())
+    }
+}
+
 impl CallTracer {
     pub fn new(result: Arc<OnceCell<Vec<Call>>>) -> Self {
         Self {
@@ -47,6 +55,12 @@ impl CallTracer {
         }
     }
 
+    /// Returns call traces collected by this tracer. Should be used with the fast VM, which (unlike legacy VMs)
+    /// doesn't notify tracers about the end of execution, so the traces aren't stored in the shared result cell.
+    pub fn into_result(mut self) -> Vec<Call> {
+        self.extract_result()
+    }
+
     fn extract_result(&mut self) -> Vec<Call> {
         std::mem::take(&mut self.stack)
             .into_iter()
@@ -0,0 +1,165 @@
+use zk_evm_1_5_0::zkevm_opcode_defs::FatPointer as LegacyFatPointer;
+use zksync_system_constants::CONTRACT_DEPLOYER_ADDRESS;
+use zksync_types::U256;
+use zksync_vm2::{
+    interface::{
+        CallframeInterface, CallingMode, GlobalStateInterface, Opcode, OpcodeType, ReturnType,
+        ShouldStop, StateInterface, Tracer,
+    },
+    FatPointer,
+};
+
+use crate::{
+    glue::GlueInto,
+    interface::{Call, CallType, VmRevertReason},
+    tracers::CallTracer,
+};
+
+/// Reads `[start, start + length)` bytes from the pointed heap, ignoring the pointer offset. This is the same way
+/// the legacy VM tracer reads memory.
+fn read_pointed_bytes<S: StateInterface>(state: &S, pointer: &FatPointer) -> Vec<u8> {
+    (0..pointer.length)
+        .map(|i| state.read_heap_byte(pointer.memory_page, pointer.start + i))
+        .collect()
+}
+
+impl Tracer for CallTracer {
+    fn after_instruction<OP: OpcodeType, S: GlobalStateInterface>(
+        &mut self,
+        state: &mut S,
+    ) -> ShouldStop {
+        match OP::VALUE {
+            Opcode::NearCall => {
+                self.increase_near_call_count();
+            }
+            Opcode::FarCall(mode) => {
+                // We use parent gas for properly calculating gas used in the trace.
+                let current_gas = u64::from(state.current_frame().gas());
+                let parent_gas = if state.number_of_callframes() > 1 {
+                    u64::from(state.callframe(1).gas()) + current_gas
+                } else {
+                    current_gas
+                };
+
+                let mut current_call = Call {
+                    r#type: CallType::Call(mode.glue_into()),
+                    gas: 0,
+                    parent_gas,
+                    ..Default::default()
+                };
+
+                self.handle_far_call_op_code_fast(state, mode, &mut current_call);
+                self.push_call_and_update_stats(current_call, 0);
+            }
+            Opcode::Ret(return_type) => {
+                self.handle_ret_op_code_fast(state, return_type);
+            }
+            _ => {}
+        }
+        ShouldStop::Continue
+    }
+}
+
+impl CallTracer {
+    fn handle_far_call_op_code_fast<S: GlobalStateInterface>(
+        &mut self,
+        state: &mut S,
+        mode: CallingMode,
+        current_call: &mut Call,
+    ) {
+        // All calls from the actual users are mimic calls,
+        // so we need to check that the previous call was to the deployer.
+        // Actually it's a call of the constructor.
+        // And at this stage caller is user and callee is deployed contract.
+        let this_address = state.current_frame().address();
+        if matches!(mode, CallingMode::Mimic) {
+            let previous_caller = if state.number_of_callframes() > 1 {
+                state.callframe(1).address()
+            } else {
+                this_address
+            };
+            if previous_caller == CONTRACT_DEPLOYER_ADDRESS {
+                current_call.r#type = CallType::Create;
+            }
+        }
+
+        // The fast VM enters a frame with no gas if the far call has failed (e.g., because of an unknown bytecode).
+        let gas = state.current_frame().gas();
+        let calldata = if gas == 0 {
+            vec![]
+        } else {
+            let pointer = FatPointer::from(state.read_register(1).0);
+            read_pointed_bytes(state, &pointer)
+        };
+
+        current_call.input = calldata;
+        current_call.from = state.current_frame().caller();
+        current_call.to = this_address;
+        current_call.value = U256::from(state.current_frame().context_u128());
+        current_call.gas = gas.into();
+    }
+
+    fn save_output_fast<S: GlobalStateInterface>(
+        &mut self,
+        state: &mut S,
+        return_type: ReturnType,
+        current_call: &mut Call,
+    ) {
+        let (raw_pointer, is_pointer) = state.read_register(1);
+        // if `raw_pointer` is not a pointer then there is no output
+        let output = if is_pointer && !LegacyFatPointer::from_u256(raw_pointer).is_trivial() {
+            let pointer = FatPointer::from(raw_pointer);
+            Some(read_pointed_bytes(state, &pointer))
+        } else {
+            None
+        };
+
+        match return_type {
+            ReturnType::Normal => {
+                current_call.output = output.unwrap_or_default();
+            }
+            ReturnType::Revert => {
+                if let Some(output) = output {
+                    current_call.revert_reason =
+                        Some(VmRevertReason::from(output.as_slice()).to_string());
+                } else {
+                    current_call.revert_reason = Some("Unknown revert reason".to_string());
+                }
+            }
+            ReturnType::Panic => {
+                current_call.error = Some("Panic".to_string());
+            }
+        }
+    }
+
+    fn handle_ret_op_code_fast<S: GlobalStateInterface>(
+        &mut self,
+        state: &mut S,
+        return_type: ReturnType,
+    ) {
+        let Some(mut current_call) = self.stack.pop() else {
+            return;
+        };
+
+        if current_call.near_calls_after > 0 {
+            current_call.near_calls_after -= 1;
+            self.push_call_and_update_stats(current_call.farcall, current_call.near_calls_after);
+            return;
+        }
+
+        current_call.farcall.gas_used = current_call
+            .farcall
+            .parent_gas
+            .saturating_sub(state.current_frame().gas().into());
+
+        self.save_output_fast(state, return_type, &mut current_call.farcall);
+
+        // If there is a parent call, push the current call to it
+        // Otherwise, push the current call to the stack, because it's the top level call
+        if let Some(parent_call) = self.stack.last_mut() {
+            parent_call.farcall.calls.push(current_call.farcall);
+        } else {
+            self.push_call_and_update_stats(current_call.farcall, current_call.near_calls_after);
+        }
+    }
+}
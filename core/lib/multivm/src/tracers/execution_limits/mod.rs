@@ -0,0 +1,103 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use once_cell::sync::OnceCell;
+
+use crate::{glue::tracers::IntoOldVmTracer, interface::Halt};
+
+pub mod vm_1_4_1;
+pub mod vm_1_4_2;
+pub mod vm_boojum_integration;
+pub mod vm_fast;
+pub mod vm_latest;
+pub mod vm_refunds_enhancement;
+pub mod vm_virtual_blocks;
+
+/// Limits on VM execution enforced by [`ExecutionLimitsTracer`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExecutionLimits {
+    /// Maximum wall-clock duration of the execution.
+    pub timeout: Option<Duration>,
+    /// Maximum number of VM cycles. For the fast VM, each executed instruction is counted as a cycle.
+    pub cycles: Option<u64>,
+}
+
+impl ExecutionLimits {
+    /// Checks whether these limits don't restrict execution in any way.
+    pub fn is_unlimited(&self) -> bool {
+        self.timeout.is_none() && self.cycles.is_none()
+    }
+}
+
+/// Tracer stopping VM execution once it exceeds the configured [`ExecutionLimits`].
+///
+/// Legacy VMs are halted with [`Halt::ExecutionTimeout`] or [`Halt::ExecutionCyclesLimitReached`] directly,
+/// while the fast VM reports a generic tracer halt. Thus, the exceeded limit is additionally recorded in a shared cell,
+/// which should be used by the caller to determine the actual halt reason.
+///
+/// Limits are not enforced for VM versions preceding virtual blocks.
+#[derive(Debug, Clone)]
+pub struct ExecutionLimitsTracer {
+    /// If not set, cycles aren't counted at all, so that an unlimited tracer has negligible overhead.
+    is_limited: bool,
+    deadline: Option<Instant>,
+    cycles_limit: u64,
+    cycles: u64,
+    result: Arc<OnceCell<Halt>>,
+}
+
+/// Creates a tracer that doesn't limit execution.
+impl Default for ExecutionLimitsTracer {
+    fn default() -> Self {
+        Self::new(ExecutionLimits::default(), Arc::default())
+    }
+}
+
+impl ExecutionLimitsTracer {
+    /// Number of cycles between consecutive wall-clock time checks. Getting the current time on each cycle
+    /// would noticeably slow down the fast VM.
+    const TIME_CHECK_INTERVAL: u64 = 1_024;
+
+    /// Creates a tracer with the specified limits. The timeout is measured starting from the tracer creation.
+    pub fn new(limits: ExecutionLimits, result: Arc<OnceCell<Halt>>) -> Self {
+        Self {
+            is_limited: !limits.is_unlimited(),
+            deadline: limits
+                .timeout
+                .and_then(|timeout| Instant::now().checked_add(timeout)),
+            cycles_limit: limits.cycles.unwrap_or(u64::MAX),
+            cycles: 0,
+            result,
+        }
+    }
+
+    /// Accounts for a single VM cycle. Returns the halt reason if the execution should be stopped.
+    #[inline(always)]
+    fn on_cycle(&mut self) -> Option<Halt> {
+        if !self.is_limited {
+            return None;
+        }
+        self.cycles += 1;
+        let halt = if self.cycles > self.cycles_limit {
+            Halt::ExecutionCyclesLimitReached
+        } else if self.cycles % Self::TIME_CHECK_INTERVAL == 0
+            && self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            Halt::ExecutionTimeout
+        } else {
+            return None;
+        };
+        self.result.get_or_init(|| halt.clone());
+        Some(halt)
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.result.get().is_some()
+    }
+}
+
+impl IntoOldVmTracer for ExecutionLimitsTracer {}
@@ -0,0 +1,17 @@
+use zksync_vm2::interface::{GlobalStateInterface, OpcodeType, ShouldStop, Tracer};
+
+use crate::tracers::ExecutionLimitsTracer;
+
+impl Tracer for ExecutionLimitsTracer {
+    #[inline(always)]
+    fn after_instruction<OP: OpcodeType, S: GlobalStateInterface>(
+        &mut self,
+        _state: &mut S,
+    ) -> ShouldStop {
+        if self.on_cycle().is_some() {
+            ShouldStop::Stop
+        } else {
+            ShouldStop::Continue
+        }
+    }
+}
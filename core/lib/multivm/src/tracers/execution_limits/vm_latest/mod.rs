@@ -0,0 +1,23 @@
+use crate::{
+    interface::{
+        storage::WriteStorage,
+        tracer::{TracerExecutionStatus, TracerExecutionStopReason},
+    },
+    tracers::{dynamic::vm_1_5_0::DynTracer, ExecutionLimitsTracer},
+    vm_latest::{BootloaderState, HistoryMode, SimpleMemory, VmTracer, ZkSyncVmState},
+};
+
+impl<S, H: HistoryMode> DynTracer<S, SimpleMemory<H>> for ExecutionLimitsTracer {}
+
+impl<S: WriteStorage, H: HistoryMode> VmTracer<S, H> for ExecutionLimitsTracer {
+    fn finish_cycle(
+        &mut self,
+        _state: &mut ZkSyncVmState<S, H>,
+        _bootloader_state: &mut BootloaderState,
+    ) -> TracerExecutionStatus {
+        match self.on_cycle() {
+            Some(halt) => TracerExecutionStatus::Stop(TracerExecutionStopReason::Abort(halt)),
+            None => TracerExecutionStatus::Continue,
+        }
+    }
+}
@@ -1,5 +1,6 @@
 pub use self::{
     call_tracer::CallTracer,
+    execution_limits::{ExecutionLimits, ExecutionLimitsTracer},
     multivm_dispatcher::TracerDispatcher,
     prestate_tracer::PrestateTracer,
     storage_invocation::StorageInvocations,
@@ -8,6 +9,7 @@ pub use self::{
 
 mod call_tracer;
 pub mod dynamic;
+mod execution_limits;
 mod multivm_dispatcher;
 pub mod old;
 mod prestate_tracer;
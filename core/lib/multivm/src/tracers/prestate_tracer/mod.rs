@@ -10,6 +10,7 @@ use crate::interface::storage::{StoragePtr, WriteStorage};
 
 pub mod vm_1_4_1;
 pub mod vm_1_4_2;
+pub mod vm_fast;
 pub mod vm_latest;
 pub mod vm_refunds_enhancement;
 pub mod vm_virtual_blocks;
@@ -53,6 +54,14 @@ pub struct PrestateTracer {
     pub post: State,
     pub config: PrestateTracerConfig,
     pub result: Arc<OnceCell<(State, State)>>,
+    fast_vm_storage: vm_fast::FastVmStorage,
+}
+
+/// Creates a tracer with a new result cell in the non-diff mode. Useful for the fast VM; see [`Self::into_result()`].
+impl Default for PrestateTracer {
+    fn default() -> Self {
+        Self::new(false, Arc::default())
+    }
 }
 
 impl PrestateTracer {
@@ -62,6 +71,7 @@ impl PrestateTracer {
             post: Default::default(),
             config: PrestateTracerConfig { diff_mode },
             result,
+            fast_vm_storage: Default::default(),
         }
     }
 }
@@ -128,7 +138,11 @@ fn get_storage_if_present(
         .collect()
 }
 
-fn process_result(result: &Arc<OnceCell<(State, State)>>, mut pre: State, post: State) {
+fn process_result(result: &Arc<OnceCell<(State, State)>>, pre: State, post: State) {
+    result.set(retain_changed_accounts(pre, post)).unwrap();
+}
+
+fn retain_changed_accounts(mut pre: State, post: State) -> (State, State) {
     pre.retain(|k, v| {
         if let Some(post_v) = post.get(k) {
             if v != post_v {
@@ -137,7 +151,7 @@ fn process_result(result: &Arc<OnceCell<(State, State)>>, mut pre: State, post:
         }
         false
     });
-    result.set((pre, post)).unwrap();
+    (pre, post)
 }
 
 fn get_account_data<T: StorageAccess>(
@@ -0,0 +1,191 @@
+use std::collections::{HashMap, HashSet};
+
+use zksync_types::{
+    get_code_key, get_nonce_key, h256_to_u256, u256_to_h256, AccountTreeId, Address, StorageKey,
+    StorageValue, U256,
+};
+use zksync_vm2::interface::{
+    CallframeInterface, GlobalStateInterface, Opcode, OpcodeType, ReturnType, ShouldStop,
+    StateInterface, Tracer,
+};
+
+use super::{
+    get_balance_key, get_storage_if_present, retain_changed_accounts, Account, PrestateTracer,
+    State,
+};
+use crate::vm_fast::read_storage_slot_operand;
+
+/// Storage slots tracked by [`PrestateTracer`] in the fast VM. Unlike legacy VMs, the fast VM doesn't write to
+/// the storage view during execution, so the tracer needs to track modified and accessed slots itself.
+///
+/// Modified slots are taken from the VM state when the tracer is first invoked, but slots that were only read before
+/// that cannot be recovered. Thus, in the non-diff mode, the tracer must observe the entire batch execution
+/// (i.e., be reused for all transactions in the batch) to report the same slots as legacy VMs, which report all slots
+/// read since the start of the batch.
+#[derive(Debug, Clone, Default)]
+pub(super) struct FastVmStorage {
+    is_initialized: bool,
+    /// Slots modified since the VM start, together with their current values.
+    modified_slots: HashMap<StorageKey, StorageValue>,
+    /// Slots accessed (read or written) while the tracer was active, plus all modified slots.
+    accessed_slots: HashSet<StorageKey>,
+    /// Accounts for which `account_slots` are populated.
+    touched_accounts: HashSet<Address>,
+    /// Values of balance, code hash and nonce slots at the time an account was first touched.
+    account_slots: HashMap<StorageKey, StorageValue>,
+    /// Slot written by the currently executed instruction.
+    pending_write: Option<StorageKey>,
+}
+
+impl FastVmStorage {
+    fn current_slot_key<S: GlobalStateInterface>(state: &mut S) -> StorageKey {
+        let address = state.current_frame().address();
+        let slot = read_storage_slot_operand(state);
+        StorageKey::new(AccountTreeId::new(address), u256_to_h256(slot))
+    }
+
+    fn read_slot<S: GlobalStateInterface>(state: &mut S, key: &StorageKey) -> StorageValue {
+        u256_to_h256(state.get_storage(*key.address(), h256_to_u256(*key.key())))
+    }
+
+    fn touch_account<S: GlobalStateInterface>(&mut self, state: &mut S, address: Address) {
+        if !self.touched_accounts.insert(address) {
+            return;
+        }
+        let account = AccountTreeId::new(address);
+        for key in [
+            get_balance_key(&account),
+            get_code_key(&address),
+            get_nonce_key(&address),
+        ] {
+            let value = Self::read_slot(state, &key);
+            self.account_slots.insert(key, value);
+        }
+    }
+
+    fn initialize<S: GlobalStateInterface>(&mut self, state: &mut S) {
+        self.is_initialized = true;
+        let storage_state: Vec<_> = state.get_storage_state().collect();
+        for ((address, slot), value) in storage_state {
+            let key = StorageKey::new(AccountTreeId::new(address), u256_to_h256(slot));
+            self.modified_slots.insert(key, u256_to_h256(value));
+            self.accessed_slots.insert(key);
+            self.touch_account(state, address);
+        }
+    }
+
+    /// Refreshes values of modified slots after a rollback. Like with legacy VMs, rolled back slots are still
+    /// considered modified.
+    fn refresh_modified_slots<S: GlobalStateInterface>(&mut self, state: &mut S) {
+        for (key, value) in &mut self.modified_slots {
+            *value = Self::read_slot(state, key);
+        }
+    }
+
+    fn read_from_storage(&self, key: &StorageKey) -> U256 {
+        let value = self
+            .modified_slots
+            .get(key)
+            .or_else(|| self.account_slots.get(key))
+            .copied()
+            .unwrap_or_default();
+        h256_to_u256(value)
+    }
+
+    fn account_data(&self, address: Address) -> Account {
+        let account = AccountTreeId::new(address);
+        Account {
+            balance: Some(self.read_from_storage(&get_balance_key(&account))),
+            code: Some(self.read_from_storage(&get_code_key(&address))),
+            nonce: Some(self.read_from_storage(&get_nonce_key(&address))),
+            storage: Some(get_storage_if_present(&account, &self.modified_slots)),
+        }
+    }
+}
+
+impl Tracer for PrestateTracer {
+    fn before_instruction<OP: OpcodeType, S: GlobalStateInterface>(&mut self, state: &mut S) {
+        let storage = &mut self.fast_vm_storage;
+        if !storage.is_initialized {
+            storage.initialize(state);
+            if self.config.diff_mode {
+                for address in storage.touched_accounts.iter().copied() {
+                    self.pre
+                        .entry(address)
+                        .or_insert_with(|| storage.account_data(address));
+                }
+            }
+        }
+
+        match OP::VALUE {
+            Opcode::StorageRead => {
+                let key = FastVmStorage::current_slot_key(state);
+                storage.touch_account(state, *key.address());
+                storage.accessed_slots.insert(key);
+            }
+            Opcode::StorageWrite => {
+                let key = FastVmStorage::current_slot_key(state);
+                storage.touch_account(state, *key.address());
+                storage.accessed_slots.insert(key);
+                storage.pending_write = Some(key);
+            }
+            _ => {}
+        }
+    }
+
+    fn after_instruction<OP: OpcodeType, S: GlobalStateInterface>(
+        &mut self,
+        state: &mut S,
+    ) -> ShouldStop {
+        let storage = &mut self.fast_vm_storage;
+        match OP::VALUE {
+            Opcode::StorageWrite => {
+                if let Some(key) = storage.pending_write.take() {
+                    let value = FastVmStorage::read_slot(state, &key);
+                    storage.modified_slots.insert(key, value);
+                    // Legacy VMs snapshot the account right after it's modified for the first time.
+                    if self.config.diff_mode && !self.pre.contains_key(key.address()) {
+                        let account = storage.account_data(*key.address());
+                        self.pre.insert(*key.address(), account);
+                    }
+                }
+            }
+            Opcode::FarCall(_) => {
+                // Far calls implicitly read the callee code hash from storage.
+                let code_address = state.current_frame().code_address();
+                let key = get_code_key(&code_address);
+                storage.touch_account(state, *key.address());
+                storage.accessed_slots.insert(key);
+            }
+            Opcode::Ret(ReturnType::Revert | ReturnType::Panic) => {
+                storage.refresh_modified_slots(state);
+            }
+            _ => {}
+        }
+        ShouldStop::Continue
+    }
+}
+
+impl PrestateTracer {
+    /// Returns the pre-state and post-state collected by this tracer. Should be used with the fast VM, which (unlike legacy VMs)
+    /// doesn't notify tracers about the end of execution, so the result isn't stored in the shared result cell.
+    pub fn into_result(self) -> (State, State) {
+        self.collected_state()
+    }
+
+    /// Returns the pre-state and post-state collected by this tracer so far. Unlike [`Self::into_result()`],
+    /// this allows to continue using the tracer, e.g. for subsequent transactions in the same batch.
+    pub fn collected_state(&self) -> (State, State) {
+        let storage = &self.fast_vm_storage;
+        let keys: HashSet<_> = if self.config.diff_mode {
+            storage.modified_slots.keys().collect()
+        } else {
+            storage.accessed_slots.iter().collect()
+        };
+        let post = keys
+            .into_iter()
+            .map(|key| (*key.address(), storage.account_data(*key.address())))
+            .collect();
+        retain_changed_accounts(self.pre.clone(), post)
+    }
+}
@@ -0,0 +1,177 @@
+//! Minimization of VM dumps exhibiting divergences between the legacy and fast VMs.
+
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::Arc,
+};
+
+use anyhow::Context as _;
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use zksync_types::H256;
+
+use crate::{
+    interface::{
+        storage::{ImmutableStorageView, StorageSnapshot, StorageView},
+        utils::{DivergenceHandler, ShadowVm, VmDump},
+        VmFactory,
+    },
+    vm_fast,
+    vm_latest::{self, HistoryEnabled},
+};
+
+type LegacyVm = vm_latest::Vm<StorageView<StorageSnapshot>, HistoryEnabled>;
+type FastVm = vm_fast::Vm<ImmutableStorageView<StorageSnapshot>>;
+
+/// Compact reproducer for a divergence between the legacy and fast VMs. Can be (de)serialized.
+///
+/// A reproducer contains a VM dump truncated at the first diverging transaction, with the storage snapshot restricted
+/// to slots and factory deps accessed before the divergence was detected. Reproducers for fixed divergences should be
+/// placed into the `divergences` directory of this crate; they are replayed by its unit tests.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DivergenceReproducer {
+    /// Hash of the first diverging transaction.
+    pub tx_hash: H256,
+    /// Divergence errors observed when creating the reproducer.
+    pub errors: String,
+    /// Minimized VM dump.
+    pub dump: VmDump,
+}
+
+impl DivergenceReproducer {
+    /// Replays this reproducer with the fast VM shadowed by the legacy VM.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the VMs diverge.
+    pub fn check(&self) -> anyhow::Result<()> {
+        if let Some(divergence) = replay::<FastVm>(self.dump.clone()) {
+            anyhow::bail!(divergence.errors);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Divergence {
+    errors: String,
+    /// Dump captured by the shadow VM when the divergence was detected.
+    dump: VmDump,
+}
+
+fn tx_count(dump: &VmDump) -> usize {
+    dump.l2_blocks.iter().map(|block| block.txs.len()).sum()
+}
+
+/// Retains the first `tx_count` transactions in the dump, together with L2 blocks containing them.
+fn truncate_dump(mut dump: VmDump, tx_count: usize) -> VmDump {
+    let mut remaining_txs = tx_count;
+    let mut block_count = 0;
+    for block in &mut dump.l2_blocks {
+        if remaining_txs == 0 {
+            break;
+        }
+        block.txs.truncate(remaining_txs);
+        remaining_txs -= block.txs.len();
+        block_count += 1;
+    }
+    // The first block is always retained since it's a part of the batch env.
+    dump.l2_blocks.truncate(block_count.max(1));
+    dump
+}
+
+/// Plays back the dump on the legacy VM shadowed by `Shadow` and returns the first detected divergence, if any.
+fn replay<Shadow>(dump: VmDump) -> Option<Divergence>
+where
+    Shadow: VmFactory<StorageView<StorageSnapshot>>,
+{
+    let divergence = Arc::new(OnceCell::new());
+    let divergence_sink = divergence.clone();
+    let handler = DivergenceHandler::new(move |errors, dump| {
+        let errors = errors.to_string();
+        divergence_sink.set(Divergence { errors, dump }).ok();
+    });
+
+    dump.play_back_custom(|l1_batch_env, system_env, storage| {
+        let mut vm = ShadowVm::<_, LegacyVm, Shadow>::new(l1_batch_env, system_env, storage);
+        vm.set_divergence_handler(handler);
+        vm
+    });
+    divergence.get().cloned()
+}
+
+/// Minimizes a VM dump exhibiting a divergence between the legacy and fast VMs (e.g., one passed to
+/// a [`DivergenceHandler`]).
+///
+/// Minimization bisects the dump down to the first diverging transaction, and then restricts the storage snapshot
+/// to the slots and factory deps accessed by both VMs up to this transaction.
+///
+/// # Errors
+///
+/// Returns an error if the dump doesn't reproduce a divergence. Note that divergences when finishing a batch
+/// cannot be reproduced since [`VmDump`] doesn't support finishing batches.
+pub fn minimize_divergence(dump: VmDump) -> anyhow::Result<DivergenceReproducer> {
+    minimize_with_shadow::<FastVm>(dump)
+}
+
+pub(crate) fn minimize_with_shadow<Shadow>(dump: VmDump) -> anyhow::Result<DivergenceReproducer>
+where
+    Shadow: VmFactory<StorageView<StorageSnapshot>>,
+{
+    let l1_batch_number = dump.l1_batch_number();
+    let total_tx_count = tx_count(&dump);
+    let divergence = replay::<Shadow>(dump.clone()).with_context(|| {
+        format!("dump for L1 batch #{l1_batch_number} doesn't reproduce a divergence")
+    })?;
+
+    // The dump captured on divergence contains all transactions executed before the divergence was detected,
+    // which provides an upper bound for bisection.
+    let (mut min_tx_count, mut max_tx_count) = (1, tx_count(&divergence.dump).max(1));
+    tracing::info!(
+        "Reproduced divergence for L1 batch #{l1_batch_number} with {total_tx_count} transactions: {}; \
+         bisecting first {max_tx_count} transactions",
+        divergence.errors
+    );
+    while min_tx_count < max_tx_count {
+        let tx_count = (min_tx_count + max_tx_count) / 2;
+        let diverges = replay::<Shadow>(truncate_dump(dump.clone(), tx_count)).is_some();
+        tracing::debug!("Bisecting {tx_count} transactions: diverges={diverges}");
+        if diverges {
+            max_tx_count = tx_count;
+        } else {
+            min_tx_count = tx_count + 1;
+        }
+    }
+
+    let truncated_dump = truncate_dump(dump, max_tx_count);
+    let tx_hash = truncated_dump
+        .l2_blocks
+        .iter()
+        .flat_map(|block| &block.txs)
+        .last()
+        .context("dump doesn't contain transactions")?
+        .hash();
+    tracing::info!("First diverging transaction #{max_tx_count}: {tx_hash:?}");
+
+    let divergence = replay::<Shadow>(truncated_dump.clone()).with_context(|| {
+        format!("divergence is not reproduced with first {max_tx_count} transactions")
+    })?;
+    // The dump captured on divergence only contains storage accessed during execution. This should be enough
+    // to reproduce the divergence, but we double-check it. Playback panics if the storage snapshot is incomplete.
+    let mut minimized_dump = divergence.dump;
+    let is_reproduced = panic::catch_unwind(AssertUnwindSafe(|| {
+        replay::<Shadow>(minimized_dump.clone()).is_some()
+    }));
+    if !is_reproduced.unwrap_or(false) {
+        tracing::warn!(
+            "Divergence is not reproduced with minimized storage; using full storage snapshot"
+        );
+        minimized_dump = truncated_dump;
+    }
+
+    Ok(DivergenceReproducer {
+        tx_hash,
+        errors: divergence.errors,
+        dump: minimized_dump,
+    })
+}
@@ -4,7 +4,10 @@ use zksync_types::{
     U256,
 };
 
-pub use self::deduplicator::{ModifiedSlot, StorageWritesDeduplicator};
+pub use self::{
+    deduplicator::{ModifiedSlot, StorageWritesDeduplicator},
+    divergence::{minimize_divergence, DivergenceReproducer},
+};
 use crate::{
     glue::{GlueFrom, GlueInto},
     interface::L1BatchEnv,
@@ -12,6 +15,7 @@ use crate::{
 
 pub(crate) mod bytecode;
 mod deduplicator;
+pub(crate) mod divergence;
 pub(crate) mod events;
 
 /// Allows to convert `LogQuery` between two different versions, even if they don't provide
@@ -0,0 +1,172 @@
+//! Divergence corpus and tests for VM dump minimization.
+
+use std::{fs, path::Path, rc::Rc};
+
+use zksync_types::Transaction;
+
+use super::{sanity_check_vm, Harness, ShadowedFastVm};
+use crate::{
+    interface::{
+        pubdata::PubdataBuilder,
+        storage::{ImmutableStorageView, StoragePtr, StorageSnapshot, StorageView},
+        utils::VmDump,
+        BytecodeCompressionResult, FinishedL1Batch, InspectExecutionMode, L1BatchEnv, L2BlockEnv,
+        PushTransactionResult, SystemEnv, VmExecutionResultAndLogs, VmFactory, VmInterface,
+    },
+    utils::{divergence::minimize_with_shadow, DivergenceReproducer},
+    vm_fast,
+};
+
+const CORPUS_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/divergences");
+/// File name of the corpus reproducer for the divergence emulated by [`DivergingVm`].
+const HARNESS_REPRODUCER: &str = "harness_storage_write.json";
+
+type FastVm = vm_fast::Vm<ImmutableStorageView<StorageSnapshot>>;
+
+/// Fast VM which diverges from the legacy VM on transactions calling the storage test contract.
+#[derive(Debug)]
+struct DivergingVm(FastVm);
+
+impl VmFactory<StorageView<StorageSnapshot>> for DivergingVm {
+    fn new(
+        batch_env: L1BatchEnv,
+        system_env: SystemEnv,
+        storage: StoragePtr<StorageView<StorageSnapshot>>,
+    ) -> Self {
+        Self(FastVm::new(batch_env, system_env, storage))
+    }
+}
+
+impl VmInterface for DivergingVm {
+    type TracerDispatcher = <FastVm as VmInterface>::TracerDispatcher;
+
+    fn push_transaction(&mut self, tx: Transaction) -> PushTransactionResult<'_> {
+        self.0.push_transaction(tx)
+    }
+
+    fn inspect(
+        &mut self,
+        dispatcher: &mut Self::TracerDispatcher,
+        execution_mode: InspectExecutionMode,
+    ) -> VmExecutionResultAndLogs {
+        self.0.inspect(dispatcher, execution_mode)
+    }
+
+    fn start_new_l2_block(&mut self, l2_block_env: L2BlockEnv) {
+        self.0.start_new_l2_block(l2_block_env);
+    }
+
+    fn inspect_transaction_with_bytecode_compression(
+        &mut self,
+        tracer: &mut Self::TracerDispatcher,
+        tx: Transaction,
+        with_compression: bool,
+    ) -> (BytecodeCompressionResult<'_>, VmExecutionResultAndLogs) {
+        let diverges = tx.execute.contract_address == Some(Harness::STORAGE_CONTRACT_ADDRESS);
+        let (compression_result, mut tx_result) = self
+            .0
+            .inspect_transaction_with_bytecode_compression(tracer, tx, with_compression);
+        if diverges {
+            tx_result.statistics.gas_used += 1;
+        }
+        (compression_result, tx_result)
+    }
+
+    fn finish_batch(&mut self, pubdata_builder: Rc<dyn PubdataBuilder>) -> FinishedL1Batch {
+        self.0.finish_batch(pubdata_builder)
+    }
+}
+
+fn serialized_len(dump: &VmDump) -> usize {
+    serde_json::to_vec(dump).unwrap().len()
+}
+
+#[test]
+fn minimizing_divergence() {
+    let (vm, _) = sanity_check_vm::<ShadowedFastVm>();
+    let dump = vm.dump_state();
+    let txs: Vec<_> = dump.l2_blocks.iter().flat_map(|block| &block.txs).collect();
+    // The 3rd transaction calls the storage test contract.
+    let diverging_tx_hash = txs[2].hash();
+
+    let reproducer = minimize_with_shadow::<DivergingVm>(dump.clone()).unwrap();
+    assert_eq!(reproducer.tx_hash, diverging_tx_hash);
+    assert!(
+        reproducer.errors.contains("statistics.gas_used"),
+        "{}",
+        reproducer.errors
+    );
+    let tx_counts_per_block: Vec<_> = reproducer
+        .dump
+        .l2_blocks
+        .iter()
+        .map(|block| block.txs.len())
+        .collect();
+    assert_eq!(tx_counts_per_block, [1, 2]);
+    assert!(serialized_len(&reproducer.dump) < serialized_len(&dump));
+
+    // The storage snapshot in the reproducer must be complete for both VMs.
+    reproducer.check().unwrap();
+    let new_reproducer = minimize_with_shadow::<DivergingVm>(reproducer.dump.clone()).unwrap();
+    assert_eq!(new_reproducer, reproducer);
+}
+
+/// Writes the reproducer for the divergence emulated by [`DivergingVm`] to the corpus. Should be run
+/// with `cargo test -p zksync_multivm -- --ignored regenerating_harness_reproducer` if the test harness
+/// or the dump format changes.
+#[test]
+#[ignore]
+fn regenerating_harness_reproducer() {
+    let (vm, _) = sanity_check_vm::<ShadowedFastVm>();
+    let reproducer = minimize_with_shadow::<DivergingVm>(vm.dump_state()).unwrap();
+    // The fast VM doesn't diverge for the reproducer, i.e., it emulates a fixed divergence.
+    reproducer.check().unwrap();
+
+    let path = Path::new(CORPUS_DIR).join(HARNESS_REPRODUCER);
+    let serialized = serde_json::to_vec_pretty(&reproducer).unwrap();
+    fs::write(&path, serialized).unwrap();
+    tracing::info!("Written reproducer to {}", path.display());
+}
+
+#[test]
+fn minimizing_dump_without_divergence() {
+    let (vm, _) = sanity_check_vm::<ShadowedFastVm>();
+    let dump = vm.dump_state();
+    let err = minimize_with_shadow::<FastVm>(dump).unwrap_err();
+    assert!(
+        err.to_string().contains("doesn't reproduce a divergence"),
+        "{err:#}"
+    );
+}
+
+/// Replays all reproducers in the divergence corpus.
+#[test]
+fn divergence_corpus() {
+    let mut replayed_count = 0;
+    for entry in fs::read_dir(CORPUS_DIR).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension() != Some("json".as_ref()) {
+            continue;
+        }
+        tracing::info!("Replaying divergence reproducer at {}", path.display());
+        check_reproducer(&path);
+        replayed_count += 1;
+    }
+    // At least the harness reproducer must be present; otherwise, the test would silently pass for an empty corpus.
+    assert!(
+        replayed_count > 0,
+        "no reproducers in {CORPUS_DIR}; expected at least {HARNESS_REPRODUCER}"
+    );
+}
+
+fn check_reproducer(path: &Path) {
+    let reproducer = fs::read(path).unwrap();
+    let reproducer: DivergenceReproducer = serde_json::from_slice(&reproducer).unwrap();
+    if let Err(err) = reproducer.check() {
+        panic!(
+            "VM divergence for reproducer at {} (tx {:?}): {err:#}",
+            path.display(),
+            reproducer.tx_hash
+        );
+    }
+}
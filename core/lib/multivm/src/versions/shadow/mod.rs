@@ -22,7 +22,9 @@ use crate::{
     vm_latest::HistoryEnabled,
 };
 
+mod corpus;
 mod tests;
+mod tracers;
 
 type ReferenceVm<S = InMemoryStorage> = vm_latest::Vm<StorageView<S>, HistoryEnabled>;
 type ShadowedFastVm<S = InMemoryStorage> = crate::vm_instance::ShadowedFastVm<S, (), ()>;
@@ -0,0 +1,272 @@
+//! Tests comparing outputs of tracers supported both by the fast and legacy VMs.
+
+use std::{mem, sync::Arc};
+
+use ethabi::Token;
+use once_cell::sync::OnceCell;
+use test_casing::test_casing;
+use zksync_test_contracts::{Account, LoadnextContractExecutionParams, TestContract, TxType};
+use zksync_types::{Address, Execute, L1BatchNumber, Transaction};
+
+use super::{tx_fee, Harness};
+use crate::{
+    interface::{
+        storage::{InMemoryStorage, StorageView},
+        Call, VmFactory, VmInterface,
+    },
+    tracers::{CallTracer, PrestateTracer},
+    versions::testonly::{default_l1_batch, default_system_env},
+    vm_latest::{self, HistoryEnabled, ToTracerPointer},
+};
+
+type ShadowedVmWithTracer<Tr> = crate::vm_instance::ShadowedFastVm<InMemoryStorage, Tr, ()>;
+type LegacyTracerDispatcher =
+    vm_latest::TracerDispatcher<StorageView<InMemoryStorage>, HistoryEnabled>;
+
+fn call_tx(
+    account: &mut Account,
+    contract: &TestContract,
+    address: Address,
+    function: &str,
+    args: &[Token],
+) -> Transaction {
+    let calldata = contract.function(function).encode_input(args).unwrap();
+    let execute = Execute {
+        contract_address: Some(address),
+        calldata,
+        value: 0.into(),
+        factory_deps: vec![],
+    };
+    account.get_l2_tx_for_execute(execute, None)
+}
+
+impl Harness {
+    /// Returns transactions covering transfers, reverts, storage writes, contract deployment and nested calls.
+    fn traced_transactions(&mut self) -> Vec<Transaction> {
+        let transfer_exec = Execute {
+            contract_address: Some(self.bob.address()),
+            calldata: vec![],
+            value: 1_000_000_000.into(),
+            factory_deps: vec![],
+        };
+        let transfer_to_bob = self
+            .alice
+            .get_l2_tx_for_execute(transfer_exec.clone(), None);
+        let out_of_gas_transfer = self
+            .bob
+            .get_l2_tx_for_execute(transfer_exec, Some(tx_fee(200_000)));
+
+        let write_fn = self.storage_contract_abi.function("simpleWrite").unwrap();
+        let simple_write_tx = self.alice.get_l2_tx_for_execute(
+            Execute {
+                contract_address: Some(Self::STORAGE_CONTRACT_ADDRESS),
+                calldata: write_fn.encode_input(&[]).unwrap(),
+                value: 0.into(),
+                factory_deps: vec![],
+            },
+            None,
+        );
+
+        let deploy_tx = self.alice.get_deploy_tx(
+            TestContract::load_test().bytecode,
+            Some(&[Token::Uint(100.into())]),
+            TxType::L2,
+        );
+        let load_test_tx = self.bob.get_loadnext_transaction(
+            deploy_tx.address,
+            LoadnextContractExecutionParams::default(),
+            TxType::L2,
+        );
+
+        let mut txs = vec![
+            transfer_to_bob,
+            out_of_gas_transfer,
+            simple_write_tx,
+            deploy_tx.tx,
+            load_test_tx,
+        ];
+        txs.extend(self.test_contract_transactions());
+        txs
+    }
+
+    /// Returns transactions calling other test contracts, covering rollbacks in nested calls, transient storage
+    /// and precompiles.
+    fn test_contract_transactions(&mut self) -> Vec<Transaction> {
+        let storage_contract = TestContract::storage_test();
+        let resetting_write_tx = call_tx(
+            &mut self.alice,
+            storage_contract,
+            Self::STORAGE_CONTRACT_ADDRESS,
+            "resettingWriteViaRevert",
+            &[],
+        );
+        let transient_store_tx = call_tx(
+            &mut self.bob,
+            storage_contract,
+            Self::STORAGE_CONTRACT_ADDRESS,
+            "testTransientStore",
+            &[],
+        );
+
+        let counter = TestContract::counter();
+        let deploy_counter = self.alice.get_deploy_tx(counter.bytecode, None, TxType::L2);
+        let reverted_increment_tx = call_tx(
+            &mut self.bob,
+            counter,
+            deploy_counter.address,
+            "incrementWithRevert",
+            &[Token::Uint(1.into()), Token::Bool(true)],
+        );
+        let deploy_proxy_counter = self.alice.get_deploy_tx(
+            TestContract::proxy_counter().bytecode,
+            Some(&[Token::Address(deploy_counter.address)]),
+            TxType::L2,
+        );
+        // The passed gas is high enough so that the proxy doesn't burn gas before calling the counter.
+        let proxy_increment_tx = call_tx(
+            &mut self.bob,
+            TestContract::proxy_counter(),
+            deploy_proxy_counter.address,
+            "increment",
+            &[Token::Uint(1.into()), Token::Uint(u64::MAX.into())],
+        );
+
+        let precompiles = TestContract::precompiles_test();
+        let deploy_precompiles = self
+            .alice
+            .get_deploy_tx(precompiles.bytecode, None, TxType::L2);
+        let keccak_tx = call_tx(
+            &mut self.bob,
+            precompiles,
+            deploy_precompiles.address,
+            "doKeccak",
+            &[Token::Uint(5.into())],
+        );
+        let sha256_tx = call_tx(
+            &mut self.alice,
+            precompiles,
+            deploy_precompiles.address,
+            "doSha256",
+            &[Token::Uint(5.into())],
+        );
+
+        let expensive = TestContract::expensive();
+        let deploy_expensive = self.bob.get_deploy_tx(expensive.bytecode, None, TxType::L2);
+        let expensive_tx = call_tx(
+            &mut self.alice,
+            expensive,
+            deploy_expensive.address,
+            "expensive",
+            &[Token::Uint(10.into())],
+        );
+        let clean_up_tx = call_tx(
+            &mut self.bob,
+            expensive,
+            deploy_expensive.address,
+            "cleanUp",
+            &[],
+        );
+
+        vec![
+            resetting_write_tx,
+            transient_store_tx,
+            deploy_counter.tx,
+            reverted_increment_tx,
+            deploy_proxy_counter.tx,
+            proxy_increment_tx,
+            deploy_precompiles.tx,
+            keccak_tx,
+            sha256_tx,
+            deploy_expensive.tx,
+            expensive_tx,
+            clean_up_tx,
+        ]
+    }
+}
+
+fn shadowed_vm_with_tracer<Tr>() -> (ShadowedVmWithTracer<Tr>, Harness)
+where
+    ShadowedVmWithTracer<Tr>: VmFactory<StorageView<InMemoryStorage>>,
+{
+    let system_env = default_system_env();
+    let l1_batch_env = default_l1_batch(L1BatchNumber(1));
+    let mut storage = InMemoryStorage::with_system_contracts();
+    let harness = Harness::new(&l1_batch_env);
+    harness.setup_storage(&mut storage);
+
+    let storage = StorageView::new(storage).to_rc_ptr();
+    let vm = ShadowedVmWithTracer::<Tr>::new(l1_batch_env, system_env, storage);
+    (vm, harness)
+}
+
+/// Gas values are not compared by `Call::eq()`, so we compare them separately.
+fn collect_gas(calls: &[Call], output: &mut Vec<(u64, u64, u64)>) {
+    for call in calls {
+        output.push((call.parent_gas, call.gas, call.gas_used));
+        collect_gas(&call.calls, output);
+    }
+}
+
+#[test]
+fn call_tracer_in_shadow_vm() {
+    let (mut vm, mut harness) = shadowed_vm_with_tracer::<CallTracer>();
+    for tx in harness.traced_transactions() {
+        let tx_hash = tx.hash();
+        let legacy_result = Arc::new(OnceCell::new());
+        let legacy_tracer: LegacyTracerDispatcher = CallTracer::new(legacy_result.clone())
+            .into_tracer_pointer()
+            .into();
+        let mut tracer = (legacy_tracer, (CallTracer::default(), ()));
+        let (compression_result, _) =
+            vm.inspect_transaction_with_bytecode_compression(&mut tracer, tx, true);
+        compression_result.unwrap();
+
+        let legacy_calls = legacy_result.get().expect("no legacy call traces").clone();
+        let (fast_tracer, ()) = tracer.1;
+        let fast_calls = fast_tracer.into_result();
+        assert!(!fast_calls.is_empty(), "{tx_hash:?}");
+        pretty_assertions::assert_eq!(fast_calls, legacy_calls, "{tx_hash:?}");
+
+        let (mut fast_gas, mut legacy_gas) = (vec![], vec![]);
+        collect_gas(&fast_calls, &mut fast_gas);
+        collect_gas(&legacy_calls, &mut legacy_gas);
+        assert_eq!(fast_gas, legacy_gas, "{tx_hash:?}");
+    }
+}
+
+#[test_casing(2, [false, true])]
+#[test]
+fn prestate_tracer_in_shadow_vm(diff_mode: bool) {
+    let (mut vm, mut harness) = shadowed_vm_with_tracer::<PrestateTracer>();
+    // In the non-diff mode, the legacy VM reports all storage slots read since the start of the batch. Hence,
+    // the fast VM tracer must observe all transactions in the batch, and we reuse it across transactions.
+    let mut batch_tracer = PrestateTracer::default();
+
+    for tx in harness.traced_transactions() {
+        let tx_hash = tx.hash();
+        let legacy_result = Arc::new(OnceCell::new());
+        let legacy_tracer: LegacyTracerDispatcher =
+            PrestateTracer::new(diff_mode, legacy_result.clone())
+                .into_tracer_pointer()
+                .into();
+        let fast_tracer = if diff_mode {
+            PrestateTracer::new(diff_mode, Arc::default())
+        } else {
+            mem::take(&mut batch_tracer)
+        };
+        let mut tracer = (legacy_tracer, (fast_tracer, ()));
+        let (compression_result, _) =
+            vm.inspect_transaction_with_bytecode_compression(&mut tracer, tx, true);
+        compression_result.unwrap();
+
+        let (legacy_pre, legacy_post) = legacy_result.get().expect("no legacy prestate").clone();
+        let (fast_tracer, ()) = tracer.1;
+        let (fast_pre, fast_post) = fast_tracer.collected_state();
+        if !diff_mode {
+            batch_tracer = fast_tracer;
+        }
+        assert!(!fast_post.is_empty(), "{tx_hash:?}");
+        pretty_assertions::assert_eq!(fast_pre, legacy_pre, "{tx_hash:?}");
+        pretty_assertions::assert_eq!(fast_post, legacy_post, "{tx_hash:?}");
+    }
+}
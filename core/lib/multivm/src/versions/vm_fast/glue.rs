@@ -1,6 +1,7 @@
 use zksync_types::{
     l2_to_l1_log::{L2ToL1Log, SystemL2ToL1Log},
     u256_to_h256,
+    zk_evm_types::FarCallOpcode,
 };
 use zksync_vm2::interface;
 
@@ -27,3 +28,13 @@ impl GlueFrom<interface::L2ToL1Log> for SystemL2ToL1Log {
         })
     }
 }
+
+impl GlueFrom<interface::CallingMode> for FarCallOpcode {
+    fn glue_from(value: interface::CallingMode) -> Self {
+        match value {
+            interface::CallingMode::Normal => Self::Normal,
+            interface::CallingMode::Delegate => Self::Delegate,
+            interface::CallingMode::Mimic => Self::Mimic,
+        }
+    }
+}
@@ -1,10 +1,10 @@
 pub use zksync_vm2::interface;
 
-pub(crate) use self::version::FastVmVersion;
 pub use self::{
     tracers::{FullValidationTracer, ValidationTracer},
     vm::Vm,
 };
+pub(crate) use self::{utils::read_storage_slot_operand, version::FastVmVersion};
 
 mod bootloader_state;
 mod bytecode;
@@ -14,7 +14,10 @@ use zksync_vm_interface::tracer::{
     TimestampAsserterParams, ValidationParams, ValidationTraces, ViolatedValidationRule,
 };
 
-use crate::{tracers::TIMESTAMP_ASSERTER_FUNCTION_SELECTOR, vm_fast::utils::read_fat_pointer};
+use crate::{
+    tracers::TIMESTAMP_ASSERTER_FUNCTION_SELECTOR,
+    vm_fast::utils::{read_fat_pointer, read_storage_slot_operand},
+};
 
 /// [`Tracer`] used for account validation per [EIP-4337] and [EIP-7562].
 ///
@@ -109,14 +112,7 @@ impl Tracer for FullValidationTracer {
             StorageRead => {
                 let address = state.current_frame().address();
                 let caller = state.current_frame().caller();
-
-                // Can unwrap because the instruction pointer does not point to a panic instruction
-                let pc = state.current_frame().program_counter().unwrap();
-                let word = pc / 4;
-                let part = pc % 4;
-                let instruction =
-                    state.current_frame().read_contract_code(word).0[3 - part as usize];
-                let slot = state.read_register((instruction >> 16) as u8 & 0b1111).0;
+                let slot = read_storage_slot_operand(state);
 
                 if self
                     .storage_containing_trusted_addresses
@@ -8,7 +8,8 @@ use prost::Message;
 use zksync_protobuf::{decode, ProtoFmt};
 use zksync_types::{
     snapshots::{
-        SnapshotFactoryDependencies, SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+        SnapshotFactoryDependencies, SnapshotHeader, SnapshotStorageLogsChunk,
+        SnapshotStorageLogsStorageKey,
     },
     L1BatchNumber,
 };
@@ -87,6 +88,23 @@ impl StoredObject for SnapshotFactoryDependencies {
     }
 }
 
+impl StoredObject for SnapshotHeader {
+    const BUCKET: Bucket = Bucket::StorageSnapshot;
+    type Key<'a> = L1BatchNumber;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("snapshot_l1_batch_{key}_header.json")
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, BoxedError> {
+        serde_json::to_vec_pretty(self).map_err(From::from)
+    }
+
+    fn deserialize(bytes: Vec<u8>) -> Result<Self, BoxedError> {
+        serde_json::from_slice(&bytes).map_err(From::from)
+    }
+}
+
 impl<K> StoredObject for SnapshotStorageLogsChunk<K>
 where
     Self: ProtoFmt,
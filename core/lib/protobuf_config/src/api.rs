@@ -85,6 +85,11 @@ impl ProtoRepr for proto::Web3JsonRpc {
             )
             .context("acceptable_overestimation")?,
             estimate_gas_optimize_search: self.estimate_gas_optimize_search.unwrap_or(false),
+            estimate_gas_cache_size: self
+                .estimate_gas_cache_size
+                .map(|x| x.try_into())
+                .transpose()
+                .context("estimate_gas_cache_size")?,
             max_tx_size: required(&self.max_tx_size)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("max_tx_size")?,
@@ -93,6 +98,8 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .map(|x| x.try_into())
                 .transpose()
                 .context("vm_execution_cache_misses_limit")?,
+            vm_execution_timeout_ms: self.vm_execution_timeout_ms,
+            vm_execution_cycles_limit: self.vm_execution_cycles_limit,
             vm_concurrency_limit: self
                 .vm_concurrency_limit
                 .map(|x| x.try_into())
@@ -118,6 +125,7 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .map(|x| x.try_into())
                 .transpose()
                 .context("latest_values_max_block_lag")?,
+            latest_values_warm_storage_path: self.latest_values_warm_storage_path.clone(),
             fee_history_limit: self.fee_history_limit,
             max_batch_request_size: self
                 .max_batch_request_size
@@ -150,6 +158,7 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .collect::<Result<Vec<_>, _>>()
                 .context("whitelisted_tokens_for_aa")?,
             extended_api_tracing: self.extended_api_tracing.unwrap_or_default(),
+            read_your_writes_max_wait_ms: self.read_your_writes_max_wait_ms,
             api_namespaces,
         })
     }
@@ -174,10 +183,13 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 this.estimate_gas_acceptable_overestimation,
             ),
             estimate_gas_optimize_search: Some(this.estimate_gas_optimize_search),
+            estimate_gas_cache_size: this.estimate_gas_cache_size.map(|x| x.try_into().unwrap()),
             max_tx_size: Some(this.max_tx_size.try_into().unwrap()),
             vm_execution_cache_misses_limit: this
                 .vm_execution_cache_misses_limit
                 .map(|x| x.try_into().unwrap()),
+            vm_execution_timeout_ms: this.vm_execution_timeout_ms,
+            vm_execution_cycles_limit: this.vm_execution_cycles_limit,
             vm_concurrency_limit: this.vm_concurrency_limit.map(|x| x.try_into().unwrap()),
             factory_deps_cache_size_mb: this
                 .factory_deps_cache_size_mb
@@ -189,6 +201,7 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .latest_values_cache_size_mb
                 .map(|x| x.try_into().unwrap()),
             latest_values_max_block_lag: this.latest_values_max_block_lag.map(NonZeroU32::get),
+            latest_values_warm_storage_path: this.latest_values_warm_storage_path.clone(),
             fee_history_limit: this.fee_history_limit,
             max_batch_request_size: this.max_batch_request_size.map(|x| x.try_into().unwrap()),
             max_response_body_size_mb: this
@@ -216,6 +229,7 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .map(|k| format!("{:?}", k))
                 .collect(),
             extended_api_tracing: Some(this.extended_api_tracing),
+            read_your_writes_max_wait_ms: this.read_your_writes_max_wait_ms,
             api_namespaces: this.api_namespaces.clone().unwrap_or_default(),
         }
     }
@@ -18,6 +18,12 @@ impl ProtoRepr for proto::ExternalNode {
             main_node_url: SensitiveUrl::from_str(
                 required(&self.main_node_url).context("main_node_url")?,
             )?,
+            main_node_fallback_urls: self
+                .main_node_fallback_urls
+                .iter()
+                .map(|url| SensitiveUrl::from_str(url))
+                .collect::<Result<_, _>>()
+                .context("main_node_fallback_urls")?,
             l1_chain_id: required(&self.l1_chain_id)
                 .map(|x| L1ChainId(*x))
                 .context("l1_chain_id")?,
@@ -43,6 +49,11 @@ impl ProtoRepr for proto::ExternalNode {
     fn build(this: &Self::Type) -> Self {
         Self {
             main_node_url: Some(this.main_node_url.expose_str().to_string()),
+            main_node_fallback_urls: this
+                .main_node_fallback_urls
+                .iter()
+                .map(|url| url.expose_str().to_string())
+                .collect(),
             l1_chain_id: Some(this.l1_chain_id.0),
             l2_chain_id: Some(this.l2_chain_id.as_u64()),
             l1_batch_commit_data_generator_mode: Some(
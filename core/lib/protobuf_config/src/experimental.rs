@@ -30,12 +30,21 @@ impl ProtoRepr for proto::Db {
                 .map(|count| NonZeroU32::new(count).context("cannot be 0"))
                 .transpose()
                 .context("state_keeper_db_max_open_files")?,
+            state_keeper_db_compaction_interval: self
+                .state_keeper_db_compaction_interval
+                .map(|interval| NonZeroU32::new(interval).context("cannot be 0"))
+                .transpose()
+                .context("state_keeper_db_compaction_interval")?,
             protective_reads_persistence_enabled: self.reads_persistence_enabled.unwrap_or(false),
             processing_delay_ms: self.processing_delay_ms.unwrap_or_default(),
             include_indices_and_filters_in_block_cache: self
                 .include_indices_and_filters_in_block_cache
                 .unwrap_or(false),
             merkle_tree_repair_stale_keys: self.merkle_tree_repair_stale_keys.unwrap_or(false),
+            merkle_tree_migrate_to_lightweight: self
+                .merkle_tree_migrate_to_lightweight
+                .unwrap_or(false),
+            merkle_tree_rebuild_path: self.merkle_tree_rebuild_path.clone(),
         })
     }
 
@@ -49,12 +58,17 @@ impl ProtoRepr for proto::Db {
             state_keeper_db_max_open_files: this
                 .state_keeper_db_max_open_files
                 .map(NonZeroU32::get),
+            state_keeper_db_compaction_interval: this
+                .state_keeper_db_compaction_interval
+                .map(NonZeroU32::get),
             reads_persistence_enabled: Some(this.protective_reads_persistence_enabled),
             processing_delay_ms: Some(this.processing_delay_ms),
             include_indices_and_filters_in_block_cache: Some(
                 this.include_indices_and_filters_in_block_cache,
             ),
             merkle_tree_repair_stale_keys: Some(this.merkle_tree_repair_stale_keys),
+            merkle_tree_migrate_to_lightweight: Some(this.merkle_tree_migrate_to_lightweight),
+            merkle_tree_rebuild_path: this.merkle_tree_rebuild_path.clone(),
         }
     }
 }
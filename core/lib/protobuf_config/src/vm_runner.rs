@@ -15,6 +15,7 @@ impl ProtoRepr for proto::ProtectiveReadsWriter {
             first_processed_batch: L1BatchNumber(
                 *required(&self.first_processed_batch).context("first_batch")? as u32,
             ),
+            use_snapshots: self.use_snapshots.unwrap_or(false),
         })
     }
 
@@ -23,6 +24,7 @@ impl ProtoRepr for proto::ProtectiveReadsWriter {
             db_path: Some(this.db_path.clone()),
             window_size: Some(this.window_size as u64),
             first_processed_batch: Some(this.first_processed_batch.0 as u64),
+            use_snapshots: Some(this.use_snapshots),
         }
     }
 }
@@ -37,6 +39,7 @@ impl ProtoRepr for proto::BasicWitnessInputProducer {
             first_processed_batch: L1BatchNumber(
                 *required(&self.first_processed_batch).context("first_batch")? as u32,
             ),
+            use_snapshots: self.use_snapshots.unwrap_or(false),
         })
     }
 
@@ -45,6 +48,7 @@ impl ProtoRepr for proto::BasicWitnessInputProducer {
             db_path: Some(this.db_path.clone()),
             window_size: Some(this.window_size as u64),
             first_processed_batch: Some(this.first_processed_batch.0 as u64),
+            use_snapshots: Some(this.use_snapshots),
         }
     }
 }
@@ -295,6 +295,7 @@ pub(super) fn mock_snapshot_header(
             })
             .collect(),
         factory_deps_filepath: "some_filepath".to_string(),
+        l1_batch_root_hash: None,
     }
 }
 
@@ -42,7 +42,7 @@ impl error::Error for AsyncCatchupFailed {}
 type AsyncOnceCell<T> = watch::Receiver<Option<T>>;
 
 /// A lazily initialized handle to RocksDB cache returned from [`AsyncCatchupTask::new()`].
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct RocksdbCell {
     initial_state: AsyncOnceCell<InitialRocksdbState>,
     db: AsyncOnceCell<RocksDB<StateKeeperColumnFamily>>,
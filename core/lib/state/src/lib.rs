@@ -14,9 +14,12 @@ pub use zksync_vm_interface::storage as interface;
 pub use self::{
     cache::sequential_cache::SequentialCache,
     catchup::{AsyncCatchupTask, RocksdbCell},
-    postgres::{PostgresStorage, PostgresStorageCaches, PostgresStorageCachesTask},
+    postgres::{
+        PostgresStorage, PostgresStorageCaches, PostgresStorageCachesTask, WarmValuesStorage,
+    },
     rocksdb::{
-        RocksdbStorage, RocksdbStorageBuilder, RocksdbStorageOptions, StateKeeperColumnFamily,
+        RocksdbCompactionStats, RocksdbStorage, RocksdbStorageBuilder, RocksdbStorageCompactor,
+        RocksdbStorageOptions, StateKeeperColumnFamily,
     },
     shadow_storage::ShadowStorage,
     storage_factory::{
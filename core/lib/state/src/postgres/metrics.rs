@@ -9,6 +9,7 @@ use vise::{Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Hi
 pub(super) enum ValuesUpdateStage {
     LoadKeys,
     RemoveStaleKeys,
+    RefreshWarmKeys,
 }
 
 #[derive(Debug, Metrics)]
@@ -29,6 +30,17 @@ pub(super) struct ValuesCacheMetrics {
     /// Number of times the negative initial writes cache was successfully used. This is distinct
     /// from cache hits (we can hit the cache, but the cached value may be outdated).
     pub effective_values: Counter,
+
+    /// Number of times the warm storage values cache was emptied because it was too far back or inconsistent
+    /// with Postgres.
+    pub warm_values_emptied: Counter,
+    /// Number of values refreshed in the warm storage values cache during a specific update.
+    #[metrics(buckets = &[10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1_000.0])]
+    pub warm_values_refreshed_keys: Histogram<usize>,
+    /// Current L2 block for the warm storage values cache.
+    pub warm_values_valid_for_miniblock: Gauge<u64>,
+    /// Estimated number of entries in the warm storage values cache.
+    pub warm_values_len: Gauge<u64>,
 }
 
 #[vise::register]
@@ -42,12 +54,26 @@ pub(super) enum Method {
     LoadFactoryDep,
 }
 
+/// Source of a value returned by `PostgresStorage::read_value()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet, EncodeLabelValue)]
+#[metrics(label = "source", rename_all = "snake_case")]
+pub(super) enum ValueSource {
+    /// In-memory values cache.
+    ValuesCache,
+    /// Warm storage values cache.
+    WarmValues,
+    Postgres,
+}
+
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "state_postgres")]
 pub(super) struct PostgresStorageMetrics {
     /// Latency of storage reading methods for Postgres-backed storage.
     #[metrics(buckets = Buckets::LATENCIES)]
     pub storage: Family<Method, Histogram<Duration>>,
+    /// Number of values returned by `read_value()` grouped by the source. Can be used to compute hit rates
+    /// for the values caches.
+    pub read_value_sources: Family<ValueSource, Counter>,
 }
 
 #[vise::register]
@@ -17,12 +17,14 @@ use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
 use zksync_types::{L1BatchNumber, L2BlockNumber, StorageKey, StorageValue, H256};
 use zksync_vm_interface::storage::ReadStorage;
 
-use self::metrics::{Method, ValuesUpdateStage, CACHE_METRICS, STORAGE_METRICS};
+use self::metrics::{Method, ValueSource, ValuesUpdateStage, CACHE_METRICS, STORAGE_METRICS};
+pub use self::warm_values::WarmValuesStorage;
 use crate::cache::{lru_cache::LruCache, CacheValue};
 
 mod metrics;
 #[cfg(test)]
 mod tests;
+mod warm_values;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct TimestampedFactoryDep {
@@ -192,12 +194,13 @@ impl ValuesCache {
         Ok(())
     }
 
+    /// Returns keys modified in the update range.
     async fn update(
         &self,
         from_l2_block: L2BlockNumber,
         to_l2_block: L2BlockNumber,
         connection: &mut Connection<'_, Core>,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<Vec<H256>> {
         tracing::debug!(
             "Updating storage values cache from L2 block {from_l2_block} to {to_l2_block}"
         );
@@ -245,7 +248,7 @@ impl ValuesCache {
         CACHE_METRICS
             .values_valid_for_miniblock
             .set(u64::from(to_l2_block.0));
-        Ok(())
+        Ok(modified_keys)
     }
 }
 
@@ -263,6 +266,7 @@ struct ValuesCacheAndUpdater {
 /// - Cache for L1 batch numbers of initial writes for storage keys (never invalidated, except after
 ///   reverting L1 batch execution)
 /// - Cache of the VM storage snapshot corresponding to the latest sealed L2 block
+/// - Optional RocksDB-backed [warm layer](WarmValuesStorage) for the latest VM storage snapshot
 #[derive(Debug, Clone)]
 pub struct PostgresStorageCaches {
     factory_deps: FactoryDepsCache,
@@ -276,6 +280,7 @@ pub struct PostgresStorageCaches {
     // it wasn't written to at the point that interests us.
     negative_initial_writes: InitialWritesCache,
     values: Option<ValuesCacheAndUpdater>,
+    warm_values: Option<WarmValuesStorage>,
 }
 
 impl PostgresStorageCaches {
@@ -297,9 +302,25 @@ impl PostgresStorageCaches {
                 initial_writes_capacity / 2,
             ),
             values: None,
+            warm_values: None,
         }
     }
 
+    /// Configures the RocksDB-backed warm layer for the VM storage values cache. The warm layer is updated
+    /// by the task returned from [`Self::configure_storage_values_cache()`]; without this task,
+    /// the warm layer will not be used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the values cache is already configured.
+    pub fn configure_warm_values_storage(&mut self, storage: WarmValuesStorage) {
+        assert!(
+            self.values.is_none(),
+            "Warm values storage must be configured before the storage values cache"
+        );
+        self.warm_values = Some(storage);
+    }
+
     /// Configures the VM storage values cache. The returned closure is the background task that will update
     /// the cache according to [`Self::schedule_values_update()`] calls. It should be spawned on a separate thread
     /// or a blocking Tokio task.
@@ -334,6 +355,7 @@ impl PostgresStorageCaches {
         PostgresStorageCachesTask {
             connection_pool,
             values_cache,
+            warm_values: self.warm_values.clone(),
             max_l2_blocks_lag,
             command_receiver,
         }
@@ -364,6 +386,7 @@ impl PostgresStorageCaches {
 pub struct PostgresStorageCachesTask {
     connection_pool: ConnectionPool<Core>,
     values_cache: ValuesCache,
+    warm_values: Option<WarmValuesStorage>,
     max_l2_blocks_lag: u32,
     command_receiver: UnboundedReceiver<L2BlockNumber>,
 }
@@ -380,10 +403,27 @@ impl PostgresStorageCachesTask {
         tracing::info!(
             max_l2_blocks_lag = self.max_l2_blocks_lag,
             values_cache.capacity = self.values_cache.capacity(),
+            warm_values = self.warm_values.is_some(),
             "Starting task"
         );
 
         let mut current_l2_block = self.values_cache.valid_for();
+        if let Some(warm_values) = &self.warm_values {
+            // Bring the (empty) values cache and the warm storage to the same L2 block, so that they can be updated
+            // in lockstep afterwards.
+            let mut connection = self
+                .connection_pool
+                .connection_tagged("values_cache_updater")
+                .await?;
+            if let Some(restored_l2_block) = warm_values.restore(&mut connection).await? {
+                self.values_cache
+                    .reset(current_l2_block, restored_l2_block)?;
+                current_l2_block = restored_l2_block;
+            } else {
+                warm_values.reset(current_l2_block, &mut connection).await?;
+            }
+        }
+
         loop {
             let to_l2_block = tokio::select! {
                 _ = stop_receiver.changed() => break,
@@ -400,17 +440,48 @@ impl PostgresStorageCachesTask {
 
             if to_l2_block.0 - current_l2_block.0 > self.max_l2_blocks_lag {
                 self.values_cache.reset(current_l2_block, to_l2_block)?;
+                if let Some(warm_values) = &self.warm_values {
+                    // Unlike the in-memory cache, the warm storage is caught up rather than reset, so that it
+                    // retains values after a server restart or if the task falls behind.
+                    let mut connection = self
+                        .connection_pool
+                        .connection_tagged("values_cache_updater")
+                        .await?;
+                    warm_values
+                        .catch_up(
+                            current_l2_block,
+                            to_l2_block,
+                            self.max_l2_blocks_lag,
+                            &mut connection,
+                        )
+                        .await?;
+                }
             } else {
                 let mut connection = self
                     .connection_pool
                     .connection_tagged("values_cache_updater")
                     .await?;
-                self.values_cache
+                let modified_keys = self
+                    .values_cache
                     .update(current_l2_block, to_l2_block, &mut connection)
                     .await?;
+                if let Some(warm_values) = &self.warm_values {
+                    warm_values
+                        .update(
+                            current_l2_block,
+                            to_l2_block,
+                            &modified_keys,
+                            &mut connection,
+                        )
+                        .await?;
+                }
             }
             current_l2_block = to_l2_block;
         }
+
+        if let Some(warm_values) = &self.warm_values {
+            warm_values.flush()?;
+        }
         Ok(())
     }
 }
@@ -503,6 +574,10 @@ impl<'a> PostgresStorage<'a> {
     fn values_cache(&self) -> Option<&ValuesCache> {
         Some(&self.caches.as_ref()?.values.as_ref()?.cache)
     }
+
+    fn warm_values(&self) -> Option<&WarmValuesStorage> {
+        self.caches.as_ref()?.warm_values.as_ref()
+    }
 }
 
 impl ReadStorage for PostgresStorage<'_> {
@@ -510,10 +585,20 @@ impl ReadStorage for PostgresStorage<'_> {
         let hashed_key = key.hashed_key();
         let latency = STORAGE_METRICS.storage[&Method::ReadValue].start();
         let values_cache = self.values_cache();
-        let cached_value =
-            values_cache.and_then(|cache| cache.get(self.l2_block_number, hashed_key));
+        let mut source = ValueSource::ValuesCache;
+        let cached_value = values_cache
+            .and_then(|cache| cache.get(self.l2_block_number, hashed_key))
+            .or_else(|| {
+                source = ValueSource::WarmValues;
+                let value = self.warm_values()?.get(self.l2_block_number, hashed_key)?;
+                if let Some(cache) = values_cache {
+                    cache.insert(self.l2_block_number, hashed_key, value);
+                }
+                Some(value)
+            });
 
         let value = cached_value.unwrap_or_else(|| {
+            source = ValueSource::Postgres;
             const RETRY_INTERVAL: Duration = Duration::from_millis(500);
             const MAX_TRIES: usize = 20;
 
@@ -537,10 +622,14 @@ impl ReadStorage for PostgresStorage<'_> {
             if let Some(cache) = self.values_cache() {
                 cache.insert(self.l2_block_number, hashed_key, value);
             }
+            if let Some(warm_values) = self.warm_values() {
+                warm_values.insert(self.l2_block_number, hashed_key, value);
+            }
             value
         });
 
         latency.observe();
+        STORAGE_METRICS.read_value_sources[&source].inc();
         value
     }
 
@@ -1,6 +1,6 @@
 //! Tests for `PostgresStorage`.
 
-use std::{collections::HashMap, mem, time::Duration};
+use std::{collections::HashMap, mem, path::PathBuf, time::Duration};
 
 use rand::{
     rngs::StdRng,
@@ -587,6 +587,235 @@ async fn using_values_cache() {
         .unwrap();
 }
 
+async fn wait_for_warm_values_update(
+    warm_values: &WarmValuesStorage,
+    target_l2_block: L2BlockNumber,
+) {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while warm_values.valid_for() != Some(target_l2_block) {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("timed out waiting for warm values update");
+}
+
+fn test_warm_values_storage(pool: &ConnectionPool<Core>, rt_handle: Handle, db_path: PathBuf) {
+    let new_l2_block_assertions =
+        test_warm_values_storage_before_restart(pool, rt_handle.clone(), db_path.clone());
+
+    // Check that the warm storage is restored after a restart.
+    let warm_values = rt_handle.block_on(WarmValuesStorage::new(db_path)).unwrap();
+    let mut caches = PostgresStorageCaches::new(1_024, 1_024);
+    caches.configure_warm_values_storage(warm_values.clone());
+    let task = caches.configure_storage_values_cache(1_024 * 1_024, 5, pool.clone());
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let update_task_handle = tokio::task::spawn(task.run(stop_receiver));
+    rt_handle.block_on(wait_for_warm_values_update(&warm_values, L2BlockNumber(1)));
+    assert_eq!(
+        caches.values.as_ref().unwrap().cache.valid_for(),
+        L2BlockNumber(1)
+    );
+
+    let connection = rt_handle.block_on(pool.connection()).unwrap();
+    let mut storage = PostgresStorage::new(rt_handle, connection, L2BlockNumber(1), false)
+        .with_caches(caches.clone());
+    for (key, value) in new_l2_block_assertions {
+        assert_eq!(
+            warm_values.get(L2BlockNumber(1), key.hashed_key()),
+            Some(value)
+        );
+        assert_eq!(storage.read_value(&key), value);
+    }
+
+    stop_sender.send_replace(true);
+    storage
+        .rt_handle
+        .block_on(update_task_handle)
+        .expect("update task panicked")
+        .unwrap();
+}
+
+/// Returns the expected storage values for L2 block #1.
+fn test_warm_values_storage_before_restart(
+    pool: &ConnectionPool<Core>,
+    rt_handle: Handle,
+    db_path: PathBuf,
+) -> [(StorageKey, StorageValue); 3] {
+    let mut connection = rt_handle.block_on(pool.connection()).unwrap();
+    rt_handle.block_on(prepare_postgres(&mut connection));
+
+    let warm_values = rt_handle.block_on(WarmValuesStorage::new(db_path)).unwrap();
+    let mut caches = PostgresStorageCaches::new(1_024, 1_024);
+    caches.configure_warm_values_storage(warm_values.clone());
+    let task = caches.configure_storage_values_cache(1_024 * 1_024, 5, pool.clone());
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let update_task_handle = tokio::task::spawn(task.run(stop_receiver));
+    rt_handle.block_on(wait_for_warm_values_update(&warm_values, L2BlockNumber(0)));
+
+    let mut storage = PostgresStorage::new(rt_handle, connection, L2BlockNumber(0), false)
+        .with_caches(caches.clone());
+    let initial_logs = gen_storage_logs(0..20);
+    let modified_key = initial_logs[1].key;
+    let unmodified_key = initial_logs[2].key;
+    let initial_value = storage.read_value(&modified_key);
+    let unmodified_value = storage.read_value(&unmodified_key);
+    let non_existing_key = gen_storage_logs(100..120)[0].key;
+    assert_eq!(storage.read_value(&non_existing_key), H256::zero());
+
+    for (key, value) in [
+        (modified_key, initial_value),
+        (unmodified_key, unmodified_value),
+        (non_existing_key, H256::zero()),
+    ] {
+        assert_eq!(
+            warm_values.get(L2BlockNumber(0), key.hashed_key()),
+            Some(value)
+        );
+    }
+
+    let logs = vec![
+        StorageLog::new_write_log(modified_key, H256::repeat_byte(1)),
+        StorageLog::new_write_log(non_existing_key, H256::repeat_byte(2)),
+    ];
+    storage.rt_handle.block_on(create_l2_block(
+        &mut storage.connection,
+        L2BlockNumber(1),
+        logs,
+    ));
+    caches.schedule_values_update(L2BlockNumber(1));
+    storage
+        .rt_handle
+        .block_on(wait_for_warm_values_update(&warm_values, L2BlockNumber(1)));
+
+    // Modified values should be refreshed rather than evicted.
+    let new_l2_block_assertions = [
+        (modified_key, H256::repeat_byte(1)),
+        (unmodified_key, unmodified_value),
+        (non_existing_key, H256::repeat_byte(2)),
+    ];
+    for (key, value) in new_l2_block_assertions {
+        assert_eq!(
+            warm_values.get(L2BlockNumber(1), key.hashed_key()),
+            Some(value)
+        );
+    }
+    // Refreshed values must not be used for older L2 blocks.
+    assert_eq!(
+        warm_values.get(L2BlockNumber(0), modified_key.hashed_key()),
+        None
+    );
+    assert_eq!(
+        warm_values.get(L2BlockNumber(0), unmodified_key.hashed_key()),
+        Some(unmodified_value)
+    );
+
+    let mut storage = PostgresStorage::new(
+        storage.rt_handle,
+        storage.connection,
+        L2BlockNumber(0),
+        false,
+    )
+    .with_caches(caches.clone());
+    assert_eq!(storage.read_value(&modified_key), initial_value);
+    assert_eq!(storage.read_value(&non_existing_key), H256::zero());
+
+    stop_sender.send_replace(true);
+    storage
+        .rt_handle
+        .block_on(update_task_handle)
+        .expect("update task panicked")
+        .unwrap();
+    // All handles to the warm storage are dropped on return, so that it can be reopened.
+    new_l2_block_assertions
+}
+
+#[tokio::test]
+async fn using_warm_values_storage() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let db_path = temp_dir.path().to_owned();
+    let handle = Handle::current();
+    tokio::task::spawn_blocking(move || test_warm_values_storage(&pool, handle, db_path))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn interleaving_warm_values_inserts_and_updates() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut connection = pool.connection().await.unwrap();
+    prepare_postgres(&mut connection).await;
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let warm_values = WarmValuesStorage::new(temp_dir.path().to_owned())
+        .await
+        .unwrap();
+    warm_values
+        .reset(L2BlockNumber(0), &mut connection)
+        .await
+        .unwrap();
+
+    let logs = gen_storage_logs(0..20);
+    let stored_key = logs[0].key.hashed_key();
+    let modified_key = logs[1].key.hashed_key();
+    let unmodified_key = logs[2].key.hashed_key();
+    warm_values.insert(L2BlockNumber(0), stored_key, logs[0].value);
+    warm_values.flush().unwrap();
+    // Values read by the VM for L2 block #0 before the update has completed, including one for a key
+    // not yet stored in RocksDB, but modified in L2 block #1.
+    warm_values.insert(L2BlockNumber(0), modified_key, logs[1].value);
+    warm_values.insert(L2BlockNumber(0), unmodified_key, logs[2].value);
+    assert_eq!(
+        warm_values.get(L2BlockNumber(0), modified_key),
+        Some(logs[1].value)
+    );
+
+    let new_logs = vec![
+        StorageLog::new_write_log(logs[0].key, H256::repeat_byte(1)),
+        StorageLog::new_write_log(logs[1].key, H256::repeat_byte(2)),
+    ];
+    create_l2_block(&mut connection, L2BlockNumber(1), new_logs).await;
+    warm_values
+        .update(
+            L2BlockNumber(0),
+            L2BlockNumber(1),
+            &[stored_key, modified_key],
+            &mut connection,
+        )
+        .await
+        .unwrap();
+    // A value inserted for the previous L2 block after the update must be ignored.
+    warm_values.insert(L2BlockNumber(0), modified_key, logs[1].value);
+
+    assert_eq!(
+        warm_values.get(L2BlockNumber(1), stored_key),
+        Some(H256::repeat_byte(1))
+    );
+    assert_eq!(warm_values.get(L2BlockNumber(1), modified_key), None);
+    assert_eq!(
+        warm_values.get(L2BlockNumber(1), unmodified_key),
+        Some(logs[2].value)
+    );
+    assert_eq!(warm_values.get(L2BlockNumber(0), stored_key), None);
+
+    // Buffered values should be persisted on flush and survive reopening the storage.
+    warm_values.insert(L2BlockNumber(1), modified_key, H256::repeat_byte(2));
+    warm_values.flush().unwrap();
+    drop(warm_values);
+    let warm_values = WarmValuesStorage::new(temp_dir.path().to_owned())
+        .await
+        .unwrap();
+    let restored_l2_block = warm_values.restore(&mut connection).await.unwrap();
+    assert_eq!(restored_l2_block, Some(L2BlockNumber(1)));
+    for (hashed_key, value) in [
+        (stored_key, H256::repeat_byte(1)),
+        (modified_key, H256::repeat_byte(2)),
+        (unmodified_key, logs[2].value),
+    ] {
+        assert_eq!(warm_values.get(L2BlockNumber(1), hashed_key), Some(value));
+    }
+}
+
 /// (Sort of) fuzzes [`ValuesCache`] by comparing outputs of [`PostgresStorage`] with and without caching
 /// on randomly generated `read_value()` queries.
 fn mini_fuzz_values_cache_inner(
@@ -0,0 +1,442 @@
+//! RocksDB-backed warm layer for the VM storage values cache.
+//!
+//! ## Storage layout
+//!
+//! | Column | Key                | Value                                       | Description                                  |
+//! | ------ | ------------------ | ------------------------------------------- | -------------------------------------------- |
+//! | Meta   | 'valid_for'        | L2 block hash ++ L2 block number (u32, LE)  | L2 block the stored values are valid for     |
+//! | Values | hashed `StorageKey`| 32 bytes value ++ L2 block number (u32, LE) | Storage value and the L2 block it's valid from |
+
+use std::{
+    collections::{HashMap, HashSet},
+    mem,
+    path::PathBuf,
+    sync::{Arc, Mutex, RwLock},
+};
+
+use anyhow::Context as _;
+use zksync_dal::{Connection, Core, CoreDal};
+use zksync_storage::{
+    db::{NamedColumnFamily, WriteBatch},
+    RocksDB,
+};
+use zksync_types::{L2BlockNumber, StorageValue, H256};
+
+use super::metrics::{ValuesUpdateStage, CACHE_METRICS};
+
+const VALID_FOR_KEY: &[u8] = b"valid_for";
+/// Maximum number of inserted values buffered in memory between updates. Values inserted after the buffer
+/// is full are dropped.
+const MAX_PENDING_VALUES: usize = 100_000;
+
+#[derive(Debug, Clone, Copy)]
+enum WarmValuesColumnFamily {
+    Meta,
+    Values,
+}
+
+impl NamedColumnFamily for WarmValuesColumnFamily {
+    const DB_NAME: &'static str = "api_warm_values";
+    const ALL: &'static [Self] = &[Self::Meta, Self::Values];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Meta => "meta",
+            Self::Values => "values",
+        }
+    }
+}
+
+fn serialize_value(value: StorageValue, loaded_at: L2BlockNumber) -> [u8; 36] {
+    let mut buffer = [0_u8; 36];
+    buffer[..32].copy_from_slice(value.as_bytes());
+    buffer[32..].copy_from_slice(&loaded_at.0.to_le_bytes());
+    buffer
+}
+
+fn deserialize_value(bytes: &[u8]) -> (StorageValue, L2BlockNumber) {
+    assert_eq!(bytes.len(), 36, "incorrect warm value format");
+    let loaded_at = u32::from_le_bytes(bytes[32..].try_into().unwrap());
+    (H256::from_slice(&bytes[..32]), L2BlockNumber(loaded_at))
+}
+
+fn serialize_valid_for(l2_block_number: L2BlockNumber, l2_block_hash: H256) -> [u8; 36] {
+    serialize_value(l2_block_hash, l2_block_number)
+}
+
+/// Persistent, disk-backed counterpart of the in-memory values cache. Unlike the in-memory cache,
+/// it is not bounded by RAM and survives server restarts, so it can hold the entire working set
+/// of storage slots read by the API sandbox (e.g., for hot DEX pools and tokens).
+///
+/// The storage holds values for a single VM storage snapshot, like the in-memory values cache. It's updated
+/// by [`PostgresStorageCachesTask`](super::PostgresStorageCachesTask) in lockstep with the in-memory cache;
+/// unlike the in-memory cache, values modified in new L2 blocks are *refreshed* rather than evicted,
+/// so that slots modified in every L2 block still don't require Postgres reads.
+///
+/// Stored values are only used after the update task has verified that the snapshot persisted
+/// in RocksDB is consistent with Postgres (i.e., the L2 block it corresponds to wasn't reverted).
+///
+/// Values inserted by the VM are buffered in memory and are only written to RocksDB by the update task,
+/// so that reading storage doesn't block on RocksDB writes. Buffered values not flushed before the task
+/// is stopped are lost.
+#[derive(Debug, Clone)]
+pub struct WarmValuesStorage {
+    db: RocksDB<WarmValuesColumnFamily>,
+    /// `None` until the snapshot is verified by the update task.
+    valid_for: Arc<RwLock<Option<L2BlockNumber>>>,
+    /// Values inserted for the current `valid_for` L2 block, but not yet written to RocksDB. Must only be accessed
+    /// while holding the `valid_for` lock.
+    pending_values: Arc<Mutex<HashMap<H256, StorageValue>>>,
+}
+
+impl WarmValuesStorage {
+    /// Opens the storage at the specified path, creating it if necessary.
+    ///
+    /// # Errors
+    ///
+    /// Propagates RocksDB I/O errors.
+    pub async fn new(path: PathBuf) -> anyhow::Result<Self> {
+        tracing::info!("Opening warm storage values cache at `{}`", path.display());
+        let db = tokio::task::spawn_blocking(move || RocksDB::new(&path))
+            .await
+            .context("panicked opening warm storage values cache")?
+            .context("failed opening warm storage values cache")?;
+        Ok(Self {
+            db,
+            valid_for: Arc::default(),
+            pending_values: Arc::default(),
+        })
+    }
+
+    pub(super) fn valid_for(&self) -> Option<L2BlockNumber> {
+        *self
+            .valid_for
+            .read()
+            .expect("warm values storage is poisoned")
+    }
+
+    /// Gets the stored value for `hashed_key` provided that the storage currently holds values
+    /// for `l2_block_number`.
+    pub(super) fn get(
+        &self,
+        l2_block_number: L2BlockNumber,
+        hashed_key: H256,
+    ) -> Option<StorageValue> {
+        let lock = self
+            .valid_for
+            .read()
+            .expect("warm values storage is poisoned");
+        let valid_for = (*lock)?;
+        if valid_for < l2_block_number {
+            return None;
+        }
+        if valid_for == l2_block_number {
+            let pending_values = self
+                .pending_values
+                .lock()
+                .expect("warm values storage is poisoned");
+            if let Some(&value) = pending_values.get(&hashed_key) {
+                return Some(value);
+            }
+        }
+        let bytes = self
+            .db
+            .get_cf(WarmValuesColumnFamily::Values, hashed_key.as_bytes())
+            .expect("failed reading from warm storage values cache")?;
+        drop(lock);
+
+        let (value, loaded_at) = deserialize_value(&bytes);
+        (loaded_at <= l2_block_number).then_some(value)
+    }
+
+    /// Stores `value` for `hashed_key`, but only if the storage currently holds values for `l2_block_number`.
+    /// The value is buffered in memory and persisted on the next [update](Self::update()).
+    pub(super) fn insert(
+        &self,
+        l2_block_number: L2BlockNumber,
+        hashed_key: H256,
+        value: StorageValue,
+    ) {
+        let lock = self
+            .valid_for
+            .read()
+            .expect("warm values storage is poisoned");
+        if *lock == Some(l2_block_number) {
+            let mut pending_values = self
+                .pending_values
+                .lock()
+                .expect("warm values storage is poisoned");
+            if pending_values.len() < MAX_PENDING_VALUES {
+                pending_values.insert(hashed_key, value);
+            }
+        } else {
+            CACHE_METRICS.stale_values.inc();
+        }
+    }
+
+    /// Checks the persisted snapshot against Postgres. Returns the L2 block the snapshot is valid for,
+    /// or `None` if the snapshot is missing or inconsistent (in which case, the caller is expected to
+    /// [reset](Self::reset()) the storage).
+    pub(super) async fn restore(
+        &self,
+        connection: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<Option<L2BlockNumber>> {
+        let Some(raw_valid_for) = self
+            .db
+            .get_cf(WarmValuesColumnFamily::Meta, VALID_FOR_KEY)
+            .context("failed reading warm storage values cache metadata")?
+        else {
+            return Ok(None);
+        };
+        let (persisted_hash, valid_for) = deserialize_value(&raw_valid_for);
+
+        let header = connection
+            .blocks_dal()
+            .get_l2_block_header(valid_for)
+            .await?;
+        if header.map_or(true, |header| header.hash != persisted_hash) {
+            tracing::info!(
+                "Warm storage values cache is valid for L2 block #{valid_for} with hash {persisted_hash:?}, \
+                 which is not present in Postgres; the cache will be reset"
+            );
+            return Ok(None);
+        }
+
+        tracing::info!("Restored warm storage values cache valid for L2 block #{valid_for}");
+        *self
+            .valid_for
+            .write()
+            .map_err(|_| anyhow::anyhow!("warm values storage is poisoned"))? = Some(valid_for);
+        CACHE_METRICS
+            .warm_values_valid_for_miniblock
+            .set(u64::from(valid_for.0));
+        Ok(Some(valid_for))
+    }
+
+    async fn load_l2_block_hash(
+        connection: &mut Connection<'_, Core>,
+        l2_block_number: L2BlockNumber,
+    ) -> anyhow::Result<Option<H256>> {
+        let header = connection
+            .blocks_dal()
+            .get_l2_block_header(l2_block_number)
+            .await?;
+        Ok(header.map(|header| header.hash))
+    }
+
+    /// Removes all stored values and makes the storage valid for `to_l2_block`.
+    pub(super) async fn reset(
+        &self,
+        to_l2_block: L2BlockNumber,
+        connection: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<()> {
+        let l2_block_hash = Self::load_l2_block_hash(connection, to_l2_block).await?;
+
+        let mut lock = self
+            .valid_for
+            .write()
+            .map_err(|_| anyhow::anyhow!("warm values storage is poisoned"))?;
+        let mut batch = self.db.new_write_batch();
+        batch.delete_range_cf(
+            WarmValuesColumnFamily::Values,
+            &[0_u8; 32][..]..&[0xff_u8; 33][..],
+        );
+        Self::put_valid_for(&mut batch, to_l2_block, l2_block_hash);
+        self.db
+            .write(batch)
+            .context("failed resetting warm storage values cache")?;
+        self.pending_values
+            .lock()
+            .map_err(|_| anyhow::anyhow!("warm values storage is poisoned"))?
+            .clear();
+        *lock = Some(to_l2_block);
+        drop(lock);
+
+        CACHE_METRICS.warm_values_emptied.inc();
+        CACHE_METRICS
+            .warm_values_valid_for_miniblock
+            .set(u64::from(to_l2_block.0));
+        Ok(())
+    }
+
+    fn put_valid_for(
+        batch: &mut WriteBatch<'_, WarmValuesColumnFamily>,
+        l2_block_number: L2BlockNumber,
+        l2_block_hash: Option<H256>,
+    ) {
+        if let Some(hash) = l2_block_hash {
+            batch.put_cf(
+                WarmValuesColumnFamily::Meta,
+                VALID_FOR_KEY,
+                &serialize_valid_for(l2_block_number, hash),
+            );
+        } else {
+            // Shouldn't happen in practice; without the hash, we cannot verify the snapshot on restart.
+            batch.delete_cf(WarmValuesColumnFamily::Meta, VALID_FOR_KEY);
+        }
+    }
+
+    /// Writes buffered values to RocksDB. Called by the update task before it stops.
+    pub(super) fn flush(&self) -> anyhow::Result<()> {
+        let lock = self
+            .valid_for
+            .write()
+            .map_err(|_| anyhow::anyhow!("warm values storage is poisoned"))?;
+        let Some(valid_for) = *lock else {
+            return Ok(());
+        };
+        let mut batch = self.db.new_write_batch();
+        self.take_pending_values(&mut batch, valid_for, &HashSet::new())?;
+        self.db
+            .write(batch)
+            .context("failed flushing warm storage values cache")?;
+        Ok(())
+    }
+
+    /// Moves buffered values to `batch`, skipping `skipped_keys`. Must be called while holding the `valid_for`
+    /// write lock.
+    fn take_pending_values(
+        &self,
+        batch: &mut WriteBatch<'_, WarmValuesColumnFamily>,
+        valid_for: L2BlockNumber,
+        skipped_keys: &HashSet<H256>,
+    ) -> anyhow::Result<()> {
+        let pending_values = mem::take(
+            &mut *self
+                .pending_values
+                .lock()
+                .map_err(|_| anyhow::anyhow!("warm values storage is poisoned"))?,
+        );
+        for (hashed_key, value) in pending_values {
+            if !skipped_keys.contains(&hashed_key) {
+                batch.put_cf(
+                    WarmValuesColumnFamily::Values,
+                    hashed_key.as_bytes(),
+                    &serialize_value(value, valid_for),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Moves the storage from `from_l2_block` to `to_l2_block`. `modified_keys` must contain all keys modified
+    /// in this L2 block range (it may contain other keys as well). Stored values for modified keys are
+    /// reloaded from Postgres; buffered values for modified keys are discarded.
+    pub(super) async fn update(
+        &self,
+        from_l2_block: L2BlockNumber,
+        to_l2_block: L2BlockNumber,
+        modified_keys: &[H256],
+        connection: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<()> {
+        let update_latency =
+            CACHE_METRICS.values_update[&ValuesUpdateStage::RefreshWarmKeys].start();
+        let raw_keys = modified_keys.iter().map(|key| key.as_bytes().to_vec());
+        let stored_keys: Vec<_> = self
+            .db
+            .multi_get_cf(WarmValuesColumnFamily::Values, raw_keys)
+            .into_iter()
+            .zip(modified_keys)
+            .filter_map(|(value, key)| match value {
+                Ok(Some(_)) => Some(Ok(*key)),
+                Ok(None) => None,
+                Err(err) => Some(Err(err)),
+            })
+            .collect::<Result<_, _>>()
+            .context("failed reading from warm storage values cache")?;
+
+        let refreshed_values = if stored_keys.is_empty() {
+            Default::default()
+        } else {
+            connection
+                .storage_logs_dal()
+                .get_storage_values(&stored_keys, to_l2_block)
+                .await?
+        };
+        let l2_block_hash = Self::load_l2_block_hash(connection, to_l2_block).await?;
+
+        let mut batch = self.db.new_write_batch();
+        for (hashed_key, value) in &refreshed_values {
+            batch.put_cf(
+                WarmValuesColumnFamily::Values,
+                hashed_key.as_bytes(),
+                &serialize_value(value.unwrap_or_default(), to_l2_block),
+            );
+        }
+        // Any value for a modified key that wasn't refreshed is outdated for `to_l2_block`. Buffered values
+        // for such keys are discarded below; stored ones are removed in the same write batch.
+        let modified_keys: HashSet<_> = modified_keys.iter().copied().collect();
+        for hashed_key in &modified_keys {
+            if !refreshed_values.contains_key(hashed_key) {
+                batch.delete_cf(WarmValuesColumnFamily::Values, hashed_key.as_bytes());
+            }
+        }
+        Self::put_valid_for(&mut batch, to_l2_block, l2_block_hash);
+
+        let mut lock = self
+            .valid_for
+            .write()
+            .map_err(|_| anyhow::anyhow!("warm values storage is poisoned"))?;
+        anyhow::ensure!(
+            *lock == Some(from_l2_block),
+            "sanity check failed: warm values storage was expected to be valid for L2 block #{from_l2_block}, \
+             but it's actually valid for {:?}",
+            *lock
+        );
+        self.take_pending_values(&mut batch, from_l2_block, &modified_keys)?;
+        self.db
+            .write(batch)
+            .context("failed updating warm storage values cache")?;
+        *lock = Some(to_l2_block);
+        drop(lock);
+        update_latency.observe();
+
+        tracing::debug!(
+            "Refreshed {} stored values in warm storage values cache for L2 block #{to_l2_block}",
+            refreshed_values.len()
+        );
+        CACHE_METRICS
+            .warm_values_refreshed_keys
+            .observe(refreshed_values.len());
+        CACHE_METRICS
+            .warm_values_valid_for_miniblock
+            .set(u64::from(to_l2_block.0));
+        CACHE_METRICS.warm_values_len.set(
+            self.db
+                .estimated_number_of_entries(WarmValuesColumnFamily::Values),
+        );
+        Ok(())
+    }
+
+    /// Moves the storage from `from_l2_block` to `to_l2_block` by loading modified keys from Postgres
+    /// in chunks of at most `max_l2_blocks_per_update` L2 blocks. Used instead of resetting the storage
+    /// when it lags too far behind (e.g., after a server restart), so that stored values are retained.
+    /// Catching up takes time proportional to the lag; values for the latest L2 blocks are not served until it completes.
+    pub(super) async fn catch_up(
+        &self,
+        from_l2_block: L2BlockNumber,
+        to_l2_block: L2BlockNumber,
+        max_l2_blocks_per_update: u32,
+        connection: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<()> {
+        tracing::info!(
+            "Catching up warm storage values cache from L2 block #{from_l2_block} to #{to_l2_block}"
+        );
+        let mut current_l2_block = from_l2_block;
+        while current_l2_block < to_l2_block {
+            let next_l2_block = current_l2_block
+                .0
+                .saturating_add(max_l2_blocks_per_update.max(1))
+                .min(to_l2_block.0);
+            let next_l2_block = L2BlockNumber(next_l2_block);
+            let modified_keys = connection
+                .storage_logs_dal()
+                .modified_keys_in_l2_blocks((current_l2_block + 1)..=next_l2_block)
+                .await?;
+            self.update(current_l2_block, next_l2_block, &modified_keys, connection)
+                .await?;
+            current_l2_block = next_l2_block;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,316 @@
+//! Periodic compaction of [`RocksdbStorage`].
+
+use std::{num::NonZeroU32, time::Duration};
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_storage::{
+    db::{NamedColumnFamily, WriteBatch},
+    RocksDB,
+};
+use zksync_types::{L1BatchNumber, H256};
+
+use super::{
+    deserialize_l1_batch_number, metrics::COMPACTION_METRICS, serialize_l1_batch_number,
+    RocksdbStorage, StateKeeperColumnFamily, StateValue,
+};
+
+/// Statistics returned by [`RocksdbStorageCompactor::compact_if_needed()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RocksdbCompactionStats {
+    /// L1 batch number the storage was at when it was compacted (i.e., the last processed L1 batch + 1).
+    pub l1_batch_number: L1BatchNumber,
+    /// Number of obsolete special keys removed from the storage.
+    pub removed_keys: u64,
+    /// Number of removed storage entries with initial writes after the L1 batch the storage is at.
+    pub removed_initial_writes: u64,
+    /// Total size of SST files in all column families before compaction, in bytes.
+    pub size_before: u64,
+    /// Total size of SST files in all column families after compaction, in bytes.
+    pub size_after: u64,
+}
+
+impl RocksdbCompactionStats {
+    /// Returns the number of bytes reclaimed by compaction.
+    pub fn reclaimed_bytes(&self) -> u64 {
+        self.size_before.saturating_sub(self.size_after)
+    }
+}
+
+/// Obsolete entries found in the state column family of the storage.
+#[derive(Debug, Default)]
+struct ObsoleteEntries {
+    /// Non-hashed keys that are not used by the current storage version (e.g., the enumeration index migration cursor).
+    keys: Vec<Box<[u8]>>,
+    /// Hashed keys together with their enumeration indices exceeding the threshold provided to the scan.
+    initial_writes: Vec<(H256, u64)>,
+}
+
+/// Periodic compactor of a [`RocksdbStorage`] cache (e.g., the state keeper cache or a VM runner cache).
+///
+/// The cache only stores the latest state, so unlike Postgres or the Merkle tree, it doesn't contain historical data
+/// that could be pruned for old L1 batches. Enumeration indices (i.e., initial writes data) for the processed L1 batches
+/// cannot be removed either since they are required to process repeated writes. Thus, the compactor removes
+/// obsolete data relative to the L1 batch the cache is at:
+///
+/// - Special keys not used by the current storage version.
+/// - Initial writes made after the L1 batch the cache is at according to Postgres (e.g., left after an interrupted
+///   revert or a Postgres restore). Such entries are not only obsolete, but make the cache treat initial writes
+///   as repeated ones.
+///
+/// Afterwards, the compactor forces a full compaction of all column families. RocksDB physically retains overwritten
+/// values and removed entries (e.g., after a revert) until they are compacted, which can take up a significant part
+/// of the disk space for keys that are frequently updated.
+///
+/// The storage is compacted once it has processed the configured number of L1 batches since the previous compaction.
+/// When run on a cache for the first time, the compactor only records the current L1 batch number, so that
+/// existing caches aren't compacted immediately after the compactor is enabled.
+#[derive(Debug, Clone)]
+pub struct RocksdbStorageCompactor {
+    db: RocksDB<StateKeeperColumnFamily>,
+    pool: ConnectionPool<Core>,
+    l1_batch_interval: NonZeroU32,
+    poll_interval: Duration,
+}
+
+impl RocksdbStorageCompactor {
+    const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+    /// Creates a compactor for the specified RocksDB instance. The instance will be compacted once it processes
+    /// `l1_batch_interval` L1 batches since the previous compaction. `pool` is used to determine initial writes
+    /// that are obsolete for the L1 batch the instance is at.
+    pub fn new(
+        db: RocksDB<StateKeeperColumnFamily>,
+        pool: ConnectionPool<Core>,
+        l1_batch_interval: NonZeroU32,
+    ) -> Self {
+        Self {
+            db,
+            pool,
+            l1_batch_interval,
+            poll_interval: Self::DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Sets the interval between checks whether the storage needs compaction. The default value is 1 minute.
+    #[must_use]
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    fn read_l1_batch_number(&self, key: &[u8]) -> anyhow::Result<Option<L1BatchNumber>> {
+        let bytes = self
+            .db
+            .get_cf(StateKeeperColumnFamily::State, key)
+            .context("failed reading L1 batch number from RocksDB")?;
+        Ok(bytes.map(|bytes| L1BatchNumber(deserialize_l1_batch_number(&bytes))))
+    }
+
+    /// Returns the L1 batch number the storage was at during the last compaction (or when the compactor was first run on it).
+    ///
+    /// # Errors
+    ///
+    /// Propagates RocksDB errors.
+    pub fn last_compacted_l1_batch(&self) -> anyhow::Result<Option<L1BatchNumber>> {
+        self.read_l1_batch_number(RocksdbStorage::LAST_COMPACTED_L1_BATCH_KEY)
+    }
+
+    /// Compacts the storage if it has processed enough L1 batches since the last compaction.
+    /// Compaction is performed on blocking threads and can take a long time for large storages.
+    ///
+    /// # Errors
+    ///
+    /// Propagates RocksDB and Postgres errors.
+    pub async fn compact_if_needed(&self) -> anyhow::Result<Option<RocksdbCompactionStats>> {
+        let this = self.clone();
+        let l1_batch_number = tokio::task::spawn_blocking(move || this.check_compaction())
+            .await
+            .context("panicked checking RocksDB storage compaction")??;
+        let Some(l1_batch_number) = l1_batch_number else {
+            return Ok(None);
+        };
+
+        let latency = COMPACTION_METRICS.duration.start();
+        let max_enumeration_index = self.max_enumeration_index(l1_batch_number).await?;
+        let this = self.clone();
+        let (mut obsolete_entries, l1_batch_after_scan) = tokio::task::spawn_blocking(move || {
+            let entries = this.scan_state(max_enumeration_index);
+            let l1_batch_number = this.read_l1_batch_number(RocksdbStorage::L1_BATCH_NUMBER_KEY)?;
+            anyhow::Ok((entries, l1_batch_number))
+        })
+        .await
+        .context("panicked scanning RocksDB storage")??;
+
+        // The storage may be updated concurrently with the scan, so new initial writes encountered by the scan
+        // must be filtered out. L1 batch number and state entries are updated atomically, so all entries seen
+        // by the scan correspond to L1 batches before `l1_batch_after_scan`.
+        let l1_batch_after_scan = l1_batch_after_scan.unwrap_or(l1_batch_number);
+        if l1_batch_after_scan != l1_batch_number {
+            let max_enumeration_index = self.max_enumeration_index(l1_batch_after_scan).await?;
+            obsolete_entries
+                .initial_writes
+                .retain(|&(_, index)| max_enumeration_index.is_some_and(|max| index > max));
+        }
+
+        let this = self.clone();
+        let stats =
+            tokio::task::spawn_blocking(move || this.compact(l1_batch_number, obsolete_entries))
+                .await
+                .context("panicked compacting RocksDB storage")??;
+        let latency = latency.observe();
+        COMPACTION_METRICS.observe(&stats);
+        tracing::info!("Compacted RocksDB storage in {latency:?}: {stats:?}");
+        Ok(Some(stats))
+    }
+
+    /// Returns the L1 batch number the storage is at if it needs compaction.
+    fn check_compaction(&self) -> anyhow::Result<Option<L1BatchNumber>> {
+        let Some(l1_batch_number) =
+            self.read_l1_batch_number(RocksdbStorage::L1_BATCH_NUMBER_KEY)?
+        else {
+            return Ok(None); // The storage is not initialized yet
+        };
+        let Some(last_compacted_l1_batch) = self.last_compacted_l1_batch()? else {
+            tracing::info!(
+                "RocksDB storage was not compacted before; recording L1 batch #{l1_batch_number} as the compaction starting point"
+            );
+            self.record_compaction(self.db.new_write_batch(), l1_batch_number)?;
+            return Ok(None);
+        };
+        let processed_l1_batches = l1_batch_number.0.saturating_sub(last_compacted_l1_batch.0);
+        Ok((processed_l1_batches >= self.l1_batch_interval.get()).then_some(l1_batch_number))
+    }
+
+    /// Returns the maximum enumeration index assigned before the specified L1 batch according to Postgres.
+    async fn max_enumeration_index(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<u64>> {
+        let Some(last_processed_l1_batch) = l1_batch_number.0.checked_sub(1) else {
+            return Ok(None);
+        };
+        let mut conn = self
+            .pool
+            .connection_tagged("state_keeper_cache_compaction")
+            .await?;
+        Ok(conn
+            .storage_logs_dedup_dal()
+            .max_enumeration_index_by_l1_batch(L1BatchNumber(last_processed_l1_batch))
+            .await?)
+    }
+
+    /// Scans the state column family for obsolete entries. Initial writes are only considered obsolete
+    /// if `max_enumeration_index` is known.
+    fn scan_state(&self, max_enumeration_index: Option<u64>) -> ObsoleteEntries {
+        let mut entries = ObsoleteEntries::default();
+        for (key, value) in self
+            .db
+            .from_iterator_cf(StateKeeperColumnFamily::State, &[])
+        {
+            if key.len() != 32 {
+                if !RocksdbStorage::is_special_key(&key) {
+                    entries.keys.push(key);
+                }
+                continue;
+            }
+
+            let Some(max_enumeration_index) = max_enumeration_index else {
+                continue;
+            };
+            if let Some(index) = StateValue::deserialize(&value).enum_index {
+                if index > max_enumeration_index {
+                    entries.initial_writes.push((H256::from_slice(&key), index));
+                }
+            }
+        }
+        entries
+    }
+
+    fn record_compaction(
+        &self,
+        mut batch: WriteBatch<'_, StateKeeperColumnFamily>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        batch.put_cf(
+            StateKeeperColumnFamily::State,
+            RocksdbStorage::LAST_COMPACTED_L1_BATCH_KEY,
+            &serialize_l1_batch_number(l1_batch_number.0),
+        );
+        self.db
+            .write(batch)
+            .context("failed recording RocksDB compaction")
+    }
+
+    fn total_size(&self) -> u64 {
+        StateKeeperColumnFamily::ALL
+            .iter()
+            .map(|&cf| self.db.total_sst_files_size(cf))
+            .sum()
+    }
+
+    fn compact(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        obsolete_entries: ObsoleteEntries,
+    ) -> anyhow::Result<RocksdbCompactionStats> {
+        let size_before = self.total_size();
+
+        let mut batch = self.db.new_write_batch();
+        let cf = StateKeeperColumnFamily::State;
+        for key in &obsolete_entries.keys {
+            tracing::info!(
+                "Removing obsolete key `{}` from RocksDB storage",
+                String::from_utf8_lossy(key)
+            );
+            batch.delete_cf(cf, key);
+        }
+        for &(hashed_key, index) in &obsolete_entries.initial_writes {
+            tracing::warn!(
+                "Removing initial write for key {hashed_key:?} with enumeration index {index} made after L1 batch #{}",
+                l1_batch_number.0.saturating_sub(1)
+            );
+            batch.delete_cf(cf, hashed_key.as_bytes());
+        }
+        self.record_compaction(batch, l1_batch_number)?;
+
+        for &cf in StateKeeperColumnFamily::ALL {
+            self.db
+                .compact_cf(cf)
+                .with_context(|| format!("failed compacting column family `{}`", cf.name()))?;
+        }
+
+        Ok(RocksdbCompactionStats {
+            l1_batch_number,
+            removed_keys: obsolete_entries.keys.len() as u64,
+            removed_initial_writes: obsolete_entries.initial_writes.len() as u64,
+            size_before,
+            size_after: self.total_size(),
+        })
+    }
+
+    /// Runs the compactor until a stop signal is received.
+    ///
+    /// # Errors
+    ///
+    /// Propagates RocksDB and Postgres errors.
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        tracing::info!(
+            "Starting RocksDB storage compaction with L1 batch interval {}",
+            self.l1_batch_interval
+        );
+        while !*stop_receiver.borrow_and_update() {
+            self.compact_if_needed().await?;
+
+            if tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, RocksDB storage compaction is shut down");
+        Ok(())
+    }
+}
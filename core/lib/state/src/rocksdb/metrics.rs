@@ -51,3 +51,39 @@ pub(super) struct RocksdbRecoveryMetrics {
 
 #[vise::register]
 pub(super) static RECOVERY_METRICS: vise::Global<RocksdbRecoveryMetrics> = vise::Global::new();
+
+/// Metrics for RocksDB storage compaction.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_state_keeper_secondary_storage_compaction")]
+pub(super) struct RocksdbCompactionMetrics {
+    /// Latency of a single compaction.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub duration: Histogram<Duration>,
+    /// Number of obsolete special keys removed during the last compaction.
+    pub removed_keys: Gauge<u64>,
+    /// Number of obsolete initial writes removed during the last compaction.
+    pub removed_initial_writes: Gauge<u64>,
+    /// Disk space reclaimed during the last compaction.
+    #[metrics(unit = Unit::Bytes)]
+    pub reclaimed_space: Gauge<u64>,
+    /// Total size of SST files after the last compaction.
+    #[metrics(unit = Unit::Bytes)]
+    pub size_after_compaction: Gauge<u64>,
+    /// L1 batch number the storage was at during the last compaction.
+    pub last_compacted_l1_batch: Gauge<u64>,
+}
+
+impl RocksdbCompactionMetrics {
+    pub fn observe(&self, stats: &super::RocksdbCompactionStats) {
+        self.removed_keys.set(stats.removed_keys);
+        self.removed_initial_writes
+            .set(stats.removed_initial_writes);
+        self.reclaimed_space.set(stats.reclaimed_bytes());
+        self.size_after_compaction.set(stats.size_after);
+        self.last_compacted_l1_batch
+            .set(stats.l1_batch_number.0.into());
+    }
+}
+
+#[vise::register]
+pub(super) static COMPACTION_METRICS: vise::Global<RocksdbCompactionMetrics> = vise::Global::new();
@@ -11,8 +11,9 @@
 //! | Column       | Key                             | Value                           | Description                               |
 //! | ------------ | ------------------------------- | ------------------------------- | ----------------------------------------- |
 //! | State        | 'block_number'                  | serialized block number         | Last processed L1 batch number (u32)      |
-//! | State        | 'enum_index_migration_cursor'   | serialized hashed key or empty  | Deprecated                                |
+//! | State        | 'enum_index_migration_cursor'   | serialized hashed key or empty  | Deprecated; removed on compaction         |
 //! |              |                                 | bytes                           |                                           |
+//! | State        | 'last_compacted_l1_batch'       | serialized block number         | L1 batch number at the last compaction    |
 //! | State        | hashed `StorageKey`             | 32 bytes value ++ 8 bytes index | State value for the given key             |
 //! |              |                                 |                    (big-endian) |                                           |
 //! | Contracts    | address (20 bytes)              | `Vec<u8>`                       | Contract contents                         |
@@ -35,10 +36,12 @@ use zksync_storage::{db::NamedColumnFamily, RocksDB, RocksDBOptions};
 use zksync_types::{L1BatchNumber, StorageKey, StorageValue, H256};
 use zksync_vm_interface::storage::ReadStorage;
 
+pub use self::compaction::{RocksdbCompactionStats, RocksdbStorageCompactor};
 #[cfg(test)]
 use self::tests::RocksdbStorageEventListener;
 use self::{metrics::METRICS, recovery::Strategy};
 
+mod compaction;
 mod metrics;
 mod recovery;
 #[cfg(test)]
@@ -273,17 +276,17 @@ impl RocksdbStorageBuilder {
 
 impl RocksdbStorage {
     const L1_BATCH_NUMBER_KEY: &'static [u8] = b"block_number";
-    #[allow(dead_code)]
-    const ENUM_INDEX_MIGRATION_CURSOR: &'static [u8] = b"enum_index_migration_cursor";
+    const LAST_COMPACTED_L1_BATCH_KEY: &'static [u8] = b"last_compacted_l1_batch";
 
     /// Desired size of log chunks loaded from Postgres during snapshot recovery.
     /// This is intentionally not configurable because chunks must be the same for the entire recovery
     /// (i.e., not changed after a node restart).
     const DESIRED_LOG_CHUNK_SIZE: u64 = 200_000;
 
-    #[allow(dead_code)]
+    /// Checks whether the provided non-hashed key in the state column family is used by the current storage version.
+    /// Other such keys are obsolete and are removed by [`RocksdbStorageCompactor`].
     fn is_special_key(key: &[u8]) -> bool {
-        key == Self::L1_BATCH_NUMBER_KEY || key == Self::ENUM_INDEX_MIGRATION_CURSOR
+        key == Self::L1_BATCH_NUMBER_KEY || key == Self::LAST_COMPACTED_L1_BATCH_KEY
     }
 
     /// Creates a new storage builder with the provided RocksDB `path`.
@@ -448,3 +448,117 @@ async fn recovery_fault_tolerance() {
         assert!(!storage.is_write_initial(&log.key));
     }
 }
+
+#[tokio::test]
+async fn compacting_rocksdb_storage() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    prepare_postgres(&mut conn).await;
+    let storage_logs = gen_storage_logs(20..40);
+    create_l2_block(&mut conn, L2BlockNumber(1), storage_logs[..10].to_vec()).await;
+    create_l1_batch(&mut conn, L1BatchNumber(1), &storage_logs[..10]).await;
+    let overwritten_logs: Vec<_> = storage_logs[..10]
+        .iter()
+        .map(|&log| StorageLog {
+            value: H256::repeat_byte(0xff),
+            ..log
+        })
+        .collect();
+    let mut new_logs = storage_logs[10..].to_vec();
+    new_logs.extend_from_slice(&overwritten_logs);
+    create_l2_block(&mut conn, L2BlockNumber(2), new_logs).await;
+    create_l1_batch(&mut conn, L1BatchNumber(2), &storage_logs[10..]).await;
+
+    let dir = TempDir::new().expect("cannot create temporary dir for state keeper");
+    let storage = sync_test_storage(&dir, &mut conn).await;
+    assert_eq!(storage.l1_batch_number().await, Some(L1BatchNumber(3)));
+
+    // The first run only records the starting point and doesn't compact the storage.
+    let compactor = RocksdbStorageCompactor::new(
+        storage.db.clone(),
+        pool.clone(),
+        NonZeroU32::new(2).unwrap(),
+    );
+    assert_eq!(compactor.last_compacted_l1_batch().unwrap(), None);
+    assert_eq!(compactor.compact_if_needed().await.unwrap(), None);
+    assert_eq!(
+        compactor.last_compacted_l1_batch().unwrap(),
+        Some(L1BatchNumber(3))
+    );
+    drop(compactor);
+
+    let more_logs = gen_storage_logs(50..60);
+    create_l2_block(&mut conn, L2BlockNumber(3), more_logs[..5].to_vec()).await;
+    create_l1_batch(&mut conn, L1BatchNumber(3), &more_logs[..5]).await;
+    drop(storage);
+    let storage = sync_test_storage(&dir, &mut conn).await;
+    assert_eq!(storage.l1_batch_number().await, Some(L1BatchNumber(4)));
+
+    // Emulate an obsolete key left by an old node version, and an initial write from a reverted L1 batch.
+    const MIGRATION_CURSOR_KEY: &[u8] = b"enum_index_migration_cursor";
+    let reverted_key = H256::repeat_byte(0xee);
+    let mut batch = storage.db.new_write_batch();
+    batch.put_cf(StateKeeperColumnFamily::State, MIGRATION_CURSOR_KEY, &[]);
+    batch.put_cf(
+        StateKeeperColumnFamily::State,
+        reverted_key.as_bytes(),
+        &StateValue::new(H256::repeat_byte(1), Some(1 << 40)).serialize(),
+    );
+    storage.db.write(batch).unwrap();
+
+    let compactor = RocksdbStorageCompactor::new(
+        storage.db.clone(),
+        pool.clone(),
+        NonZeroU32::new(2).unwrap(),
+    );
+    // Not enough L1 batches were processed since the starting point.
+    assert_eq!(compactor.compact_if_needed().await.unwrap(), None);
+    drop(compactor);
+
+    create_l2_block(&mut conn, L2BlockNumber(4), more_logs[5..].to_vec()).await;
+    create_l1_batch(&mut conn, L1BatchNumber(4), &more_logs[5..]).await;
+    drop(storage);
+    let mut storage = sync_test_storage(&dir, &mut conn).await;
+    assert_eq!(storage.l1_batch_number().await, Some(L1BatchNumber(5)));
+
+    let compactor = RocksdbStorageCompactor::new(
+        storage.db.clone(),
+        pool.clone(),
+        NonZeroU32::new(2).unwrap(),
+    );
+    let stats = compactor
+        .compact_if_needed()
+        .await
+        .unwrap()
+        .expect("storage was not compacted");
+    assert_eq!(stats.l1_batch_number, L1BatchNumber(5));
+    assert_eq!(stats.removed_keys, 1);
+    assert_eq!(stats.removed_initial_writes, 1);
+    assert_eq!(
+        compactor.last_compacted_l1_batch().unwrap(),
+        Some(L1BatchNumber(5))
+    );
+    assert!(storage
+        .db
+        .get_cf(StateKeeperColumnFamily::State, MIGRATION_CURSOR_KEY)
+        .unwrap()
+        .is_none());
+    assert!(storage
+        .db
+        .get_cf(StateKeeperColumnFamily::State, reverted_key.as_bytes())
+        .unwrap()
+        .is_none());
+    // The storage is not compacted again until it processes enough L1 batches.
+    assert_eq!(compactor.compact_if_needed().await.unwrap(), None);
+
+    // Compaction must not influence the storage contents.
+    assert_eq!(storage.l1_batch_number().await, Some(L1BatchNumber(5)));
+    for log in &overwritten_logs {
+        assert_eq!(storage.read_value(&log.key), log.value);
+        assert!(!storage.is_write_initial(&log.key));
+    }
+    for log in storage_logs[10..].iter().chain(&more_logs) {
+        assert_eq!(storage.read_value(&log.key), log.value);
+        assert!(!storage.is_write_initial(&log.key));
+    }
+}
@@ -479,6 +479,29 @@ impl<CF: NamedColumnFamily> RocksDB<CF> {
             .unwrap_or(0)
     }
 
+    /// Returns the total size of SST files for the specified column family in bytes.
+    pub fn total_sst_files_size(&self, cf: CF) -> u64 {
+        const ERROR_MSG: &str = "failed to get total SST files size";
+
+        let cf = self.column_family(cf);
+        self.inner
+            .db
+            .property_int_value_cf(cf, properties::TOTAL_SST_FILES_SIZE)
+            .expect(ERROR_MSG)
+            .unwrap_or(0)
+    }
+
+    /// Flushes memtables and compacts the entire key range of the specified column family, which physically removes
+    /// overwritten and deleted entries. This is a blocking operation that can take a long time for large DBs.
+    pub fn compact_cf(&self, cf: CF) -> Result<(), rocksdb::Error> {
+        let cf = self.column_family(cf);
+        self.inner.db.flush_cf(cf)?;
+        self.inner
+            .db
+            .compact_range_cf(cf, None::<&[u8]>, None::<&[u8]>);
+        Ok(())
+    }
+
     pub fn multi_get<K, I>(&self, keys: I) -> Vec<Result<Option<Vec<u8>>, rocksdb::Error>>
     where
         K: AsRef<[u8]>,
@@ -4,19 +4,28 @@ use serde_json::Value;
 use serde_with::{hex::Hex, serde_as};
 use strum::Display;
 use zksync_basic_types::{
+    bytecode::{BytecodeHash, BytecodeMarker},
     web3::{AccessList, Bytes, Index},
     Bloom, L1BatchNumber, SLChainId, H160, H256, H64, U256, U64,
 };
 use zksync_contracts::BaseSystemContractsHashes;
+use zksync_system_constants::{
+    SYSTEM_CONTEXT_CURRENT_L2_BLOCK_HASHES_POSITION, SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION,
+    SYSTEM_CONTEXT_CURRENT_TX_ROLLING_HASH_POSITION, SYSTEM_CONTEXT_STORED_L2_BLOCK_HASHES,
+};
 
 pub use crate::transaction_request::{
     Eip712Meta, SerializationTransactionError, TransactionRequest,
 };
 use crate::{
+    block::{unpack_block_info, L2BlockHasher},
+    commitment::L1BatchCommitmentComponents,
     debug_flat_call::{DebugCallFlat, ResultDebugCallFlat},
+    fee::Fee,
+    h256_to_u256,
     protocol_version::L1VerifierConfig,
     tee_types::TeeType,
-    Address, L2BlockNumber, ProtocolVersionId,
+    u256_to_h256, Address, L2BlockNumber, ProtocolVersionId,
 };
 
 pub mod en;
@@ -898,6 +907,125 @@ pub struct L1BatchDetails {
     pub base: BlockDetailsBase,
 }
 
+/// Information allowing to check that an L2 block belongs to an L1 batch committed to the settlement layer.
+///
+/// No data returned by the API should be trusted by itself. The check is performed as follows:
+///
+/// 1. The batch commitment is recomputed from `commitment_components` via [`L1BatchCommitmentComponents::verify()`]
+///    and compared with the commitment published by the commit transaction (`l1_batch.commit_tx_hash`).
+///    This makes `commitment_components` trusted, including the state root hash after the batch.
+/// 2. Values of the `SystemContext` storage slots returned by [`L2BlockInclusionProof::system_context_slots()`]
+///    are obtained via `zks_getProof` for the batch, and their Merkle proofs are verified against the state root hash.
+/// 3. The block is checked against these values via [`L2BlockInclusionProof::verify()`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockCommitmentInfo {
+    pub number: L2BlockNumber,
+    pub hash: H256,
+    pub l1_batch: L1BatchDetails,
+    pub commitment_components: L1BatchCommitmentComponents,
+    pub inclusion_proof: L2BlockInclusionProof,
+}
+
+/// Preimage of an L2 block hash (excluding the previous block hash) as computed by the VM.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L2BlockHashPreimage {
+    pub number: L2BlockNumber,
+    pub timestamp: u64,
+    /// Rolling hash of the block transactions; see [`L2BlockHasher::finalize()`] for details.
+    pub txs_rolling_hash: H256,
+}
+
+impl L2BlockHashPreimage {
+    pub fn hash(&self, prev_l2_block_hash: H256, protocol_version: ProtocolVersionId) -> H256 {
+        L2BlockHasher::hash(
+            self.number,
+            self.timestamp,
+            prev_l2_block_hash,
+            self.txs_rolling_hash,
+            protocol_version,
+        )
+    }
+}
+
+/// Proof that an L2 block is included into an L1 batch.
+///
+/// The proof is anchored at the last L2 block in the batch, the hash of which is derived from the `SystemContext`
+/// storage after the batch (the current block info, the transactions rolling hash and the stored hash
+/// of the previous block). Hashes of subsequent blocks are chained from the checked block up to the last block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L2BlockInclusionProof {
+    /// Hash preimages of L2 blocks following the checked block in the batch, up to and including the last block
+    /// in the batch. Empty if the checked block is the last one.
+    pub next_blocks: Vec<L2BlockHashPreimage>,
+}
+
+impl L2BlockInclusionProof {
+    fn last_block_number(&self, block_number: L2BlockNumber) -> L2BlockNumber {
+        self.next_blocks
+            .last()
+            .map_or(block_number, |block| block.number)
+    }
+
+    /// Returns `SystemContext` storage slots with values necessary to [verify](Self::verify()) this proof.
+    pub fn system_context_slots(&self, block_number: L2BlockNumber) -> [H256; 3] {
+        let last_block_number = self.last_block_number(block_number);
+        let prev_block_number = last_block_number.0.saturating_sub(1);
+        let prev_block_hash_slot = h256_to_u256(SYSTEM_CONTEXT_CURRENT_L2_BLOCK_HASHES_POSITION)
+            + U256::from(prev_block_number % SYSTEM_CONTEXT_STORED_L2_BLOCK_HASHES);
+        [
+            SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION,
+            SYSTEM_CONTEXT_CURRENT_TX_ROLLING_HASH_POSITION,
+            u256_to_h256(prev_block_hash_slot),
+        ]
+    }
+
+    /// Verifies that the L2 block with the specified number and hash is included into the batch.
+    ///
+    /// `system_context_values` are values of [`Self::system_context_slots()`] (in the same order) after the batch.
+    /// The caller is responsible for checking them against the trusted state root hash of the batch.
+    pub fn verify(
+        &self,
+        block_number: L2BlockNumber,
+        block_hash: H256,
+        protocol_version: ProtocolVersionId,
+        system_context_values: [H256; 3],
+    ) -> anyhow::Result<()> {
+        let mut number = block_number;
+        let mut hash = block_hash;
+        for block in &self.next_blocks {
+            anyhow::ensure!(
+                block.number == number + 1,
+                "non-sequential L2 block #{} following #{number}",
+                block.number
+            );
+            hash = block.hash(hash, protocol_version);
+            number = block.number;
+        }
+
+        let [block_info, txs_rolling_hash, prev_block_hash] = system_context_values;
+        let (last_block_number, last_block_timestamp) = unpack_block_info(h256_to_u256(block_info));
+        anyhow::ensure!(
+            last_block_number == u64::from(number.0),
+            "last L2 block in the batch is #{last_block_number} according to the state, but the proof ends at #{number}"
+        );
+        let expected_hash = L2BlockHasher::hash(
+            number,
+            last_block_timestamp,
+            prev_block_hash,
+            txs_rolling_hash,
+            protocol_version,
+        );
+        anyhow::ensure!(
+            hash == expected_hash,
+            "hash of the last L2 block #{number} in the batch {hash:?} differs from the one derived from the state: {expected_hash:?}"
+        );
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StorageProof {
@@ -967,6 +1095,108 @@ pub struct FeeHistory {
     pub l2_pubdata_price: Vec<U256>,
 }
 
+/// Detailed gas estimation output returned from `zks_estimateGasDetailed`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasEstimationDetails {
+    /// Estimated fee; same as returned by `zks_estimateFee`.
+    pub fee: Fee,
+    /// Whether the estimate was served from the cache of recent estimates. If set, other fields
+    /// describe the estimation process that has populated the cache.
+    pub cached: bool,
+    /// Number of pubdata bytes published by the transaction executed with the maximum gas limit.
+    pub pubdata_published: U64,
+    /// Gas charged for the published pubdata.
+    pub gas_charged_for_pubdata: U64,
+    /// Initial lower bound for the binary search (excluding the operator overhead).
+    pub initial_lower_bound: U64,
+    /// Initial upper bound for the binary search (excluding the operator overhead).
+    pub initial_upper_bound: U64,
+    /// Optimistic gas limit tested before the binary search, if any.
+    pub optimistic_gas_limit: Option<U64>,
+    /// Binary search iterations in the order they were performed.
+    pub iterations: Vec<GasEstimationIteration>,
+    /// Gas limit found by the binary search before scaling (excluding the operator overhead).
+    pub unscaled_gas_limit: U64,
+    /// Reason the final gas limit bound was chosen.
+    pub final_bound_reason: GasEstimationBoundReason,
+}
+
+/// Single binary search iteration in [`GasEstimationDetails`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasEstimationIteration {
+    /// Tested gas limit (excluding the operator overhead).
+    pub gas_limit: U64,
+    /// Whether the transaction succeeded with this gas limit.
+    pub success: bool,
+}
+
+/// Reason the final gas limit bound was chosen during gas estimation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GasEstimationBoundReason {
+    /// The optimistic gas limit succeeded, and no lower gas limit has succeeded afterwards.
+    OptimisticGasLimit,
+    /// The binary search has converged to a succeeding gas limit within the acceptable overestimation.
+    BinarySearch,
+    /// The initial upper bound was never tested, e.g. because all tested gas limits have failed.
+    InitialUpperBound,
+}
+
+/// Kind of the bytecode of a deployed contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BytecodeKind {
+    /// Native EraVM bytecode.
+    EraVm,
+    /// EVM bytecode executed by the EVM emulator.
+    Evm,
+}
+
+impl From<BytecodeMarker> for BytecodeKind {
+    fn from(marker: BytecodeMarker) -> Self {
+        match marker {
+            BytecodeMarker::EraVm => Self::EraVm,
+            BytecodeMarker::Evm => Self::Evm,
+        }
+    }
+}
+
+/// Information about the bytecode of a deployed contract returned from `zks_getContractBytecodeInfo`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractBytecodeInfo {
+    /// Bytecode kind.
+    pub kind: BytecodeKind,
+    /// Versioned bytecode hash as stored in the `AccountCodeStorage` system contract.
+    pub bytecode_hash: H256,
+    /// Bytecode length in bytes. For EVM bytecodes, this is the length of the raw (unpadded) bytecode,
+    /// i.e. the length of the bytecode returned from `eth_getCode`.
+    pub bytecode_len: U64,
+    /// Whether the contract constructor has finished executing.
+    pub is_constructed: bool,
+}
+
+impl ContractBytecodeInfo {
+    /// Parses information from a versioned bytecode hash.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the hash has an unknown bytecode marker.
+    pub fn new(bytecode_hash: H256) -> anyhow::Result<Self> {
+        let parsed_hash = BytecodeHash::try_from(bytecode_hash)?;
+        Ok(Self {
+            kind: parsed_hash.marker().into(),
+            bytecode_hash,
+            bytecode_len: (parsed_hash.len_in_bytes() as u64).into(),
+            // The second byte of the hash is set to 1 by the `ContractDeployer` system contract while the contract
+            // is being constructed.
+            is_constructed: bytecode_hash.as_bytes()[1] == 0,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1001,4 +1231,106 @@ mod tests {
         serde_json::from_str::<OldProtocolVersion>(&serde_json::to_string(&new_version).unwrap())
             .unwrap();
     }
+    #[test]
+    fn parsing_contract_bytecode_info() {
+        let era_vm_hash = BytecodeHash::for_bytecode(&[0; 64 + 32]).value();
+        let info = ContractBytecodeInfo::new(era_vm_hash).unwrap();
+        assert_eq!(info.kind, BytecodeKind::EraVm);
+        assert_eq!(info.bytecode_len, 96.into());
+        assert!(info.is_constructed);
+
+        let mut evm_hash = BytecodeHash::for_raw_evm_bytecode(&[1; 10]).value();
+        evm_hash.0[1] = 1; // mark the contract as being constructed
+        let info = ContractBytecodeInfo::new(evm_hash).unwrap();
+        assert_eq!(info.kind, BytecodeKind::Evm);
+        assert_eq!(info.bytecode_len, 10.into());
+        assert!(!info.is_constructed);
+
+        let serialized = serde_json::to_value(&info).unwrap();
+        assert_eq!(serialized["kind"], "evm");
+        assert_eq!(serialized["bytecodeLen"], "0xa");
+
+        ContractBytecodeInfo::new(H256::repeat_byte(0xff)).unwrap_err();
+    }
+
+    #[test]
+    fn verifying_l2_block_inclusion_proof() {
+        let protocol_version = ProtocolVersionId::latest();
+        let block_hash = H256::repeat_byte(1);
+        let next_blocks: Vec<_> = (11..=12)
+            .map(|number| L2BlockHashPreimage {
+                number: L2BlockNumber(number),
+                timestamp: number.into(),
+                txs_rolling_hash: H256::repeat_byte(number as u8),
+            })
+            .collect();
+        let prev_block_hash = next_blocks[0].hash(block_hash, protocol_version);
+        let last_block = &next_blocks[1];
+        let system_context_values = [
+            u256_to_h256(crate::block::pack_block_info(12, last_block.timestamp)),
+            last_block.txs_rolling_hash,
+            prev_block_hash,
+        ];
+
+        let proof = L2BlockInclusionProof { next_blocks };
+        let slots = proof.system_context_slots(L2BlockNumber(10));
+        assert_eq!(slots[0], SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION);
+        assert_eq!(
+            h256_to_u256(slots[2]),
+            h256_to_u256(SYSTEM_CONTEXT_CURRENT_L2_BLOCK_HASHES_POSITION) + U256::from(11)
+        );
+        proof
+            .verify(
+                L2BlockNumber(10),
+                block_hash,
+                protocol_version,
+                system_context_values,
+            )
+            .unwrap();
+
+        let err = proof
+            .verify(
+                L2BlockNumber(10),
+                H256::zero(),
+                protocol_version,
+                system_context_values,
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("differs"), "{err}");
+        let err = proof
+            .verify(
+                L2BlockNumber(9),
+                block_hash,
+                protocol_version,
+                system_context_values,
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("non-sequential"), "{err}");
+
+        let mut tampered_values = system_context_values;
+        tampered_values[0] = u256_to_h256(crate::block::pack_block_info(13, 13));
+        proof
+            .verify(
+                L2BlockNumber(10),
+                block_hash,
+                protocol_version,
+                tampered_values,
+            )
+            .unwrap_err();
+
+        // The last block in the batch is checked directly against the state.
+        let last_block_hash = last_block.hash(prev_block_hash, protocol_version);
+        let proof = L2BlockInclusionProof {
+            next_blocks: vec![],
+        };
+        assert_eq!(proof.system_context_slots(L2BlockNumber(12)), slots);
+        proof
+            .verify(
+                L2BlockNumber(12),
+                last_block_hash,
+                protocol_version,
+                system_context_values,
+            )
+            .unwrap();
+    }
 }
@@ -302,8 +302,7 @@ pub struct L1BatchAuxiliaryCommonOutput {
     protocol_version: ProtocolVersionId,
 }
 
-#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
-#[cfg_attr(test, derive(Serialize, Deserialize))]
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
 pub struct BlobHash {
     pub commitment: H256,
     pub linear_hash: H256,
@@ -511,19 +510,12 @@ impl L1BatchAuxiliaryOutput {
                 blob_hashes,
                 ..
             } => {
-                result.extend(system_logs_linear_hash.as_bytes());
-                result.extend(state_diffs_hash.as_bytes());
-                result.extend(
-                    aux_commitments
-                        .bootloader_initial_content_commitment
-                        .as_bytes(),
+                result = post_boojum_aux_output_bytes(
+                    *system_logs_linear_hash,
+                    *state_diffs_hash,
+                    aux_commitments,
+                    blob_hashes,
                 );
-                result.extend(aux_commitments.events_queue_commitment.as_bytes());
-
-                for b in blob_hashes {
-                    result.extend(b.linear_hash.as_bytes());
-                    result.extend(b.commitment.as_bytes());
-                }
             }
         }
 
@@ -542,6 +534,29 @@ impl L1BatchAuxiliaryOutput {
     }
 }
 
+fn post_boojum_aux_output_bytes(
+    system_logs_linear_hash: H256,
+    state_diffs_hash: H256,
+    aux_commitments: &AuxCommitments,
+    blob_hashes: &[BlobHash],
+) -> Vec<u8> {
+    let mut result = Vec::with_capacity(4 * 32 + blob_hashes.len() * 64);
+    result.extend(system_logs_linear_hash.as_bytes());
+    result.extend(state_diffs_hash.as_bytes());
+    result.extend(
+        aux_commitments
+            .bootloader_initial_content_commitment
+            .as_bytes(),
+    );
+    result.extend(aux_commitments.events_queue_commitment.as_bytes());
+
+    for b in blob_hashes {
+        result.extend(b.linear_hash.as_bytes());
+        result.extend(b.commitment.as_bytes());
+    }
+    result
+}
+
 /// Meta parameters for an L1 batch. They are the same for each L1 batch per run.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct L1BatchMetaParameters {
@@ -628,6 +643,21 @@ pub struct L1BatchCommitmentHash {
     pub commitment: H256,
 }
 
+impl L1BatchCommitmentHash {
+    fn new(pass_through_data: H256, meta_parameters: H256, aux_output: H256) -> Self {
+        let mut result = Vec::with_capacity(3 * 32);
+        result.extend_from_slice(pass_through_data.as_bytes());
+        result.extend_from_slice(meta_parameters.as_bytes());
+        result.extend_from_slice(aux_output.as_bytes());
+        Self {
+            pass_through_data,
+            aux_output,
+            meta_parameters,
+            commitment: H256::from_slice(&keccak256(&result)),
+        }
+    }
+}
+
 impl L1BatchCommitment {
     pub fn new(input: CommitmentInput) -> Self {
         let meta_parameters = L1BatchMetaParameters {
@@ -675,21 +705,44 @@ impl L1BatchCommitment {
     }
 
     pub fn hash(&self) -> L1BatchCommitmentHash {
-        let mut result = vec![];
-        let pass_through_data_hash = self.pass_through_data.hash();
-        result.extend_from_slice(pass_through_data_hash.as_bytes());
-        let metadata_hash = self.meta_parameters.hash();
-        result.extend_from_slice(metadata_hash.as_bytes());
-        let auxiliary_output_hash = self.auxiliary_output.hash();
-        result.extend_from_slice(auxiliary_output_hash.as_bytes());
-        let hash = keccak256(&result);
-        let commitment = H256::from_slice(&hash);
-        L1BatchCommitmentHash {
-            pass_through_data: pass_through_data_hash,
-            aux_output: auxiliary_output_hash,
-            meta_parameters: metadata_hash,
-            commitment,
-        }
+        L1BatchCommitmentHash::new(
+            self.pass_through_data.hash(),
+            self.meta_parameters.hash(),
+            self.auxiliary_output.hash(),
+        )
+    }
+
+    /// Returns components of this commitment sufficient to recompute its hash. Returns `None` for pre-Boojum batches,
+    /// since their auxiliary output isn't supported by [`L1BatchCommitmentComponents`].
+    pub fn components(
+        &self,
+        system_logs: Vec<SystemL2ToL1Log>,
+    ) -> Option<L1BatchCommitmentComponents> {
+        let L1BatchAuxiliaryOutput::PostBoojum {
+            state_diffs_hash,
+            aux_commitments,
+            blob_hashes,
+            ..
+        } = &self.auxiliary_output
+        else {
+            return None;
+        };
+        let rollup_state = &self.pass_through_data.shared_states[0];
+        Some(L1BatchCommitmentComponents {
+            rollup_root_hash: rollup_state.root_hash,
+            rollup_last_leaf_index: rollup_state.last_leaf_index,
+            zkporter_is_available: self.meta_parameters.zkporter_is_available,
+            bootloader_code_hash: self.meta_parameters.bootloader_code_hash,
+            default_aa_code_hash: self.meta_parameters.default_aa_code_hash,
+            evm_emulator_code_hash: self.meta_parameters.evm_emulator_code_hash,
+            protocol_version: self.meta_parameters.protocol_version,
+            system_logs,
+            state_diffs_hash: *state_diffs_hash,
+            bootloader_initial_content_commitment: aux_commitments
+                .bootloader_initial_content_commitment,
+            events_queue_commitment: aux_commitments.events_queue_commitment,
+            blob_hashes: blob_hashes.clone(),
+        })
     }
 
     pub fn artifacts(&self) -> L1BatchCommitmentArtifacts {
@@ -725,6 +778,146 @@ impl L1BatchCommitment {
     }
 }
 
+/// Components of an L1 batch commitment allowing to recompute it. Unlike [`CommitmentInput`], state diffs
+/// are represented by their hash only, so these components are compact enough to be served to light clients
+/// (e.g., wallets and bridges) via the API. Only post-Boojum batches are supported.
+///
+/// The commitment recomputed from these components must be compared with the commitment published
+/// on the settlement layer (e.g., in the `BlockCommit` event of the commit transaction). Once the commitment
+/// is verified, other components (e.g., the state root hash or [system logs](Self::system_logs)) can be trusted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BatchCommitmentComponents {
+    // Pass-through data
+    /// Root hash of the state Merkle tree after the batch.
+    pub rollup_root_hash: H256,
+    /// Last enumeration index in the state Merkle tree after the batch.
+    pub rollup_last_leaf_index: u64,
+    // Meta parameters
+    pub zkporter_is_available: bool,
+    pub bootloader_code_hash: H256,
+    pub default_aa_code_hash: H256,
+    pub evm_emulator_code_hash: Option<H256>,
+    pub protocol_version: Option<ProtocolVersionId>,
+    // Auxiliary output
+    /// System L2-to-L1 logs emitted in the batch (e.g., with the L2-to-L1 logs tree root and the previous batch hash).
+    pub system_logs: Vec<SystemL2ToL1Log>,
+    /// Hash of the packed state diffs produced by the batch.
+    pub state_diffs_hash: H256,
+    pub bootloader_initial_content_commitment: H256,
+    pub events_queue_commitment: H256,
+    /// Linear hashes and commitments of blobs used to publish the batch pubdata.
+    pub blob_hashes: Vec<BlobHash>,
+}
+
+impl L1BatchCommitmentComponents {
+    /// Assembles components from the persisted batch data. Returns `None` if the batch is pre-Boojum, or if some of
+    /// the necessary data is missing (e.g., for batches processed by old node versions).
+    pub fn new(
+        header: &L1BatchHeader,
+        metadata: &L1BatchMetadata,
+        blob_hashes: Vec<BlobHash>,
+    ) -> Option<Self> {
+        let protocol_version = header.protocol_version?;
+        if protocol_version.is_pre_boojum() {
+            return None;
+        }
+        // For pre-gateway batches, the state diffs hash may be not persisted, but it's always present in system logs.
+        let state_diffs_hash = match metadata.state_diff_hash {
+            Some(hash) => hash,
+            None if protocol_version.is_pre_gateway() => {
+                find_system_log_value(&header.system_logs, STATE_DIFF_HASH_KEY_PRE_GATEWAY)?
+            }
+            None => return None,
+        };
+
+        Some(Self {
+            rollup_root_hash: metadata.root_hash,
+            rollup_last_leaf_index: metadata.rollup_last_leaf_index,
+            zkporter_is_available: metadata.block_meta_params.zkporter_is_available,
+            bootloader_code_hash: metadata.block_meta_params.bootloader_code_hash,
+            default_aa_code_hash: metadata.block_meta_params.default_aa_code_hash,
+            evm_emulator_code_hash: metadata.block_meta_params.evm_emulator_code_hash,
+            protocol_version: metadata.block_meta_params.protocol_version,
+            system_logs: header.system_logs.clone(),
+            state_diffs_hash,
+            bootloader_initial_content_commitment: metadata.bootloader_initial_content_commitment?,
+            events_queue_commitment: metadata.events_queue_commitment?,
+            blob_hashes,
+        })
+    }
+
+    pub fn meta_parameters(&self) -> L1BatchMetaParameters {
+        L1BatchMetaParameters {
+            zkporter_is_available: self.zkporter_is_available,
+            bootloader_code_hash: self.bootloader_code_hash,
+            default_aa_code_hash: self.default_aa_code_hash,
+            evm_emulator_code_hash: self.evm_emulator_code_hash,
+            protocol_version: self.protocol_version,
+        }
+    }
+
+    /// Returns the value of the system log with the specified key, e.g. [`L2_TO_L1_LOGS_TREE_ROOT_KEY`].
+    pub fn system_log_value(&self, key: u32) -> Option<H256> {
+        find_system_log_value(&self.system_logs, key)
+    }
+
+    /// Recomputes the auxiliary output hash from its preimage.
+    pub fn aux_output_hash(&self) -> H256 {
+        let system_logs_linear_hash = H256(keccak256(&serialize_commitments(&self.system_logs)));
+        let aux_commitments = AuxCommitments {
+            events_queue_commitment: self.events_queue_commitment,
+            bootloader_initial_content_commitment: self.bootloader_initial_content_commitment,
+        };
+        let bytes = post_boojum_aux_output_bytes(
+            system_logs_linear_hash,
+            self.state_diffs_hash,
+            &aux_commitments,
+            &self.blob_hashes,
+        );
+        H256(keccak256(&bytes))
+    }
+
+    /// Recomputes the batch commitment from these components.
+    pub fn hash(&self) -> L1BatchCommitmentHash {
+        let pass_through_data = L1BatchPassThroughData {
+            shared_states: vec![
+                RootState {
+                    last_leaf_index: self.rollup_last_leaf_index,
+                    root_hash: self.rollup_root_hash,
+                },
+                RootState {
+                    last_leaf_index: 0,
+                    root_hash: H256::zero(),
+                },
+            ],
+        };
+        L1BatchCommitmentHash::new(
+            pass_through_data.hash(),
+            self.meta_parameters().hash(),
+            self.aux_output_hash(),
+        )
+    }
+
+    /// Checks that the commitment recomputed from these components is equal to `expected_commitment`, which should be
+    /// obtained from a trusted source (e.g., the settlement layer).
+    pub fn verify(&self, expected_commitment: H256) -> anyhow::Result<()> {
+        let commitment = self.hash().commitment;
+        anyhow::ensure!(
+            commitment == expected_commitment,
+            "recomputed commitment {commitment:?} differs from the expected one {expected_commitment:?}"
+        );
+        Ok(())
+    }
+}
+
+fn find_system_log_value(system_logs: &[SystemL2ToL1Log], key: u32) -> Option<H256> {
+    let key = u256_to_h256(key.into());
+    system_logs
+        .iter()
+        .find_map(|log| (log.0.key == key).then_some(log.0.value))
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(test, derive(Serialize, Deserialize))]
 pub struct AuxCommitments {
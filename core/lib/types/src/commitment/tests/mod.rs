@@ -17,6 +17,10 @@ fn run_test(test_name: &str) {
     let contents = read_to_string(format!("src/commitment/tests/{test_name}.json")).unwrap();
     let commitment_test: CommitmentTest = serde_json::from_str(&contents).unwrap();
 
+    let system_logs = match &commitment_test.input {
+        CommitmentInput::PreBoojum { .. } => vec![],
+        CommitmentInput::PostBoojum { system_logs, .. } => system_logs.clone(),
+    };
     let commitment = L1BatchCommitment::new(commitment_test.input);
 
     assert_eq!(
@@ -29,6 +33,30 @@ fn run_test(test_name: &str) {
         commitment_test.auxiliary_output
     );
     assert_eq!(commitment.hash(), commitment_test.hashes);
+
+    let Some(mut components) = commitment.components(system_logs) else {
+        assert!(commitment_test
+            .meta_parameters
+            .protocol_version
+            .unwrap()
+            .is_pre_boojum());
+        return;
+    };
+    assert_eq!(components.hash(), commitment_test.hashes);
+    components
+        .verify(commitment_test.hashes.commitment)
+        .unwrap();
+    components.verify(H256::zero()).unwrap_err();
+    assert_eq!(
+        components.system_log_value(L2_TO_L1_LOGS_TREE_ROOT_KEY),
+        Some(commitment.l2_l1_logs_merkle_root())
+    );
+
+    // Tampering with the auxiliary output preimage must be detected.
+    components.system_logs.pop().unwrap();
+    components
+        .verify(commitment_test.hashes.commitment)
+        .unwrap_err();
 }
 
 #[test]
@@ -224,6 +224,23 @@ pub struct CompilationArtifacts {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub deployed_bytecode: Option<Vec<u8>>,
     pub abi: serde_json::Value,
+    /// Locations of immutable variables in the deployed bytecode (`deployedBytecode.immutableReferences` in `solc` output).
+    /// Immutable values are only known after the constructor is executed, so they are zeroed in the compiled deployed bytecode.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub immutable_refs: Vec<ImmutableReference>,
+    /// Total length in bytes of immutable values appended to the deployed bytecode (based on `layout.code_layout`
+    /// in `vyper` output). Only set for EVM contracts compiled with `vyper`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub immutables_len: Option<usize>,
+}
+
+/// Location of an immutable variable value in the deployed EVM bytecode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImmutableReference {
+    /// Start offset in bytes.
+    pub start: usize,
+    /// Length in bytes.
+    pub length: usize,
 }
 
 impl CompilationArtifacts {
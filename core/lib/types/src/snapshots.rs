@@ -63,6 +63,10 @@ pub struct SnapshotHeader {
     /// Ordered by chunk IDs.
     pub storage_logs_chunks: Vec<SnapshotStorageLogsChunkMetadata>,
     pub factory_deps_filepath: String,
+    /// Root hash of the Merkle tree after the snapshot L1 batch. Can be used to verify the snapshot storage logs.
+    /// May be absent for snapshots created by older versions of the snapshot creator.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l1_batch_root_hash: Option<H256>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
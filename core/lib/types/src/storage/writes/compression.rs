@@ -185,6 +185,61 @@ pub fn compress_with_best_strategy(prev_value: U256, new_value: U256) -> Vec<u8>
         })
 }
 
+/// Value compressed using one of the strategies supported by [`compress_with_best_strategy()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressedValue {
+    /// Full 32-byte new value.
+    None(U256),
+    /// Difference to add to the previous value.
+    Add(U256),
+    /// Difference to subtract from the previous value.
+    Sub(U256),
+    /// New value that fits into fewer than 31 bytes.
+    Transform(U256),
+}
+
+impl CompressedValue {
+    /// Parses a compressed value (the metadata byte followed by the compressed bytes) from the start of `bytes`.
+    /// Returns the parsed value and the number of consumed bytes.
+    pub fn parse(bytes: &[u8]) -> anyhow::Result<(Self, usize)> {
+        let &metadata = bytes
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("missing compressed value metadata byte"))?;
+        let operation_id = metadata & 0b111;
+        let size = usize::from(metadata >> 3);
+        let size = if operation_id == 0 {
+            anyhow::ensure!(size == 0, "unexpected length {size} for uncompressed value");
+            32
+        } else {
+            size
+        };
+        let value_bytes = bytes.get(1..=size).ok_or_else(|| {
+            anyhow::anyhow!(
+                "compressed value is truncated: expected {size} bytes, got {}",
+                bytes.len() - 1
+            )
+        })?;
+        let value = U256::from_big_endian(value_bytes);
+        let value = match operation_id {
+            0 => Self::None(value),
+            1 => Self::Add(value),
+            2 => Self::Sub(value),
+            3 => Self::Transform(value),
+            _ => anyhow::bail!("unknown compression operation ID {operation_id}"),
+        };
+        Ok((value, size + 1))
+    }
+
+    /// Restores the new value given the previous value of the slot.
+    pub fn apply(self, prev_value: U256) -> U256 {
+        match self {
+            Self::None(value) | Self::Transform(value) => value,
+            Self::Add(diff) => prev_value.overflowing_add(diff).0,
+            Self::Sub(diff) => prev_value.overflowing_sub(diff).0,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::ops::{Add, BitAnd, Shr, Sub};
@@ -369,4 +424,38 @@ mod tests {
         assert!(compression_sub_strategy.compress_value_only().is_none());
         assert!(compression_sub_strategy.compress_extended().is_none());
     }
+
+    #[test]
+    fn parsing_compressed_values() {
+        let values = [
+            U256::zero(),
+            U256::from(1),
+            U256::from(255438218),
+            U256::from(255438638),
+            U256::MAX,
+            U256::MAX - 1,
+            U256::from(1) << 248,
+        ];
+        for prev_value in values {
+            for new_value in values {
+                let compressed = compress_with_best_strategy(prev_value, new_value);
+                let (parsed, len) = CompressedValue::parse(&compressed).unwrap();
+                assert_eq!(len, compressed.len());
+                assert_eq!(parsed.apply(prev_value), new_value, "{parsed:?}");
+            }
+        }
+
+        let (parsed, len) = CompressedValue::parse(&[0b1001, 1, 0xff]).unwrap();
+        assert_eq!(len, 2);
+        assert_eq!(parsed, CompressedValue::Add(U256::from(1)));
+
+        let err = CompressedValue::parse(&[0b10001, 1])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("truncated"), "{err}");
+        let err = CompressedValue::parse(&[0b1111, 1])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("unknown compression operation"), "{err}");
+    }
 }
@@ -3,6 +3,7 @@ use std::{convert::TryInto, fmt};
 use serde::{de, ser::SerializeTuple, Deserialize, Deserializer, Serialize, Serializer};
 use zksync_basic_types::{Address, U256};
 
+pub use self::compression::CompressedValue;
 pub(crate) use self::compression::{compress_with_best_strategy, COMPRESSION_VERSION_NUMBER};
 use crate::H256;
 
@@ -173,6 +174,79 @@ pub fn compress_state_diffs(mut state_diffs: Vec<StateDiffRecord>) -> Vec<u8> {
     prepend_header(res)
 }
 
+/// State diffs parsed from the representation produced by [`compress_state_diffs()`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompressedStateDiffs {
+    /// Initial writes: derived (hashed) keys and compressed values. Enumeration indices are assigned to the keys
+    /// in the order of this list.
+    pub initial_writes: Vec<(H256, CompressedValue)>,
+    /// Repeated writes: enumeration indices and compressed values.
+    pub repeated_writes: Vec<(u64, CompressedValue)>,
+}
+
+impl CompressedStateDiffs {
+    /// Parses compressed state diffs (including the header) from the start of `bytes`.
+    /// Returns the parsed diffs and the number of consumed bytes.
+    pub fn parse(bytes: &[u8]) -> anyhow::Result<(Self, usize)> {
+        anyhow::ensure!(
+            bytes.len() >= 5,
+            "compressed state diffs header is truncated"
+        );
+        anyhow::ensure!(
+            bytes[0] == COMPRESSION_VERSION_NUMBER,
+            "unsupported state diff compression version {}",
+            bytes[0]
+        );
+        let mut len_bytes = [0_u8; 4];
+        len_bytes[1..].copy_from_slice(&bytes[1..4]);
+        let len = u32::from_be_bytes(len_bytes) as usize;
+        anyhow::ensure!(
+            bytes[4] == BYTES_PER_ENUMERATION_INDEX,
+            "unsupported enumeration index size {}",
+            bytes[4]
+        );
+        let mut data = bytes.get(5..5 + len).ok_or_else(|| {
+            anyhow::anyhow!(
+                "compressed state diffs are truncated: expected {len} bytes, got {}",
+                bytes.len() - 5
+            )
+        })?;
+
+        anyhow::ensure!(data.len() >= 2, "missing initial writes count");
+        let initial_writes_count = u16::from_be_bytes([data[0], data[1]]);
+        data = &data[2..];
+
+        let key_len = usize::from(BYTES_PER_DERIVED_KEY);
+        let mut initial_writes = Vec::with_capacity(initial_writes_count.into());
+        for i in 0..initial_writes_count {
+            anyhow::ensure!(data.len() >= key_len, "initial write #{i} is truncated");
+            let derived_key = H256::from_slice(&data[..key_len]);
+            let (value, value_len) = CompressedValue::parse(&data[key_len..])
+                .map_err(|err| err.context(format!("failed parsing initial write #{i}")))?;
+            initial_writes.push((derived_key, value));
+            data = &data[key_len + value_len..];
+        }
+
+        let index_len = usize::from(BYTES_PER_ENUMERATION_INDEX);
+        let mut repeated_writes = vec![];
+        while !data.is_empty() {
+            let i = repeated_writes.len();
+            anyhow::ensure!(data.len() >= index_len, "repeated write #{i} is truncated");
+            let enumeration_index = u32::from_be_bytes(data[..index_len].try_into().unwrap());
+            let (value, value_len) = CompressedValue::parse(&data[index_len..])
+                .map_err(|err| err.context(format!("failed parsing repeated write #{i}")))?;
+            repeated_writes.push((enumeration_index.into(), value));
+            data = &data[index_len + value_len..];
+        }
+
+        let diffs = Self {
+            initial_writes,
+            repeated_writes,
+        };
+        Ok((diffs, 5 + len))
+    }
+}
+
 /// Adds the header to the beginning of the compressed state diffs so it can be used as part of the overall
 /// pubdata. Need to prepend: compression version || number of compressed state diffs || number of bytes used for
 /// enumeration index.
@@ -588,6 +662,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parsing_compressed_state_diffs() {
+        let state_diffs: Vec<_> = (0_u8..10)
+            .map(|i| StateDiffRecord {
+                address: Address::repeat_byte(i),
+                key: U256::from(i),
+                derived_key: [i; 32],
+                enumeration_index: if i % 3 == 0 { 0 } else { u64::from(i) * 1_000 },
+                initial_value: U256::from(i) * 100,
+                final_value: if i % 2 == 0 {
+                    U256::from(i) * 200
+                } else {
+                    U256::MAX - i
+                },
+            })
+            .collect();
+        let mut compressed = compress_state_diffs(state_diffs.clone());
+        let compressed_len = compressed.len();
+        compressed.extend([0xff; 10]); // trailing data must not be consumed
+
+        let (diffs, len) = CompressedStateDiffs::parse(&compressed).unwrap();
+        assert_eq!(len, compressed_len);
+        assert_eq!(diffs.initial_writes.len(), 4);
+        assert_eq!(diffs.repeated_writes.len(), 6);
+        for (derived_key, value) in &diffs.initial_writes {
+            let record = state_diffs
+                .iter()
+                .find(|rec| rec.derived_key == derived_key.0)
+                .unwrap();
+            assert!(record.is_write_initial());
+            assert_eq!(value.apply(record.initial_value), record.final_value);
+        }
+        for &(enumeration_index, value) in &diffs.repeated_writes {
+            let record = state_diffs
+                .iter()
+                .find(|rec| rec.enumeration_index == enumeration_index)
+                .unwrap();
+            assert_eq!(value.apply(record.initial_value), record.final_value);
+        }
+
+        let err = CompressedStateDiffs::parse(&compressed[..compressed_len - 1])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("truncated"), "{err}");
+    }
+
     #[test]
     fn check_tree_write_serde() {
         let tree_write = TreeWrite {
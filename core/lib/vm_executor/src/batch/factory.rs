@@ -16,7 +16,6 @@ use zksync_multivm::{
     is_supported_by_fast_vm,
     pubdata_builders::pubdata_params_to_builder,
     tracers::CallTracer,
-    vm_fast,
     vm_latest::HistoryEnabled,
     FastVmInstance, LegacyVmInstance, MultiVmTracer,
 };
@@ -26,7 +25,7 @@ use super::{
     executor::{Command, MainBatchExecutor},
     metrics::{TxExecutionStage, BATCH_TIP_METRICS, EXECUTOR_METRICS, KEEPER_METRICS},
 };
-use crate::shared::{InteractionType, Sealed, STORAGE_METRICS};
+use crate::shared::{FastVmTracer, InteractionType, Sealed, STORAGE_METRICS};
 
 /// Encapsulates a tracer used during batch processing. Currently supported tracers are `()` (no-op) and [`TraceCalls`].
 ///
@@ -37,7 +36,7 @@ pub trait BatchTracer: fmt::Debug + 'static + Send + Sealed {
     const TRACE_CALLS: bool;
     /// Tracer for the fast VM.
     #[doc(hidden)]
-    type Fast: vm_fast::interface::Tracer + Default;
+    type Fast: FastVmTracer;
 }
 
 impl Sealed for () {}
@@ -56,7 +55,7 @@ impl Sealed for TraceCalls {}
 
 impl BatchTracer for TraceCalls {
     const TRACE_CALLS: bool = true;
-    type Fast = (); // TODO: change once call tracing is implemented in fast VM
+    type Fast = CallTracer;
 }
 
 /// The default implementation of [`BatchExecutorFactory`].
@@ -221,23 +220,39 @@ impl<S: ReadStorage, Tr: BatchTracer> BatchVm<S, Tr> {
         };
         let mut legacy_tracer = legacy_tracer.into();
 
-        let (compression_result, tx_result) = match self {
-            Self::Legacy(vm) => vm.inspect_transaction_with_bytecode_compression(
-                &mut legacy_tracer,
-                tx,
-                with_compression,
-            ),
+        let (compression_result, tx_result, fast_call_traces) = match self {
+            Self::Legacy(vm) => {
+                let (compression_result, tx_result) = vm
+                    .inspect_transaction_with_bytecode_compression(
+                        &mut legacy_tracer,
+                        tx,
+                        with_compression,
+                    );
+                (compression_result, tx_result, vec![])
+            }
             Self::Fast(vm) => {
                 let mut tracer = (legacy_tracer.into(), Default::default());
-                vm.inspect_transaction_with_bytecode_compression(&mut tracer, tx, with_compression)
+                let (compression_result, tx_result) = vm
+                    .inspect_transaction_with_bytecode_compression(
+                        &mut tracer,
+                        tx,
+                        with_compression,
+                    );
+                let (fast_tracer, ()) = tracer.1;
+                (
+                    compression_result,
+                    tx_result,
+                    fast_tracer.into_call_traces(),
+                )
             }
         };
 
         let compressed_bytecodes = compression_result.map(Cow::into_owned);
+        // If the fast VM is shadowed, call traces are collected by both VMs; we use ones from the main (legacy) VM.
         let call_traces = Arc::try_unwrap(call_tracer_result)
             .expect("failed extracting call traces")
             .take()
-            .unwrap_or_default();
+            .unwrap_or(fast_call_traces);
         BatchTransactionExecutionResult {
             tx_result: Box::new(tx_result),
             compressed_bytecodes,
@@ -20,12 +20,15 @@ use zksync_multivm::{
         storage::{ReadStorage, StorageView, StorageWithOverrides, WriteStorage},
         tracer::{ValidationError, ValidationParams, ValidationTraces},
         utils::{DivergenceHandler, ShadowMut, ShadowVm},
-        Call, ExecutionResult, InspectExecutionMode, OneshotEnv, OneshotTracingParams,
+        Call, ExecutionResult, Halt, InspectExecutionMode, OneshotEnv, OneshotTracingParams,
         OneshotTransactionExecutionResult, StoredL2BlockEnv, TxExecutionArgs, TxExecutionMode,
         VmFactory, VmInterface,
     },
     is_supported_by_fast_vm,
-    tracers::{CallTracer, StorageInvocations, TracerDispatcher, ValidationTracer},
+    tracers::{
+        CallTracer, ExecutionLimits, ExecutionLimitsTracer, StorageInvocations, TracerDispatcher,
+        ValidationTracer,
+    },
     utils::adjust_pubdata_price_for_tx,
     vm_fast,
     vm_latest::{HistoryDisabled, HistoryEnabled},
@@ -52,6 +55,7 @@ pub use self::{
     env::OneshotEnvParameters,
     mock::MockOneshotExecutor,
 };
+use crate::shared::FastVmTracer;
 
 mod block;
 mod contracts;
@@ -67,6 +71,7 @@ pub struct MainOneshotExecutor {
     fast_vm_mode: FastVmMode,
     panic_on_divergence: bool,
     missed_storage_invocation_limit: usize,
+    execution_limits: ExecutionLimits,
     execution_latency_histogram: Option<&'static vise::Histogram<Duration>>,
 }
 
@@ -78,10 +83,22 @@ impl MainOneshotExecutor {
             fast_vm_mode: FastVmMode::Old,
             panic_on_divergence: false,
             missed_storage_invocation_limit,
+            execution_limits: ExecutionLimits::default(),
             execution_latency_histogram: None,
         }
     }
 
+    /// Sets wall-clock time and VM cycle limits for a single VM execution (an anti-DoS measure). Like the cache miss limit,
+    /// these limits are applied for calls and gas estimations, but not during transaction validation. If a limit is exceeded,
+    /// execution is halted with [`Halt::ExecutionTimeout`] or [`Halt::ExecutionCyclesLimitReached`].
+    ///
+    /// Limits are not enforced if the executor runs in the shadow [fast VM mode](Self::set_fast_vm_mode()), since they would lead
+    /// to spurious divergences between VMs; a warning is logged in this case.
+    pub fn set_execution_limits(&mut self, limits: ExecutionLimits) {
+        self.execution_limits = limits;
+        self.warn_on_ignored_execution_limits();
+    }
+
     /// Sets the fast VM mode used by this executor.
     pub fn set_fast_vm_mode(&mut self, fast_vm_mode: FastVmMode) {
         if !matches!(fast_vm_mode, FastVmMode::Old) {
@@ -90,6 +107,17 @@ impl MainOneshotExecutor {
             );
         }
         self.fast_vm_mode = fast_vm_mode;
+        self.warn_on_ignored_execution_limits();
+    }
+
+    fn warn_on_ignored_execution_limits(&self) {
+        if matches!(self.fast_vm_mode, FastVmMode::Shadow) && !self.execution_limits.is_unlimited()
+        {
+            tracing::warn!(
+                limits = ?self.execution_limits,
+                "Execution limits are not enforced in the shadow fast VM mode"
+            );
+        }
     }
 
     /// Causes the VM to panic on divergence whenever it executes in the shadow mode. By default, a divergence is logged on `ERROR` level.
@@ -105,13 +133,9 @@ impl MainOneshotExecutor {
         self.execution_latency_histogram = Some(histogram);
     }
 
-    fn select_fast_vm_mode(
-        &self,
-        env: &OneshotEnv,
-        tracing_params: &OneshotTracingParams,
-    ) -> FastVmMode {
-        if tracing_params.trace_calls || !is_supported_by_fast_vm(env.system.version) {
-            FastVmMode::Old // the fast VM doesn't support call tracing or old protocol versions
+    fn select_fast_vm_mode(&self, env: &OneshotEnv) -> FastVmMode {
+        if !is_supported_by_fast_vm(env.system.version) {
+            FastVmMode::Old // the fast VM doesn't support old protocol versions
         } else {
             self.fast_vm_mode
         }
@@ -130,15 +154,15 @@ where
         args: TxExecutionArgs,
         tracing_params: OneshotTracingParams,
     ) -> anyhow::Result<OneshotTransactionExecutionResult> {
-        let missed_storage_invocation_limit = match env.system.execution_mode {
-            // storage accesses are not limited for tx validation
-            TxExecutionMode::VerifyExecute => usize::MAX,
+        let (missed_storage_invocation_limit, execution_limits) = match env.system.execution_mode {
+            // storage accesses and execution are not limited for tx validation
+            TxExecutionMode::VerifyExecute => (usize::MAX, ExecutionLimits::default()),
             TxExecutionMode::EthCall | TxExecutionMode::EstimateFee => {
-                self.missed_storage_invocation_limit
+                (self.missed_storage_invocation_limit, self.execution_limits)
             }
         };
         let sandbox = VmSandbox {
-            fast_vm_mode: self.select_fast_vm_mode(&env, &tracing_params),
+            fast_vm_mode: self.select_fast_vm_mode(&env),
             panic_on_divergence: self.panic_on_divergence,
             storage,
             env,
@@ -147,14 +171,19 @@ where
         };
 
         tokio::task::spawn_blocking(move || {
-            sandbox.execute_in_vm(|vm, transaction| {
-                vm.inspect_transaction_with_bytecode_compression(
+            if tracing_params.trace_calls {
+                sandbox.inspect_transaction::<CallTracer>(
                     missed_storage_invocation_limit,
+                    execution_limits,
                     tracing_params,
-                    transaction,
-                    true,
                 )
-            })
+            } else {
+                sandbox.inspect_transaction::<()>(
+                    missed_storage_invocation_limit,
+                    execution_limits,
+                    tracing_params,
+                )
+            }
         })
         .await
         .context("VM execution panicked")
@@ -181,11 +210,7 @@ where
 
         let l1_batch_env = env.l1_batch.clone();
         let sandbox = VmSandbox {
-            fast_vm_mode: if !is_supported_by_fast_vm(env.system.version) {
-                FastVmMode::Old // the fast VM doesn't support old protocol versions
-            } else {
-                self.fast_vm_mode
-            },
+            fast_vm_mode: self.select_fast_vm_mode(&env),
             panic_on_divergence: self.panic_on_divergence,
             storage,
             env,
@@ -235,51 +260,85 @@ enum Vm<S: ReadStorage, Tr, Val> {
     Fast(FastVmInstance<S, Tr, Val>),
 }
 
-impl<S: ReadStorage> Vm<S, (), ()> {
+impl<S: ReadStorage, Tr: FastVmTracer> Vm<S, (Tr, ExecutionLimitsTracer), ()> {
     fn inspect_transaction_with_bytecode_compression(
         &mut self,
         missed_storage_invocation_limit: usize,
+        execution_limits: ExecutionLimits,
         params: OneshotTracingParams,
         tx: Transaction,
         with_compression: bool,
     ) -> OneshotTransactionExecutionResult {
         let mut calls_result = Arc::<OnceCell<_>>::default();
-        let (compression_result, tx_result) = match self {
+        let exceeded_limit = Arc::<OnceCell<Halt>>::default();
+        let (compression_result, mut tx_result, fast_call_traces) = match self {
             Self::Legacy(vm) => {
+                let limits_tracer = (!execution_limits.is_unlimited())
+                    .then(|| ExecutionLimitsTracer::new(execution_limits, exceeded_limit.clone()));
                 let mut tracers = Self::create_legacy_tracers(
                     missed_storage_invocation_limit,
                     params.trace_calls.then(|| calls_result.clone()),
+                    limits_tracer,
                 );
-                vm.inspect_transaction_with_bytecode_compression(&mut tracers, tx, with_compression)
+                let (compression_result, tx_result) = vm
+                    .inspect_transaction_with_bytecode_compression(
+                        &mut tracers,
+                        tx,
+                        with_compression,
+                    );
+                (compression_result, tx_result, vec![])
             }
             Self::Fast(vm) => {
-                assert!(
-                    !params.trace_calls,
-                    "Call tracing is not supported by fast VM yet"
-                );
+                // If the fast VM is shadowed, the main (legacy) VM traces calls as well.
                 let legacy_tracers = Self::create_legacy_tracers::<HistoryEnabled>(
                     missed_storage_invocation_limit,
+                    params.trace_calls.then(|| calls_result.clone()),
                     None,
                 );
-                let mut full_tracer = (legacy_tracers.into(), ((), ()));
-                vm.inspect_transaction_with_bytecode_compression(
-                    &mut full_tracer,
-                    tx,
-                    with_compression,
+                // An unlimited tracer (e.g., for transaction validation) doesn't count instructions.
+                let limits_tracer = match vm {
+                    FastVmInstance::Fast(_) => {
+                        ExecutionLimitsTracer::new(execution_limits, exceeded_limit.clone())
+                    }
+                    // Limits would lead to spurious divergences in the shadow mode.
+                    FastVmInstance::Shadowed(_) => ExecutionLimitsTracer::default(),
+                };
+                let mut full_tracer = (legacy_tracers.into(), ((Tr::default(), limits_tracer), ()));
+                let (compression_result, tx_result) = vm
+                    .inspect_transaction_with_bytecode_compression(
+                        &mut full_tracer,
+                        tx,
+                        with_compression,
+                    );
+                let ((fast_tracer, _), ()) = full_tracer.1;
+                (
+                    compression_result,
+                    tx_result,
+                    fast_tracer.into_call_traces(),
                 )
             }
         };
 
+        // The fast VM doesn't propagate the halt reason from tracers, so we override it for all VMs for consistency.
+        if let Some(halt) = exceeded_limit.get() {
+            tx_result.result = ExecutionResult::Halt {
+                reason: halt.clone(),
+            };
+        }
+
         OneshotTransactionExecutionResult {
             tx_result: Box::new(tx_result),
             compression_result: compression_result.map(drop),
-            call_traces: Arc::make_mut(&mut calls_result).take().unwrap_or_default(),
+            call_traces: Arc::make_mut(&mut calls_result)
+                .take()
+                .unwrap_or(fast_call_traces),
         }
     }
 
     fn create_legacy_tracers<H: HistoryMode>(
         missed_storage_invocation_limit: usize,
         calls_result: Option<Arc<OnceCell<Vec<Call>>>>,
+        limits_tracer: Option<ExecutionLimitsTracer>,
     ) -> TracerDispatcher<StorageView<S>, H> {
         let mut tracers = vec![];
         if let Some(calls_result) = calls_result {
@@ -287,6 +346,9 @@ impl<S: ReadStorage> Vm<S, (), ()> {
         }
         tracers
             .push(StorageInvocations::new(missed_storage_invocation_limit).into_tracer_pointer());
+        if let Some(limits_tracer) = limits_tracer {
+            tracers.push(limits_tracer.into_tracer_pointer());
+        }
         tracers.into()
     }
 }
@@ -402,6 +464,26 @@ impl<S: ReadStorage> VmSandbox<S> {
         }
     }
 
+    /// This method is blocking.
+    fn inspect_transaction<Tr: FastVmTracer>(
+        self,
+        missed_storage_invocation_limit: usize,
+        execution_limits: ExecutionLimits,
+        tracing_params: OneshotTracingParams,
+    ) -> OneshotTransactionExecutionResult {
+        self.execute_in_vm(
+            |vm: &mut Vm<_, (Tr, ExecutionLimitsTracer), ()>, transaction| {
+                vm.inspect_transaction_with_bytecode_compression(
+                    missed_storage_invocation_limit,
+                    execution_limits,
+                    tracing_params,
+                    transaction,
+                    true,
+                )
+            },
+        )
+    }
+
     fn execute_in_vm<T, Tr, Val>(
         mut self,
         action: impl FnOnce(&mut Vm<StorageWithOverrides<S>, Tr, Val>, Transaction) -> T,
@@ -27,17 +27,13 @@ fn selecting_vm_for_execution() {
             l1_batch: default_l1_batch_env(1),
             current_block: None,
         };
-        let mode = executor.select_fast_vm_mode(&env, &OneshotTracingParams::default());
+        let mode = executor.select_fast_vm_mode(&env);
         assert_matches!(mode, FastVmMode::New);
 
-        // Tracing calls is not supported by the new VM.
-        let mode = executor.select_fast_vm_mode(&env, &OneshotTracingParams { trace_calls: true });
-        assert_matches!(mode, FastVmMode::Old);
-
-        // Old protocol versions are not supported either.
+        // Old protocol versions are not supported by the new VM.
         let mut old_env = env.clone();
         old_env.system.version = ProtocolVersionId::Version22;
-        let mode = executor.select_fast_vm_mode(&old_env, &OneshotTracingParams::default());
+        let mode = executor.select_fast_vm_mode(&old_env);
         assert_matches!(mode, FastVmMode::Old);
     }
 }
@@ -70,9 +66,13 @@ fn setting_up_nonce_and_balance_in_storage() {
     );
 }
 
-#[test_casing(9, Product((EXEC_MODES, FAST_VM_MODES)))]
-#[tokio::test]
-async fn inspecting_transfer(exec_mode: TxExecutionMode, fast_vm_mode: FastVmMode) {
+fn prepare_transfer(
+    exec_mode: TxExecutionMode,
+) -> (
+    StorageWithOverrides<InMemoryStorage>,
+    OneshotEnv,
+    TxExecutionArgs,
+) {
     let tx = create_l2_transaction(1_000_000_000.into(), Nonce(0));
     let mut storage = InMemoryStorage::with_system_contracts();
     storage.set_value(
@@ -92,6 +92,13 @@ async fn inspecting_transfer(exec_mode: TxExecutionMode, fast_vm_mode: FastVmMod
         l1_batch,
     };
     let args = TxExecutionArgs::for_gas_estimate(tx.into());
+    (storage, env, args)
+}
+
+#[test_casing(9, Product((EXEC_MODES, FAST_VM_MODES)))]
+#[tokio::test]
+async fn inspecting_transfer(exec_mode: TxExecutionMode, fast_vm_mode: FastVmMode) {
+    let (storage, env, args) = prepare_transfer(exec_mode);
     let tracing = OneshotTracingParams::default();
 
     let mut executor = MainOneshotExecutor::new(usize::MAX);
@@ -103,4 +110,101 @@ async fn inspecting_transfer(exec_mode: TxExecutionMode, fast_vm_mode: FastVmMod
     result.compression_result.unwrap();
     let exec_result = result.tx_result.result;
     assert!(!exec_result.is_failed(), "{exec_result:?}");
+    assert!(result.call_traces.is_empty());
+}
+
+#[test_casing(3, FAST_VM_MODES)]
+#[tokio::test]
+async fn tracing_calls_in_transfer(fast_vm_mode: FastVmMode) {
+    let tracing = || OneshotTracingParams { trace_calls: true };
+    let (storage, env, args) = prepare_transfer(TxExecutionMode::EthCall);
+    let reference_result = MainOneshotExecutor::new(usize::MAX)
+        .inspect_transaction_with_bytecode_compression(storage, env, args, tracing())
+        .await
+        .unwrap();
+    assert!(!reference_result.call_traces.is_empty());
+
+    let (storage, env, args) = prepare_transfer(TxExecutionMode::EthCall);
+    let mut executor = MainOneshotExecutor::new(usize::MAX);
+    executor.set_fast_vm_mode(fast_vm_mode);
+    let result = executor
+        .inspect_transaction_with_bytecode_compression(storage, env, args, tracing())
+        .await
+        .unwrap();
+    let exec_result = result.tx_result.result;
+    assert!(!exec_result.is_failed(), "{exec_result:?}");
+    assert_eq!(result.call_traces, reference_result.call_traces);
+}
+
+#[test_casing(4, Product(([FastVmMode::Old, FastVmMode::New], [false, true])))]
+#[tokio::test]
+async fn exceeding_execution_limits(fast_vm_mode: FastVmMode, use_timeout: bool) {
+    let (limits, expected_halt) = if use_timeout {
+        let limits = ExecutionLimits {
+            timeout: Some(Duration::ZERO),
+            cycles: None,
+        };
+        (limits, Halt::ExecutionTimeout)
+    } else {
+        let limits = ExecutionLimits {
+            timeout: None,
+            cycles: Some(100),
+        };
+        (limits, Halt::ExecutionCyclesLimitReached)
+    };
+    let mut executor = MainOneshotExecutor::new(usize::MAX);
+    executor.set_fast_vm_mode(fast_vm_mode);
+    executor.set_execution_limits(limits);
+
+    let (storage, env, args) = prepare_transfer(TxExecutionMode::EthCall);
+    let result = executor
+        .inspect_transaction_with_bytecode_compression(
+            storage,
+            env,
+            args,
+            OneshotTracingParams::default(),
+        )
+        .await
+        .unwrap();
+    assert_matches!(
+        &result.tx_result.result,
+        ExecutionResult::Halt { reason } if *reason == expected_halt
+    );
+
+    // Limits must not be applied when executing transactions.
+    let (storage, env, args) = prepare_transfer(TxExecutionMode::VerifyExecute);
+    let result = executor
+        .inspect_transaction_with_bytecode_compression(
+            storage,
+            env,
+            args,
+            OneshotTracingParams::default(),
+        )
+        .await
+        .unwrap();
+    let exec_result = result.tx_result.result;
+    assert!(!exec_result.is_failed(), "{exec_result:?}");
+}
+
+#[tokio::test]
+async fn execution_limits_are_ignored_in_shadow_mode() {
+    let mut executor = MainOneshotExecutor::new(usize::MAX);
+    executor.set_fast_vm_mode(FastVmMode::Shadow);
+    executor.set_execution_limits(ExecutionLimits {
+        timeout: Some(Duration::ZERO),
+        cycles: Some(100),
+    });
+
+    let (storage, env, args) = prepare_transfer(TxExecutionMode::EthCall);
+    let result = executor
+        .inspect_transaction_with_bytecode_compression(
+            storage,
+            env,
+            args,
+            OneshotTracingParams::default(),
+        )
+        .await
+        .unwrap();
+    let exec_result = result.tx_result.result;
+    assert!(!exec_result.is_failed(), "{exec_result:?}");
 }
@@ -3,11 +3,33 @@
 use std::time::Duration;
 
 use vise::{Buckets, EncodeLabelSet, EncodeLabelValue, Family, Histogram, Metrics};
-use zksync_multivm::interface::storage::StorageViewStats;
+use zksync_multivm::{
+    interface::{storage::StorageViewStats, Call},
+    tracers::CallTracer,
+    vm_fast,
+};
 
 /// Marker for sealed traits. Intentionally not exported from the crate.
 pub trait Sealed {}
 
+/// Fast VM tracer used by executors, which may collect call traces. Intentionally not exported from the crate.
+pub trait FastVmTracer: vm_fast::interface::Tracer + Default {
+    /// Returns collected call traces, or an empty vector if the tracer doesn't trace calls.
+    fn into_call_traces(self) -> Vec<Call>;
+}
+
+impl FastVmTracer for () {
+    fn into_call_traces(self) -> Vec<Call> {
+        vec![]
+    }
+}
+
+impl FastVmTracer for CallTracer {
+    fn into_call_traces(self) -> Vec<Call> {
+        self.into_result()
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
 #[metrics(label = "interaction", rename_all = "snake_case")]
 pub(crate) enum InteractionType {
@@ -43,6 +43,10 @@ pub enum Halt {
     TracerCustom(String),
     FailedToPublishCompressedBytecodes,
     FailedBlockTimestampAssertion,
+    // Tx execution took more wall-clock time than allowed (only applicable to oneshot execution in the API server)
+    ExecutionTimeout,
+    // Tx execution took more VM cycles than allowed (only applicable to oneshot execution in the API server)
+    ExecutionCyclesLimitReached,
 }
 
 impl fmt::Display for Halt {
@@ -120,6 +124,12 @@ impl fmt::Display for Halt {
             Halt::FailedBlockTimestampAssertion => {
                 write!(f, "Transaction failed block.timestamp assertion")
             }
+            Halt::ExecutionTimeout => {
+                write!(f, "Transaction execution timed out")
+            }
+            Halt::ExecutionCyclesLimitReached => {
+                write!(f, "Transaction execution exceeded the VM cycles limit")
+            }
         }
     }
 }
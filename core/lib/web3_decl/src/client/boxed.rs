@@ -11,11 +11,11 @@ use serde::de::DeserializeOwned;
 
 use super::{ForWeb3Network, Network, TaggedClient};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct RawParams(pub(super) Option<Box<JsonRawValue>>);
 
 impl RawParams {
-    fn new(params: impl ToRpcParams) -> Result<Self, serde_json::Error> {
+    pub(super) fn new(params: impl ToRpcParams) -> Result<Self, serde_json::Error> {
         params.to_rpc_params().map(Self)
     }
 }
@@ -98,6 +98,35 @@ impl<Net: Network> Clone for Box<DynClient<Net>> {
     }
 }
 
+/// Parses a batch response with catch-all JSON values returned by [`ObjectSafeClient::generic_batch_request()`].
+pub(super) fn parse_batch_response<'a, R>(
+    raw_responses: BatchResponse<'a, serde_json::Value>,
+) -> Result<BatchResponse<'a, R>, Error>
+where
+    R: DeserializeOwned + fmt::Debug + 'a,
+{
+    let mut successful_calls = 0;
+    let mut failed_calls = 0;
+    let mut responses = Vec::with_capacity(raw_responses.len());
+    for raw_response in raw_responses {
+        responses.push(match raw_response {
+            Ok(json) => {
+                successful_calls += 1;
+                Ok(serde_json::from_value::<R>(json)?)
+            }
+            Err(err) => {
+                failed_calls += 1;
+                Err(err)
+            }
+        })
+    }
+    Ok(BatchResponse::new(
+        successful_calls,
+        responses,
+        failed_calls,
+    ))
+}
+
 #[async_trait]
 impl<Net: Network> ClientT for &DynClient<Net> {
     async fn notification<Params>(&self, method: &str, params: Params) -> Result<(), Error>
@@ -128,26 +157,7 @@ impl<Net: Network> ClientT for &DynClient<Net> {
         R: DeserializeOwned + fmt::Debug + 'a,
     {
         let raw_responses = (**self).generic_batch_request(batch).await?;
-        let mut successful_calls = 0;
-        let mut failed_calls = 0;
-        let mut responses = Vec::with_capacity(raw_responses.len());
-        for raw_response in raw_responses {
-            responses.push(match raw_response {
-                Ok(json) => {
-                    successful_calls += 1;
-                    Ok(serde_json::from_value::<R>(json)?)
-                }
-                Err(err) => {
-                    failed_calls += 1;
-                    Err(err)
-                }
-            })
-        }
-        Ok(BatchResponse::new(
-            successful_calls,
-            responses,
-            failed_calls,
-        ))
+        parse_batch_response(raw_responses)
     }
 }
 
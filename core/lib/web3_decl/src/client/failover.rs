@@ -0,0 +1,523 @@
+//! Failover RPC client distributing requests among multiple upstreams.
+
+use std::{
+    collections::HashSet,
+    fmt,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use jsonrpsee::{
+    core::{
+        client::{BatchResponse, ClientT, Error},
+        params::BatchRequestBuilder,
+        traits::ToRpcParams,
+    },
+    types::error::ErrorCode,
+};
+use serde::de::DeserializeOwned;
+use tokio::time::Instant;
+
+use super::{
+    boxed::{parse_batch_response, RawParams},
+    metrics::{
+        FailoverClientMetrics, UpstreamCallLabels, UpstreamCallResult, UpstreamLabels,
+        FAILOVER_METRICS,
+    },
+    CallOrigin, DynClient, ForWeb3Network, Network, TaggedClient,
+};
+
+/// Checks whether the error signals that the upstream is unavailable, as opposed to an error returned
+/// by a functioning upstream (e.g., an RPC-level error caused by invalid call params).
+fn is_upstream_failure(err: &Error) -> bool {
+    match err {
+        Error::Transport(_) | Error::RequestTimeout => true,
+        Error::Call(err) => err.code() == ErrorCode::ServerIsBusy.code(),
+        _ => false,
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Upstream<Net: Network> {
+    label: String,
+    client: Box<DynClient<Net>>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct UpstreamHealth {
+    /// If set, the upstream is considered unhealthy until the specified moment.
+    unhealthy_until: Option<Instant>,
+}
+
+impl UpstreamHealth {
+    fn is_healthy(&self, now: Instant) -> bool {
+        self.unhealthy_until.map_or(true, |until| until <= now)
+    }
+}
+
+#[derive(Debug)]
+struct FailoverState {
+    /// Index of the active upstream.
+    active: usize,
+    health: Vec<UpstreamHealth>,
+}
+
+/// JSON-RPC client distributing requests among multiple upstreams (e.g., the main node and trusted external nodes).
+///
+/// # Routing
+///
+/// - Routing is sticky: all requests are sent to the *active* upstream (initially, the first upstream
+///   added to the builder) as long as it is healthy.
+/// - If an upstream fails to process a request because of a transport error, a timeout or overload, it is marked
+///   as unhealthy for the configured cooldown period, and the request is retried with the next upstream.
+///   Healthy upstreams are tried first; unhealthy upstreams are only tried as a last resort.
+/// - Once an upstream other than the active one successfully processes a request and the active upstream
+///   is unhealthy, the former upstream becomes active.
+///
+/// RPC-level errors (e.g., invalid params) are returned to the caller as-is and don't affect routing.
+///
+/// Upstreams are expected to be rate-limited on their own (e.g., by being built as [`Client`](super::Client)s).
+/// The routing state is shared among all clones of the client.
+#[derive(Clone)]
+pub struct FailoverClient<Net: Network> {
+    upstreams: Arc<[Upstream<Net>]>,
+    state: Arc<Mutex<FailoverState>>,
+    cooldown: Duration,
+    component_name: &'static str,
+    network: Net,
+    metrics: &'static FailoverClientMetrics,
+}
+
+impl<Net: Network> fmt::Debug for FailoverClient<Net> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let labels: Vec<_> = self
+            .upstreams
+            .iter()
+            .map(|upstream| &upstream.label)
+            .collect();
+        formatter
+            .debug_struct("FailoverClient")
+            .field("upstreams", &labels)
+            .field("cooldown", &self.cooldown)
+            .field("component_name", &self.component_name)
+            .field("network", &self.network)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<Net: Network> FailoverClient<Net> {
+    /// Creates a builder for a client working with the specified network.
+    pub fn builder(network: Net) -> FailoverClientBuilder<Net> {
+        FailoverClientBuilder {
+            network,
+            upstreams: vec![],
+            cooldown: FailoverClientBuilder::<Net>::DEFAULT_COOLDOWN,
+        }
+    }
+
+    /// Returns the label of the currently active upstream.
+    pub fn active_upstream(&self) -> &str {
+        let active = self
+            .state
+            .lock()
+            .expect("failover state is poisoned")
+            .active;
+        &self.upstreams[active].label
+    }
+
+    fn upstream_labels(&self, idx: usize) -> UpstreamLabels {
+        UpstreamLabels {
+            network: self.network.metric_label(),
+            upstream: self.upstreams[idx].label.clone(),
+        }
+    }
+
+    fn call_labels(&self, idx: usize, result: UpstreamCallResult) -> UpstreamCallLabels {
+        UpstreamCallLabels {
+            network: self.network.metric_label(),
+            component: self.component_name,
+            upstream: self.upstreams[idx].label.clone(),
+            result,
+        }
+    }
+
+    /// Returns indices of upstreams in the order they should be queried: starting from the active upstream,
+    /// with healthy upstreams going first.
+    fn upstream_order(&self) -> Vec<usize> {
+        let now = Instant::now();
+        let state = self.state.lock().expect("failover state is poisoned");
+        let len = self.upstreams.len();
+        let (mut healthy, unhealthy): (Vec<_>, Vec<_>) = (0..len)
+            .map(|i| (state.active + i) % len)
+            .partition(|&idx| state.health[idx].is_healthy(now));
+        healthy.extend(unhealthy);
+        healthy
+    }
+
+    fn report_response(&self, idx: usize, latency: Duration) {
+        let labels = self.upstream_labels(idx);
+        self.metrics.upstream_calls[&self.call_labels(idx, UpstreamCallResult::Response)].inc();
+        self.metrics.upstream_latency[&labels].observe(latency);
+
+        let now = Instant::now();
+        let (recovered, prev_active, switched) = {
+            let mut state = self.state.lock().expect("failover state is poisoned");
+            let recovered = state.health[idx].unhealthy_until.take().is_some();
+            let prev_active = state.active;
+            let switched = prev_active != idx && !state.health[prev_active].is_healthy(now);
+            if switched {
+                state.active = idx;
+            }
+            (recovered, prev_active, switched)
+        };
+
+        let network = labels.network.as_str();
+        let upstream = self.upstreams[idx].label.as_str();
+        if recovered {
+            self.metrics.upstream_healthy[&labels].set(1);
+            tracing::info!(
+                network,
+                component = self.component_name,
+                upstream,
+                "Upstream `{upstream}` has recovered"
+            );
+        }
+        if switched {
+            self.metrics.upstream_active[&self.upstream_labels(prev_active)].set(0);
+            self.metrics.upstream_active[&labels].set(1);
+            self.metrics.upstream_switches[&labels.network].inc();
+            tracing::warn!(
+                network,
+                component = self.component_name,
+                upstream,
+                "Switched active upstream from `{}` to `{upstream}`",
+                self.upstreams[prev_active].label
+            );
+        }
+    }
+
+    fn report_failure(&self, idx: usize, origin: CallOrigin<'_>, err: &Error) {
+        self.metrics.upstream_calls[&self.call_labels(idx, UpstreamCallResult::Failure)].inc();
+
+        let now = Instant::now();
+        let was_healthy = {
+            let mut state = self.state.lock().expect("failover state is poisoned");
+            let health = &mut state.health[idx];
+            let was_healthy = health.is_healthy(now);
+            health.unhealthy_until = Some(now + self.cooldown);
+            was_healthy
+        };
+
+        if was_healthy {
+            let labels = self.upstream_labels(idx);
+            self.metrics.upstream_healthy[&labels].set(0);
+            let upstream = self.upstreams[idx].label.as_str();
+            tracing::warn!(
+                network = labels.network,
+                component = self.component_name,
+                upstream,
+                %origin,
+                "Upstream `{upstream}` failed processing {origin}, marking it as unhealthy for {:?}: {err}",
+                self.cooldown
+            );
+        }
+    }
+
+    async fn call<'a, T>(
+        &'a self,
+        origin: CallOrigin<'a>,
+        call: impl Fn(&'a DynClient<Net>) -> BoxFuture<'a, Result<T, Error>> + Send,
+    ) -> Result<T, Error> {
+        let mut last_err = None;
+        for idx in self.upstream_order() {
+            let started_at = Instant::now();
+            match call(self.upstreams[idx].client.as_ref()).await {
+                Err(err) if is_upstream_failure(&err) => {
+                    self.report_failure(idx, origin, &err);
+                    last_err = Some(err);
+                }
+                result => {
+                    self.report_response(idx, started_at.elapsed());
+                    return result;
+                }
+            }
+        }
+        Err(last_err.expect("failover client has no upstreams"))
+    }
+}
+
+impl<Net: Network> ForWeb3Network for FailoverClient<Net> {
+    type Net = Net;
+
+    fn network(&self) -> Self::Net {
+        self.network
+    }
+
+    fn component(&self) -> &'static str {
+        self.component_name
+    }
+}
+
+impl<Net: Network> TaggedClient for FailoverClient<Net> {
+    fn set_component(&mut self, component_name: &'static str) {
+        self.component_name = component_name;
+        self.upstreams = self
+            .upstreams
+            .iter()
+            .map(|upstream| Upstream {
+                label: upstream.label.clone(),
+                client: upstream.client.clone().for_component(component_name),
+            })
+            .collect();
+    }
+}
+
+#[async_trait]
+impl<Net: Network> ClientT for FailoverClient<Net> {
+    async fn notification<Params>(&self, method: &str, params: Params) -> Result<(), Error>
+    where
+        Params: ToRpcParams + Send,
+    {
+        let params = RawParams::new(params)?;
+        self.call(CallOrigin::Notification(method), |client| {
+            client.generic_notification(method, params.clone())
+        })
+        .await
+    }
+
+    async fn request<R, Params>(&self, method: &str, params: Params) -> Result<R, Error>
+    where
+        R: DeserializeOwned,
+        Params: ToRpcParams + Send,
+    {
+        let params = RawParams::new(params)?;
+        let raw_response = self
+            .call(CallOrigin::Request(method), |client| {
+                client.generic_request(method, params.clone())
+            })
+            .await?;
+        serde_json::from_value(raw_response).map_err(Error::ParseError)
+    }
+
+    async fn batch_request<'a, R>(
+        &self,
+        batch: BatchRequestBuilder<'a>,
+    ) -> Result<BatchResponse<'a, R>, Error>
+    where
+        R: DeserializeOwned + fmt::Debug + 'a,
+    {
+        let raw_responses = self
+            .call(CallOrigin::BatchRequest(&batch), |client| {
+                client.generic_batch_request(batch.clone())
+            })
+            .await?;
+        parse_batch_response(raw_responses)
+    }
+}
+
+/// Builder for a [`FailoverClient`].
+#[derive(Debug)]
+pub struct FailoverClientBuilder<Net: Network> {
+    network: Net,
+    upstreams: Vec<Upstream<Net>>,
+    cooldown: Duration,
+}
+
+impl<Net: Network> FailoverClientBuilder<Net> {
+    const DEFAULT_COOLDOWN: Duration = Duration::from_secs(30);
+
+    /// Adds an upstream with the specified label. The label is used in logs and as a metrics label,
+    /// so it should not contain sensitive info (e.g., API keys). Upstreams are prioritized in the order
+    /// they are added; i.e., the first added upstream is initially active.
+    pub fn with_upstream(mut self, label: impl Into<String>, client: Box<DynClient<Net>>) -> Self {
+        self.upstreams.push(Upstream {
+            label: label.into(),
+            client,
+        });
+        self
+    }
+
+    /// Sets the period for which a failed upstream is considered unhealthy. Unhealthy upstreams are queried
+    /// only if all healthy upstreams fail. The default value is 30 seconds.
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Builds the client.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no upstreams are specified, or if upstream labels are not unique.
+    pub fn build(self) -> anyhow::Result<FailoverClient<Net>> {
+        anyhow::ensure!(
+            !self.upstreams.is_empty(),
+            "failover client must have at least one upstream"
+        );
+        let mut labels = HashSet::with_capacity(self.upstreams.len());
+        for upstream in &self.upstreams {
+            anyhow::ensure!(
+                labels.insert(upstream.label.as_str()),
+                "upstream label `{}` is not unique",
+                upstream.label
+            );
+        }
+
+        tracing::info!(
+            "Creating failover JSON-RPC client for network {:?} with upstreams {labels:?} and cooldown {:?}",
+            self.network,
+            self.cooldown
+        );
+        let client = FailoverClient {
+            state: Arc::new(Mutex::new(FailoverState {
+                active: 0,
+                health: vec![UpstreamHealth::default(); self.upstreams.len()],
+            })),
+            upstreams: self.upstreams.into(),
+            cooldown: self.cooldown,
+            component_name: "",
+            network: self.network,
+            metrics: &FAILOVER_METRICS,
+        };
+        for idx in 0..client.upstreams.len() {
+            let labels = client.upstream_labels(idx);
+            client.metrics.upstream_healthy[&labels].set(1);
+            client.metrics.upstream_active[&labels].set(u64::from(idx == 0));
+        }
+        Ok(client)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use assert_matches::assert_matches;
+    use jsonrpsee::http_client::transport;
+    use zksync_types::U64;
+
+    use super::*;
+    use crate::{
+        client::{MockClient, L2},
+        namespaces::EthNamespaceClient,
+    };
+
+    fn mock_upstream(
+        block_number: u64,
+        failing_calls: usize,
+    ) -> (Box<DynClient<L2>>, Arc<AtomicUsize>) {
+        let call_count = Arc::<AtomicUsize>::default();
+        let call_count_for_handler = call_count.clone();
+        let client = MockClient::builder(L2::default())
+            .method("eth_blockNumber", move || {
+                let call_idx = call_count_for_handler.fetch_add(1, Ordering::SeqCst);
+                if call_idx < failing_calls {
+                    let http_err = transport::Error::Rejected { status_code: 503 };
+                    Err(Error::Transport(http_err.into()))
+                } else {
+                    Ok(U64::from(block_number))
+                }
+            })
+            .build();
+        (Box::new(client), call_count)
+    }
+
+    #[test]
+    fn building_client_with_invalid_upstreams() {
+        let err = FailoverClient::builder(L2::default())
+            .build()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("at least one upstream"), "{err}");
+
+        let (upstream, _) = mock_upstream(1, 0);
+        let err = FailoverClient::builder(L2::default())
+            .with_upstream("main", upstream.clone())
+            .with_upstream("main", upstream)
+            .build()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("not unique"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn failover_with_sticky_routing() {
+        let (main_upstream, main_calls) = mock_upstream(1, usize::MAX);
+        let (fallback_upstream, fallback_calls) = mock_upstream(2, 0);
+        let client = FailoverClient::builder(L2::default())
+            .with_upstream("main", main_upstream)
+            .with_upstream("fallback", fallback_upstream)
+            .build()
+            .unwrap();
+        assert_eq!(client.active_upstream(), "main");
+        let boxed_client = Box::new(client.clone()).for_component("test");
+
+        let block_number = boxed_client.get_block_number().await.unwrap();
+        assert_eq!(block_number, 2.into());
+        assert_eq!(client.active_upstream(), "fallback");
+        assert_eq!(main_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(fallback_calls.load(Ordering::SeqCst), 1);
+
+        // The unhealthy main upstream should not be queried anymore.
+        for _ in 0..5 {
+            let block_number = boxed_client.get_block_number().await.unwrap();
+            assert_eq!(block_number, 2.into());
+        }
+        assert_eq!(client.active_upstream(), "fallback");
+        assert_eq!(main_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(fallback_calls.load(Ordering::SeqCst), 6);
+    }
+
+    #[tokio::test]
+    async fn rpc_errors_do_not_trigger_failover() {
+        let (main_upstream, _) = mock_upstream(1, 0);
+        let (fallback_upstream, fallback_calls) = mock_upstream(2, 0);
+        let client = FailoverClient::builder(L2::default())
+            .with_upstream("main", main_upstream)
+            .with_upstream("fallback", fallback_upstream)
+            .build()
+            .unwrap();
+
+        let err = client
+            .request::<U64, _>("unknown", jsonrpsee::rpc_params![])
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::Call(_));
+        assert_eq!(client.active_upstream(), "main");
+        assert_eq!(fallback_calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn unhealthy_upstreams_are_used_as_last_resort() {
+        tokio::time::pause();
+
+        let (main_upstream, main_calls) = mock_upstream(1, usize::MAX);
+        let (fallback_upstream, fallback_calls) = mock_upstream(2, 1);
+        let client = FailoverClient::builder(L2::default())
+            .with_upstream("main", main_upstream)
+            .with_upstream("fallback", fallback_upstream)
+            .with_cooldown(Duration::from_secs(10))
+            .build()
+            .unwrap();
+
+        let err = client.get_block_number().await.unwrap_err();
+        assert_matches!(err, Error::Transport(_));
+        assert_eq!(client.active_upstream(), "main");
+
+        // Both upstreams are unhealthy, so they should be queried in the original order.
+        let block_number = client.get_block_number().await.unwrap();
+        assert_eq!(block_number, 2.into());
+        assert_eq!(client.active_upstream(), "fallback");
+        assert_eq!(main_calls.load(Ordering::SeqCst), 2);
+        assert_eq!(fallback_calls.load(Ordering::SeqCst), 2);
+
+        // After the cooldown, the main upstream is healthy again, but routing remains sticky.
+        tokio::time::advance(Duration::from_secs(15)).await;
+        let block_number = client.get_block_number().await.unwrap();
+        assert_eq!(block_number, 2.into());
+        assert_eq!(client.active_upstream(), "fallback");
+        assert_eq!(main_calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -4,8 +4,8 @@ use std::time::Duration;
 
 use jsonrpsee::{core::client, http_client::transport};
 use vise::{
-    Buckets, Counter, DurationAsSecs, EncodeLabelSet, EncodeLabelValue, Family, Histogram, Info,
-    LabeledFamily, Metrics, Unit,
+    Buckets, Counter, DurationAsSecs, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram,
+    Info, LabeledFamily, Metrics, Unit,
 };
 
 use super::{AcquireStats, CallOrigin, SharedRateLimit};
@@ -211,3 +211,46 @@ impl L2ClientMetrics {
 
 #[vise::register]
 pub(super) static METRICS: vise::Global<L2ClientMetrics> = vise::Global::new();
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, EncodeLabelSet)]
+pub(super) struct UpstreamLabels {
+    pub network: String,
+    pub upstream: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+pub(super) enum UpstreamCallResult {
+    /// Upstream has returned a response (possibly, with an RPC-level error).
+    Response,
+    /// Upstream has failed, e.g. because of a transport error or a timeout.
+    Failure,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, EncodeLabelSet)]
+pub(super) struct UpstreamCallLabels {
+    pub network: String,
+    pub component: &'static str,
+    pub upstream: String,
+    pub result: UpstreamCallResult,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "l2_client_failover")]
+pub(super) struct FailoverClientMetrics {
+    /// Number of calls to an upstream, grouped by the call result.
+    pub upstream_calls: Family<UpstreamCallLabels, Counter>,
+    /// Latency of calls to an upstream.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub upstream_latency: Family<UpstreamLabels, Histogram<Duration>>,
+    /// Whether an upstream is currently considered healthy (1) or not (0).
+    pub upstream_healthy: Family<UpstreamLabels, Gauge<u64>>,
+    /// Whether an upstream is currently active, i.e. receives all requests (1) or not (0).
+    pub upstream_active: Family<UpstreamLabels, Gauge<u64>>,
+    /// Number of times the active upstream was switched.
+    #[metrics(labels = ["network"])]
+    pub upstream_switches: LabeledFamily<String, Counter>,
+}
+
+#[vise::register]
+pub(super) static FAILOVER_METRICS: vise::Global<FailoverClientMetrics> = vise::Global::new();
@@ -5,7 +5,8 @@ use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
 use zksync_types::{
     api::{
-        state_override::StateOverride, BlockDetails, BridgeAddresses, L1BatchDetails,
+        state_override::StateOverride, BlockCommitmentInfo, BlockDetails, BlockIdVariant,
+        BridgeAddresses, ContractBytecodeInfo, GasEstimationDetails, L1BatchDetails,
         L2ToL1LogProof, Proof, ProtocolVersion, TransactionDetailedResult, TransactionDetails,
     },
     fee::Fee,
@@ -35,6 +36,13 @@ pub trait ZksNamespace {
         state_override: Option<StateOverride>,
     ) -> RpcResult<Fee>;
 
+    #[method(name = "estimateGasDetailed")]
+    async fn estimate_gas_detailed(
+        &self,
+        req: CallRequest,
+        state_override: Option<StateOverride>,
+    ) -> RpcResult<GasEstimationDetails>;
+
     #[method(name = "estimateGasL1ToL2")]
     async fn estimate_gas_l1_to_l2(
         &self,
@@ -111,9 +119,22 @@ pub trait ZksNamespace {
     async fn get_l1_batch_details(&self, batch: L1BatchNumber)
         -> RpcResult<Option<L1BatchDetails>>;
 
+    #[method(name = "getBlockCommitmentInfo")]
+    async fn get_block_commitment_info(
+        &self,
+        block_number: L2BlockNumber,
+    ) -> RpcResult<Option<BlockCommitmentInfo>>;
+
     #[method(name = "getBytecodeByHash")]
     async fn get_bytecode_by_hash(&self, hash: H256) -> RpcResult<Option<Vec<u8>>>;
 
+    #[method(name = "getContractBytecodeInfo")]
+    async fn get_contract_bytecode_info(
+        &self,
+        address: Address,
+        block: Option<BlockIdVariant>,
+    ) -> RpcResult<Option<ContractBytecodeInfo>>;
+
     #[method(name = "getL1GasPrice")]
     async fn get_l1_gas_price(&self) -> RpcResult<U64>;
 
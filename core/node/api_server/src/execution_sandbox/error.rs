@@ -28,6 +28,8 @@ pub(crate) enum SandboxExecutionError {
     UnexpectedVMBehavior(String),
     #[error("Transaction failed block.timestamp assertion")]
     FailedBlockTimestampAssertion,
+    #[error("VM execution limit exceeded: {0}")]
+    ExecutionLimitExceeded(String),
 }
 
 impl From<Halt> for SandboxExecutionError {
@@ -70,6 +72,9 @@ impl From<Halt> for SandboxExecutionError {
                 Self::UnexpectedVMBehavior("Failed to publish compressed bytecodes".to_string())
             }
             Halt::FailedBlockTimestampAssertion => Self::FailedBlockTimestampAssertion,
+            halt @ (Halt::ExecutionTimeout | Halt::ExecutionCyclesLimitReached) => {
+                Self::ExecutionLimitExceeded(halt.to_string())
+            }
         }
     }
 }
@@ -6,12 +6,15 @@ use anyhow::Context as _;
 use async_trait::async_trait;
 use tokio::runtime::Handle;
 use zksync_dal::{Connection, Core};
-use zksync_multivm::interface::{
-    executor::{OneshotExecutor, TransactionValidator},
-    storage::{ReadStorage, StorageWithOverrides},
-    tracer::{TimestampAsserterParams, ValidationError, ValidationParams, ValidationTraces},
-    Call, OneshotEnv, OneshotTracingParams, OneshotTransactionExecutionResult,
-    TransactionExecutionMetrics, TxExecutionArgs, VmExecutionResultAndLogs,
+use zksync_multivm::{
+    interface::{
+        executor::{OneshotExecutor, TransactionValidator},
+        storage::{ReadStorage, StorageWithOverrides},
+        tracer::{TimestampAsserterParams, ValidationError, ValidationParams, ValidationTraces},
+        Call, OneshotEnv, OneshotTracingParams, OneshotTransactionExecutionResult,
+        TransactionExecutionMetrics, TxExecutionArgs, VmExecutionResultAndLogs,
+    },
+    tracers::ExecutionLimits,
 };
 use zksync_state::{PostgresStorage, PostgresStorageCaches};
 use zksync_types::{
@@ -23,7 +26,10 @@ use super::{
     vm_metrics::{self, SandboxStage},
     BlockArgs, VmPermit, SANDBOX_METRICS,
 };
-use crate::{execution_sandbox::storage::apply_state_override, tx_sender::SandboxExecutorOptions};
+use crate::{
+    execution_sandbox::storage::{apply_state_override, SharedStorageReads},
+    tx_sender::SandboxExecutorOptions,
+};
 
 /// Action that can be executed by [`SandboxExecutor`].
 #[derive(Debug)]
@@ -107,10 +113,12 @@ impl SandboxExecutor {
         options: SandboxExecutorOptions,
         caches: PostgresStorageCaches,
         missed_storage_invocation_limit: usize,
+        execution_limits: ExecutionLimits,
         timestamp_asserter_params: Option<TimestampAsserterParams>,
     ) -> Self {
         let mut executor = MainOneshotExecutor::new(missed_storage_invocation_limit);
         executor.set_fast_vm_mode(options.fast_vm_mode);
+        executor.set_execution_limits(execution_limits);
         #[cfg(test)]
         executor.panic_on_divergence();
         executor
@@ -151,11 +159,42 @@ impl SandboxExecutor {
         block_args: &BlockArgs,
         state_override: Option<StateOverride>,
     ) -> anyhow::Result<SandboxExecutionOutput> {
-        let total_factory_deps = action.factory_deps_count() as u16;
         let (env, storage) = self
             .prepare_env_and_storage(connection, block_args, &action)
             .await?;
+        self.execute_with_storage(vm_permit, storage, env, action, state_override)
+            .await
+    }
+
+    /// Same as [`Self::execute_in_sandbox()`], but shares storage reads with other executions using the same `shared_reads`.
+    /// All such executions must use the same `block_args`.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub(crate) async fn execute_in_sandbox_with_shared_reads(
+        &self,
+        vm_permit: VmPermit,
+        connection: Connection<'static, Core>,
+        action: SandboxAction,
+        block_args: &BlockArgs,
+        state_override: Option<StateOverride>,
+        shared_reads: &SharedStorageReads,
+    ) -> anyhow::Result<SandboxExecutionOutput> {
+        let (env, storage) = self
+            .prepare_env_and_storage(connection, block_args, &action)
+            .await?;
+        let storage = shared_reads.wrap(storage);
+        self.execute_with_storage(vm_permit, storage, env, action, state_override)
+            .await
+    }
 
+    async fn execute_with_storage<S: ReadStorage + Send + 'static>(
+        &self,
+        vm_permit: VmPermit,
+        storage: S,
+        env: OneshotEnv,
+        action: SandboxAction,
+        state_override: Option<StateOverride>,
+    ) -> anyhow::Result<SandboxExecutionOutput> {
+        let total_factory_deps = action.factory_deps_count() as u16;
         let state_override = state_override.unwrap_or_default();
         let storage = apply_state_override(storage, &state_override);
         let (execution_args, tracing_params) = action.into_parts();
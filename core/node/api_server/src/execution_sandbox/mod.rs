@@ -16,6 +16,7 @@ use self::vm_metrics::SandboxStage;
 pub(super) use self::{
     error::SandboxExecutionError,
     execute::{SandboxAction, SandboxExecutor},
+    storage::SharedStorageReads,
     validate::ValidationError,
     vm_metrics::{SubmitTxStage, SANDBOX_METRICS},
 };
@@ -1,13 +1,90 @@
 //! VM storage functionality specifically used in the VM sandbox.
 
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
 use zksync_multivm::interface::storage::{ReadStorage, StorageWithOverrides};
 use zksync_types::{
     api::state_override::{OverrideState, StateOverride},
     get_code_key, get_known_code_key, get_nonce_key, h256_to_u256, u256_to_h256,
     utils::{decompose_full_nonce, nonces_to_full_nonce, storage_key_for_eth_balance},
-    AccountTreeId, StorageKey, H256,
+    AccountTreeId, StorageKey, StorageValue, H256,
 };
 
+#[derive(Debug, Default)]
+struct StorageReads {
+    values: HashMap<StorageKey, StorageValue>,
+    initial_writes: HashMap<StorageKey, bool>,
+    factory_deps: HashMap<H256, Option<Vec<u8>>>,
+    enumeration_indices: HashMap<StorageKey, Option<u64>>,
+}
+
+/// Storage reads shared among multiple sandbox executions on top of the same state, e.g. among binary search iterations
+/// during gas estimation. Besides values, caches initial write checks and enumeration indices, which would otherwise
+/// be queried from Postgres during each execution.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SharedStorageReads(Arc<Mutex<StorageReads>>);
+
+impl SharedStorageReads {
+    fn lock(&self) -> MutexGuard<'_, StorageReads> {
+        self.0.lock().expect("shared storage reads are poisoned")
+    }
+
+    pub(super) fn wrap<S: ReadStorage>(&self, storage: S) -> StorageWithSharedReads<S> {
+        StorageWithSharedReads {
+            inner: storage,
+            reads: self.clone(),
+        }
+    }
+}
+
+/// [`ReadStorage`] wrapper that first looks up [`SharedStorageReads`].
+#[derive(Debug)]
+pub(super) struct StorageWithSharedReads<S> {
+    inner: S,
+    reads: SharedStorageReads,
+}
+
+impl<S: ReadStorage> ReadStorage for StorageWithSharedReads<S> {
+    fn read_value(&mut self, key: &StorageKey) -> StorageValue {
+        if let Some(&value) = self.reads.lock().values.get(key) {
+            return value;
+        }
+        let value = self.inner.read_value(key);
+        self.reads.lock().values.insert(*key, value);
+        value
+    }
+
+    fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        if let Some(&is_initial) = self.reads.lock().initial_writes.get(key) {
+            return is_initial;
+        }
+        let is_initial = self.inner.is_write_initial(key);
+        self.reads.lock().initial_writes.insert(*key, is_initial);
+        is_initial
+    }
+
+    fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        if let Some(dep) = self.reads.lock().factory_deps.get(&hash) {
+            return dep.clone();
+        }
+        let dep = self.inner.load_factory_dep(hash);
+        self.reads.lock().factory_deps.insert(hash, dep.clone());
+        dep
+    }
+
+    fn get_enumeration_index(&mut self, key: &StorageKey) -> Option<u64> {
+        if let Some(&index) = self.reads.lock().enumeration_indices.get(key) {
+            return index;
+        }
+        let index = self.inner.get_enumeration_index(key);
+        self.reads.lock().enumeration_indices.insert(*key, index);
+        index
+    }
+}
+
 /// This method is blocking.
 pub(super) fn apply_state_override<S: ReadStorage>(
     storage: S,
@@ -140,4 +217,28 @@ mod tests {
         let erased_value = storage.read_value(&erased_key);
         assert_eq!(erased_value, H256::zero());
     }
+
+    #[test]
+    fn sharing_storage_reads() {
+        let key = StorageKey::new(AccountTreeId::new(Address::repeat_byte(1)), H256::zero());
+        let new_key = StorageKey::new(AccountTreeId::new(Address::repeat_byte(2)), H256::zero());
+        let mut storage = InMemoryStorage::default();
+        storage.set_value(key, H256::repeat_byte(1));
+
+        let reads = SharedStorageReads::default();
+        let mut wrapped = reads.wrap(storage);
+        assert_eq!(wrapped.read_value(&key), H256::repeat_byte(1));
+        assert!(!wrapped.is_write_initial(&key));
+        assert!(wrapped.is_write_initial(&new_key));
+
+        // Modify the underlying storage; the wrapper must return the originally read data.
+        let mut storage = wrapped.inner;
+        storage.set_value(key, H256::repeat_byte(2));
+        storage.set_value(new_key, H256::repeat_byte(3));
+        let mut wrapped = reads.wrap(storage);
+        assert_eq!(wrapped.read_value(&key), H256::repeat_byte(1));
+        assert!(wrapped.is_write_initial(&new_key));
+        // Reads not cached previously are delegated to the underlying storage.
+        assert_eq!(wrapped.read_value(&new_key), H256::repeat_byte(3));
+    }
 }
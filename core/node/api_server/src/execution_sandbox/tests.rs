@@ -5,7 +5,10 @@ use std::collections::HashMap;
 use assert_matches::assert_matches;
 use test_casing::test_casing;
 use zksync_dal::ConnectionPool;
-use zksync_multivm::{interface::ExecutionResult, utils::derive_base_fee_and_gas_per_pubdata};
+use zksync_multivm::{
+    interface::ExecutionResult, tracers::ExecutionLimits,
+    utils::derive_base_fee_and_gas_per_pubdata,
+};
 use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
 use zksync_node_test_utils::{create_l1_batch, create_l2_block, prepare_recovery_snapshot};
 use zksync_state::PostgresStorageCaches;
@@ -217,6 +220,7 @@ async fn test_instantiating_vm(connection: Connection<'static, Core>, block_args
         SandboxExecutorOptions::mock().await,
         PostgresStorageCaches::new(1, 1),
         usize::MAX,
+        ExecutionLimits::default(),
         None,
     );
 
@@ -266,6 +270,7 @@ async fn validating_transaction(set_balance: bool) {
         SandboxExecutorOptions::mock().await,
         PostgresStorageCaches::new(1, 1),
         usize::MAX,
+        ExecutionLimits::default(),
         None,
     );
 
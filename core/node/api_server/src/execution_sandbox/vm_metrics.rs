@@ -1,7 +1,8 @@
 use std::time::Duration;
 
 use vise::{
-    Buckets, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LatencyObserver, Metrics,
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LatencyObserver,
+    Metrics,
 };
 use zksync_multivm::{
     interface::{TransactionExecutionMetrics, VmEvent, VmExecutionResultAndLogs},
@@ -100,6 +101,10 @@ pub(crate) struct SandboxMetrics {
     /// is (as expected) greater than the final gas estimate.
     #[metrics(buckets = Buckets::linear(-0.05..=0.15, 0.01))]
     pub estimate_gas_optimistic_gas_limit_relative_diff: Histogram<f64>,
+    /// Number of gas estimates served from the cache.
+    pub estimate_gas_cache_hits: Counter,
+    /// Number of gas estimates not found in the cache.
+    pub estimate_gas_cache_misses: Counter,
 }
 
 impl SandboxMetrics {
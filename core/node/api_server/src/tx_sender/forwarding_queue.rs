@@ -0,0 +1,597 @@
+//! Persistent queue for transactions forwarded by an external node to the main node.
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use chrono::{TimeZone, Utc};
+use tokio::sync::watch;
+use zksync_dal::{
+    forwarded_txs_dal::{ForwardedTx, ForwardedTxStatus},
+    helpers::wait_for_l1_batch,
+    transactions_dal::L2TxSubmissionResult,
+    Connection, ConnectionPool, Core, CoreDal, DalError,
+};
+use zksync_multivm::interface::{tracer::ValidationTraces, TransactionExecutionMetrics};
+use zksync_shared_metrics::{TxStage, APP_METRICS};
+use zksync_types::{
+    api, l2::L2Tx, transaction_request::TransactionRequest, Address, Nonce, H256, U256,
+};
+use zksync_web3_decl::{
+    client::{DynClient, L2},
+    error::{ClientRpcContext, EnrichedClientError, Web3Error},
+    namespaces::EthNamespaceClient,
+};
+
+use super::{tx_sink::TxSink, SubmitTxError};
+
+/// Configuration for [`TxForwarder`].
+#[derive(Debug, Clone)]
+pub struct TxForwardingConfig {
+    /// Interval between polling the queue if there are no transactions to forward.
+    pub poll_interval: Duration,
+    /// Maximum number of transactions forwarded in a single iteration.
+    pub batch_size: usize,
+    /// Delay before the first retry after a failed delivery attempt. Subsequent delays grow exponentially.
+    pub initial_retry_delay: Duration,
+    /// Upper bound for the delay between delivery attempts.
+    pub max_retry_delay: Duration,
+    /// How long transactions rejected by the main node are retained so that their status can be queried.
+    pub rejected_tx_retention: Duration,
+}
+
+impl Default for TxForwardingConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_millis(100),
+            batch_size: 100,
+            initial_retry_delay: Duration::from_millis(500),
+            max_retry_delay: Duration::from_secs(30),
+            rejected_tx_retention: Duration::from_secs(3_600),
+        }
+    }
+}
+
+impl TxForwardingConfig {
+    fn retry_delay(&self, attempts: u32) -> Duration {
+        let factor = 1_u32.checked_shl(attempts).unwrap_or(u32::MAX);
+        self.initial_retry_delay
+            .saturating_mul(factor)
+            .min(self.max_retry_delay)
+    }
+}
+
+/// [`TxSink`] for the external node that persists accepted transactions to Postgres instead of forwarding them
+/// to the main node immediately. Transactions are then delivered by [`TxForwarder`], so that submission succeeds
+/// even if the main node is temporarily unavailable.
+///
+/// Transactions are validated locally by `TxSender` before reaching the sink. Queued transactions are reported
+/// as pending by the API until they are synced from the main node, replaced, or rejected by the main node.
+#[derive(Debug)]
+pub struct TxForwardingQueue {
+    pool: ConnectionPool<Core>,
+}
+
+impl TxForwardingQueue {
+    pub fn new(pool: ConnectionPool<Core>) -> Self {
+        Self { pool }
+    }
+
+    /// Creates a task delivering queued transactions to the main node.
+    pub fn forwarder(&self, client: Box<DynClient<L2>>, config: TxForwardingConfig) -> TxForwarder {
+        TxForwarder {
+            pool: self.pool.clone(),
+            client: client.for_component("tx_forwarder"),
+            config,
+        }
+    }
+
+    /// Finds a queued transaction that is neither included into a synced L2 block nor replaced.
+    async fn find_tx(
+        storage: &mut Connection<'_, Core>,
+        hash: H256,
+    ) -> Result<Option<ForwardedTx>, Web3Error> {
+        let Some(tx) = storage
+            .forwarded_txs_dal()
+            .get_tx(hash)
+            .await
+            .map_err(DalError::generalize)?
+        else {
+            return Ok(None);
+        };
+
+        let nonces = storage
+            .storage_web3_dal()
+            .get_nonces_for_addresses(&[tx.initiator_address])
+            .await
+            .map_err(DalError::generalize)?;
+        if let Some(&stored_nonce) = nonces.get(&tx.initiator_address) {
+            // `stored_nonce` is the *next* nonce of the account, thus, strict inequality check
+            if tx.nonce < stored_nonce {
+                return Ok(None);
+            }
+        }
+        Ok(Some(tx))
+    }
+
+    fn parse_tx(tx: ForwardedTx) -> anyhow::Result<L2Tx> {
+        let (request, hash) = TransactionRequest::from_bytes_unverified(&tx.raw_tx)
+            .context("failed parsing queued transaction")?;
+        // The transaction was already validated on submission, so we don't check its size here.
+        let mut l2_tx = L2Tx::from_request(request, usize::MAX, true)
+            .context("failed parsing queued transaction")?;
+        l2_tx.set_input(tx.raw_tx, hash);
+        l2_tx.received_timestamp_ms = tx.received_at.and_utc().timestamp_millis() as u64;
+        Ok(l2_tx)
+    }
+}
+
+#[async_trait::async_trait]
+impl TxSink for TxForwardingQueue {
+    async fn submit_tx(
+        &self,
+        tx: &L2Tx,
+        _execution_metrics: TransactionExecutionMetrics,
+        _validation_traces: ValidationTraces,
+    ) -> Result<L2TxSubmissionResult, SubmitTxError> {
+        let raw_tx = tx.common_data.input_data().expect("raw tx is absent");
+        let mut storage = self
+            .pool
+            .connection_tagged("api")
+            .await
+            .map_err(|err| SubmitTxError::Internal(err.generalize().into()))?;
+        let inserted = storage
+            .forwarded_txs_dal()
+            .insert_tx(tx.hash(), tx.initiator_account(), tx.nonce(), raw_tx)
+            .await
+            .map_err(|err| SubmitTxError::Internal(err.generalize().into()))?;
+
+        let submission_result = if inserted {
+            tracing::info!("Queued tx {:?} for forwarding to the main node", tx.hash());
+            L2TxSubmissionResult::Proxied
+        } else {
+            L2TxSubmissionResult::Duplicate
+        };
+        Ok(submission_result)
+    }
+
+    async fn lookup_pending_nonce(
+        &self,
+        account_address: Address,
+        last_known_nonce: u32,
+    ) -> Result<Option<Nonce>, Web3Error> {
+        let mut storage = self
+            .pool
+            .connection_tagged("api")
+            .await
+            .map_err(DalError::generalize)?;
+        let nonces = storage
+            .forwarded_txs_dal()
+            .get_pending_nonces(account_address, Nonce(last_known_nonce))
+            .await
+            .map_err(DalError::generalize)?;
+
+        let mut pending_nonce = Nonce(last_known_nonce);
+        for nonce in nonces {
+            // If nonces are not sequential, then we should not increment the pending nonce.
+            if nonce != pending_nonce {
+                break;
+            }
+            pending_nonce += 1;
+        }
+        Ok(Some(pending_nonce))
+    }
+
+    async fn lookup_tx(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        id: api::TransactionId,
+    ) -> Result<Option<api::Transaction>, Web3Error> {
+        let api::TransactionId::Hash(hash) = id else {
+            return Ok(None);
+        };
+        let Some(tx) = Self::find_tx(storage, hash).await? else {
+            return Ok(None);
+        };
+        if matches!(tx.status, ForwardedTxStatus::Rejected { .. }) {
+            // Rejected transactions will never be included, so we don't report them as pending.
+            return Ok(None);
+        }
+        Ok(Some(Self::parse_tx(tx)?.into()))
+    }
+
+    async fn lookup_tx_details(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        hash: H256,
+    ) -> Result<Option<api::TransactionDetails>, Web3Error> {
+        let Some(tx) = Self::find_tx(storage, hash).await? else {
+            return Ok(None);
+        };
+        let status = match &tx.status {
+            ForwardedTxStatus::Rejected { .. } => api::TransactionStatus::Failed,
+            ForwardedTxStatus::Queued { .. } | ForwardedTxStatus::Forwarded => {
+                api::TransactionStatus::Pending
+            }
+        };
+        let received_at = Utc.from_utc_datetime(&tx.received_at);
+        let tx = Self::parse_tx(tx)?;
+        Ok(Some(api::TransactionDetails {
+            is_l1_originated: false,
+            status,
+            fee: U256::zero(), // always zero for pending transactions
+            gas_per_pubdata: tx.common_data.fee.gas_per_pubdata_limit,
+            initiator_address: tx.initiator_account(),
+            received_at,
+            eth_commit_tx_hash: None,
+            eth_prove_tx_hash: None,
+            eth_execute_tx_hash: None,
+        }))
+    }
+}
+
+/// Outcome of a single delivery attempt.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DeliveryOutcome {
+    Forwarded,
+    Retried,
+    Rejected,
+}
+
+/// Task delivering transactions from [`TxForwardingQueue`] to the main node with retries,
+/// and removing transactions that are synced back from the main node or replaced.
+#[derive(Debug)]
+pub struct TxForwarder {
+    pool: ConnectionPool<Core>,
+    client: Box<DynClient<L2>>,
+    config: TxForwardingConfig,
+}
+
+impl TxForwarder {
+    async fn send_tx(&self, tx: &ForwardedTx) -> Result<H256, EnrichedClientError> {
+        self.client
+            .send_raw_transaction(tx.raw_tx.clone().into())
+            .rpc_context("send_raw_transaction")
+            .with_arg("tx_hash", &tx.hash)
+            .await
+    }
+
+    async fn forward_tx(&self, tx: &ForwardedTx) -> anyhow::Result<DeliveryOutcome> {
+        let ForwardedTxStatus::Queued { attempts } = tx.status else {
+            anyhow::bail!(
+                "unexpected status of queued tx {:?}: {:?}",
+                tx.hash,
+                tx.status
+            );
+        };
+        let result = self.send_tx(tx).await;
+
+        let mut storage = self.pool.connection_tagged("tx_forwarder").await?;
+        let mut dal = storage.forwarded_txs_dal();
+        Ok(match result {
+            Ok(_) => {
+                tracing::info!("Forwarded tx {:?} to the main node", tx.hash);
+                APP_METRICS.processed_txs[&TxStage::Proxied].inc();
+                dal.mark_tx_as_forwarded(tx.hash).await?;
+                DeliveryOutcome::Forwarded
+            }
+            Err(err) if err.is_retriable() => {
+                let retry_delay = self.config.retry_delay(attempts);
+                tracing::info!(
+                    "Failed forwarding tx {:?} to the main node (attempt #{}), will retry in {retry_delay:?}: {err}",
+                    tx.hash,
+                    attempts + 1
+                );
+                dal.record_failed_attempt(tx.hash, &err.to_string(), retry_delay)
+                    .await?;
+                DeliveryOutcome::Retried
+            }
+            Err(err) => {
+                tracing::info!("Main node rejected tx {:?}: {err}", tx.hash);
+                dal.mark_tx_as_rejected(tx.hash, &err.to_string()).await?;
+                DeliveryOutcome::Rejected
+            }
+        })
+    }
+
+    /// Forwards a batch of due transactions. Returns the number of processed transactions.
+    async fn forward_txs(&self) -> anyhow::Result<usize> {
+        let mut storage = self.pool.connection_tagged("tx_forwarder").await?;
+        let txs = storage
+            .forwarded_txs_dal()
+            .get_txs_to_forward(self.config.batch_size)
+            .await?;
+        drop(storage);
+
+        for tx in &txs {
+            self.forward_tx(tx).await?;
+        }
+        Ok(txs.len())
+    }
+
+    async fn collect_garbage(&self) -> anyhow::Result<()> {
+        let mut storage = self.pool.connection_tagged("tx_forwarder").await?;
+        let addresses = storage
+            .forwarded_txs_dal()
+            .get_initiator_addresses()
+            .await?;
+        let stored_nonces = storage
+            .storage_web3_dal()
+            .get_nonces_for_addresses(&addresses)
+            .await?;
+        let removed_stale_txs = storage
+            .forwarded_txs_dal()
+            .remove_stale_txs(&stored_nonces)
+            .await?;
+        let removed_rejected_txs = storage
+            .forwarded_txs_dal()
+            .remove_rejected_txs(self.config.rejected_tx_retention)
+            .await?;
+        if removed_stale_txs + removed_rejected_txs > 0 {
+            tracing::debug!(
+                "Removed {removed_stale_txs} included / replaced and {removed_rejected_txs} rejected txs from the forwarding queue"
+            );
+        }
+        Ok(())
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        tracing::info!("Waiting for at least one L1 batch in Postgres to start TxForwarder");
+        // Same as for `TxProxy`, the earliest L1 batch may be absent immediately after snapshot recovery.
+        let earliest_l1_batch_number =
+            wait_for_l1_batch(&self.pool, self.config.poll_interval, &mut stop_receiver)
+                .await
+                .context("error while waiting for L1 batch in Postgres")?;
+        if earliest_l1_batch_number.is_none() {
+            tracing::info!("Received shutdown signal before TxForwarder is started; shutting down");
+            return Ok(());
+        }
+
+        while !*stop_receiver.borrow() {
+            let processed_txs = self.forward_txs().await?;
+            self.collect_garbage().await?;
+            if processed_txs < self.config.batch_size {
+                tokio::time::timeout(self.config.poll_interval, stop_receiver.changed())
+                    .await
+                    .ok();
+            }
+        }
+        tracing::info!("Stop signal received; TxForwarder is shutting down");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use zksync_crypto_primitives::K256PrivateKey;
+    use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
+    use zksync_node_test_utils::create_l2_block;
+    use zksync_types::{
+        fee::Fee, get_intrinsic_constants, get_nonce_key, l2::L2Tx, web3::Bytes, L2BlockNumber,
+        L2ChainId, StorageLog,
+    };
+    use zksync_web3_decl::{
+        client::MockClient,
+        jsonrpsee::{core::ClientError, types::ErrorObject},
+    };
+
+    use super::*;
+
+    fn create_signed_tx(private_key: &K256PrivateKey, nonce: u32) -> L2Tx {
+        let fee = Fee {
+            gas_limit: (get_intrinsic_constants().l2_tx_intrinsic_gas * 2).into(),
+            max_fee_per_gas: 100_u64.into(),
+            max_priority_fee_per_gas: 0_u64.into(),
+            gas_per_pubdata_limit: 800_u64.into(),
+        };
+        L2Tx::new_signed(
+            Some(Address::repeat_byte(1)),
+            vec![],
+            Nonce(nonce),
+            fee,
+            U256::zero(),
+            L2ChainId::default(),
+            private_key,
+            vec![],
+            Default::default(),
+        )
+        .unwrap()
+    }
+
+    async fn submit_tx(queue: &TxForwardingQueue, tx: &L2Tx) -> L2TxSubmissionResult {
+        queue
+            .submit_tx(
+                tx,
+                TransactionExecutionMetrics::default(),
+                ValidationTraces::default(),
+            )
+            .await
+            .unwrap()
+    }
+
+    async fn prepare_storage(pool: &ConnectionPool<Core>) {
+        let mut storage = pool.connection().await.unwrap();
+        insert_genesis_batch(&mut storage, &GenesisParams::mock())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn queueing_transactions() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        prepare_storage(&pool).await;
+        let queue = TxForwardingQueue::new(pool.clone());
+        let private_key = K256PrivateKey::random();
+        let txs: Vec<_> = [0, 1, 3]
+            .into_iter()
+            .map(|nonce| create_signed_tx(&private_key, nonce))
+            .collect();
+        let initiator = txs[0].initiator_account();
+
+        for tx in &txs {
+            assert_eq!(submit_tx(&queue, tx).await, L2TxSubmissionResult::Proxied);
+        }
+        assert_eq!(
+            submit_tx(&queue, &txs[0]).await,
+            L2TxSubmissionResult::Duplicate
+        );
+
+        let pending_nonce = queue.lookup_pending_nonce(initiator, 0).await.unwrap();
+        assert_eq!(pending_nonce, Some(Nonce(2)));
+        let pending_nonce = queue.lookup_pending_nonce(initiator, 3).await.unwrap();
+        assert_eq!(pending_nonce, Some(Nonce(4)));
+
+        let mut storage = pool.connection().await.unwrap();
+        let found_tx = queue
+            .lookup_tx(&mut storage, api::TransactionId::Hash(txs[1].hash()))
+            .await
+            .unwrap()
+            .expect("no transaction");
+        assert_eq!(found_tx.hash, txs[1].hash());
+        assert_eq!(found_tx.from, Some(initiator));
+        assert_eq!(found_tx.nonce, 1.into());
+
+        let tx_details = queue
+            .lookup_tx_details(&mut storage, txs[1].hash())
+            .await
+            .unwrap()
+            .expect("no transaction");
+        assert_eq!(tx_details.status, api::TransactionStatus::Pending);
+        assert_eq!(tx_details.initiator_address, initiator);
+    }
+
+    #[tokio::test]
+    async fn forwarding_transactions_with_retries() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        prepare_storage(&pool).await;
+        let queue = TxForwardingQueue::new(pool.clone());
+        let tx = create_signed_tx(&K256PrivateKey::random(), 0);
+        submit_tx(&queue, &tx).await;
+
+        let call_count = Arc::new(AtomicUsize::new(0));
+        let main_node_client = MockClient::builder(L2::default())
+            .method("eth_sendRawTransaction", {
+                let call_count = call_count.clone();
+                let tx = tx.clone();
+                move |bytes: Bytes| {
+                    assert_eq!(bytes.0, tx.common_data.input_data().unwrap());
+                    if call_count.fetch_add(1, Ordering::SeqCst) == 0 {
+                        Err(ClientError::RequestTimeout)
+                    } else {
+                        Ok(tx.hash())
+                    }
+                }
+            })
+            .build();
+        let config = TxForwardingConfig {
+            initial_retry_delay: Duration::ZERO,
+            ..TxForwardingConfig::default()
+        };
+        let forwarder = queue.forwarder(Box::new(main_node_client), config);
+
+        assert_eq!(forwarder.forward_txs().await.unwrap(), 1);
+        let mut storage = pool.connection().await.unwrap();
+        let queued_tx = storage
+            .forwarded_txs_dal()
+            .get_tx(tx.hash())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(queued_tx.status, ForwardedTxStatus::Queued { attempts: 1 });
+
+        assert_eq!(forwarder.forward_txs().await.unwrap(), 1);
+        assert_eq!(call_count.load(Ordering::SeqCst), 2);
+        let queued_tx = storage
+            .forwarded_txs_dal()
+            .get_tx(tx.hash())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(queued_tx.status, ForwardedTxStatus::Forwarded);
+        assert_eq!(forwarder.forward_txs().await.unwrap(), 0);
+
+        // The transaction should still be reported as pending until it's synced.
+        let tx_details = queue
+            .lookup_tx_details(&mut storage, tx.hash())
+            .await
+            .unwrap()
+            .expect("no transaction");
+        assert_eq!(tx_details.status, api::TransactionStatus::Pending);
+
+        // Emulate the transaction getting synced.
+        storage
+            .blocks_dal()
+            .insert_l2_block(&create_l2_block(1))
+            .await
+            .unwrap();
+        let nonce_key = get_nonce_key(&tx.initiator_account());
+        let nonce_log = StorageLog::new_write_log(nonce_key, H256::from_low_u64_be(1));
+        storage
+            .storage_logs_dal()
+            .insert_storage_logs(L2BlockNumber(1), &[nonce_log])
+            .await
+            .unwrap();
+
+        let found_tx = queue
+            .lookup_tx(&mut storage, api::TransactionId::Hash(tx.hash()))
+            .await
+            .unwrap();
+        assert!(found_tx.is_none(), "{found_tx:?}");
+        forwarder.collect_garbage().await.unwrap();
+        let queued_tx = storage.forwarded_txs_dal().get_tx(tx.hash()).await.unwrap();
+        assert!(queued_tx.is_none(), "{queued_tx:?}");
+    }
+
+    #[tokio::test]
+    async fn transaction_rejected_by_main_node() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        prepare_storage(&pool).await;
+        let queue = TxForwardingQueue::new(pool.clone());
+        let tx = create_signed_tx(&K256PrivateKey::random(), 0);
+        submit_tx(&queue, &tx).await;
+
+        let main_node_client = MockClient::builder(L2::default())
+            .method("eth_sendRawTransaction", |_bytes: Bytes| {
+                let err = ErrorObject::owned(3, "nonce too low", None::<()>);
+                Err::<H256, _>(ClientError::Call(err))
+            })
+            .build();
+        let forwarder = queue.forwarder(Box::new(main_node_client), TxForwardingConfig::default());
+        assert_eq!(forwarder.forward_txs().await.unwrap(), 1);
+        assert_eq!(forwarder.forward_txs().await.unwrap(), 0);
+
+        let mut storage = pool.connection().await.unwrap();
+        let found_tx = queue
+            .lookup_tx(&mut storage, api::TransactionId::Hash(tx.hash()))
+            .await
+            .unwrap();
+        assert!(found_tx.is_none(), "{found_tx:?}");
+        let tx_details = queue
+            .lookup_tx_details(&mut storage, tx.hash())
+            .await
+            .unwrap()
+            .expect("no transaction");
+        assert_eq!(tx_details.status, api::TransactionStatus::Failed);
+
+        // Rejected transactions should not affect the pending nonce.
+        let pending_nonce = queue
+            .lookup_pending_nonce(tx.initiator_account(), 0)
+            .await
+            .unwrap();
+        assert_eq!(pending_nonce, Some(Nonce(0)));
+    }
+
+    #[test]
+    fn retry_delays() {
+        let config = TxForwardingConfig::default();
+        assert_eq!(config.retry_delay(0), Duration::from_millis(500));
+        assert_eq!(config.retry_delay(1), Duration::from_secs(1));
+        assert_eq!(config.retry_delay(3), Duration::from_secs(4));
+        assert_eq!(config.retry_delay(10), config.max_retry_delay);
+        assert_eq!(config.retry_delay(100), config.max_retry_delay);
+    }
+}
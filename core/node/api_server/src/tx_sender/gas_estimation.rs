@@ -1,9 +1,11 @@
-use std::{ops, time::Instant};
+use std::{num::NonZeroUsize, ops, sync::Mutex, time::Instant};
 
 use anyhow::Context;
+use lru::LruCache;
+use tokio::sync::OnceCell;
 use zksync_dal::CoreDal;
 use zksync_multivm::{
-    interface::{TransactionExecutionMetrics, VmExecutionResultAndLogs},
+    interface::{ExecutionResult, Halt, TransactionExecutionMetrics, VmExecutionResultAndLogs},
     utils::{
         adjust_pubdata_price_for_tx, derive_base_fee_and_gas_per_pubdata, derive_overhead,
         get_max_batch_gas_limit,
@@ -11,14 +13,24 @@ use zksync_multivm::{
 };
 use zksync_system_constants::MAX_L2_TX_GAS_LIMIT;
 use zksync_types::{
-    api::state_override::StateOverride, fee::Fee, fee_model::BatchFeeInput, get_code_key,
-    ExecuteTransactionCommon, PackedEthSignature, ProtocolVersionId, Transaction, H256,
+    api::{
+        state_override::StateOverride, GasEstimationBoundReason, GasEstimationDetails,
+        GasEstimationIteration,
+    },
+    fee::Fee,
+    fee_model::BatchFeeInput,
+    get_code_key,
+    web3::keccak256,
+    Address, ExecuteTransactionCommon, L2BlockNumber, PackedEthSignature, ProtocolVersionId,
+    Transaction, H256, U256,
 };
 
 use super::{result::ApiCallResult, SubmitTxError, TxSender};
-use crate::execution_sandbox::{BlockArgs, SandboxAction, VmPermit, SANDBOX_METRICS};
+use crate::execution_sandbox::{
+    BlockArgs, SandboxAction, SandboxExecutionError, SharedStorageReads, VmPermit, SANDBOX_METRICS,
+};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub(crate) enum BinarySearchKind {
     /// Full binary search.
     Full,
@@ -36,12 +48,95 @@ impl BinarySearchKind {
     }
 }
 
+/// Key for [`GasEstimationCache`]. Covers all estimation inputs except for the block timestamp, which is assumed
+/// not to influence the estimate in practice.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct GasEstimationCacheKey {
+    block_number: L2BlockNumber,
+    initiator: Address,
+    target: Option<Address>,
+    /// Hash of the remaining transaction data (incl. calldata) and the fee input.
+    digest: H256,
+    estimated_fee_scale_factor: u64,
+    acceptable_overestimation: u64,
+    kind: BinarySearchKind,
+}
+
+/// LRU cache for recent gas estimates. Estimates with state overrides are never cached.
+#[derive(Debug)]
+pub(super) struct GasEstimationCache(Mutex<LruCache<GasEstimationCacheKey, GasEstimationDetails>>);
+
+impl GasEstimationCache {
+    pub(super) fn new(capacity: NonZeroUsize) -> Self {
+        Self(Mutex::new(LruCache::new(capacity)))
+    }
+
+    fn get(&self, key: &GasEstimationCacheKey) -> Option<GasEstimationDetails> {
+        let mut cache = self.0.lock().expect("gas estimation cache is poisoned");
+        cache.get(key).cloned()
+    }
+
+    fn insert(&self, key: GasEstimationCacheKey, details: GasEstimationDetails) {
+        let mut cache = self.0.lock().expect("gas estimation cache is poisoned");
+        cache.put(key, details);
+    }
+}
+
+/// Output of the gas limit binary search.
+#[derive(Debug)]
+struct BinarySearchOutput {
+    upper_bound: u64,
+    iterations: Vec<GasEstimationIteration>,
+}
+
+impl BinarySearchOutput {
+    fn final_bound_reason(&self, initial_pivot: Option<u64>) -> GasEstimationBoundReason {
+        // The upper bound is always equal to the last succeeding gas limit, if any.
+        let last_success = self
+            .iterations
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, iteration)| iteration.success);
+        match last_success {
+            None => GasEstimationBoundReason::InitialUpperBound,
+            Some((0, iteration)) if Some(iteration.gas_limit.as_u64()) == initial_pivot => {
+                GasEstimationBoundReason::OptimisticGasLimit
+            }
+            Some(_) => GasEstimationBoundReason::BinarySearch,
+        }
+    }
+}
+
 impl TxSender {
+    pub async fn get_txs_fee_in_wei(
+        &self,
+        tx: Transaction,
+        block_args: BlockArgs,
+        estimated_fee_scale_factor: f64,
+        acceptable_overestimation: u64,
+        state_override: Option<StateOverride>,
+        kind: BinarySearchKind,
+    ) -> Result<Fee, SubmitTxError> {
+        let details = self
+            .get_txs_fee_in_wei_detailed(
+                tx,
+                block_args,
+                estimated_fee_scale_factor,
+                acceptable_overestimation,
+                state_override,
+                kind,
+            )
+            .await?;
+        Ok(details.fee)
+    }
+
+    /// Same as [`Self::get_txs_fee_in_wei()`], but also returns details on how the estimate was obtained.
     #[tracing::instrument(level = "debug", skip_all, fields(
         initiator = ?tx.initiator_account(),
         nonce = ?tx.nonce(),
     ))]
-    pub async fn get_txs_fee_in_wei(
+    pub async fn get_txs_fee_in_wei_detailed(
         &self,
         tx: Transaction,
         block_args: BlockArgs,
@@ -49,11 +144,33 @@ impl TxSender {
         acceptable_overestimation: u64,
         state_override: Option<StateOverride>,
         kind: BinarySearchKind,
-    ) -> Result<Fee, SubmitTxError> {
+    ) -> Result<GasEstimationDetails, SubmitTxError> {
         let estimation_started_at = Instant::now();
         let mut estimator = GasEstimator::new(self, tx, block_args, state_override).await?;
         estimator.adjust_transaction_fee();
 
+        let cache = self
+            .0
+            .gas_estimation_cache
+            .as_ref()
+            .filter(|_| estimator.state_override.is_none());
+        let cache = cache.map(|cache| {
+            let key =
+                estimator.cache_key(estimated_fee_scale_factor, acceptable_overestimation, kind);
+            (cache, key)
+        });
+        if let Some((cache, key)) = &cache {
+            if let Some(details) = cache.get(key) {
+                SANDBOX_METRICS.estimate_gas_cache_hits.inc();
+                tracing::debug!("Gas estimate is served from cache: {details:?}");
+                return Ok(GasEstimationDetails {
+                    cached: true,
+                    ..details
+                });
+            }
+            SANDBOX_METRICS.estimate_gas_cache_misses.inc();
+        }
+
         let initial_estimate = estimator.initialize().await?;
         tracing::trace!(
             "preparation took {:?}, starting binary search",
@@ -83,9 +200,15 @@ impl TxSender {
             }
         };
 
-        let (unscaled_gas_limit, iteration_count) =
-            Self::binary_search(&estimator, bounds, initial_pivot, acceptable_overestimation)
-                .await?;
+        let search_output = Self::binary_search(
+            &estimator,
+            bounds.clone(),
+            initial_pivot,
+            acceptable_overestimation,
+        )
+        .await?;
+        let unscaled_gas_limit = search_output.upper_bound;
+        let iteration_count = search_output.iterations.len();
         // Metrics are intentionally reported regardless of the binary search mode, so that the collected stats can be used to adjust
         // optimized binary search params (e.g., the initial pivot multiplier).
         if let Some(lower_bound) = optimized_lower_bound {
@@ -114,9 +237,26 @@ impl TxSender {
         );
 
         let suggested_gas_limit = (unscaled_gas_limit as f64 * estimated_fee_scale_factor) as u64;
-        estimator
+        let final_bound_reason = search_output.final_bound_reason(initial_pivot);
+        let fee = estimator
             .finalize(suggested_gas_limit, estimated_fee_scale_factor)
-            .await
+            .await?;
+        let details = GasEstimationDetails {
+            fee,
+            cached: false,
+            pubdata_published: initial_estimate.pubdata_published.into(),
+            gas_charged_for_pubdata: initial_estimate.gas_charged_for_pubdata.into(),
+            initial_lower_bound: (*bounds.start()).into(),
+            initial_upper_bound: (*bounds.end()).into(),
+            optimistic_gas_limit: initial_pivot.map(Into::into),
+            iterations: search_output.iterations,
+            unscaled_gas_limit: unscaled_gas_limit.into(),
+            final_bound_reason,
+        };
+        if let Some((cache, key)) = cache {
+            cache.insert(key, details.clone());
+        }
+        Ok(details)
     }
 
     async fn binary_search(
@@ -124,8 +264,8 @@ impl TxSender {
         bounds: ops::RangeInclusive<u64>,
         initial_pivot: Option<u64>,
         acceptable_overestimation: u64,
-    ) -> Result<(u64, usize), SubmitTxError> {
-        let mut number_of_iterations = 0;
+    ) -> Result<BinarySearchOutput, SubmitTxError> {
+        let mut iterations = vec![];
         let mut lower_bound = *bounds.start();
         let mut upper_bound = *bounds.end();
 
@@ -133,12 +273,16 @@ impl TxSender {
             let iteration_started_at = Instant::now();
             let (result, _) = estimator.step(pivot).await?;
             Self::adjust_search_bounds(&mut lower_bound, &mut upper_bound, pivot, &result);
+            iterations.push(GasEstimationIteration {
+                gas_limit: pivot.into(),
+                success: !result.result.is_failed(),
+            });
 
             tracing::trace!(
-                "iteration {number_of_iterations} took {:?}. lower_bound: {lower_bound}, upper_bound: {upper_bound}",
+                "iteration {} took {:?}. lower_bound: {lower_bound}, upper_bound: {upper_bound}",
+                iterations.len() - 1,
                 iteration_started_at.elapsed()
             );
-            number_of_iterations += 1;
         }
 
         // We are using binary search to find the minimal values of gas_limit under which the transaction succeeds.
@@ -150,17 +294,24 @@ impl TxSender {
             let iteration_started_at = Instant::now();
             let (result, _) = estimator.step(mid).await?;
             Self::adjust_search_bounds(&mut lower_bound, &mut upper_bound, mid, &result);
+            iterations.push(GasEstimationIteration {
+                gas_limit: mid.into(),
+                success: !result.result.is_failed(),
+            });
 
             tracing::trace!(
-                "iteration {number_of_iterations} took {:?}. lower_bound: {lower_bound}, upper_bound: {upper_bound}",
+                "iteration {} took {:?}. lower_bound: {lower_bound}, upper_bound: {upper_bound}",
+                iterations.len() - 1,
                 iteration_started_at.elapsed()
             );
-            number_of_iterations += 1;
         }
         SANDBOX_METRICS
             .estimate_gas_binary_search_iterations
-            .observe(number_of_iterations);
-        Ok((upper_bound, number_of_iterations))
+            .observe(iterations.len());
+        Ok(BinarySearchOutput {
+            upper_bound,
+            iterations,
+        })
     }
 
     async fn ensure_sufficient_balance(
@@ -235,6 +386,8 @@ pub(super) struct InitialGasEstimate {
     /// Operator-defined overhead for the estimated transaction. For recent VM versions, the overhead only depends
     /// on the transaction encoding size.
     pub operator_overhead: u64,
+    /// Set to 0 if not estimated (e.g., for L1 transactions).
+    pub pubdata_published: u32,
     pub gas_charged_for_pubdata: u64,
 }
 
@@ -287,6 +440,8 @@ impl InitialGasEstimate {
     }
 }
 
+type StepOutput = (VmExecutionResultAndLogs, TransactionExecutionMetrics);
+
 /// Encapsulates gas estimation process for a specific transaction.
 ///
 /// Public for testing purposes.
@@ -295,7 +450,13 @@ pub(super) struct GasEstimator<'a> {
     sender: &'a TxSender,
     transaction: Transaction,
     state_override: Option<StateOverride>,
-    vm_permit: VmPermit,
+    /// VM permit acquired on the first execution and held for the whole duration of the binary search.
+    vm_permit: OnceCell<VmPermit>,
+    /// Storage reads shared among all executions, since they are performed on top of the same state.
+    shared_reads: SharedStorageReads,
+    /// Last succeeding [`Self::step()`] together with the tested gas limit. Allows to not re-execute the transaction
+    /// on finalization if the gas limit is unchanged.
+    last_successful_step: Mutex<Option<(u64, StepOutput)>>,
     fee_input: BatchFeeInput,
     base_fee: u64,
     gas_per_pubdata_byte: u64,
@@ -336,15 +497,13 @@ impl<'a> GasEstimator<'a> {
             }
         }
 
-        // Acquire the vm token for the whole duration of the binary search.
-        let vm_permit = sender.0.vm_concurrency_limiter.acquire().await;
-        let vm_permit = vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
-
         Ok(Self {
             sender,
             transaction,
             state_override,
-            vm_permit,
+            vm_permit: OnceCell::new(),
+            shared_reads: SharedStorageReads::default(),
+            last_successful_step: Mutex::new(None),
             fee_input,
             base_fee,
             gas_per_pubdata_byte,
@@ -369,6 +528,42 @@ impl<'a> GasEstimator<'a> {
         }
     }
 
+    fn cache_key(
+        &self,
+        estimated_fee_scale_factor: f64,
+        acceptable_overestimation: u64,
+        kind: BinarySearchKind,
+    ) -> GasEstimationCacheKey {
+        // Erase transaction data that doesn't influence the estimate.
+        let mut tx = self.transaction.clone();
+        tx.received_timestamp_ms = 0;
+        tx.raw_bytes = None;
+        match &mut tx.common_data {
+            ExecuteTransactionCommon::L1(common_data) => {
+                common_data.gas_limit = U256::zero();
+            }
+            ExecuteTransactionCommon::L2(common_data) => {
+                common_data.fee.gas_limit = U256::zero();
+                common_data.input = None;
+            }
+            ExecuteTransactionCommon::ProtocolUpgrade(common_data) => {
+                common_data.gas_limit = U256::zero();
+            }
+        }
+        let digest_input =
+            serde_json::to_vec(&(&tx, self.fee_input)).expect("failed serializing transaction");
+
+        GasEstimationCacheKey {
+            block_number: self.block_args.resolved_block_number(),
+            initiator: tx.initiator_account(),
+            target: tx.execute.contract_address,
+            digest: H256(keccak256(&digest_input)),
+            estimated_fee_scale_factor: estimated_fee_scale_factor.to_bits(),
+            acceptable_overestimation,
+            kind,
+        }
+    }
+
     pub(super) async fn initialize(&self) -> Result<InitialGasEstimate, SubmitTxError> {
         let operator_overhead = self.tx_overhead(self.max_gas_limit);
 
@@ -385,6 +580,7 @@ impl<'a> GasEstimator<'a> {
                 total_gas_charged: None,
                 computational_gas_used: None,
                 operator_overhead,
+                pubdata_published: 0,
                 gas_charged_for_pubdata: 0,
             })
         } else {
@@ -397,14 +593,15 @@ impl<'a> GasEstimator<'a> {
             result.check_api_call_result()?;
 
             // It is assumed that there is no overflow here
-            let gas_charged_for_pubdata =
-                u64::from(result.statistics.pubdata_published) * self.gas_per_pubdata_byte;
+            let pubdata_published = result.statistics.pubdata_published;
+            let gas_charged_for_pubdata = u64::from(pubdata_published) * self.gas_per_pubdata_byte;
 
             let total_gas_charged = self.max_gas_limit.checked_sub(result.refunds.gas_refunded);
             Ok(InitialGasEstimate {
                 total_gas_charged,
                 computational_gas_used: Some(result.statistics.computational_gas_used.into()),
                 operator_overhead,
+                pubdata_published,
                 gas_charged_for_pubdata,
             })
         }
@@ -422,21 +619,59 @@ impl<'a> GasEstimator<'a> {
         .into()
     }
 
-    async fn step(
-        &self,
-        tx_gas_limit: u64,
-    ) -> Result<(VmExecutionResultAndLogs, TransactionExecutionMetrics), SubmitTxError> {
+    async fn step(&self, tx_gas_limit: u64) -> Result<StepOutput, SubmitTxError> {
+        let memoized_output = {
+            let last_step = self
+                .last_successful_step
+                .lock()
+                .expect("last step is poisoned");
+            last_step
+                .as_ref()
+                .filter(|(gas_limit, _)| *gas_limit == tx_gas_limit)
+                .map(|(_, output)| output.clone())
+        };
+        if let Some(output) = memoized_output {
+            return Ok(output);
+        }
+
         let gas_limit_with_overhead = tx_gas_limit + self.tx_overhead(tx_gas_limit);
         // We need to ensure that we never use a gas limit that is higher than the maximum allowed
         let forced_gas_limit =
             gas_limit_with_overhead.min(get_max_batch_gas_limit(self.protocol_version.into()));
-        self.unadjusted_step(forced_gas_limit).await
+        let output = self.unadjusted_step(forced_gas_limit).await?;
+        // Exceeding execution limits doesn't depend on the gas limit (or, if it does, we cannot afford larger gas limits),
+        // so there's no point continuing the binary search.
+        if let ExecutionResult::Halt {
+            reason: reason @ (Halt::ExecutionTimeout | Halt::ExecutionCyclesLimitReached),
+        } = &output.0.result
+        {
+            return Err(SandboxExecutionError::from(reason.clone()).into());
+        }
+        if !output.0.result.is_failed() {
+            let mut last_step = self
+                .last_successful_step
+                .lock()
+                .expect("last step is poisoned");
+            *last_step = Some((tx_gas_limit, output.clone()));
+        }
+        Ok(output)
+    }
+
+    async fn vm_permit(&self) -> Result<VmPermit, SubmitTxError> {
+        let vm_permit = self
+            .vm_permit
+            .get_or_try_init(|| async {
+                let vm_permit = self.sender.0.vm_concurrency_limiter.acquire().await;
+                vm_permit.ok_or(SubmitTxError::ServerShuttingDown)
+            })
+            .await?;
+        Ok(vm_permit.clone())
     }
 
     pub(super) async fn unadjusted_step(
         &self,
         forced_gas_limit: u64,
-    ) -> Result<(VmExecutionResultAndLogs, TransactionExecutionMetrics), SubmitTxError> {
+    ) -> Result<StepOutput, SubmitTxError> {
         let mut tx = self.transaction.clone();
         match &mut tx.common_data {
             ExecuteTransactionCommon::L1(l1_common_data) => {
@@ -465,15 +700,17 @@ impl<'a> GasEstimator<'a> {
             fee_input: self.fee_input,
             base_fee: self.base_fee,
         };
+        let vm_permit = self.vm_permit().await?;
         let connection = self.sender.acquire_replica_connection().await?;
         let executor = &self.sender.0.executor;
         let execution_output = executor
-            .execute_in_sandbox(
-                self.vm_permit.clone(),
+            .execute_in_sandbox_with_shared_reads(
+                vm_permit,
                 connection,
                 action,
                 &self.block_args,
                 self.state_override.clone(),
+                &self.shared_reads,
             )
             .await?;
         Ok((execution_output.vm, execution_output.metrics))
@@ -1,6 +1,6 @@
 //! Helper module to submit transactions into the ZKsync Network.
 
-use std::{sync::Arc, time::Duration};
+use std::{num::NonZeroUsize, sync::Arc, time::Duration};
 
 use anyhow::Context as _;
 use tokio::sync::RwLock;
@@ -13,6 +13,7 @@ use zksync_multivm::{
         tracer::TimestampAsserterParams as TracerTimestampAsserterParams, OneshotTracingParams,
         TransactionExecutionMetrics, VmExecutionResultAndLogs,
     },
+    tracers::ExecutionLimits,
     utils::{
         derive_base_fee_and_gas_per_pubdata, get_max_batch_gas_limit, get_max_new_factory_deps,
     },
@@ -38,12 +39,19 @@ use zksync_vm_executor::oneshot::{
 };
 
 pub(super) use self::{gas_estimation::BinarySearchKind, result::SubmitTxError};
-use self::{master_pool_sink::MasterPoolSink, result::ApiCallResult, tx_sink::TxSink};
-use crate::execution_sandbox::{
-    BlockArgs, SandboxAction, SandboxExecutor, SubmitTxStage, VmConcurrencyBarrier,
-    VmConcurrencyLimiter, SANDBOX_METRICS,
+use self::{
+    gas_estimation::GasEstimationCache, master_pool_sink::MasterPoolSink, result::ApiCallResult,
+    tx_sink::TxSink,
+};
+use crate::{
+    execution_sandbox::{
+        BlockArgs, SandboxAction, SandboxExecutor, SubmitTxStage, VmConcurrencyBarrier,
+        VmConcurrencyLimiter, SANDBOX_METRICS,
+    },
+    web3::backend_jsonrpsee::session_master_pool,
 };
 
+pub mod forwarding_queue;
 mod gas_estimation;
 pub mod master_pool_sink;
 pub mod proxy;
@@ -204,10 +212,15 @@ impl TxSenderBuilder {
             .config
             .vm_execution_cache_misses_limit
             .unwrap_or(usize::MAX);
+        let execution_limits = ExecutionLimits {
+            timeout: self.config.vm_execution_timeout,
+            cycles: self.config.vm_execution_cycles_limit,
+        };
         let executor = SandboxExecutor::real(
             executor_options,
             storage_caches,
             missed_storage_invocation_limit,
+            execution_limits,
             self.config.timestamp_asserter_params.clone().map(|params| {
                 TracerTimestampAsserterParams {
                     address: params.address,
@@ -216,6 +229,9 @@ impl TxSenderBuilder {
             }),
         );
 
+        let gas_estimation_cache =
+            NonZeroUsize::new(self.config.estimate_gas_cache_size).map(GasEstimationCache::new);
+
         TxSender(Arc::new(TxSenderInner {
             sender_config: self.config,
             tx_sink: self.tx_sink,
@@ -225,6 +241,7 @@ impl TxSenderBuilder {
             whitelisted_tokens_for_aa_cache,
             sealer,
             executor,
+            gas_estimation_cache,
         }))
     }
 }
@@ -240,6 +257,12 @@ pub struct TxSenderConfig {
     pub max_nonce_ahead: u32,
     pub max_allowed_l2_tx_gas_limit: u64,
     pub vm_execution_cache_misses_limit: Option<usize>,
+    /// Max wall-clock duration of a single VM execution for calls and gas estimation.
+    pub vm_execution_timeout: Option<Duration>,
+    /// Max number of VM cycles in a single VM execution for calls and gas estimation.
+    pub vm_execution_cycles_limit: Option<u64>,
+    /// Maximum number of recent gas estimates to cache. 0 means that the cache is disabled.
+    pub estimate_gas_cache_size: usize,
     pub validation_computational_gas_limit: u32,
     pub chain_id: L2ChainId,
     pub whitelisted_tokens_for_aa: Vec<Address>,
@@ -266,6 +289,9 @@ impl TxSenderConfig {
             max_nonce_ahead: web3_json_config.max_nonce_ahead,
             max_allowed_l2_tx_gas_limit: state_keeper_config.max_allowed_l2_tx_gas_limit,
             vm_execution_cache_misses_limit: web3_json_config.vm_execution_cache_misses_limit,
+            vm_execution_timeout: web3_json_config.vm_execution_timeout(),
+            vm_execution_cycles_limit: web3_json_config.vm_execution_cycles_limit,
+            estimate_gas_cache_size: web3_json_config.estimate_gas_cache_size(),
             validation_computational_gas_limit: state_keeper_config
                 .validation_computational_gas_limit,
             chain_id,
@@ -289,6 +315,8 @@ pub struct TxSenderInner {
     /// Batch sealer used to check whether transaction can be executed by the sequencer.
     pub(super) sealer: Arc<dyn ConditionalSealer>,
     pub(super) executor: SandboxExecutor,
+    /// Cache for recent gas estimates. `None` if caching is disabled.
+    pub(super) gas_estimation_cache: Option<GasEstimationCache>,
 }
 
 #[derive(Clone)]
@@ -310,6 +338,13 @@ impl TxSender {
     }
 
     async fn acquire_replica_connection(&self) -> anyhow::Result<Connection<'static, Core>> {
+        // If the replica DB lags behind the read-your-writes watermark of the processed request, read from the master DB.
+        if let Some(master_pool) = session_master_pool() {
+            return master_pool
+                .connection_tagged("api")
+                .await
+                .context("failed acquiring connection to master DB");
+        }
         self.0
             .replica_connection_pool
             .connection_tagged("api")
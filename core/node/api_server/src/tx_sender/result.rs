@@ -69,6 +69,9 @@ pub enum SubmitTxError {
     Internal(#[from] anyhow::Error),
     #[error("transaction failed block.timestamp assertion")]
     FailedBlockTimestampAssertion,
+    /// Execution in the API sandbox exceeded the wall-clock time or VM cycles limit set by the operator.
+    #[error("execution limit exceeded: {0}")]
+    ExecutionLimitExceeded(String),
 }
 
 impl SubmitTxError {
@@ -99,6 +102,7 @@ impl SubmitTxError {
             Self::ProxyError(_) => "proxy-error",
             Self::Internal(_) => "internal",
             Self::FailedBlockTimestampAssertion => "failed-block-timestamp-assertion",
+            Self::ExecutionLimitExceeded(_) => "execution-limit-exceeded",
         }
     }
 
@@ -139,6 +143,9 @@ impl From<SandboxExecutionError> for SubmitTxError {
             SandboxExecutionError::FailedBlockTimestampAssertion => {
                 Self::FailedBlockTimestampAssertion
             }
+            SandboxExecutionError::ExecutionLimitExceeded(reason) => {
+                Self::ExecutionLimitExceeded(reason)
+            }
         }
     }
 }
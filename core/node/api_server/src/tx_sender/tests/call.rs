@@ -1,8 +1,9 @@
 //! Tests for `eth_call`.
 
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 use assert_matches::assert_matches;
+use test_casing::{test_casing, Product};
 use zksync_multivm::interface::ExecutionResult;
 use zksync_node_test_utils::create_l2_transaction;
 use zksync_types::{
@@ -217,6 +218,41 @@ async fn eth_call_out_of_gas() {
     assert_matches!(err, SubmitTxError::ExecutionReverted(..));
 }
 
+#[test_casing(4, Product(([FastVmMode::Old, FastVmMode::New], [false, true])))]
+#[tokio::test]
+async fn eth_call_exceeding_execution_limits(fast_vm_mode: FastVmMode, use_timeout: bool) {
+    let alice = K256PrivateKey::random();
+    let state_override = StateBuilder::default()
+        .with_infinite_loop_contract()
+        .build();
+    let execution_limits = if use_timeout {
+        ExecutionLimits {
+            timeout: Some(Duration::ZERO),
+            cycles: None,
+        }
+    } else {
+        ExecutionLimits {
+            timeout: None,
+            cycles: Some(10_000),
+        }
+    };
+
+    let pool = ConnectionPool::<Core>::constrained_test_pool(1).await;
+    let tx_sender = create_real_tx_sender_with_limits(pool, fast_vm_mode, execution_limits).await;
+    let tx_as_call = alice.create_infinite_loop_tx().into();
+    let err = test_call(&tx_sender, state_override, tx_as_call)
+        .await
+        .unwrap_err();
+    let SubmitTxError::ExecutionLimitExceeded(message) = err else {
+        panic!("Unexpected error: {err:?}");
+    };
+    if use_timeout {
+        assert!(message.contains("timed out"), "{message}");
+    } else {
+        assert!(message.contains("cycles"), "{message}");
+    }
+}
+
 #[tokio::test]
 async fn eth_call_with_load_test_transactions() {
     let alice = K256PrivateKey::random();
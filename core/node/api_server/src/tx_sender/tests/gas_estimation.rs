@@ -1,12 +1,19 @@
 //! Tests for gas estimation (mostly with the real oneshot VM executor).
 
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 use assert_matches::assert_matches;
 use test_casing::{test_casing, Product};
+use zksync_multivm::interface::{ExecutionResult, VmRevertReason};
 use zksync_system_constants::CODE_ORACLE_ADDRESS;
 use zksync_types::{
-    api::state_override::{OverrideAccount, OverrideState},
+    api::{
+        state_override::{OverrideAccount, OverrideState},
+        GasEstimationBoundReason, GasEstimationDetails,
+    },
     bytecode::BytecodeHash,
     web3::keccak256,
     K256PrivateKey,
@@ -15,7 +22,7 @@ use zksync_types::{
 use super::*;
 use crate::{
     testonly::{StateBuilder, TestAccount},
-    tx_sender::gas_estimation::GasEstimator,
+    tx_sender::gas_estimation::{GasEstimationCache, GasEstimator},
 };
 
 /// Initial pivot multiplier empirically sufficient for most tx types.
@@ -513,3 +520,122 @@ async fn estimating_gas_for_infinite_loop_tx() {
         assert_matches!(err, SubmitTxError::ExecutionReverted(msg, _) if msg.is_empty());
     }
 }
+
+#[tokio::test]
+async fn caching_gas_estimates() {
+    const GAS_LIMIT_THRESHOLD: u64 = 100_000;
+
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+    drop(storage);
+
+    let execution_count = Arc::new(AtomicUsize::new(0));
+    let mut tx_executor = MockOneshotExecutor::default();
+    tx_executor.set_tx_responses({
+        let execution_count = execution_count.clone();
+        move |tx, _| {
+            execution_count.fetch_add(1, Ordering::SeqCst);
+            if tx.gas_limit() >= GAS_LIMIT_THRESHOLD.into() {
+                ExecutionResult::Success { output: vec![] }
+            } else {
+                ExecutionResult::Revert {
+                    output: VmRevertReason::VmError,
+                }
+            }
+        }
+    });
+    let tx_executor = SandboxExecutor::mock(tx_executor).await;
+    let (mut tx_sender, _) = create_test_tx_sender(pool, L2ChainId::default(), tx_executor).await;
+    let cache = GasEstimationCache::new(NonZeroUsize::new(10).unwrap());
+    Arc::get_mut(&mut tx_sender.0).unwrap().gas_estimation_cache = Some(cache);
+    let block_args = pending_block_args(&tx_sender).await;
+
+    let tx: Transaction = K256PrivateKey::random().create_transfer(0.into()).into();
+    let fee_scale_factor = 1.0;
+    let details = tx_sender
+        .get_txs_fee_in_wei_detailed(
+            tx.clone(),
+            block_args.clone(),
+            fee_scale_factor,
+            1_000,
+            None,
+            BinarySearchKind::Full,
+        )
+        .await
+        .unwrap();
+    assert!(!details.cached);
+    assert_eq!(
+        details.final_bound_reason,
+        GasEstimationBoundReason::BinarySearch
+    );
+    assert_eq!(details.optimistic_gas_limit, None);
+    assert!(!details.iterations.is_empty());
+    let last_success = details.iterations.iter().rev().find(|it| it.success);
+    assert_eq!(last_success.unwrap().gas_limit, details.unscaled_gas_limit);
+    assert!(
+        details.fee.gas_limit >= GAS_LIMIT_THRESHOLD.into(),
+        "{details:?}"
+    );
+    // The initial execution + binary search iterations. Finalization must reuse the last succeeding iteration.
+    assert_eq!(
+        execution_count.load(Ordering::SeqCst),
+        details.iterations.len() + 1
+    );
+
+    let cached_details = tx_sender
+        .get_txs_fee_in_wei_detailed(
+            tx.clone(),
+            block_args.clone(),
+            fee_scale_factor,
+            1_000,
+            None,
+            BinarySearchKind::Full,
+        )
+        .await
+        .unwrap();
+    assert!(cached_details.cached);
+    assert_eq!(
+        cached_details,
+        GasEstimationDetails {
+            cached: true,
+            ..details.clone()
+        }
+    );
+    assert_eq!(
+        execution_count.load(Ordering::SeqCst),
+        details.iterations.len() + 1
+    );
+
+    // Estimates with state overrides must not be cached.
+    let new_details = tx_sender
+        .get_txs_fee_in_wei_detailed(
+            tx.clone(),
+            block_args.clone(),
+            fee_scale_factor,
+            1_000,
+            Some(StateOverride::default()),
+            BinarySearchKind::Full,
+        )
+        .await
+        .unwrap();
+    assert!(!new_details.cached);
+    assert_eq!(new_details.fee, details.fee);
+
+    // Estimation params are a part of the cache key.
+    let new_details = tx_sender
+        .get_txs_fee_in_wei_detailed(
+            tx,
+            block_args,
+            fee_scale_factor,
+            0,
+            None,
+            BinarySearchKind::Full,
+        )
+        .await
+        .unwrap();
+    assert!(!new_details.cached);
+    assert!(new_details.iterations.len() > details.iterations.len());
+}
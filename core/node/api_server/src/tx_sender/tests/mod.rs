@@ -138,6 +138,14 @@ async fn getting_nonce_for_account_after_snapshot_recovery() {
 }
 
 async fn create_real_tx_sender(pool: ConnectionPool<Core>) -> TxSender {
+    create_real_tx_sender_with_limits(pool, FastVmMode::Shadow, ExecutionLimits::default()).await
+}
+
+async fn create_real_tx_sender_with_limits(
+    pool: ConnectionPool<Core>,
+    fast_vm_mode: FastVmMode,
+    execution_limits: ExecutionLimits,
+) -> TxSender {
     let mut storage = pool.connection().await.unwrap();
     let genesis_params = GenesisParams::mock();
     insert_genesis_batch(&mut storage, &genesis_params)
@@ -153,10 +161,16 @@ async fn create_real_tx_sender(pool: ConnectionPool<Core>) -> TxSender {
     )
     .await
     .unwrap();
-    executor_options.set_fast_vm_mode(FastVmMode::Shadow);
+    executor_options.set_fast_vm_mode(fast_vm_mode);
 
     let pg_caches = PostgresStorageCaches::new(1, 1);
-    let tx_executor = SandboxExecutor::real(executor_options, pg_caches, usize::MAX, None);
+    let tx_executor = SandboxExecutor::real(
+        executor_options,
+        pg_caches,
+        usize::MAX,
+        execution_limits,
+        None,
+    );
     create_test_tx_sender(pool, genesis_params.config().l2_chain_id, tx_executor)
         .await
         .0
@@ -0,0 +1,262 @@
+//! API key identification and per-key quotas for JSON-RPC servers.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    num::NonZeroU32,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use anyhow::Context as _;
+use governor::{
+    clock::DefaultClock,
+    middleware::NoOpMiddleware,
+    state::{InMemoryState, NotKeyed},
+    Quota, RateLimiter,
+};
+use vise::{Counter, EncodeLabelSet, Family, Metrics};
+use zksync_config::configs::api::{ApiKeysConfig, MethodComputeUnits};
+use zksync_web3_decl::jsonrpsee::{
+    server::middleware::rpc::{layer::ResponseFuture, RpcServiceT},
+    types::{error::ErrorCode, ErrorObject, Request},
+    MethodResponse,
+};
+
+/// Label used for requests without a recognized API key.
+const ANONYMOUS_KEY_NAME: &str = "anonymous";
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, EncodeLabelSet)]
+struct ApiKeyLabels {
+    key: String,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_jsonrpc_backend_api_key")]
+struct ApiKeyMetrics {
+    /// Number of requests per API key.
+    requests: Family<ApiKeyLabels, Counter>,
+    /// Number of compute units spent per API key.
+    compute_units: Family<ApiKeyLabels, Counter>,
+    /// Number of requests per API key rejected because of the exceeded quota.
+    rate_limited: Family<ApiKeyLabels, Counter>,
+    /// Number of requests rejected because they don't have a recognized API key.
+    unauthorized: Counter,
+}
+
+#[vise::register]
+static METRICS: vise::Global<ApiKeyMetrics> = vise::Global::new();
+
+/// API key extracted from an HTTP request.
+#[derive(Clone)]
+struct ApiKey(Arc<str>);
+
+tokio::task_local! {
+    /// API key of the HTTP request being processed. `jsonrpsee` doesn't propagate HTTP request extensions
+    /// to RPC requests, so the key is passed to [`ApiKeyMiddleware`] via a task-local instead.
+    static CURRENT_API_KEY: Option<ApiKey>;
+}
+
+impl fmt::Debug for ApiKey {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.debug_tuple("ApiKey").field(&"_").finish()
+    }
+}
+
+type DirectRateLimiter = RateLimiter<NotKeyed, InMemoryState, DefaultClock, NoOpMiddleware>;
+
+struct KeyQuota {
+    labels: ApiKeyLabels,
+    rate_limiter: DirectRateLimiter,
+}
+
+impl fmt::Debug for KeyQuota {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("KeyQuota")
+            .field("name", &self.labels.key)
+            .finish_non_exhaustive()
+    }
+}
+
+impl KeyQuota {
+    fn new(name: &str, compute_units_per_minute: NonZeroU32) -> Self {
+        Self {
+            labels: ApiKeyLabels {
+                key: name.to_owned(),
+            },
+            rate_limiter: RateLimiter::direct(Quota::per_minute(compute_units_per_minute)),
+        }
+    }
+}
+
+/// Quotas for all API keys accepted by a server. Unlike [`LimitMiddleware`](super::LimitMiddleware) limits,
+/// quotas are shared among all connections using the same key.
+#[derive(Debug)]
+pub(crate) struct ApiKeyQuotas {
+    header_name: http::HeaderName,
+    key_in_url_path: bool,
+    by_key: HashMap<String, KeyQuota>,
+    anonymous: Option<KeyQuota>,
+    method_costs: MethodComputeUnits,
+}
+
+impl ApiKeyQuotas {
+    pub(crate) fn new(config: &ApiKeysConfig) -> anyhow::Result<Self> {
+        let header_name = http::HeaderName::from_bytes(config.header_name.as_bytes())
+            .with_context(|| format!("invalid API key header name: {}", config.header_name))?;
+        let by_key = config
+            .keys
+            .iter()
+            .map(|quota| {
+                let key_quota = KeyQuota::new(&quota.name, quota.compute_units_per_minute);
+                (quota.key.clone(), key_quota)
+            })
+            .collect();
+        let anonymous = config
+            .anonymous_compute_units_per_minute
+            .map(|limit| KeyQuota::new(ANONYMOUS_KEY_NAME, limit));
+        Ok(Self {
+            header_name,
+            key_in_url_path: config.key_in_url_path,
+            by_key,
+            anonymous,
+            method_costs: config.method_costs.clone(),
+        })
+    }
+
+    pub(crate) fn header_name(&self) -> &http::HeaderName {
+        &self.header_name
+    }
+
+    /// Extracts an API key from the request header or, if the header is missing and this is enabled in the config,
+    /// from the last segment of the URL path.
+    fn extract_key<B>(&self, request: &http::Request<B>) -> Option<ApiKey> {
+        if let Some(header) = request.headers().get(&self.header_name) {
+            let key = header.to_str().ok()?;
+            return Some(ApiKey(key.into()));
+        }
+        if !self.key_in_url_path {
+            return None;
+        }
+        let path_segment = request.uri().path().rsplit('/').find(|s| !s.is_empty())?;
+        Some(ApiKey(path_segment.into()))
+    }
+
+    fn quota(&self, key: Option<&ApiKey>) -> Option<&KeyQuota> {
+        key.and_then(|key| self.by_key.get(&*key.0))
+            .or(self.anonymous.as_ref())
+    }
+}
+
+/// HTTP middleware layer extracting API keys from requests.
+#[derive(Debug, Clone)]
+pub(crate) struct ApiKeyExtractorLayer {
+    quotas: Arc<ApiKeyQuotas>,
+}
+
+impl ApiKeyExtractorLayer {
+    pub(crate) fn new(quotas: Arc<ApiKeyQuotas>) -> Self {
+        Self { quotas }
+    }
+}
+
+impl<S> tower::Layer<S> for ApiKeyExtractorLayer {
+    type Service = ApiKeyExtractor<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ApiKeyExtractor {
+            inner,
+            quotas: self.quotas.clone(),
+        }
+    }
+}
+
+/// HTTP middleware extracting API keys from requests. The extracted key is passed to [`ApiKeyMiddleware`]
+/// via a task-local set while calling the inner service. `jsonrpsee` instantiates the RPC middleware synchronously
+/// in this call, both for HTTP requests and WebSocket upgrade requests, so the key is captured when
+/// the middleware is created. For WebSocket connections, this means that the key is extracted from the upgrade request
+/// and applies to all calls over the connection.
+///
+/// Since the key is only available during the synchronous call, this middleware must directly wrap
+/// the `jsonrpsee` service (i.e., be the innermost HTTP middleware).
+#[derive(Debug, Clone)]
+pub(crate) struct ApiKeyExtractor<S> {
+    inner: S,
+    quotas: Arc<ApiKeyQuotas>,
+}
+
+impl<S, B> tower::Service<http::Request<B>> for ApiKeyExtractor<S>
+where
+    S: tower::Service<http::Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let key = self.quotas.extract_key(&request);
+        CURRENT_API_KEY.sync_scope(key, || self.inner.call(request))
+    }
+}
+
+/// RPC-level middleware enforcing per-key quotas measured in compute units. Calls without a key set by
+/// [`ApiKeyExtractor`] are treated as anonymous.
+pub(crate) struct ApiKeyMiddleware<S> {
+    inner: S,
+    quotas: Arc<ApiKeyQuotas>,
+    key: Option<ApiKey>,
+}
+
+impl<S> ApiKeyMiddleware<S> {
+    /// Creates a middleware for the HTTP request or WebSocket connection currently processed by [`ApiKeyExtractor`].
+    pub(crate) fn new(inner: S, quotas: Arc<ApiKeyQuotas>) -> Self {
+        let key = CURRENT_API_KEY.try_with(Option::clone).ok().flatten();
+        Self { inner, quotas, key }
+    }
+}
+
+impl<'a, S> RpcServiceT<'a> for ApiKeyMiddleware<S>
+where
+    S: Send + Sync + RpcServiceT<'a>,
+{
+    type Future = ResponseFuture<S::Future>;
+
+    fn call(&self, request: Request<'a>) -> Self::Future {
+        let Some(quota) = self.quotas.quota(self.key.as_ref()) else {
+            METRICS.unauthorized.inc();
+            let rp = MethodResponse::error(
+                request.id,
+                ErrorObject::borrowed(
+                    ErrorCode::ServerError(http::StatusCode::UNAUTHORIZED.as_u16().into()).code(),
+                    "Missing or unknown API key",
+                    None,
+                ),
+            );
+            return ResponseFuture::ready(rp);
+        };
+
+        METRICS.requests[&quota.labels].inc();
+        let cost = self.quotas.method_costs.get(request.method_name());
+        // Methods costing more than the entire quota are always rejected.
+        if quota.rate_limiter.check_n(cost).is_err() {
+            METRICS.rate_limited[&quota.labels].inc();
+            let rp = MethodResponse::error(
+                request.id,
+                ErrorObject::borrowed(
+                    ErrorCode::ServerError(http::StatusCode::TOO_MANY_REQUESTS.as_u16().into())
+                        .code(),
+                    "API key quota exceeded",
+                    None,
+                ),
+            );
+            return ResponseFuture::ready(rp);
+        }
+        METRICS.compute_units[&quota.labels].inc_by(cost.get().into());
+        ResponseFuture::future(self.inner.call(request))
+    }
+}
@@ -0,0 +1,337 @@
+//! Read-your-writes consistency for HTTP API servers reading from Postgres replicas.
+//!
+//! Consistency is based on *watermarks*, which are positions in the Postgres write-ahead log (WAL). Responses
+//! to requests performing writes (e.g., `eth_sendRawTransaction`) carry the master DB watermark after the write
+//! in the [`WATERMARK_HEADER`]. If a client passes this watermark in subsequent requests, the server waits until
+//! its replica DB catches up with the watermark (up to a configurable bound). If the replica doesn't catch up in time,
+//! the request is served using the master DB. The watermark is opaque for clients; they should just remember
+//! the latest received value.
+//!
+//! If a request is routed to the master DB, all its DB reads use the master DB, i.e. both reads performed
+//! by method handlers via `RpcState` and reads performed by `TxSender` (including the VM sandbox storage
+//! for `eth_call`, `eth_estimateGas` etc.). The following data is *not* covered:
+//!
+//! - WebSocket servers, including pubsub notifications (they are always based on the replica DB).
+//! - Data served from in-memory caches populated from the replica DB, e.g. the mempool cache used for
+//!   pending transaction filters.
+
+use std::{
+    cell::Cell,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures::future::BoxFuture;
+use tokio::time::Instant;
+use vise::{Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Histogram, Metrics, Unit};
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+
+/// HTTP header used to pass consistency watermarks in requests and responses.
+pub const WATERMARK_HEADER: &str = "x-zksync-db-watermark";
+/// Interval between polling the replica DB for its WAL position.
+const REPLICA_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "outcome", rename_all = "snake_case")]
+enum WatermarkOutcome {
+    /// Replica was up to date when the request was received.
+    UpToDate,
+    /// Replica has caught up with the watermark after waiting.
+    CaughtUp,
+    /// Replica hasn't caught up with the watermark in time; the request was served using the master DB.
+    RoutedToMaster,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_jsonrpc_backend_consistency")]
+struct ConsistencyMetrics {
+    /// Outcomes for requests with a consistency watermark.
+    requests: Family<WatermarkOutcome, Counter>,
+    /// Time spent waiting for the replica DB to catch up with the watermark.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    replica_wait: Histogram<Duration>,
+    /// Number of responses with a watermark after a write.
+    write_watermarks: Counter,
+}
+
+#[vise::register]
+static METRICS: vise::Global<ConsistencyMetrics> = vise::Global::new();
+
+/// Read-your-writes consistency configuration for an API server.
+#[derive(Debug, Clone)]
+pub struct ReadYourWritesConfig {
+    /// Pool for the master DB used to serve requests if the replica DB lags behind.
+    pub master_pool: ConnectionPool<Core>,
+    /// Maximum time to wait for the replica DB to catch up with the requested watermark.
+    pub max_wait: Duration,
+}
+
+#[derive(Debug)]
+struct SessionState {
+    /// Master DB pool if the request should be served using it.
+    master_pool: Option<ConnectionPool<Core>>,
+    has_writes: Cell<bool>,
+}
+
+tokio::task_local! {
+    static SESSION: SessionState;
+}
+
+/// Checks whether the currently processed HTTP request should be served using the master DB.
+pub(crate) fn uses_master_pool() -> bool {
+    SESSION
+        .try_with(|session| session.master_pool.is_some())
+        .unwrap_or(false)
+}
+
+/// Returns the master DB pool if the currently processed HTTP request should be served using it.
+pub(crate) fn session_master_pool() -> Option<ConnectionPool<Core>> {
+    SESSION
+        .try_with(|session| session.master_pool.clone())
+        .ok()
+        .flatten()
+}
+
+/// Marks the currently processed HTTP request as performing a write to the master DB, so that the response
+/// will carry the corresponding watermark. No-op if read-your-writes consistency is disabled.
+pub(crate) fn mark_write() {
+    SESSION
+        .try_with(|session| session.has_writes.set(true))
+        .ok();
+}
+
+#[derive(Debug)]
+struct ConsistencyTracker {
+    replica_pool: ConnectionPool<Core>,
+    config: ReadYourWritesConfig,
+    /// Latest observed WAL position of the replica DB. Allows to skip DB queries for outdated watermarks.
+    replica_position: AtomicU64,
+}
+
+impl ConsistencyTracker {
+    async fn get_position(pool: &ConnectionPool<Core>) -> anyhow::Result<u64> {
+        let mut connection = pool.connection_tagged("api").await?;
+        Ok(connection.system_dal().get_wal_position().await?)
+    }
+
+    async fn update_replica_position(&self) -> anyhow::Result<u64> {
+        let position = Self::get_position(&self.replica_pool).await?;
+        Ok(self
+            .replica_position
+            .fetch_max(position, Ordering::Relaxed)
+            .max(position))
+    }
+
+    /// Waits until the replica DB catches up with the specified watermark. Returns `true` if the request
+    /// should be served using the master DB.
+    async fn wait_for_replica(&self, watermark: u64) -> bool {
+        if self.replica_position.load(Ordering::Relaxed) >= watermark {
+            METRICS.requests[&WatermarkOutcome::UpToDate].inc();
+            return false;
+        }
+
+        let started_at = Instant::now();
+        let deadline = started_at + self.config.max_wait;
+        loop {
+            match self.update_replica_position().await {
+                Ok(position) if position >= watermark => {
+                    let elapsed = started_at.elapsed();
+                    METRICS.replica_wait.observe(elapsed);
+                    METRICS.requests[&WatermarkOutcome::CaughtUp].inc();
+                    return false;
+                }
+                Ok(_) => { /* continue waiting */ }
+                Err(err) => {
+                    tracing::warn!("Failed getting replica DB position: {err:#}");
+                    break;
+                }
+            }
+            if Instant::now() + REPLICA_POLL_INTERVAL > deadline {
+                break;
+            }
+            tokio::time::sleep(REPLICA_POLL_INTERVAL).await;
+        }
+
+        METRICS.replica_wait.observe(started_at.elapsed());
+        METRICS.requests[&WatermarkOutcome::RoutedToMaster].inc();
+        true
+    }
+
+    async fn master_position(&self) -> Option<u64> {
+        match Self::get_position(&self.config.master_pool).await {
+            Ok(position) => Some(position),
+            Err(err) => {
+                tracing::warn!("Failed getting master DB position: {err:#}");
+                None
+            }
+        }
+    }
+}
+
+/// HTTP middleware layer providing read-your-writes consistency. Not applicable to WebSocket servers since
+/// WS method calls are not processed in the context of the upgrade request.
+#[derive(Debug, Clone)]
+pub(crate) struct ReadYourWritesLayer {
+    tracker: Arc<ConsistencyTracker>,
+}
+
+impl ReadYourWritesLayer {
+    pub(crate) fn new(replica_pool: ConnectionPool<Core>, config: ReadYourWritesConfig) -> Self {
+        Self {
+            tracker: Arc::new(ConsistencyTracker {
+                replica_pool,
+                config,
+                replica_position: AtomicU64::new(0),
+            }),
+        }
+    }
+}
+
+impl<S> tower::Layer<S> for ReadYourWritesLayer {
+    type Service = ReadYourWrites<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ReadYourWrites {
+            inner,
+            tracker: self.tracker.clone(),
+        }
+    }
+}
+
+/// HTTP middleware providing read-your-writes consistency.
+#[derive(Debug, Clone)]
+pub(crate) struct ReadYourWrites<S> {
+    inner: S,
+    tracker: Arc<ConsistencyTracker>,
+}
+
+impl<S, B, RB> tower::Service<http::Request<B>> for ReadYourWrites<S>
+where
+    S: tower::Service<http::Request<B>, Response = http::Response<RB>> + Clone + Send + 'static,
+    S::Future: Send,
+    S::Error: Send + 'static,
+    B: Send + 'static,
+    RB: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        // Use the service that was checked for readiness and leave a fresh clone in its place.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let tracker = self.tracker.clone();
+        let watermark = request
+            .headers()
+            .get(WATERMARK_HEADER)
+            .and_then(|value| value.to_str().ok()?.parse::<u64>().ok());
+
+        Box::pin(async move {
+            let use_master_pool = match watermark {
+                Some(watermark) => tracker.wait_for_replica(watermark).await,
+                None => false,
+            };
+            let session = SessionState {
+                master_pool: use_master_pool.then(|| tracker.config.master_pool.clone()),
+                has_writes: Cell::new(false),
+            };
+            let (mut response, has_writes) = SESSION
+                .scope(session, async move {
+                    let response = inner.call(request).await;
+                    (response, SESSION.with(|session| session.has_writes.get()))
+                })
+                .await;
+
+            let response_watermark = if has_writes {
+                METRICS.write_watermarks.inc();
+                tracker.master_position().await
+            } else {
+                None
+            };
+            let response_watermark = response_watermark.max(watermark);
+            if let (Ok(response), Some(watermark)) = (&mut response, response_watermark) {
+                response
+                    .headers_mut()
+                    .insert(WATERMARK_HEADER, watermark.into());
+            }
+            response
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use tower::{Layer, ServiceExt};
+
+    use super::*;
+
+    async fn call(
+        layer: &ReadYourWritesLayer,
+        watermark: Option<u64>,
+        write: bool,
+    ) -> (bool, Option<u64>) {
+        let service = tower::service_fn(move |_: http::Request<()>| async move {
+            if write {
+                mark_write();
+            }
+            Ok::<_, Infallible>(http::Response::new(uses_master_pool()))
+        });
+        let service = layer.layer(service);
+
+        let mut request = http::Request::new(());
+        if let Some(watermark) = watermark {
+            request
+                .headers_mut()
+                .insert(WATERMARK_HEADER, watermark.into());
+        }
+        let response = service.oneshot(request).await.unwrap();
+        let response_watermark = response
+            .headers()
+            .get(WATERMARK_HEADER)
+            .map(|value| value.to_str().unwrap().parse().unwrap());
+        (*response.body(), response_watermark)
+    }
+
+    #[tokio::test]
+    async fn read_your_writes_consistency() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let config = ReadYourWritesConfig {
+            master_pool: pool.clone(),
+            max_wait: Duration::from_millis(50),
+        };
+        let layer = ReadYourWritesLayer::new(pool, config);
+
+        let (used_master_pool, watermark) = call(&layer, None, false).await;
+        assert!(!used_master_pool);
+        assert_eq!(watermark, None);
+
+        let (used_master_pool, watermark) = call(&layer, None, true).await;
+        assert!(!used_master_pool);
+        let watermark = watermark.expect("no watermark after write");
+        assert!(watermark > 0);
+
+        // The replica (which is the same DB in this test) is guaranteed to be up to date.
+        let (used_master_pool, new_watermark) = call(&layer, Some(watermark), false).await;
+        assert!(!used_master_pool);
+        assert_eq!(new_watermark, Some(watermark));
+
+        // Emulate the replica lagging behind.
+        let far_watermark = u64::MAX / 2;
+        let started_at = Instant::now();
+        let (used_master_pool, new_watermark) = call(&layer, Some(far_watermark), false).await;
+        assert!(used_master_pool);
+        assert!(started_at.elapsed() >= Duration::from_millis(40));
+        assert_eq!(new_watermark, Some(far_watermark));
+    }
+}
@@ -7,7 +7,10 @@ use zksync_web3_decl::{
     jsonrpsee::types::{error::ErrorCode, ErrorObjectOwned},
 };
 
+pub use self::consistency::{ReadYourWritesConfig, WATERMARK_HEADER};
 pub(crate) use self::{
+    api_keys::{ApiKeyExtractorLayer, ApiKeyMiddleware, ApiKeyQuotas},
+    consistency::{mark_write, session_master_pool, uses_master_pool, ReadYourWritesLayer},
     metadata::{MethodMetadata, MethodTracer},
     middleware::{
         CorrelationMiddleware, LimitMiddleware, MetadataLayer, ShutdownMiddleware, TrafficTracker,
@@ -15,6 +18,8 @@ pub(crate) use self::{
 };
 use crate::tx_sender::SubmitTxError;
 
+mod api_keys;
+mod consistency;
 mod metadata;
 mod middleware;
 pub mod namespaces;
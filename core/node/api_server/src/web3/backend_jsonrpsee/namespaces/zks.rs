@@ -3,7 +3,8 @@ use std::collections::HashMap;
 use zksync_multivm::interface::VmEvent;
 use zksync_types::{
     api::{
-        state_override::StateOverride, ApiStorageLog, BlockDetails, BridgeAddresses,
+        state_override::StateOverride, ApiStorageLog, BlockCommitmentInfo, BlockDetails,
+        BlockIdVariant, BridgeAddresses, ContractBytecodeInfo, GasEstimationDetails,
         L1BatchDetails, L2ToL1LogProof, Log, Proof, ProtocolVersion, TransactionDetailedResult,
         TransactionDetails,
     },
@@ -32,6 +33,16 @@ impl ZksNamespaceServer for ZksNamespace {
             .map_err(|err| self.current_method().map_err(err))
     }
 
+    async fn estimate_gas_detailed(
+        &self,
+        req: CallRequest,
+        state_override: Option<StateOverride>,
+    ) -> RpcResult<GasEstimationDetails> {
+        self.estimate_gas_detailed_impl(req, state_override)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
     async fn estimate_gas_l1_to_l2(
         &self,
         req: CallRequest,
@@ -148,12 +159,31 @@ impl ZksNamespaceServer for ZksNamespace {
             .map_err(|err| self.current_method().map_err(err))
     }
 
+    async fn get_block_commitment_info(
+        &self,
+        block_number: L2BlockNumber,
+    ) -> RpcResult<Option<BlockCommitmentInfo>> {
+        self.get_block_commitment_info_impl(block_number)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
     async fn get_bytecode_by_hash(&self, hash: H256) -> RpcResult<Option<Vec<u8>>> {
         self.get_bytecode_by_hash_impl(hash)
             .await
             .map_err(|err| self.current_method().map_err(err))
     }
 
+    async fn get_contract_bytecode_info(
+        &self,
+        address: Address,
+        block: Option<BlockIdVariant>,
+    ) -> RpcResult<Option<ContractBytecodeInfo>> {
+        self.get_contract_bytecode_info_impl(address, block.map(Into::into))
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
     // to be removed in favor of `get_batch_fee_input`
     async fn get_l1_gas_price(&self) -> RpcResult<U64> {
         match self.get_batch_fee_input_impl().await {
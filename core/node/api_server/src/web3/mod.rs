@@ -9,7 +9,7 @@ use tokio::{
     task::JoinHandle,
 };
 use tower_http::{cors::CorsLayer, metrics::InFlightRequestsLayer};
-use zksync_config::configs::api::{MaxResponseSize, MaxResponseSizeOverrides};
+use zksync_config::configs::api::{ApiKeysConfig, MaxResponseSize, MaxResponseSizeOverrides};
 use zksync_dal::{helpers::wait_for_l1_batch, ConnectionPool, Core};
 use zksync_health_check::{HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_metadata_calculator::api_server::TreeApiClient;
@@ -33,8 +33,9 @@ use zksync_web3_decl::{
 
 use self::{
     backend_jsonrpsee::{
-        CorrelationMiddleware, LimitMiddleware, MetadataLayer, MethodTracer, ShutdownMiddleware,
-        TrafficTracker,
+        ApiKeyExtractorLayer, ApiKeyMiddleware, ApiKeyQuotas, CorrelationMiddleware,
+        LimitMiddleware, MetadataLayer, MethodTracer, ReadYourWritesConfig, ReadYourWritesLayer,
+        ShutdownMiddleware, TrafficTracker, WATERMARK_HEADER,
     },
     mempool_cache::MempoolCache,
     metrics::API_METRICS,
@@ -137,6 +138,8 @@ struct OptionalApiParams {
     tree_api: Option<Arc<dyn TreeApiClient>>,
     mempool_cache: Option<MempoolCache>,
     extended_tracing: bool,
+    api_keys: Option<ApiKeysConfig>,
+    read_your_writes: Option<ReadYourWritesConfig>,
     pub_sub_events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
     l2_l1_log_proof_handler: Option<Box<DynClient<L2>>>,
 }
@@ -282,6 +285,18 @@ impl ApiBuilder {
         self
     }
 
+    /// Enables per-API-key compute unit quotas.
+    pub fn with_api_keys(mut self, config: ApiKeysConfig) -> Self {
+        self.optional.api_keys = Some(config);
+        self
+    }
+
+    /// Enables read-your-writes consistency for the HTTP server. Has no effect for the WebSocket server.
+    pub fn with_read_your_writes(mut self, config: ReadYourWritesConfig) -> Self {
+        self.optional.read_your_writes = Some(config);
+        self
+    }
+
     pub fn with_sealed_l2_block_handle(
         mut self,
         sealed_l2_block_handle: SealedL2BlockNumber,
@@ -381,6 +396,10 @@ impl ApiServer {
             current_method: self.method_tracer,
             installed_filters,
             connection_pool: self.pool,
+            master_pool: self
+                .optional
+                .read_your_writes
+                .map(|config| config.master_pool),
             tx_sender: self.tx_sender,
             sync_state: self.optional.sync_state,
             api_config: self.config,
@@ -646,6 +665,32 @@ impl ApiServer {
         if extended_tracing {
             tracing::info!("Enabled extended call tracing for {transport_str} API server; this might negatively affect performance");
         }
+        let api_key_quotas = self
+            .optional
+            .api_keys
+            .as_ref()
+            .map(|config| ApiKeyQuotas::new(config).map(Arc::new))
+            .transpose()
+            .context("invalid API keys config")?;
+        if let Some(quotas) = &api_key_quotas {
+            tracing::info!("Enabled API key quotas for {transport_str} API server: {quotas:?}");
+        }
+        let read_your_writes = match &self.optional.read_your_writes {
+            Some(config) if is_http => {
+                tracing::info!(
+                    "Enabled read-your-writes consistency for {transport_str} API server with max wait {:?}",
+                    config.max_wait
+                );
+                Some(ReadYourWritesLayer::new(self.pool.clone(), config.clone()))
+            }
+            Some(_) => {
+                tracing::warn!(
+                    "Read-your-writes consistency is ignored for {transport_str} transport"
+                );
+                None
+            }
+            None => None,
+        };
 
         let rpc = self.build_rpc_module(pub_sub).await?;
         let registered_method_names = Arc::new(rpc.method_names().collect::<HashSet<_>>());
@@ -657,12 +702,25 @@ impl ApiServer {
 
         // Setup CORS.
         let cors = is_http.then(|| {
+            let api_key_header = api_key_quotas
+                .as_ref()
+                .map(|quotas| quotas.header_name().clone());
+            let watermark_header = read_your_writes
+                .is_some()
+                .then(|| http::HeaderName::from_static(WATERMARK_HEADER));
             CorsLayer::new()
                 // Allow `POST` when accessing the resource
                 .allow_methods([http::Method::POST])
                 // Allow requests from any origin
                 .allow_origin(tower_http::cors::Any)
-                .allow_headers([http::header::CONTENT_TYPE])
+                .allow_headers(
+                    [http::header::CONTENT_TYPE]
+                        .into_iter()
+                        .chain(api_key_header)
+                        .chain(watermark_header.clone())
+                        .collect::<Vec<_>>(),
+                )
+                .expose_headers(watermark_header.into_iter().collect::<Vec<_>>())
         });
         // Setup metrics for the number of in-flight requests.
         let (in_flight_requests, counter) = InFlightRequestsLayer::pair();
@@ -675,7 +733,10 @@ impl ApiServer {
         // Assemble server middleware.
         let middleware = tower::ServiceBuilder::new()
             .layer(in_flight_requests)
-            .option_layer(cors);
+            .option_layer(cors)
+            .option_layer(read_your_writes)
+            // Must be the innermost layer; see `ApiKeyExtractor` docs.
+            .option_layer(api_key_quotas.clone().map(ApiKeyExtractorLayer::new));
 
         // Settings shared by HTTP and WS servers.
         let max_connections = !is_http
@@ -703,6 +764,10 @@ impl ApiServer {
                 extended_tracing.then(|| tower::layer::layer_fn(CorrelationMiddleware::new)),
             )
             .layer(metadata_layer)
+            // Same as with `LimitMiddleware` below, we want to capture quota errors with `metadata_layer`.
+            .option_layer(api_key_quotas.map(|quotas| {
+                tower::layer::layer_fn(move |svc| ApiKeyMiddleware::new(svc, quotas.clone()))
+            }))
             // We want to capture limit middleware errors with `metadata_layer`; hence, `LimitMiddleware` is placed after it.
             .option_layer((!is_http).then(|| {
                 tower::layer::layer_fn(move |svc| {
@@ -69,6 +69,11 @@ impl SnapshotsNamespace {
             .await
             .map_err(DalError::generalize)?
             .with_context(|| format!("missing L2 blocks for L1 batch #{l1_batch_number}"))?;
+        let tree_data = storage_processor
+            .blocks_dal()
+            .get_l1_batch_tree_data(l1_batch_number)
+            .await
+            .map_err(DalError::generalize)?;
 
         Ok(Some(SnapshotHeader {
             version: snapshot_metadata.version.into(),
@@ -76,6 +81,7 @@ impl SnapshotsNamespace {
             l2_block_number,
             storage_logs_chunks: chunks,
             factory_deps_filepath: snapshot_metadata.factory_deps_filepath,
+            l1_batch_root_hash: tree_data.map(|data| data.hash),
         }))
     }
 }
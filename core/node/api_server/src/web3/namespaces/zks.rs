@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use anyhow::Context as _;
 use zksync_crypto_primitives::hasher::{keccak::KeccakHasher, Hasher};
 use zksync_dal::{Connection, Core, CoreDal, DalError};
+use zksync_l1_contract_interface::i_executor::commit::kzg::pubdata_to_blob_hashes;
 use zksync_metadata_calculator::api_server::TreeApiError;
 use zksync_mini_merkle_tree::MiniMerkleTree;
 use zksync_multivm::interface::VmExecutionResultAndLogs;
@@ -10,12 +11,18 @@ use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
 use zksync_types::{
     address_to_h256,
     api::{
-        state_override::StateOverride, BlockDetails, BridgeAddresses, GetLogsFilter,
-        L1BatchDetails, L2ToL1LogProof, Proof, ProtocolVersion, StorageProof, TransactionDetails,
+        state_override::StateOverride, BlockCommitmentInfo, BlockDetails, BlockId, BlockNumber,
+        BridgeAddresses, ContractBytecodeInfo, GasEstimationDetails, GetLogsFilter, L1BatchDetails,
+        L2BlockInclusionProof, L2ToL1LogProof, Proof, ProtocolVersion, StorageProof,
+        TransactionDetails,
+    },
+    blob::num_blobs_required,
+    commitment::{
+        BlobHash, L1BatchCommitmentComponents, L1BatchCommitmentMode, L1BatchWithMetadata,
     },
     fee::Fee,
     fee_model::{FeeParams, PubdataIndependentBatchFeeModelInput},
-    h256_to_u256,
+    get_code_key, h256_to_u256,
     l1::L1Tx,
     l2::L2Tx,
     l2_to_l1_log::{l2_to_l1_logs_tree_size, L2ToL1Log, LOG_PROOF_SUPPORTED_METADATA_VERSION},
@@ -36,7 +43,11 @@ use crate::{
     execution_sandbox::BlockArgs,
     tx_sender::BinarySearchKind,
     utils::open_readonly_transaction,
-    web3::{backend_jsonrpsee::MethodTracer, metrics::API_METRICS, RpcState},
+    web3::{
+        backend_jsonrpsee::{mark_write, MethodTracer},
+        metrics::API_METRICS,
+        RpcState,
+    },
 };
 
 #[derive(Debug)]
@@ -58,6 +69,40 @@ impl ZksNamespace {
         request: CallRequest,
         state_override: Option<StateOverride>,
     ) -> Result<Fee, Web3Error> {
+        let (tx, block_args) = self.prepare_fee_estimation(request).await?;
+        self.estimate_fee(tx.into(), block_args, state_override)
+            .await
+    }
+
+    pub async fn estimate_gas_detailed_impl(
+        &self,
+        request: CallRequest,
+        state_override: Option<StateOverride>,
+    ) -> Result<GasEstimationDetails, Web3Error> {
+        let (tx, block_args) = self.prepare_fee_estimation(request).await?;
+        let scale_factor = self.state.api_config.estimate_gas_scale_factor;
+        let acceptable_overestimation =
+            self.state.api_config.estimate_gas_acceptable_overestimation;
+        let search_kind = BinarySearchKind::new(self.state.api_config.estimate_gas_optimize_search);
+
+        Ok(self
+            .state
+            .tx_sender
+            .get_txs_fee_in_wei_detailed(
+                tx.into(),
+                block_args,
+                scale_factor,
+                acceptable_overestimation as u64,
+                state_override,
+                search_kind,
+            )
+            .await?)
+    }
+
+    async fn prepare_fee_estimation(
+        &self,
+        request: CallRequest,
+    ) -> Result<(L2Tx, BlockArgs), Web3Error> {
         let mut request_with_gas_per_pubdata_overridden = request;
         self.state
             .set_nonce_for_call_request(&mut request_with_gas_per_pubdata_overridden)
@@ -80,8 +125,7 @@ impl ZksNamespace {
         // not consider provided ones.
         tx.common_data.fee.max_priority_fee_per_gas = 0u64.into();
         tx.common_data.fee.gas_per_pubdata_limit = U256::from(DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE);
-        self.estimate_fee(tx.into(), block_args, state_override)
-            .await
+        Ok((tx, block_args))
     }
 
     pub async fn estimate_l1_to_l2_gas_impl(
@@ -560,6 +604,102 @@ impl ZksNamespace {
             .map_err(DalError::generalize)?)
     }
 
+    pub async fn get_block_commitment_info_impl(
+        &self,
+        block_number: L2BlockNumber,
+    ) -> Result<Option<BlockCommitmentInfo>, Web3Error> {
+        let mut storage = self.state.acquire_connection().await?;
+        self.state
+            .start_info
+            .ensure_not_pruned(block_number, &mut storage)
+            .await?;
+
+        let Some(block) = storage
+            .blocks_web3_dal()
+            .get_block_details(block_number)
+            .await
+            .map_err(DalError::generalize)?
+        else {
+            return Ok(None);
+        };
+        let Some(hash) = block.base.root_hash else {
+            return Ok(None);
+        };
+        let Some(l1_batch) = storage
+            .blocks_web3_dal()
+            .get_l1_batch_details(block.l1_batch_number)
+            .await
+            .map_err(DalError::generalize)?
+        else {
+            return Ok(None); // The block is not sealed in an L1 batch yet
+        };
+        let Some(l1_batch_with_metadata) = storage
+            .blocks_dal()
+            .get_l1_batch_metadata(block.l1_batch_number)
+            .await
+            .map_err(DalError::generalize)?
+        else {
+            return Ok(None); // Commitment for the batch is not computed yet
+        };
+        let (_, last_block_in_batch) = storage
+            .blocks_web3_dal()
+            .get_l2_block_range_of_l1_batch(block.l1_batch_number)
+            .await
+            .map_err(DalError::generalize)?
+            .context("L1 batch should contain at least one L2 block")?;
+        let next_blocks = if block_number < last_block_in_batch {
+            storage
+                .blocks_web3_dal()
+                .get_l2_block_hash_preimages(block_number + 1..=last_block_in_batch)
+                .await
+                .map_err(DalError::generalize)?
+        } else {
+            vec![]
+        };
+        drop(storage);
+
+        let L1BatchWithMetadata {
+            header, metadata, ..
+        } = l1_batch_with_metadata;
+        let Some(protocol_version) = header.protocol_version else {
+            return Ok(None); // The batch is too old
+        };
+        let commitment_mode = self.state.api_config.l1_batch_commit_data_generator_mode;
+        let blob_hashes = match &header.pubdata_input {
+            // Blob hashes in the commitment are zeroed for the genesis batch and for Validium chains.
+            _ if header.number == L1BatchNumber(0)
+                || commitment_mode == L1BatchCommitmentMode::Validium =>
+            {
+                vec![BlobHash::default(); num_blobs_required(&protocol_version)]
+            }
+            Some(pubdata_input) => {
+                let pubdata_input = pubdata_input.clone();
+                tokio::task::spawn_blocking(move || {
+                    pubdata_to_blob_hashes(protocol_version, pubdata_input)
+                })
+                .await
+                .context("panicked computing blob hashes")?
+            }
+            None if !protocol_version.is_post_1_4_2() => {
+                vec![BlobHash::default(); num_blobs_required(&protocol_version)]
+            }
+            None => return Ok(None), // The batch is too old
+        };
+        let Some(commitment_components) =
+            L1BatchCommitmentComponents::new(&header, &metadata, blob_hashes)
+        else {
+            return Ok(None); // The batch is pre-Boojum or its auxiliary commitments are not persisted
+        };
+
+        Ok(Some(BlockCommitmentInfo {
+            number: block_number,
+            hash,
+            l1_batch,
+            commitment_components,
+            inclusion_proof: L2BlockInclusionProof { next_blocks },
+        }))
+    }
+
     pub async fn get_bytecode_by_hash_impl(
         &self,
         hash: H256,
@@ -572,6 +712,30 @@ impl ZksNamespace {
             .map_err(DalError::generalize)?)
     }
 
+    pub async fn get_contract_bytecode_info_impl(
+        &self,
+        address: Address,
+        block_id: Option<BlockId>,
+    ) -> Result<Option<ContractBytecodeInfo>, Web3Error> {
+        let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Pending));
+        self.current_method().set_block_id(block_id);
+
+        let mut connection = self.state.acquire_connection().await?;
+        let block_number = self.state.resolve_block(&mut connection, block_id).await?;
+        let bytecode_hash = connection
+            .storage_web3_dal()
+            .get_historical_value_unchecked(get_code_key(&address).hashed_key(), block_number)
+            .await
+            .map_err(DalError::generalize)?;
+        if bytecode_hash.is_zero() {
+            return Ok(None);
+        }
+        let info = ContractBytecodeInfo::new(bytecode_hash).with_context(|| {
+            format!("invalid bytecode hash at address {address:?}: {bytecode_hash:?}")
+        })?;
+        Ok(Some(info))
+    }
+
     #[tracing::instrument(skip(self))]
     pub fn get_fee_params_impl(&self) -> FeeParams {
         self.state
@@ -698,6 +862,9 @@ impl ZksNamespace {
         tx.set_input(tx_bytes.0, hash);
 
         let submit_result = self.state.tx_sender.submit_tx(tx, block_args).await;
+        if submit_result.is_ok() {
+            mark_write();
+        }
         submit_result.map(|result| (hash, result.1)).map_err(|err| {
             tracing::debug!("Send raw transaction error: {err}");
             API_METRICS.submit_tx_error[&err.prom_error_code()].inc();
@@ -30,7 +30,7 @@ use zksync_web3_decl::{
 };
 
 use super::{
-    backend_jsonrpsee::MethodTracer,
+    backend_jsonrpsee::{uses_master_pool, MethodTracer},
     mempool_cache::MempoolCache,
     metrics::{FilterType, FILTER_METRICS},
     TypedFilter,
@@ -243,6 +243,9 @@ pub(crate) struct RpcState {
     pub(super) current_method: Arc<MethodTracer>,
     pub(super) installed_filters: Option<Arc<Mutex<Filters>>>,
     pub(super) connection_pool: ConnectionPool<Core>,
+    /// Master DB pool used to serve requests for which the replica DB lags behind. Only set if read-your-writes
+    /// consistency is enabled.
+    pub(super) master_pool: Option<ConnectionPool<Core>>,
     pub(super) tree_api: Option<Arc<dyn TreeApiClient>>,
     pub(super) tx_sender: TxSender,
     pub(super) sync_state: Option<SyncState>,
@@ -293,8 +296,11 @@ impl RpcState {
     pub(crate) fn acquire_connection(
         &self,
     ) -> impl Future<Output = Result<Connection<'static, Core>, Web3Error>> + '_ {
-        self.connection_pool
-            .connection_tagged("api")
+        let pool = match &self.master_pool {
+            Some(master_pool) if uses_master_pool() => master_pool,
+            _ => &self.connection_pool,
+        };
+        pool.connection_tagged("api")
             .map_err(|err| err.generalize().into())
     }
 
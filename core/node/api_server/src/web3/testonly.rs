@@ -109,6 +109,8 @@ pub struct TestServerBuilder {
     tx_executor: MockOneshotExecutor,
     executor_options: Option<SandboxExecutorOptions>,
     method_tracer: Arc<MethodTracer>,
+    api_keys: Option<ApiKeysConfig>,
+    read_your_writes: Option<ReadYourWritesConfig>,
 }
 
 impl TestServerBuilder {
@@ -120,6 +122,8 @@ impl TestServerBuilder {
             tx_executor: MockOneshotExecutor::default(),
             executor_options: None,
             method_tracer: Arc::default(),
+            api_keys: None,
+            read_your_writes: None,
         }
     }
 
@@ -143,6 +147,21 @@ impl TestServerBuilder {
         self
     }
 
+    /// Enables per-API-key quotas for the server.
+    #[must_use]
+    pub fn with_api_keys(mut self, config: ApiKeysConfig) -> Self {
+        self.api_keys = Some(config);
+        self
+    }
+
+    /// Enables read-your-writes consistency for the server. The pool passed to [`Self::new()`] is used
+    /// as the replica pool.
+    #[must_use]
+    pub fn with_read_your_writes(mut self, config: ReadYourWritesConfig) -> Self {
+        self.read_your_writes = Some(config);
+        self
+    }
+
     /// Builds an HTTP server.
     pub async fn build_http(self, stop_receiver: watch::Receiver<bool>) -> ApiServerHandles {
         self.spawn_server(ApiTransportLabel::Http, None, stop_receiver)
@@ -176,6 +195,8 @@ impl TestServerBuilder {
             pool,
             api_config,
             method_tracer,
+            api_keys,
+            read_your_writes,
         } = self;
 
         let tx_executor = if let Some(options) = executor_options {
@@ -209,6 +230,16 @@ impl TestServerBuilder {
                 builder
             }
         };
+        let server_builder = if let Some(api_keys) = api_keys {
+            server_builder.with_api_keys(api_keys)
+        } else {
+            server_builder
+        };
+        let server_builder = if let Some(config) = read_your_writes {
+            server_builder.with_read_your_writes(config)
+        } else {
+            server_builder
+        };
         let server_handles = server_builder
             .with_polling_interval(POLL_INTERVAL)
             .with_tx_sender(tx_sender)
@@ -19,8 +19,8 @@ use zksync_config::{
 use zksync_contracts::BaseSystemContracts;
 use zksync_dal::{transactions_dal::L2TxSubmissionResult, Connection, ConnectionPool, CoreDal};
 use zksync_multivm::interface::{
-    tracer::ValidationTraces, TransactionExecutionMetrics, TransactionExecutionResult,
-    TxExecutionStatus, VmEvent, VmExecutionMetrics,
+    tracer::ValidationTraces, ExecutionResult, TransactionExecutionMetrics,
+    TransactionExecutionResult, TxExecutionStatus, VmEvent, VmExecutionMetrics,
 };
 use zksync_node_genesis::{insert_genesis_batch, mock_genesis_config, GenesisParams};
 use zksync_node_test_utils::{
@@ -43,6 +43,7 @@ use zksync_types::{
     storage::get_code_key,
     system_contracts::get_system_smart_contracts,
     tokens::{TokenInfo, TokenMetadata},
+    transaction_request::CallRequest,
     tx::IncludedTxLocation,
     u256_to_h256,
     utils::{storage_key_for_eth_balance, storage_key_for_standard_token_balance},
@@ -60,6 +61,7 @@ use zksync_web3_decl::{
             error::{ErrorCode, INVALID_PARAMS_CODE, OVERSIZED_RESPONSE_CODE},
             ErrorObjectOwned,
         },
+        ws_client::WsClientBuilder,
     },
     namespaces::{EnNamespaceClient, EthNamespaceClient, ZksNamespaceClient},
 };
@@ -509,6 +511,52 @@ async fn http_server_basics() {
     test_http_server(HttpServerBasicsTest).await;
 }
 
+#[derive(Debug)]
+struct BlockCommitmentInfoTest;
+
+#[async_trait]
+impl HttpTest for BlockCommitmentInfoTest {
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let info = client
+            .get_block_commitment_info(L2BlockNumber(0))
+            .await?
+            .context("no commitment info for genesis block")?;
+        assert_eq!(info.number, L2BlockNumber(0));
+        assert_eq!(info.l1_batch.number, L1BatchNumber(0));
+        assert_eq!(
+            info.commitment_components.rollup_root_hash,
+            info.l1_batch.base.root_hash.unwrap()
+        );
+        assert!(info.inclusion_proof.next_blocks.is_empty());
+
+        let mut storage = pool.connection().await?;
+        let genesis_metadata = storage
+            .blocks_dal()
+            .get_l1_batch_metadata(L1BatchNumber(0))
+            .await?
+            .context("no genesis metadata")?;
+        info.commitment_components
+            .verify(genesis_metadata.metadata.commitment)?;
+
+        // A block that is not sealed in an L1 batch yet has no commitment info.
+        store_l2_block(&mut storage, L2BlockNumber(1), &[]).await?;
+        let info = client.get_block_commitment_info(L2BlockNumber(1)).await?;
+        assert!(info.is_none(), "{info:?}");
+        let info = client.get_block_commitment_info(L2BlockNumber(2)).await?;
+        assert!(info.is_none(), "{info:?}");
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_block_commitment_info() {
+    test_http_server(BlockCommitmentInfoTest).await;
+}
+
 #[derive(Debug)]
 struct BlockMethodsWithSnapshotRecovery;
 
@@ -1205,10 +1253,29 @@ impl HttpTest for GetBytecodeTest {
                 .get_code(*contract.account_id.address(), None)
                 .await?;
             assert_eq!(bytecode.0, contract.bytecode);
+
+            let info = client
+                .get_contract_bytecode_info(*contract.account_id.address(), None)
+                .await?
+                .expect("no bytecode info for system contract");
+            assert_eq!(info.kind, api::BytecodeKind::EraVm);
+            assert_eq!(info.bytecode_len.as_usize(), contract.bytecode.len());
+            assert!(info.is_constructed);
         }
 
         let bytecode = client.get_code(genesis_evm_address, None).await?;
         assert_eq!(bytecode.0, PROCESSED_EVM_BYTECODE);
+        let info = client
+            .get_contract_bytecode_info(genesis_evm_address, None)
+            .await?
+            .expect("no bytecode info for EVM contract");
+        assert_eq!(info.kind, api::BytecodeKind::Evm);
+        assert_eq!(info.bytecode_len.as_usize(), PROCESSED_EVM_BYTECODE.len());
+        assert_eq!(
+            info.bytecode_hash,
+            BytecodeHash::for_evm_bytecode(PROCESSED_EVM_BYTECODE.len(), PADDED_EVM_BYTECODE)
+                .value()
+        );
 
         let latest_block_variants = [
             api::BlockNumber::Pending,
@@ -1263,6 +1330,10 @@ impl HttpTest for GetBytecodeTest {
                 .get_code(new_bytecode_address, Some(at_block))
                 .await?;
             assert!(bytecode.0.is_empty());
+            let info = client
+                .get_contract_bytecode_info(new_bytecode_address, Some(at_block))
+                .await?;
+            assert_eq!(info, None);
         }
 
         for at_block in latest_block_variants
@@ -1401,3 +1472,247 @@ impl HttpTest for FeeHistoryTest {
 async fn getting_fee_history() {
     test_http_server(FeeHistoryTest).await;
 }
+
+#[tokio::test]
+async fn enforcing_api_key_quotas() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let network_config = NetworkConfig::for_tests();
+    let mut storage = pool.connection().await.unwrap();
+    StorageInitialization::genesis()
+        .prepare_storage(&network_config, &mut storage)
+        .await
+        .unwrap();
+    drop(storage);
+
+    let api_keys = ApiKeysConfig {
+        keys: "alice:0123abcd=3,bob:4567ef00=100".parse().unwrap(),
+        method_costs: "eth_getBlockByNumber=1000".parse().unwrap(),
+        anonymous_compute_units_per_minute: None,
+        header_name: "x-api-key".to_owned(),
+        key_in_url_path: false,
+    };
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let api_config = InternalApiConfig::new(
+        &Web3JsonRpcConfig::for_tests(),
+        &ContractsConfig::for_tests(),
+        &GenesisConfig::for_tests(),
+    );
+    let mut server_handles = TestServerBuilder::new(pool, api_config)
+        .with_api_keys(api_keys)
+        .build_http(stop_receiver)
+        .await;
+    let local_addr = server_handles.wait_until_ready().await;
+
+    // Requests without a key are rejected since there's no anonymous quota.
+    let client = <HttpClient>::builder()
+        .build(format!("http://{local_addr}/"))
+        .unwrap();
+    let err = client
+        .request::<U64, _>("eth_blockNumber", rpc_params![])
+        .await
+        .unwrap_err();
+    assert_matches!(err, ClientError::Call(err) if err.code() == 401);
+
+    // Keys in the URL path are ignored unless enabled in the config.
+    let client = <HttpClient>::builder()
+        .build(format!("http://{local_addr}/0123abcd"))
+        .unwrap();
+    let err = client
+        .request::<U64, _>("eth_blockNumber", rpc_params![])
+        .await
+        .unwrap_err();
+    assert_matches!(err, ClientError::Call(err) if err.code() == 401);
+
+    let mut headers = http::HeaderMap::new();
+    headers.insert("x-api-key", http::HeaderValue::from_static("0123abcd"));
+    let client = <HttpClient>::builder()
+        .set_headers(headers)
+        .build(format!("http://{local_addr}/"))
+        .unwrap();
+    for _ in 0..3 {
+        client
+            .request::<U64, _>("eth_blockNumber", rpc_params![])
+            .await
+            .unwrap();
+    }
+    let err = client
+        .request::<U64, _>("eth_blockNumber", rpc_params![])
+        .await
+        .unwrap_err();
+    assert_matches!(err, ClientError::Call(err) if err.code() == 429);
+
+    // Key specified in the header. Quotas are tracked separately for each key.
+    let mut headers = http::HeaderMap::new();
+    headers.insert("x-api-key", http::HeaderValue::from_static("4567ef00"));
+    let client = <HttpClient>::builder()
+        .set_headers(headers)
+        .build(format!("http://{local_addr}/"))
+        .unwrap();
+    client
+        .request::<U64, _>("eth_blockNumber", rpc_params![])
+        .await
+        .unwrap();
+    // The method cost exceeds the entire quota for the key.
+    let err = client
+        .request::<serde_json::Value, _>("eth_getBlockByNumber", rpc_params!["latest", false])
+        .await
+        .unwrap_err();
+    assert_matches!(err, ClientError::Call(err) if err.code() == 429);
+
+    // Calls in a batch are accounted separately.
+    let mut batch = BatchRequestBuilder::new();
+    batch.insert("eth_blockNumber", rpc_params![]).unwrap();
+    batch.insert("eth_chainId", rpc_params![]).unwrap();
+    let response = client.batch_request::<U64>(batch).await.unwrap();
+    assert_eq!(response.num_successful_calls(), 2);
+
+    stop_sender.send_replace(true);
+    server_handles.shutdown().await;
+}
+
+#[tokio::test]
+async fn enforcing_api_key_quotas_for_ws_server() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let network_config = NetworkConfig::for_tests();
+    let mut storage = pool.connection().await.unwrap();
+    StorageInitialization::genesis()
+        .prepare_storage(&network_config, &mut storage)
+        .await
+        .unwrap();
+    drop(storage);
+
+    let api_keys = ApiKeysConfig {
+        keys: "alice:0123abcd=2".parse().unwrap(),
+        method_costs: Default::default(),
+        anonymous_compute_units_per_minute: None,
+        header_name: "x-api-key".to_owned(),
+        key_in_url_path: true,
+    };
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let api_config = InternalApiConfig::new(
+        &Web3JsonRpcConfig::for_tests(),
+        &ContractsConfig::for_tests(),
+        &GenesisConfig::for_tests(),
+    );
+    let (mut server_handles, _) = TestServerBuilder::new(pool, api_config)
+        .with_api_keys(api_keys)
+        .build_ws(None, stop_receiver)
+        .await;
+    let local_addr = server_handles.wait_until_ready().await;
+
+    let client = WsClientBuilder::default()
+        .build(format!("ws://{local_addr}/"))
+        .await
+        .unwrap();
+    let err = client
+        .request::<U64, _>("eth_blockNumber", rpc_params![])
+        .await
+        .unwrap_err();
+    assert_matches!(err, ClientError::Call(err) if err.code() == 401);
+
+    // The key in the upgrade request applies to all calls over the connection. Since WebSocket clients
+    // in browsers cannot set headers, the key is specified in the URL path.
+    let client = WsClientBuilder::default()
+        .build(format!("ws://{local_addr}/0123abcd"))
+        .await
+        .unwrap();
+    for _ in 0..2 {
+        client
+            .request::<U64, _>("eth_blockNumber", rpc_params![])
+            .await
+            .unwrap();
+    }
+    let err = client
+        .request::<U64, _>("eth_blockNumber", rpc_params![])
+        .await
+        .unwrap_err();
+    assert_matches!(err, ClientError::Call(err) if err.code() == 429);
+
+    stop_sender.send_replace(true);
+    server_handles.shutdown().await;
+}
+
+#[tokio::test]
+async fn read_your_writes_with_lagging_replica() {
+    // The replica DB is emulated with a separate DB that doesn't receive writes to the master DB.
+    let replica_pool = ConnectionPool::<Core>::test_pool().await;
+    let master_pool = ConnectionPool::<Core>::test_pool().await;
+    let network_config = NetworkConfig::for_tests();
+    for pool in [&replica_pool, &master_pool] {
+        let mut storage = pool.connection().await.unwrap();
+        StorageInitialization::genesis()
+            .prepare_storage(&network_config, &mut storage)
+            .await
+            .unwrap();
+    }
+    let tx = create_l2_transaction(10, 200);
+    let tx_hash = tx.hash();
+    let mut storage = master_pool.connection().await.unwrap();
+    store_l2_block(
+        &mut storage,
+        L2BlockNumber(1),
+        &[execute_l2_transaction(tx)],
+    )
+    .await
+    .unwrap();
+    drop(storage);
+
+    let mut tx_executor = MockOneshotExecutor::default();
+    tx_executor.set_call_responses(|_, env| {
+        assert_eq!(env.l1_batch.first_l2_block.number, 1);
+        ExecutionResult::Success {
+            output: b"output".to_vec(),
+        }
+    });
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let api_config = InternalApiConfig::new(
+        &Web3JsonRpcConfig::for_tests(),
+        &ContractsConfig::for_tests(),
+        &GenesisConfig::for_tests(),
+    );
+    let mut server_handles = TestServerBuilder::new(replica_pool, api_config)
+        .with_tx_executor(tx_executor)
+        .with_read_your_writes(ReadYourWritesConfig {
+            master_pool,
+            max_wait: Duration::from_millis(50),
+        })
+        .build_http(stop_receiver)
+        .await;
+    let local_addr = server_handles.wait_until_ready().await;
+
+    let call_request = CallRequest {
+        from: Some(Address::repeat_byte(1)),
+        to: Some(Address::repeat_byte(2)),
+        data: Some(b"call".to_vec().into()),
+        ..CallRequest::default()
+    };
+    let block = api::BlockIdVariant::BlockNumber(1.into());
+
+    // Without a watermark, requests are served from the replica DB.
+    let client = <HttpClient>::builder()
+        .build(format!("http://{local_addr}/"))
+        .unwrap();
+    let tx = client.get_transaction_by_hash(tx_hash).await.unwrap();
+    assert!(tx.is_none(), "{tx:?}");
+    client
+        .call(call_request.clone(), Some(block), None)
+        .await
+        .unwrap_err();
+
+    // Both DBs share the Postgres WAL, so the watermark is set ahead of the current WAL position to emulate
+    // the replica lagging behind. Such requests should be served from the master DB after waiting.
+    let mut headers = http::HeaderMap::new();
+    headers.insert(WATERMARK_HEADER, (u64::MAX / 2).into());
+    let client = <HttpClient>::builder()
+        .set_headers(headers)
+        .build(format!("http://{local_addr}/"))
+        .unwrap();
+    let tx = client.get_transaction_by_hash(tx_hash).await.unwrap();
+    assert_eq!(tx.expect("transaction not found").hash, tx_hash);
+    // The call is processed by `TxSender` and the VM sandbox, which should use the master DB as well.
+    let output = client.call(call_request, Some(block), None).await.unwrap();
+    assert_eq!(output.0, b"output");
+
+    stop_sender.send_replace(true);
+    server_handles.shutdown().await;
+}
@@ -928,13 +928,15 @@ async fn trace_call_method_with_evm_emulator() {
 enum EstimateMethod {
     EthEstimateGas,
     ZksEstimateFee,
+    ZksEstimateGasDetailed,
     ZksEstimateGasL1ToL2,
 }
 
 impl EstimateMethod {
-    const ALL: [Self; 3] = [
+    const ALL: [Self; 4] = [
         Self::EthEstimateGas,
         Self::ZksEstimateFee,
+        Self::ZksEstimateGasDetailed,
         Self::ZksEstimateGasL1ToL2,
     ];
 
@@ -945,6 +947,10 @@ impl EstimateMethod {
                 .estimate_fee(req, None)
                 .await
                 .map(|fee| fee.gas_limit),
+            Self::ZksEstimateGasDetailed => client
+                .estimate_gas_detailed(req, None)
+                .await
+                .map(|details| details.fee.gas_limit),
             Self::ZksEstimateGasL1ToL2 => client.estimate_gas_l1_to_l2(req, None).await,
         }
     }
@@ -1057,13 +1063,13 @@ impl HttpTest for EstimateGasTest {
     }
 }
 
-#[test_casing(3, EstimateMethod::ALL)]
+#[test_casing(4, EstimateMethod::ALL)]
 #[tokio::test]
 async fn estimate_gas_basics(method: EstimateMethod) {
     test_http_server(EstimateGasTest::new(method, false)).await;
 }
 
-#[test_casing(3, EstimateMethod::ALL)]
+#[test_casing(4, EstimateMethod::ALL)]
 #[tokio::test]
 async fn estimate_gas_after_snapshot_recovery(method: EstimateMethod) {
     test_http_server(EstimateGasTest::new(method, true)).await;
@@ -1164,7 +1170,7 @@ impl HttpTest for EstimateGasWithoutToAddressTest {
     }
 }
 
-#[test_casing(3, EstimateMethod::ALL)]
+#[test_casing(4, EstimateMethod::ALL)]
 #[tokio::test]
 async fn estimate_gas_fails_without_to_address(method: EstimateMethod) {
     test_http_server(EstimateGasWithoutToAddressTest { method }).await;
@@ -1214,7 +1220,7 @@ impl HttpTest for EstimateGasTestWithEvmEmulator {
     }
 }
 
-#[test_casing(3, EstimateMethod::ALL)]
+#[test_casing(4, EstimateMethod::ALL)]
 #[tokio::test]
 async fn estimate_gas_with_evm_emulator(method: EstimateMethod) {
     test_http_server(EstimateGasTestWithEvmEmulator { method }).await;
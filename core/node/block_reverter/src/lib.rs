@@ -24,6 +24,7 @@ use zksync_types::{
     web3::BlockNumber,
     Address, L1BatchNumber, L2ChainId, H160, H256, U256,
 };
+use zksync_vm_runner::impls::VmRunnerWorkload;
 
 #[cfg(test)]
 mod tests;
@@ -103,6 +104,7 @@ pub struct BlockReverter {
     storage_cache_paths: Vec<String>,
     merkle_tree_path: Option<String>,
     snapshots_object_store: Option<Arc<dyn ObjectStore>>,
+    vm_runner_workloads: Vec<Arc<dyn VmRunnerWorkload>>,
 }
 
 impl BlockReverter {
@@ -115,6 +117,7 @@ impl BlockReverter {
             storage_cache_paths: Vec::new(),
             merkle_tree_path: None,
             snapshots_object_store: None,
+            vm_runner_workloads: Vec::new(),
         }
     }
 
@@ -151,6 +154,15 @@ impl BlockReverter {
         self
     }
 
+    /// Adds a custom VM runner workload which data should be rolled back together with Postgres.
+    pub fn add_vm_runner_workload_to_rollback(
+        &mut self,
+        workload: Arc<dyn VmRunnerWorkload>,
+    ) -> &mut Self {
+        self.vm_runner_workloads.push(workload);
+        self
+    }
+
     /// Rolls back previously enabled DBs (Postgres + RocksDB) and the snapshot object store to a previous state.
     pub async fn roll_back(&self, last_l1_batch_to_keep: L1BatchNumber) -> anyhow::Result<()> {
         if !self.allow_rolling_back_executed_batches {
@@ -376,6 +388,19 @@ impl BlockReverter {
             .vm_runner_dal()
             .delete_bwip_data(last_l1_batch_to_keep)
             .await?;
+        tracing::info!("Rolling back vm_runner_workloads");
+        transaction
+            .vm_runner_dal()
+            .delete_workloads_data(last_l1_batch_to_keep)
+            .await?;
+        for workload in &self.vm_runner_workloads {
+            let name = workload.name();
+            tracing::info!("Rolling back data of VM runner workload `{name}`");
+            workload
+                .revert(&mut transaction, last_l1_batch_to_keep)
+                .await
+                .with_context(|| format!("failed rolling back VM runner workload `{name}`"))?;
+        }
         tracing::info!("Rolling back L2 blocks");
         transaction
             .blocks_dal()
@@ -5,11 +5,11 @@ use itertools::Itertools;
 use tokio::{sync::watch, task::JoinHandle};
 use zksync_dal::{ConnectionPool, Core, CoreDal};
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
-use zksync_l1_contract_interface::i_executor::commit::kzg::pubdata_to_blob_commitments;
+use zksync_l1_contract_interface::i_executor::commit::kzg::pubdata_to_blob_hashes;
 use zksync_types::{
     blob::num_blobs_required,
     commitment::{
-        AuxCommitments, BlobHash, CommitmentCommonInput, CommitmentInput, L1BatchAuxiliaryOutput,
+        AuxCommitments, CommitmentCommonInput, CommitmentInput, L1BatchAuxiliaryOutput,
         L1BatchCommitment, L1BatchCommitmentArtifacts, L1BatchCommitmentMode,
     },
     h256_to_u256,
@@ -20,8 +20,8 @@ use zksync_types::{
 use crate::{
     metrics::{CommitmentStage, METRICS},
     utils::{
-        convert_vm_events_to_log_queries, pubdata_to_blob_linear_hashes, read_aggregation_root,
-        CommitmentComputer, RealCommitmentComputer,
+        convert_vm_events_to_log_queries, read_aggregation_root, CommitmentComputer,
+        RealCommitmentComputer,
     },
 };
 
@@ -270,24 +270,7 @@ impl CommitmentGenerator {
                 let pubdata_input = header.pubdata_input.with_context(|| {
                     format!("`pubdata_input` is missing for L1 batch #{l1_batch_number}")
                 })?;
-
-                let commitments = pubdata_to_blob_commitments(
-                    num_blobs_required(&protocol_version),
-                    &pubdata_input,
-                );
-                let linear_hashes = pubdata_to_blob_linear_hashes(
-                    num_blobs_required(&protocol_version),
-                    pubdata_input,
-                );
-
-                commitments
-                    .into_iter()
-                    .zip(linear_hashes)
-                    .map(|(commitment, linear_hash)| BlobHash {
-                        commitment,
-                        linear_hash,
-                    })
-                    .collect::<Vec<_>>()
+                pubdata_to_blob_hashes(protocol_version, pubdata_input)
             } else {
                 vec![Default::default(); num_blobs_required(&protocol_version)]
             };
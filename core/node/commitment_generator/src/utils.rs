@@ -9,7 +9,6 @@ use zk_evm_1_5_0::{
     zk_evm_abstractions::queries::LogQuery as LogQuery_1_5_0,
 };
 use zksync_dal::{Connection, Core, CoreDal};
-use zksync_l1_contract_interface::i_executor::commit::kzg::ZK_SYNC_BYTES_PER_BLOB;
 use zksync_multivm::{interface::VmEvent, utils::get_used_bootloader_memory_bytes};
 use zksync_system_constants::message_root::{AGG_TREE_HEIGHT_KEY, AGG_TREE_NODES_KEY};
 use zksync_types::{
@@ -173,32 +172,6 @@ pub(crate) fn convert_vm_events_to_log_queries(events: &[VmEvent]) -> Vec<LogQue
         .collect()
 }
 
-pub(crate) fn pubdata_to_blob_linear_hashes(
-    blobs_required: usize,
-    mut pubdata_input: Vec<u8>,
-) -> Vec<H256> {
-    // Now, we need to calculate the linear hashes of the blobs.
-    // Firstly, let's pad the pubdata to the size of the blob.
-    if pubdata_input.len() % ZK_SYNC_BYTES_PER_BLOB != 0 {
-        pubdata_input.resize(
-            pubdata_input.len()
-                + (ZK_SYNC_BYTES_PER_BLOB - pubdata_input.len() % ZK_SYNC_BYTES_PER_BLOB),
-            0,
-        );
-    }
-
-    let mut result = vec![H256::zero(); blobs_required];
-
-    pubdata_input
-        .chunks(ZK_SYNC_BYTES_PER_BLOB)
-        .enumerate()
-        .for_each(|(i, chunk)| {
-            result[i] = H256(keccak256(chunk));
-        });
-
-    result
-}
-
 pub(crate) async fn read_aggregation_root(
     connection: &mut Connection<'_, Core>,
     l1_batch_number: L1BatchNumber,
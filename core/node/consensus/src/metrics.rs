@@ -7,6 +7,12 @@ pub(crate) struct Metrics {
     /// It is used only as a fallback when the p2p syncing is disabled or falling behind.
     /// so it shouldn't be increasing under normal circumstances if p2p syncing is enabled.
     pub fetch_block: vise::Counter,
+    /// First L2 block advertised to peers as available from this node.
+    /// Moves forward when blocks are pruned; for nodes recovered from a snapshot
+    /// it starts right after the snapshot L2 block.
+    pub advertised_first_block: vise::Gauge<u64>,
+    /// Next L2 block (i.e., one past the last certified block) advertised to peers.
+    pub advertised_next_block: vise::Gauge<u64>,
 }
 
 #[vise::register]
@@ -15,7 +15,10 @@ use zksync_web3_decl::{
 };
 
 use super::{Connection, PayloadQueue};
-use crate::storage::{ConnectionPool, InsertCertificateError};
+use crate::{
+    metrics::METRICS,
+    storage::{ConnectionPool, InsertCertificateError},
+};
 
 fn to_fetched_block(
     number: validator::BlockNumber,
@@ -62,12 +65,14 @@ pub(crate) struct Store {
     /// L2 block QCs received from consensus
     block_certificates: ctx::channel::UnboundedSender<validator::CommitQC>,
     /// Range of L2 blocks for which we have a QC persisted.
-    blocks_persisted: sync::watch::Receiver<storage::BlockStoreState>,
+    /// This is the range advertised to peers over gossip, so it has to be kept in sync with pruning.
+    blocks_persisted: PersistedBlockState,
     /// Main node client. None if this node is the main node.
     client: Option<Box<DynClient<L2>>>,
 }
 
-struct PersistedBlockState(sync::watch::Sender<storage::BlockStoreState>);
+#[derive(Clone, Debug)]
+struct PersistedBlockState(Arc<sync::watch::Sender<storage::BlockStoreState>>);
 
 /// Background task of the `Store`.
 pub struct StoreRunner {
@@ -89,7 +94,7 @@ impl Store {
         let blocks_persisted = conn.block_store_state(ctx).await.wrap("blocks_range()")?;
         drop(conn);
 
-        let blocks_persisted = sync::watch::channel(blocks_persisted).0;
+        let blocks_persisted = PersistedBlockState::new(blocks_persisted);
         let (block_certs_send, block_certs_recv) = ctx::channel::unbounded();
 
         Ok((
@@ -97,12 +102,12 @@ impl Store {
                 pool: pool.clone(),
                 block_certificates: block_certs_send,
                 block_payloads: Arc::new(sync::Mutex::new(payload_queue)),
-                blocks_persisted: blocks_persisted.subscribe(),
+                blocks_persisted: blocks_persisted.clone(),
                 client,
             },
             StoreRunner {
                 pool,
-                blocks_persisted: PersistedBlockState(blocks_persisted),
+                blocks_persisted,
                 block_certificates: block_certs_recv,
             },
         ))
@@ -137,6 +142,17 @@ impl Store {
 }
 
 impl PersistedBlockState {
+    fn new(state: storage::BlockStoreState) -> Self {
+        Self::report(&state);
+        Self(Arc::new(sync::watch::channel(state).0))
+    }
+
+    /// Reports the advertised block range to metrics.
+    fn report(state: &storage::BlockStoreState) {
+        METRICS.advertised_first_block.set(state.first.0);
+        METRICS.advertised_next_block.set(state.next().0);
+    }
+
     /// Updates `persisted` to new.
     /// Ends of the range can only be moved forward.
     /// If `persisted.first` is moved forward, it means that blocks have been pruned.
@@ -152,6 +168,7 @@ impl PersistedBlockState {
             if p.next() < new.next() {
                 p.last = new.last;
             }
+            Self::report(p);
             true
         });
     }
@@ -171,6 +188,7 @@ impl PersistedBlockState {
                 return false;
             }
             p.last = Some(storage::Last::Final(cert));
+            Self::report(p);
             true
         });
     }
@@ -293,7 +311,7 @@ impl storage::PersistentBlockStore for Store {
     }
 
     fn persisted(&self) -> sync::watch::Receiver<storage::BlockStoreState> {
-        self.blocks_persisted.clone()
+        self.blocks_persisted.0.subscribe()
     }
 
     async fn block(
@@ -301,12 +319,29 @@ impl storage::PersistentBlockStore for Store {
         ctx: &ctx::Ctx,
         number: validator::BlockNumber,
     ) -> ctx::Result<validator::Block> {
-        Ok(self
-            .conn(ctx)
-            .await?
-            .block(ctx, number)
-            .await?
-            .context("not found")?)
+        let mut conn = self.conn(ctx).await?;
+        if let Some(block) = conn.block(ctx, number).await? {
+            return Ok(block);
+        }
+        // The block might have been pruned since the advertised range was last refreshed
+        // by `StoreRunner`. Refresh it right away, so that peers stop requesting pruned blocks
+        // from this node and fetch them from the peers which still have them instead.
+        // Nodes recovered from a snapshot never have blocks preceding the recovery point,
+        // so they are handled in the same way.
+        let state = conn
+            .block_store_state(ctx)
+            .await
+            .wrap("block_store_state()")?;
+        self.blocks_persisted.update(state);
+        let first = self.blocks_persisted.0.borrow().first;
+        if number < first {
+            return Err(anyhow::format_err!(
+                "block {number} has been pruned or precedes the snapshot recovery point; \
+                 available blocks start at {first}"
+            )
+            .into());
+        }
+        Err(anyhow::format_err!("block {number} not found").into())
     }
 
     async fn verify_pregenesis_block(
@@ -79,6 +79,15 @@ impl ConfigSet {
             net,
         }
     }
+
+    /// Adds `peer` to the static outbound gossip connections of this node.
+    pub(super) fn add_gossip_peer(&mut self, peer: &ConfigSet) {
+        self.net
+            .gossip
+            .static_outbound
+            .insert(peer.net.gossip.key.public(), peer.net.public_addr.clone());
+        self.config = make_config(&self.net, self.config.genesis_spec.clone());
+    }
 }
 
 pub(super) fn new_configs(rng: &mut impl Rng, setup: &Setup, seed_peers: usize) -> Vec<ConfigSet> {
@@ -334,6 +334,115 @@ async fn test_nodes_from_various_snapshots(version: ProtocolVersionId) {
     .unwrap();
 }
 
+// Test nodes syncing from a node recovered from a snapshot. Blocks preceding the snapshot
+// have to be fetched from the peers that have them.
+#[test_casing(2, VERSIONS)]
+#[tokio::test]
+async fn test_serving_blocks_from_snapshot(version: ProtocolVersionId) {
+    zksync_concurrency::testonly::abort_on_panic();
+    let ctx = &ctx::test_root(&ctx::AffineClock::new(10.));
+    let rng = &mut ctx.rng();
+    let setup = Setup::new(rng, 1);
+    let validator_cfg = testonly::new_configs(rng, &setup, 0)[0].clone();
+    let account = &mut Account::random();
+
+    // topology:
+    // validator <-> snapshot node <-> 2nd snapshot node
+    //     ^                ^
+    //     +-- genesis node +
+    let snapshot_node_cfg = validator_cfg.new_fullnode(rng);
+    let snapshot_node2_cfg = snapshot_node_cfg.new_fullnode(rng);
+    let mut genesis_node_cfg = snapshot_node_cfg.new_fullnode(rng);
+    genesis_node_cfg.add_gossip_peer(&validator_cfg);
+
+    scope::run!(ctx, |ctx, s| async {
+        tracing::info!("spawn validator");
+        let validator_pool = ConnectionPool::from_genesis(version).await;
+        let (mut validator, runner) =
+            testonly::StateKeeper::new(ctx, validator_pool.clone()).await?;
+        s.spawn_bg(runner.run(ctx).instrument(tracing::info_span!("validator")));
+        s.spawn_bg(run_main_node(
+            ctx,
+            validator_cfg.config.clone(),
+            validator_cfg.secrets.clone(),
+            validator_pool.clone(),
+        ));
+
+        tracing::info!("produce some batches");
+        validator.push_random_blocks(rng, account, 5).await;
+        validator.seal_batch().await;
+        validator_pool
+            .wait_for_block_certificate(ctx, validator.last_block())
+            .await?;
+
+        tracing::info!("take snapshot and start a node from it");
+        let snapshot = validator_pool.snapshot(ctx).await?;
+        let recovery_point = validator::BlockNumber(snapshot.l2_block.number.0.into());
+        let node_pool = ConnectionPool::from_snapshot(snapshot).await;
+        let snapshot = validator_pool.snapshot(ctx).await?;
+        let node_pool2 = ConnectionPool::from_snapshot(snapshot).await;
+        let (node, runner) = testonly::StateKeeper::new(ctx, node_pool.clone()).await?;
+        s.spawn_bg(
+            runner
+                .run(ctx)
+                .instrument(tracing::info_span!("snapshot_node")),
+        );
+        let conn = validator.connect(ctx).await?;
+        s.spawn_bg(node.run_consensus(ctx, conn, snapshot_node_cfg.clone()));
+
+        validator.push_random_blocks(rng, account, 5).await;
+        node_pool
+            .wait_for_block_certificate(ctx, validator.last_block())
+            .await?;
+        // The snapshot node should advertise blocks starting right after the recovery point.
+        let (store, _) = Store::new(ctx, node_pool.clone(), None, None)
+            .await
+            .wrap("Store::new()")?;
+        assert_eq!(store.persisted().borrow().first, recovery_point + 1);
+        assert!(store.block(ctx, recovery_point).await.is_err());
+
+        tracing::info!("start nodes syncing from the snapshot node");
+        let (node, runner) = testonly::StateKeeper::new(ctx, node_pool2.clone()).await?;
+        s.spawn_bg(
+            runner
+                .run(ctx)
+                .instrument(tracing::info_span!("snapshot_node2")),
+        );
+        let conn = validator.connect(ctx).await?;
+        s.spawn_bg(node.run_consensus(ctx, conn, snapshot_node2_cfg));
+
+        let genesis_node_pool = ConnectionPool::from_genesis(version).await;
+        let (node, runner) = testonly::StateKeeper::new(ctx, genesis_node_pool.clone()).await?;
+        s.spawn_bg(
+            runner
+                .run(ctx)
+                .instrument(tracing::info_span!("genesis_node")),
+        );
+        let conn = validator.connect(ctx).await?;
+        s.spawn_bg(node.run_consensus(ctx, conn, genesis_node_cfg));
+
+        validator.push_random_blocks(rng, account, 5).await;
+        let want = validator_pool
+            .wait_for_blocks_and_verify_certs(ctx, validator.last_block())
+            .await?;
+        // The snapshot node is the only gossip peer of the 2nd snapshot node,
+        // so all certificates have been served by it.
+        let got = node_pool2
+            .wait_for_blocks_and_verify_certs(ctx, validator.last_block())
+            .await?;
+        assert_eq!(got[0].number(), recovery_point + 1);
+        assert_eq!(want[want.len() - got.len()..], got[..]);
+        // Blocks preceding the recovery point are only available from the validator.
+        let got = genesis_node_pool
+            .wait_for_blocks_and_verify_certs(ctx, validator.last_block())
+            .await?;
+        assert_eq!(want, got);
+        Ok(())
+    })
+    .await
+    .unwrap();
+}
+
 #[test_casing(4, Product((FROM_SNAPSHOT,VERSIONS)))]
 #[tokio::test]
 async fn test_config_change(from_snapshot: bool, version: ProtocolVersionId) {
@@ -817,11 +926,27 @@ async fn test_with_pruning(version: ProtocolVersionId) {
             .wait_for_batch_info(ctx, to_prune.next(), POLL_INTERVAL)
             .await
             .wrap("wait_for_batch_info()")?;
+        let (store, _) = Store::new(ctx, node_pool.clone(), None, None)
+            .await
+            .wrap("Store::new()")?;
+        let (_, pruned_block) = node_pool
+            .connection(ctx)
+            .await
+            .wrap("connection()")?
+            .get_l2_block_range_of_l1_batch(ctx, to_prune)
+            .await
+            .wrap("get_l2_block_range_of_l1_batch()")?
+            .context("batch not found")?;
+        assert!(store.persisted().borrow().first <= pruned_block);
+
         tracing::info!("Prune some blocks and sync more");
         node_pool
             .prune_batches(ctx, to_prune)
             .await
             .wrap("prune_batches")?;
+        // Requesting a pruned block should shrink the advertised range right away.
+        assert!(store.block(ctx, pruned_block).await.is_err());
+        assert!(pruned_block < store.persisted().borrow().first);
         validator.push_random_blocks(rng, account, 5).await;
         node_pool
             .wait_for_blocks(ctx, validator.last_block())
@@ -471,7 +471,8 @@ impl ConsistencyChecker {
             Some(chain_id) => {
                 let Some(chain_data) = self.chain_data_by_id(chain_id) else {
                     return Err(CheckError::Validation(anyhow::anyhow!(
-                        "failed to find client for chain id {chain_id}"
+                        "L1 batch #{batch_number} is committed to settlement layer with chain ID {chain_id}, \
+                         but there is no client for it; is the gateway client configured?"
                     )));
                 };
                 chain_data
@@ -499,7 +500,12 @@ impl ConsistencyChecker {
             .client
             .get_tx(commit_tx_hash)
             .await?
-            .with_context(|| format!("commit transaction {commit_tx_hash:?} not found on L1"))
+            .with_context(|| {
+                format!(
+                    "commit transaction {commit_tx_hash:?} not found on target chain with id {}",
+                    chain_data.chain_id
+                )
+            })
             .map_err(CheckError::Internal)?; // we've got a transaction receipt previously, thus an internal error
 
         if let Some(diamond_proxy_addr) = chain_data.diamond_proxy_addr {
@@ -609,6 +609,183 @@ async fn checker_works_with_different_settlement_layers() {
     checker_task.await.unwrap().unwrap();
 }
 
+/// Sends a commit transaction for `l1_batches` to the specified settlement layer and returns its hash.
+async fn commit_on_settlement_layer(
+    client: &MockSettlementLayer,
+    diamond_proxy_addr: Address,
+    l1_batches: &[L1BatchWithMetadata],
+    nonce: usize,
+    commitment_mode: L1BatchCommitmentMode,
+) -> H256 {
+    let input_data = build_commit_tx_input_data(l1_batches, commitment_mode);
+    let signed_tx = client
+        .sign_prepared_tx(
+            input_data,
+            VALIDATOR_TIMELOCK_ADDR,
+            Options {
+                nonce: Some(nonce.into()),
+                ..Options::default()
+            },
+        )
+        .unwrap();
+    client.as_ref().send_raw_tx(signed_tx.raw_tx).await.unwrap();
+    client.execute_tx(signed_tx.hash, true, 1).with_logs(
+        l1_batches
+            .iter()
+            .map(|batch| Log {
+                address: diamond_proxy_addr,
+                ..l1_batch_commit_log(batch)
+            })
+            .collect(),
+    );
+    signed_tx.hash
+}
+
+#[test_casing(2, COMMITMENT_MODES)]
+#[tokio::test]
+async fn checker_works_across_settlement_layer_migration(commitment_mode: L1BatchCommitmentMode) {
+    const FIRST_GATEWAY_BATCH: u32 = 6;
+
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    let l1_batches: Vec<_> = (1..=10).map(create_l1_batch_with_metadata).collect();
+    let l1_client = create_mock_ethereum();
+    let gateway_client = create_mock_gateway();
+    let mut commit_tx_hash_by_l1_batch = HashMap::with_capacity(l1_batches.len());
+    let mut chain_id_by_l1_batch = HashMap::with_capacity(l1_batches.len());
+
+    // Batches before the migration are committed to L1, and the remaining ones to the gateway.
+    let (pre_migration, post_migration) = l1_batches.split_at(FIRST_GATEWAY_BATCH as usize - 1);
+    for (client, diamond_proxy_addr, batches) in [
+        (&l1_client, L1_DIAMOND_PROXY_ADDR, pre_migration),
+        (&gateway_client, GATEWAY_DIAMOND_PROXY_ADDR, post_migration),
+    ] {
+        let chain_id = client.as_ref().fetch_chain_id().await.unwrap();
+        for (nonce, batch) in batches.iter().enumerate() {
+            let tx_hash = commit_on_settlement_layer(
+                client,
+                diamond_proxy_addr,
+                slice::from_ref(batch),
+                nonce,
+                commitment_mode,
+            )
+            .await;
+            commit_tx_hash_by_l1_batch.insert(batch.header.number, tx_hash);
+            chain_id_by_l1_batch.insert(batch.header.number, chain_id);
+        }
+    }
+
+    let (l1_batch_updates_sender, mut l1_batch_updates_receiver) = mpsc::unbounded_channel();
+    let mut checker = ConsistencyChecker::new(
+        Box::new(l1_client.into_client()),
+        Some(Box::new(gateway_client.into_client())),
+        100,
+        pool.clone(),
+        commitment_mode,
+        L2ChainId::new(ERA_CHAIN_ID).unwrap(),
+    )
+    .await
+    .unwrap()
+    .with_l1_diamond_proxy_addr(L1_DIAMOND_PROXY_ADDR);
+    checker.sleep_interval = Duration::from_millis(10);
+    checker.event_handler = Box::new(l1_batch_updates_sender);
+    checker.l1_data_mismatch_behavior = L1DataMismatchBehavior::Bail;
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let checker_task = tokio::spawn(checker.run(stop_receiver));
+
+    for save_action in SAVE_ACTION_MAPPERS[0].1(&l1_batches) {
+        save_action
+            .apply(
+                &mut storage,
+                &commit_tx_hash_by_l1_batch,
+                &chain_id_by_l1_batch,
+            )
+            .await;
+    }
+
+    // Batches must be checked sequentially across the migration boundary.
+    for expected_batch in &l1_batches {
+        let checked_batch = l1_batch_updates_receiver.recv().await.unwrap();
+        assert_eq!(checked_batch, expected_batch.header.number);
+    }
+
+    stop_sender.send_replace(true);
+    checker_task.await.unwrap().unwrap();
+}
+
+#[derive(Debug, Clone, Copy)]
+enum SettlementLayerMismatch {
+    /// The batch is recorded as committed to the gateway, but the commit tx is on L1.
+    WrongLayer,
+    /// The batch is recorded as committed to the gateway, but the checker has no gateway client.
+    MissingClient,
+}
+
+#[test_casing(2, [SettlementLayerMismatch::WrongLayer, SettlementLayerMismatch::MissingClient])]
+#[tokio::test]
+async fn checker_detects_settlement_layer_mismatch(mismatch: SettlementLayerMismatch) {
+    let commitment_mode = L1BatchCommitmentMode::Rollup;
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    let l1_batch = create_l1_batch_with_metadata(1);
+    let l1_client = create_mock_ethereum();
+    let commit_tx_hash = commit_on_settlement_layer(
+        &l1_client,
+        L1_DIAMOND_PROXY_ADDR,
+        slice::from_ref(&l1_batch),
+        0,
+        commitment_mode,
+    )
+    .await;
+    let commit_tx_hash_by_l1_batch = HashMap::from([(l1_batch.header.number, commit_tx_hash)]);
+    let chain_id_by_l1_batch =
+        HashMap::from([(l1_batch.header.number, SLChainId(GATEWAY_CHAIN_ID))]);
+    for save_action in SAVE_ACTION_MAPPERS[0].1(slice::from_ref(&l1_batch)) {
+        save_action
+            .apply(
+                &mut storage,
+                &commit_tx_hash_by_l1_batch,
+                &chain_id_by_l1_batch,
+            )
+            .await;
+    }
+    drop(storage);
+
+    let mut checker = create_mock_checker(l1_client, pool, commitment_mode).await;
+    if matches!(mismatch, SettlementLayerMismatch::WrongLayer) {
+        let gateway_client = create_mock_gateway().into_client();
+        checker.gateway_chain_data = Some(SLChainAccess {
+            chain_id: gateway_client.fetch_chain_id().await.unwrap(),
+            client: Box::new(gateway_client),
+            diamond_proxy_addr: Some(GATEWAY_DIAMOND_PROXY_ADDR),
+        });
+    }
+
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let err = tokio::time::timeout(Duration::from_secs(30), checker.run(stop_receiver))
+        .await
+        .expect("Timed out waiting for checker to stop")
+        .unwrap_err();
+    let err = format!("{err:#}");
+    match mismatch {
+        SettlementLayerMismatch::WrongLayer => {
+            assert!(err.contains("not found on target chain"), "{err}");
+        }
+        SettlementLayerMismatch::MissingClient => {
+            assert!(err.contains("there is no client for it"), "{err}");
+        }
+    }
+}
+
 #[test_casing(8, Product((SAVE_ACTION_MAPPERS, COMMITMENT_MODES)))]
 #[tokio::test]
 async fn checker_processes_pre_boojum_batches(
@@ -0,0 +1,360 @@
+//! Export of the data pruned from Postgres to an object store ("cold storage").
+
+use std::{
+    fmt,
+    io::{BufRead, BufReader, Write},
+    mem, ops,
+    sync::Arc,
+};
+
+use anyhow::Context as _;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use vise::{EncodeLabelSet, EncodeLabelValue};
+use zksync_dal::{Connection, Core, CoreDal};
+use zksync_object_store::{Bucket, ObjectStore, StoredObject, _reexports::BoxedError};
+use zksync_types::{
+    api::{self, GetLogsFilter},
+    L1BatchNumber, L2BlockNumber, Transaction, H256,
+};
+use zksync_vm_interface::Call;
+
+use crate::metrics::METRICS;
+
+/// Version of the export format recorded in [`ExportManifest`].
+const EXPORT_FORMAT_VERSION: u32 = 0;
+
+/// Kind of data exported for a pruned chunk of L1 batches. Each kind is stored in a separate file.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    EncodeLabelValue,
+    EncodeLabelSet,
+)]
+#[serde(rename_all = "snake_case")]
+#[metrics(label = "kind", rename_all = "snake_case")]
+pub enum ExportedDataKind {
+    /// L2 block headers in the Web3 API format ([`api::Block`]).
+    Blocks,
+    /// Transactions in the server format ([`Transaction`]).
+    Transactions,
+    /// Transaction receipts in the Web3 API format ([`api::TransactionReceipt`]).
+    Receipts,
+    /// Events in the Web3 API format ([`api::Log`]).
+    Events,
+    /// Call traces for transactions ([`ExportedCallTrace`]).
+    CallTraces,
+}
+
+impl ExportedDataKind {
+    const ALL: [Self; 5] = [
+        Self::Blocks,
+        Self::Transactions,
+        Self::Receipts,
+        Self::Events,
+        Self::CallTraces,
+    ];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Blocks => "blocks",
+            Self::Transactions => "transactions",
+            Self::Receipts => "receipts",
+            Self::Events => "events",
+            Self::CallTraces => "call_traces",
+        }
+    }
+}
+
+impl fmt::Display for ExportedDataKind {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+/// Identifier of an exported chunk of L1 batches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportedChunkKey {
+    pub first_l1_batch: L1BatchNumber,
+    pub last_l1_batch: L1BatchNumber,
+}
+
+/// Key of an [`ExportedDataFile`] in the object store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportedDataFileKey {
+    pub chunk: ExportedChunkKey,
+    pub kind: ExportedDataKind,
+}
+
+/// Call trace of a single transaction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedCallTrace {
+    pub block_number: L2BlockNumber,
+    pub index_in_block: usize,
+    pub tx_hash: H256,
+    pub call: Call,
+}
+
+/// File with exported records of a single [`ExportedDataKind`]. Serialized as gzipped JSON lines
+/// (i.e., each record is serialized as JSON on a separate line), so that it can be processed with standard tools.
+#[derive(Debug, Clone)]
+pub struct ExportedDataFile<T> {
+    pub records: Vec<T>,
+}
+
+impl<T: Serialize + DeserializeOwned> StoredObject for ExportedDataFile<T> {
+    const BUCKET: Bucket = Bucket::PrunedData;
+    type Key<'a> = ExportedDataFileKey;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        let ExportedChunkKey {
+            first_l1_batch,
+            last_l1_batch,
+        } = key.chunk;
+        format!(
+            "pruned_l1_batches_{first_l1_batch}_{last_l1_batch}_{}.jsonl.gzip",
+            key.kind
+        )
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, BoxedError> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        for record in &self.records {
+            serde_json::to_writer(&mut encoder, record)?;
+            encoder.write_all(b"\n")?;
+        }
+        encoder.finish().map_err(From::from)
+    }
+
+    fn deserialize(bytes: Vec<u8>) -> Result<Self, BoxedError> {
+        let reader = BufReader::new(GzDecoder::new(&bytes[..]));
+        let mut records = vec![];
+        for line in reader.lines() {
+            let line = line?;
+            if !line.is_empty() {
+                records.push(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(Self { records })
+    }
+}
+
+/// Information about a single file in [`ExportManifest`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedFileInfo {
+    pub kind: ExportedDataKind,
+    /// Key of the file in the [`Bucket::PrunedData`] bucket.
+    pub key: String,
+    pub record_count: usize,
+}
+
+/// Manifest of an exported chunk of L1 batches. The manifest is uploaded after all data files, so its presence
+/// signals that the chunk was fully exported.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportManifest {
+    pub version: u32,
+    pub first_l1_batch: L1BatchNumber,
+    pub last_l1_batch: L1BatchNumber,
+    pub first_l2_block: L2BlockNumber,
+    pub last_l2_block: L2BlockNumber,
+    pub files: Vec<ExportedFileInfo>,
+}
+
+impl StoredObject for ExportManifest {
+    const BUCKET: Bucket = Bucket::PrunedData;
+    type Key<'a> = ExportedChunkKey;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        let ExportedChunkKey {
+            first_l1_batch,
+            last_l1_batch,
+        } = key;
+        format!("pruned_l1_batches_{first_l1_batch}_{last_l1_batch}_manifest.json")
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, BoxedError> {
+        serde_json::to_vec_pretty(self).map_err(From::from)
+    }
+
+    fn deserialize(bytes: Vec<u8>) -> Result<Self, BoxedError> {
+        serde_json::from_slice(&bytes).map_err(From::from)
+    }
+}
+
+/// Exporter of the data that is about to be hard-pruned to an object store.
+#[derive(Debug, Clone)]
+pub struct PrunedDataExporter {
+    object_store: Arc<dyn ObjectStore>,
+}
+
+impl PrunedDataExporter {
+    pub fn new(object_store: Arc<dyn ObjectStore>) -> Self {
+        Self { object_store }
+    }
+
+    /// Exports data for the specified L1 batches / L2 blocks. Data is read using the provided `storage`,
+    /// so it must not be pruned yet.
+    pub(crate) async fn export_chunk(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        l1_batches: ops::RangeInclusive<L1BatchNumber>,
+        l2_blocks: ops::RangeInclusive<L2BlockNumber>,
+    ) -> anyhow::Result<ExportManifest> {
+        let latency = METRICS.export_duration.start();
+        let chunk = ExportedChunkKey {
+            first_l1_batch: *l1_batches.start(),
+            last_l1_batch: *l1_batches.end(),
+        };
+
+        // Transactions are loaded once and reused to load receipts.
+        let mut transactions = Self::load_transactions(storage, l2_blocks.clone()).await?;
+        let tx_hashes: Vec<_> = transactions.iter().map(Transaction::hash).collect();
+
+        let mut files = Vec::with_capacity(ExportedDataKind::ALL.len());
+        for kind in ExportedDataKind::ALL {
+            let key = ExportedDataFileKey { chunk, kind };
+            let (object_key, record_count) = match kind {
+                ExportedDataKind::Blocks => {
+                    let records = Self::load_blocks(storage, l2_blocks.clone()).await?;
+                    self.put_file(key, records).await?
+                }
+                ExportedDataKind::Transactions => {
+                    self.put_file(key, mem::take(&mut transactions)).await?
+                }
+                ExportedDataKind::Receipts => {
+                    let records = Self::load_receipts(storage, &tx_hashes).await?;
+                    self.put_file(key, records).await?
+                }
+                ExportedDataKind::Events => {
+                    let records = Self::load_events(storage, l2_blocks.clone()).await?;
+                    self.put_file(key, records).await?
+                }
+                ExportedDataKind::CallTraces => {
+                    let records = Self::load_call_traces(storage, l2_blocks.clone()).await?;
+                    self.put_file(key, records).await?
+                }
+            };
+            METRICS.exported_records[&kind].observe(record_count as u64);
+            files.push(ExportedFileInfo {
+                kind,
+                key: object_key,
+                record_count,
+            });
+        }
+
+        let manifest = ExportManifest {
+            version: EXPORT_FORMAT_VERSION,
+            first_l1_batch: *l1_batches.start(),
+            last_l1_batch: *l1_batches.end(),
+            first_l2_block: *l2_blocks.start(),
+            last_l2_block: *l2_blocks.end(),
+            files,
+        };
+        let manifest_key = self
+            .object_store
+            .put(chunk, &manifest)
+            .await
+            .context("failed uploading export manifest")?;
+        let latency = latency.observe();
+        tracing::info!(
+            "Exported data for L1 batches {l1_batches:?} (L2 blocks {l2_blocks:?}) to `{manifest_key}` in {latency:?}"
+        );
+        Ok(manifest)
+    }
+
+    async fn put_file<T: Serialize + DeserializeOwned>(
+        &self,
+        key: ExportedDataFileKey,
+        records: Vec<T>,
+    ) -> anyhow::Result<(String, usize)> {
+        let record_count = records.len();
+        let object_key = self
+            .object_store
+            .put(key, &ExportedDataFile { records })
+            .await
+            .with_context(|| format!("failed uploading exported {}", key.kind))?;
+        Ok((object_key, record_count))
+    }
+
+    async fn load_blocks(
+        storage: &mut Connection<'_, Core>,
+        l2_blocks: ops::RangeInclusive<L2BlockNumber>,
+    ) -> anyhow::Result<Vec<api::Block<H256>>> {
+        // Missing L2 blocks are possible for the first chunk after snapshot recovery; they are skipped by the query.
+        Ok(storage.blocks_web3_dal().get_api_blocks(l2_blocks).await?)
+    }
+
+    async fn load_transactions(
+        storage: &mut Connection<'_, Core>,
+        l2_blocks: ops::RangeInclusive<L2BlockNumber>,
+    ) -> anyhow::Result<Vec<Transaction>> {
+        let mut transactions: Vec<_> = storage
+            .transactions_web3_dal()
+            .get_raw_l2_blocks_transactions(*l2_blocks.start()..*l2_blocks.end() + 1)
+            .await?
+            .into_iter()
+            .collect();
+        transactions.sort_unstable_by_key(|(number, _)| *number);
+        Ok(transactions
+            .into_iter()
+            .flat_map(|(_, transactions)| transactions)
+            .collect())
+    }
+
+    /// Loads receipts for all transactions in the exported L2 block range with a single query.
+    async fn load_receipts(
+        storage: &mut Connection<'_, Core>,
+        tx_hashes: &[H256],
+    ) -> anyhow::Result<Vec<api::TransactionReceipt>> {
+        if tx_hashes.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut receipts = storage
+            .transactions_web3_dal()
+            .get_transaction_receipts(tx_hashes)
+            .await?;
+        receipts.sort_unstable_by_key(|receipt| (receipt.block_number, receipt.transaction_index));
+        Ok(receipts)
+    }
+
+    async fn load_events(
+        storage: &mut Connection<'_, Core>,
+        l2_blocks: ops::RangeInclusive<L2BlockNumber>,
+    ) -> anyhow::Result<Vec<api::Log>> {
+        let filter = GetLogsFilter {
+            from_block: *l2_blocks.start(),
+            to_block: *l2_blocks.end(),
+            addresses: vec![],
+            topics: vec![],
+        };
+        Ok(storage
+            .events_web3_dal()
+            .get_logs(filter, i32::MAX as usize)
+            .await?)
+    }
+
+    async fn load_call_traces(
+        storage: &mut Connection<'_, Core>,
+        l2_blocks: ops::RangeInclusive<L2BlockNumber>,
+    ) -> anyhow::Result<Vec<ExportedCallTrace>> {
+        let traces = storage
+            .blocks_web3_dal()
+            .get_traces_for_l2_blocks(l2_blocks)
+            .await?;
+        Ok(traces
+            .into_iter()
+            .map(|(call, meta)| ExportedCallTrace {
+                block_number: L2BlockNumber(meta.block_number),
+                index_in_block: meta.index_in_block,
+                tx_hash: meta.tx_hash,
+                call,
+            })
+            .collect())
+    }
+}
@@ -8,6 +8,7 @@ use std::{
 use anyhow::Context as _;
 use serde::{Deserialize, Serialize};
 use tokio::sync::watch;
+pub use zksync_dal::pruning_dal::RetentionRules;
 use zksync_dal::{
     pruning_dal::{HardPruningInfo, PruningInfo, SoftPruningInfo},
     Connection, ConnectionPool, Core, CoreDal,
@@ -15,6 +16,10 @@ use zksync_dal::{
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_types::{L1BatchNumber, L2BlockNumber};
 
+pub use self::export::{
+    ExportManifest, ExportedCallTrace, ExportedChunkKey, ExportedDataFile, ExportedDataFileKey,
+    ExportedDataKind, ExportedFileInfo, PrunedDataExporter,
+};
 use self::{
     metrics::{ConditionOutcome, PruneType, METRICS},
     prune_conditions::{
@@ -23,6 +28,7 @@ use self::{
     },
 };
 
+mod export;
 mod metrics;
 mod prune_conditions;
 #[cfg(test)]
@@ -39,6 +45,8 @@ pub struct DbPrunerConfig {
     /// Minimum age of an L1 batch in order for it to be eligible for pruning. Setting this to zero
     /// will effectively disable this pruning criterion.
     pub minimum_l1_batch_age: Duration,
+    /// Rules specifying data that should be retained during hard pruning.
+    pub retention_rules: RetentionRules,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -82,6 +90,7 @@ pub struct DbPruner {
     connection_pool: ConnectionPool<Core>,
     health_updater: HealthUpdater,
     prune_conditions: Vec<Arc<dyn PruneCondition>>,
+    exporter: Option<PrunedDataExporter>,
 }
 
 impl DbPruner {
@@ -121,9 +130,17 @@ impl DbPruner {
             connection_pool,
             health_updater: ReactiveHealthCheck::new("db_pruner").1,
             prune_conditions,
+            exporter: None,
         }
     }
 
+    /// Sets the exporter that will export data to an object store before it is hard-pruned.
+    /// If exporting fails, data is not hard-pruned.
+    pub fn with_exporter(mut self, exporter: PrunedDataExporter) -> Self {
+        self.exporter = Some(exporter);
+        self
+    }
+
     pub fn health_check(&self) -> ReactiveHealthCheck {
         self.health_updater.subscribe()
     }
@@ -232,6 +249,16 @@ impl DbPruner {
         stop_receiver: &mut watch::Receiver<bool>,
     ) -> anyhow::Result<PruningIterationOutcome> {
         let latency = METRICS.pruning_chunk_duration[&PruneType::Hard].start();
+        if let Some(exporter) = &self.exporter {
+            tokio::select! {
+                result = Self::export_data_to_prune(exporter, storage) => result?,
+                _ = stop_receiver.changed() => {
+                    tracing::info!("Exporting data interrupted");
+                    return Ok(PruningIterationOutcome::Interrupted);
+                }
+            }
+        }
+
         let mut transaction = storage.start_transaction().await?;
 
         let mut current_pruning_info = transaction.pruning_dal().get_pruning_info().await?;
@@ -252,9 +279,10 @@ impl DbPruner {
 
         let mut dal = transaction.pruning_dal();
         let stats = tokio::select! {
-            result = dal.hard_prune_batches_range(
+            result = dal.hard_prune_batches_range_with_retention(
                 soft_pruned.l1_batch,
                 soft_pruned.l2_block,
+                &self.config.retention_rules,
             ) => result?,
 
             _ = stop_receiver.changed() => {
@@ -287,6 +315,28 @@ impl DbPruner {
         Ok(PruningIterationOutcome::Pruned)
     }
 
+    async fn export_data_to_prune(
+        exporter: &PrunedDataExporter,
+        storage: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<()> {
+        let pruning_info = storage.pruning_dal().get_pruning_info().await?;
+        let soft_pruned = pruning_info.last_soft_pruned.with_context(|| {
+            format!("bogus pruning info {pruning_info:?}: trying to export data, but there is no soft-pruned data")
+        })?;
+        let (first_l1_batch, first_l2_block) = match pruning_info.last_hard_pruned {
+            Some(info) => (info.l1_batch + 1, info.l2_block + 1),
+            None => (L1BatchNumber(0), L2BlockNumber(0)),
+        };
+        exporter
+            .export_chunk(
+                storage,
+                first_l1_batch..=soft_pruned.l1_batch,
+                first_l2_block..=soft_pruned.l2_block,
+            )
+            .await?;
+        Ok(())
+    }
+
     async fn run_single_iteration(
         &self,
         stop_receiver: &mut watch::Receiver<bool>,
@@ -5,7 +5,7 @@ use vise::{
 };
 use zksync_dal::pruning_dal::HardPruningStats;
 
-use crate::prune_conditions::PruneCondition;
+use crate::{export::ExportedDataKind, prune_conditions::PruneCondition};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
 #[metrics(label = "prune_type", rename_all = "snake_case")]
@@ -56,6 +56,15 @@ pub(super) struct DbPrunerMetrics {
     /// Number of entities deleted during a single hard pruning iteration, grouped by entity type.
     #[metrics(buckets = ENTITY_COUNT_BUCKETS)]
     deleted_entities: Family<PrunedEntityType, Histogram<u64>>,
+    /// Number of L2 block headers retained according to retention rules during a single hard pruning iteration.
+    #[metrics(buckets = ENTITY_COUNT_BUCKETS)]
+    retained_l2_blocks: Histogram<u64>,
+    /// Latency of exporting a chunk of L1 batches to the object store before hard pruning.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub export_duration: Histogram<Duration>,
+    /// Number of records exported for a chunk of L1 batches, grouped by the data kind.
+    #[metrics(buckets = ENTITY_COUNT_BUCKETS)]
+    pub exported_records: Family<ExportedDataKind, Histogram<u64>>,
     /// Number of times a certain condition has resulted in a specific outcome (succeeded, failed, or errored).
     condition_outcomes: Family<ConditionOutcomeLabels, Counter>,
 }
@@ -69,11 +78,13 @@ impl DbPrunerMetrics {
             deleted_events,
             deleted_call_traces,
             deleted_l2_to_l1_logs,
+            retained_l2_blocks,
         } = stats;
         tracing::info!(
             "Performed pruning of database, deleted {deleted_l1_batches} L1 batches, {deleted_l2_blocks} L2 blocks, \
              {deleted_storage_logs} storage logs, \
-             {deleted_events} events, {deleted_call_traces} call traces, {deleted_l2_to_l1_logs} L2-to-L1 logs"
+             {deleted_events} events, {deleted_call_traces} call traces, {deleted_l2_to_l1_logs} L2-to-L1 logs; \
+             retained {retained_l2_blocks} L2 block headers"
         );
 
         self.deleted_entities[&PrunedEntityType::L1Batch].observe(deleted_l1_batches);
@@ -82,6 +93,7 @@ impl DbPrunerMetrics {
         self.deleted_entities[&PrunedEntityType::Event].observe(deleted_events);
         self.deleted_entities[&PrunedEntityType::L2ToL1Log].observe(deleted_l2_to_l1_logs);
         self.deleted_entities[&PrunedEntityType::CallTrace].observe(deleted_call_traces);
+        self.retained_l2_blocks.observe(retained_l2_blocks);
     }
 
     pub fn observe_condition(&self, condition: &dyn PruneCondition, outcome: ConditionOutcome) {
@@ -11,8 +11,9 @@ use zksync_node_test_utils::{
     create_l1_batch, create_l1_batch_metadata, create_l2_block,
     l1_batch_metadata_to_commitment_artifacts,
 };
+use zksync_object_store::MockObjectStore;
 use zksync_types::{
-    aggregated_operations::AggregatedActionType, L2BlockNumber, ProtocolVersion, H256,
+    aggregated_operations::AggregatedActionType, api, L2BlockNumber, ProtocolVersion, H256,
 };
 
 use super::*;
@@ -78,6 +79,7 @@ async fn is_l1_batch_prunable_works() {
             removal_delay: Duration::ZERO,
             pruned_batch_chunk_size: 1,
             minimum_l1_batch_age: Duration::ZERO,
+            retention_rules: RetentionRules::default(),
         },
         ConnectionPool::test_pool().await,
         vec![failing_check, other_failing_check],
@@ -148,6 +150,7 @@ async fn hard_pruning_ignores_conditions_checks() {
             removal_delay: Duration::ZERO,
             pruned_batch_chunk_size: 5,
             minimum_l1_batch_age: Duration::ZERO,
+            retention_rules: RetentionRules::default(),
         },
         pool.clone(),
         vec![nothing_prunable_check],
@@ -197,6 +200,7 @@ async fn pruner_catches_up_with_hard_pruning_up_to_soft_pruning_boundary_ignorin
             removal_delay: Duration::ZERO,
             pruned_batch_chunk_size: 5,
             minimum_l1_batch_age: Duration::ZERO,
+            retention_rules: RetentionRules::default(),
         },
         pool.clone(),
         vec![], //No checks, so every batch is prunable
@@ -235,6 +239,7 @@ async fn unconstrained_pruner_with_fresh_database() {
             removal_delay: Duration::ZERO,
             pruned_batch_chunk_size: 3,
             minimum_l1_batch_age: Duration::ZERO,
+            retention_rules: RetentionRules::default(),
         },
         pool.clone(),
         vec![], //No checks, so every batch is prunable
@@ -275,6 +280,7 @@ async fn pruning_blocked_after_first_chunk() {
             removal_delay: Duration::ZERO,
             pruned_batch_chunk_size: 3,
             minimum_l1_batch_age: Duration::ZERO,
+            retention_rules: RetentionRules::default(),
         },
         pool.clone(),
         vec![first_chunk_prunable_check],
@@ -315,6 +321,7 @@ async fn pruner_is_resistant_to_errors() {
             removal_delay: Duration::ZERO,
             pruned_batch_chunk_size: 3,
             minimum_l1_batch_age: Duration::ZERO,
+            retention_rules: RetentionRules::default(),
         },
         pool.clone(),
         vec![erroneous_condition],
@@ -485,6 +492,7 @@ async fn pruner_with_real_conditions() {
         removal_delay: Duration::from_millis(10), // non-zero to not have a tight loop in `DbPruner::run()`
         pruned_batch_chunk_size: 1,
         minimum_l1_batch_age: Duration::ZERO,
+        retention_rules: RetentionRules::default(),
     };
     let pruner = DbPruner::new(config, pool.clone());
     let mut health_check = pruner.health_check();
@@ -530,6 +538,76 @@ async fn pruner_with_real_conditions() {
     pruner_handle.await.unwrap().unwrap();
 }
 
+#[test(tokio::test)]
+async fn pruner_exports_data_before_hard_pruning() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    insert_l2_blocks(&mut conn, 10, 2).await;
+
+    let object_store = MockObjectStore::arc();
+    let pruner = DbPruner::with_conditions(
+        DbPrunerConfig {
+            removal_delay: Duration::ZERO,
+            pruned_batch_chunk_size: 3,
+            minimum_l1_batch_age: Duration::ZERO,
+            retention_rules: RetentionRules::default(),
+        },
+        pool.clone(),
+        vec![], //No checks, so every batch is prunable
+    )
+    .with_exporter(PrunedDataExporter::new(object_store.clone()));
+
+    let (_stop_sender, mut stop_receiver) = watch::channel(false);
+    for (first_l1_batch, last_l1_batch, first_l2_block, last_l2_block) in
+        [(0, 3, 0, 7), (4, 6, 8, 13)]
+    {
+        let outcome = pruner
+            .run_single_iteration(&mut stop_receiver)
+            .await
+            .unwrap();
+        assert_matches!(outcome, PruningIterationOutcome::Pruned);
+
+        let chunk = ExportedChunkKey {
+            first_l1_batch: L1BatchNumber(first_l1_batch),
+            last_l1_batch: L1BatchNumber(last_l1_batch),
+        };
+        let manifest: ExportManifest = object_store.get(chunk).await.unwrap();
+        assert_eq!(manifest.first_l2_block, L2BlockNumber(first_l2_block));
+        assert_eq!(manifest.last_l2_block, L2BlockNumber(last_l2_block));
+        assert_eq!(manifest.files.len(), 5);
+
+        let blocks_info = manifest
+            .files
+            .iter()
+            .find(|file| file.kind == ExportedDataKind::Blocks)
+            .unwrap();
+        let expected_block_count = (last_l2_block - first_l2_block + 1) as usize;
+        assert_eq!(blocks_info.record_count, expected_block_count);
+
+        let blocks: ExportedDataFile<api::Block<H256>> = object_store
+            .get(ExportedDataFileKey {
+                chunk,
+                kind: ExportedDataKind::Blocks,
+            })
+            .await
+            .unwrap();
+        let block_numbers: Vec<_> = blocks
+            .records
+            .iter()
+            .map(|block| block.number.as_u32())
+            .collect();
+        assert_eq!(
+            block_numbers,
+            (first_l2_block..=last_l2_block).collect::<Vec<_>>()
+        );
+    }
+
+    assert_eq!(
+        test_pruning_info(6, 13),
+        conn.pruning_dal().get_pruning_info().await.unwrap()
+    );
+}
+
 #[tokio::test]
 async fn pruning_iteration_timely_shuts_down() {
     let pool = ConnectionPool::<Core>::test_pool().await;
@@ -541,6 +619,7 @@ async fn pruning_iteration_timely_shuts_down() {
             removal_delay: Duration::MAX, // intentionally chosen so that pruning iterations stuck
             pruned_batch_chunk_size: 3,
             minimum_l1_batch_age: Duration::ZERO,
+            retention_rules: RetentionRules::default(),
         },
         pool.clone(),
         vec![], //No checks, so every batch is prunable
@@ -570,6 +649,7 @@ async fn pruner_timely_shuts_down() {
             removal_delay: Duration::MAX, // intentionally chosen so that pruning iterations stuck
             pruned_batch_chunk_size: 3,
             minimum_l1_batch_age: Duration::ZERO,
+            retention_rules: RetentionRules::default(),
         },
         pool.clone(),
         vec![], //No checks, so every batch is prunable
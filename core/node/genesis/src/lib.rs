@@ -188,6 +188,54 @@ pub fn mock_genesis_config() -> GenesisConfig {
     }
 }
 
+/// Storage state after the genesis L1 batch. Used to seed storage reconstruction from L1 data.
+#[derive(Debug)]
+pub struct GenesisStorageState {
+    /// Storage writes performed in genesis ordered by their enumeration indices; i.e., the write at position `i`
+    /// has enumeration index `i + 1`.
+    pub storage_writes: Vec<(StorageKey, H256)>,
+    /// Factory deps deployed in genesis (incl. base system contracts) keyed by the bytecode hash.
+    pub factory_deps: HashMap<H256, Vec<u8>>,
+}
+
+/// Computes the storage state after the genesis L1 batch for the provided params. Custom genesis states are not supported.
+pub fn genesis_storage_state(genesis_params: &GenesisParams) -> GenesisStorageState {
+    let storage_logs = get_storage_logs(&genesis_params.system_contracts);
+    let storage_writes = get_deduped_log_queries(&storage_logs)
+        .into_iter()
+        .filter(|log_query| log_query.rw_flag)
+        .map(|log| {
+            let key = StorageKey::new(AccountTreeId::new(log.address), u256_to_h256(log.key));
+            (key, u256_to_h256(log.written_value))
+        })
+        .collect();
+
+    let base_system_contracts = &genesis_params.base_system_contracts;
+    let base_factory_deps = [
+        &base_system_contracts.bootloader,
+        &base_system_contracts.default_aa,
+    ]
+    .into_iter()
+    .chain(base_system_contracts.evm_emulator.as_ref())
+    .map(|contract| (contract.hash, contract.code.clone()));
+    let factory_deps = genesis_params
+        .system_contracts
+        .iter()
+        .map(|contract| {
+            (
+                BytecodeHash::for_bytecode(&contract.bytecode).value(),
+                contract.bytecode.clone(),
+            )
+        })
+        .chain(base_factory_deps)
+        .collect();
+
+    GenesisStorageState {
+        storage_writes,
+        factory_deps,
+    }
+}
+
 pub fn make_genesis_batch_params(
     deduped_log_queries: Vec<LogQuery>,
     base_system_contract_hashes: BaseSystemContractsHashes,
@@ -64,3 +64,43 @@ async fn running_genesis_with_non_latest_protocol_version() {
     insert_genesis_batch(&mut conn, &params).await.unwrap();
     assert!(!conn.blocks_dal().is_genesis_needed().await.unwrap());
 }
+
+#[tokio::test]
+async fn genesis_storage_state_matches_inserted_genesis() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    let params = GenesisParams::mock();
+    let genesis_batch_params = insert_genesis_batch(&mut conn, &params).await.unwrap();
+
+    let state = genesis_storage_state(&params);
+    let tree_instructions: Vec<_> = state
+        .storage_writes
+        .iter()
+        .enumerate()
+        .map(|(i, (key, value))| {
+            TreeInstruction::write(key.hashed_key_u256(), i as u64 + 1, *value)
+        })
+        .collect();
+    let metadata = ZkSyncTree::process_genesis_batch(&tree_instructions);
+    assert_eq!(metadata.root_hash, genesis_batch_params.root_hash);
+
+    let hashed_keys: Vec<_> = state
+        .storage_writes
+        .iter()
+        .map(|(key, _)| key.hashed_key())
+        .collect();
+    let indices = conn
+        .storage_logs_dal()
+        .get_l1_batches_and_indices_for_initial_writes(&hashed_keys)
+        .await
+        .unwrap();
+    for (i, hashed_key) in hashed_keys.iter().enumerate() {
+        assert_eq!(indices[hashed_key], (L1BatchNumber(0), i as u64 + 1));
+    }
+
+    let factory_deps = conn
+        .factory_deps_dal()
+        .dump_all_factory_deps_for_tests()
+        .await;
+    assert_eq!(factory_deps, state.factory_deps);
+}
@@ -0,0 +1,311 @@
+//! Fetching and decoding of L1 batch commitments from L1.
+
+use std::collections::BTreeMap;
+
+use anyhow::Context as _;
+use zksync_contracts::{hyperchain_contract, POST_SHARED_BRIDGE_COMMIT_FUNCTION};
+use zksync_eth_client::{
+    clients::{DynClient, L1},
+    CallFunctionArgs, EthInterface,
+};
+use zksync_l1_contract_interface::i_executor::structures::{
+    CommitBatchInfo, StoredBatchInfo, PUBDATA_SOURCE_BLOBS, PUBDATA_SOURCE_CALLDATA,
+    SUPPORTED_ENCODING_VERSION,
+};
+use zksync_types::{
+    ethabi::{self, ParamType, Token},
+    web3::{keccak256, BlockNumber, FilterBuilder},
+    Address, L1BatchNumber, ProtocolVersionId, H256, U256,
+};
+
+use crate::pubdata::Pubdata;
+
+/// Length of the header prepended to operator DA input for rollups after the gateway upgrade, not counting blob hashes:
+/// `state_diff_hash (32 bytes) || pubdata_hash (32 bytes) || blob_count (1 byte)`.
+const POST_GATEWAY_DA_HEADER_LEN: usize = 65;
+
+/// L1 batch data extracted from a commit transaction on L1.
+#[derive(Debug, Clone)]
+pub(crate) struct CommittedBatch {
+    pub number: L1BatchNumber,
+    pub timestamp: u64,
+    /// Next enumeration index after the batch (i.e., the number of leaves in the Merkle tree + 1).
+    pub index_repeated_storage_changes: u64,
+    pub new_state_root: H256,
+    pub pubdata: Pubdata,
+}
+
+/// Layout of the commit transaction calldata.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CommitLayout {
+    /// `commitBatchesSharedBridge(uint256, StoredBatchInfo, CommitBatchInfo[])`.
+    PreGateway,
+    /// `commitBatchesSharedBridge(uint256, uint256, uint256, bytes)` with ABI-encoded batches in the last arg.
+    PostGateway,
+}
+
+/// Fetches commit transactions for the L1 diamond proxy contract and extracts pubdata from them.
+#[derive(Debug)]
+pub(crate) struct L1CommitFetcher {
+    client: Box<DynClient<L1>>,
+    diamond_proxy_addr: Address,
+    contract: ethabi::Contract,
+    logs_block_range: u64,
+}
+
+impl L1CommitFetcher {
+    pub fn new(
+        client: Box<DynClient<L1>>,
+        diamond_proxy_addr: Address,
+        logs_block_range: u64,
+    ) -> Self {
+        Self {
+            client: client.for_component("l1_recovery"),
+            diamond_proxy_addr,
+            contract: hyperchain_contract(),
+            logs_block_range,
+        }
+    }
+
+    /// Returns the number of the last executed L1 batch. Executed batches cannot be reverted, so it's safe to recover from them.
+    pub async fn last_executed_batch(&self) -> anyhow::Result<L1BatchNumber> {
+        let count: U256 = CallFunctionArgs::new("getTotalBatchesExecuted", ())
+            .for_contract(self.diamond_proxy_addr, &self.contract)
+            .call(&self.client)
+            .await
+            .context("failed calling `getTotalBatchesExecuted`")?;
+        let count = u32::try_from(count)
+            .map_err(|err| anyhow::anyhow!("executed L1 batch count overflow: {err}"))?;
+        Ok(L1BatchNumber(count))
+    }
+
+    /// Returns the current protocol version of the chain.
+    pub async fn protocol_version(&self) -> anyhow::Result<ProtocolVersionId> {
+        let packed_version: U256 = CallFunctionArgs::new("getProtocolVersion", ())
+            .for_contract(self.diamond_proxy_addr, &self.contract)
+            .call(&self.client)
+            .await
+            .context("failed calling `getProtocolVersion`")?;
+        ProtocolVersionId::try_from_packed_semver(packed_version)
+            .map_err(|err| anyhow::anyhow!("invalid protocol version: {err}"))
+    }
+
+    /// Returns hashes of commit transactions for L1 batches `1..=last_batch` based on `BlockCommit` events
+    /// emitted by the diamond proxy starting from `from_block`. If a batch was committed multiple times (e.g., because
+    /// it was reverted in between), the latest commitment wins.
+    pub async fn commit_tx_hashes(
+        &self,
+        from_block: u64,
+        last_batch: L1BatchNumber,
+    ) -> anyhow::Result<BTreeMap<L1BatchNumber, H256>> {
+        let event = self
+            .contract
+            .event("BlockCommit")
+            .context("`BlockCommit` event not found for ZKsync L1 contract")?;
+        let latest_block = self.client.block_number().await?.as_u64();
+
+        let mut tx_hashes = BTreeMap::new();
+        let mut range_start = from_block;
+        while range_start <= latest_block {
+            let range_end = (range_start + self.logs_block_range - 1).min(latest_block);
+            let filter = FilterBuilder::default()
+                .address(vec![self.diamond_proxy_addr])
+                .topics(Some(vec![event.signature()]), None, None, None)
+                .from_block(BlockNumber::Number(range_start.into()))
+                .to_block(BlockNumber::Number(range_end.into()))
+                .build();
+            let logs = self.client.logs(&filter).await?;
+            tracing::debug!(
+                "Fetched {} `BlockCommit` events for L1 blocks {range_start}..={range_end}",
+                logs.len()
+            );
+
+            for log in logs {
+                let batch_number = log
+                    .topics
+                    .get(1)
+                    .context("`BlockCommit` event doesn't have batch number topic")?;
+                let batch_number = U256::from_big_endian(batch_number.as_bytes());
+                let batch_number = u32::try_from(batch_number)
+                    .map_err(|err| anyhow::anyhow!("L1 batch number overflow: {err}"))?;
+                let tx_hash = log
+                    .transaction_hash
+                    .context("`BlockCommit` event doesn't have transaction hash")?;
+                tx_hashes.insert(L1BatchNumber(batch_number), tx_hash);
+            }
+            range_start = range_end + 1;
+        }
+
+        tx_hashes.retain(|&number, _| number > L1BatchNumber(0) && number <= last_batch);
+        let expected_count = last_batch.0 as usize;
+        anyhow::ensure!(
+            tx_hashes.len() == expected_count,
+            "found commit transactions only for {} of {expected_count} executed L1 batches starting from L1 block #{from_block}; \
+             is the starting L1 block correct?",
+            tx_hashes.len()
+        );
+        Ok(tx_hashes)
+    }
+
+    /// Fetches the commit transaction with the specified hash and extracts all L1 batches committed in it.
+    pub async fn fetch_commit(&self, tx_hash: H256) -> anyhow::Result<Vec<CommittedBatch>> {
+        let tx = self
+            .client
+            .get_tx(tx_hash)
+            .await?
+            .with_context(|| format!("commit transaction {tx_hash:?} not found on L1"))?;
+        self.decode_commit_calldata(&tx.input.0)
+            .with_context(|| format!("failed decoding commit transaction {tx_hash:?}"))
+    }
+
+    fn decode_commit_calldata(&self, calldata: &[u8]) -> anyhow::Result<Vec<CommittedBatch>> {
+        let post_gateway_function = self
+            .contract
+            .function("commitBatchesSharedBridge")
+            .context("L1 contract does not have `commitBatchesSharedBridge` function")?;
+        let selector = calldata.get(..4).context("commit calldata is too short")?;
+        let (function, layout) = if selector == POST_SHARED_BRIDGE_COMMIT_FUNCTION.short_signature()
+        {
+            (
+                &*POST_SHARED_BRIDGE_COMMIT_FUNCTION,
+                CommitLayout::PreGateway,
+            )
+        } else if selector == post_gateway_function.short_signature() {
+            (post_gateway_function, CommitLayout::PostGateway)
+        } else {
+            anyhow::bail!(
+                "unsupported commit function selector {selector:?}; only shared bridge commitments are supported"
+            );
+        };
+
+        let mut tokens = function
+            .decode_input(&calldata[4..])
+            .context("failed decoding calldata for L1 commit function")?;
+        let last_token = tokens
+            .pop()
+            .context("unexpected signature for L1 commit function")?;
+        let commitments = match layout {
+            CommitLayout::PreGateway => last_token
+                .into_array()
+                .context("unexpected signature for L1 commit function")?,
+            CommitLayout::PostGateway => {
+                let bytes = last_token
+                    .into_bytes()
+                    .context("unexpected signature for L1 commit function")?;
+                let (&version, encoded_data) =
+                    bytes.split_first().context("commit data is empty")?;
+                anyhow::ensure!(
+                    version == SUPPORTED_ENCODING_VERSION,
+                    "unsupported commit data encoding version: {version}"
+                );
+                let mut decoded = ethabi::decode(
+                    &[
+                        StoredBatchInfo::schema(),
+                        ParamType::Array(Box::new(CommitBatchInfo::post_gateway_schema())),
+                    ],
+                    encoded_data,
+                )
+                .context("failed decoding commit data")?;
+                decoded
+                    .pop()
+                    .and_then(Token::into_array)
+                    .context("unexpected commit data format")?
+            }
+        };
+
+        commitments
+            .into_iter()
+            .map(|commitment| decode_commitment(commitment, layout))
+            .collect()
+    }
+}
+
+fn decode_commitment(commitment: Token, layout: CommitLayout) -> anyhow::Result<CommittedBatch> {
+    let Token::Tuple(tokens) = commitment else {
+        anyhow::bail!("unexpected L1 batch commitment shape: {commitment:?}");
+    };
+    anyhow::ensure!(
+        tokens.len() == 10,
+        "unexpected number of tokens in L1 batch commitment: {}",
+        tokens.len()
+    );
+    let uint = |idx: usize, name: &str| -> anyhow::Result<u64> {
+        let value = tokens[idx]
+            .clone()
+            .into_uint()
+            .with_context(|| format!("`{name}` is not a uint"))?;
+        u64::try_from(value).map_err(|err| anyhow::anyhow!("`{name}` overflow: {err}"))
+    };
+
+    let number = uint(0, "batchNumber")?;
+    let number = L1BatchNumber(
+        u32::try_from(number).map_err(|err| anyhow::anyhow!("L1 batch number overflow: {err}"))?,
+    );
+    let timestamp = uint(1, "timestamp")?;
+    let index_repeated_storage_changes = uint(2, "indexRepeatedStorageChanges")?;
+    let new_state_root = tokens[3]
+        .clone()
+        .into_fixed_bytes()
+        .filter(|bytes| bytes.len() == 32)
+        .context("`newStateRoot` is not bytes32")?;
+    let new_state_root = H256::from_slice(&new_state_root);
+    let da_input = tokens[9]
+        .clone()
+        .into_bytes()
+        .context("pubdata commitments are not bytes")?;
+
+    let pubdata = extract_pubdata(&da_input, layout)
+        .with_context(|| format!("failed extracting pubdata for L1 batch #{number}"))?;
+    Ok(CommittedBatch {
+        number,
+        timestamp,
+        index_repeated_storage_changes,
+        new_state_root,
+        pubdata,
+    })
+}
+
+/// Extracts pubdata from the last field of `CommitBatchInfo` (`pubdataCommitments` before the gateway upgrade,
+/// `operatorDAInput` after it).
+fn extract_pubdata(da_input: &[u8], layout: CommitLayout) -> anyhow::Result<Pubdata> {
+    let (expected_pubdata_hash, da_input) = match layout {
+        CommitLayout::PreGateway => (None, da_input),
+        CommitLayout::PostGateway => {
+            anyhow::ensure!(
+                da_input.len() >= POST_GATEWAY_DA_HEADER_LEN,
+                "DA input is too short ({} bytes); L1 recovery is not supported for validiums",
+                da_input.len()
+            );
+            let pubdata_hash = H256::from_slice(&da_input[32..64]);
+            let blob_count = usize::from(da_input[64]);
+            let header_len = POST_GATEWAY_DA_HEADER_LEN + 32 * blob_count;
+            let da_input = da_input
+                .get(header_len..)
+                .context("DA input is shorter than its header")?;
+            (Some(pubdata_hash), da_input)
+        }
+    };
+
+    let (&source, data) = da_input
+        .split_first()
+        .context("pubdata source is missing; L1 recovery is not supported for validiums")?;
+    match source {
+        PUBDATA_SOURCE_CALLDATA => { /* supported */ }
+        PUBDATA_SOURCE_BLOBS => {
+            anyhow::bail!(
+                "pubdata is published in EIP-4844 blobs; L1 recovery only supports the calldata pubdata sending mode"
+            )
+        }
+        _ => anyhow::bail!("unsupported pubdata source: {source}"),
+    }
+
+    let (pubdata, pubdata_len) = Pubdata::parse(data)?;
+    if let Some(expected_hash) = expected_pubdata_hash {
+        let actual_hash = H256(keccak256(&data[..pubdata_len]));
+        anyhow::ensure!(
+            actual_hash == expected_hash,
+            "pubdata hash mismatch: expected {expected_hash:?} from the DA header, got {actual_hash:?}"
+        );
+    }
+    Ok(pubdata)
+}
@@ -0,0 +1,322 @@
+//! Recovery of node storage from pubdata published on L1.
+//!
+//! Unlike genesis sync or snapshot recovery, this recovery mode doesn't trust the main node or consensus peers for
+//! the storage state. Instead, the state is reconstructed by replaying state diffs from `commitBatches` transactions
+//! on top of the genesis state, and the Merkle tree root hash is checked against the state root committed on L1
+//! after each L1 batch. Only executed L1 batches are recovered, since they cannot be reverted.
+//!
+//! After the state is reconstructed, it's persisted to Postgres in the same layout as for snapshot recovery
+//! (storage logs, initial writes and factory deps at the last recovered L1 batch + a snapshot recovery status),
+//! so that other node components don't need to distinguish between these recovery modes.
+//! Persisting is atomic; if recovery is interrupted, it will start from scratch on the next node run.
+//!
+//! Only rollups publishing pubdata in calldata are supported. Recovery fails early for validiums (pubdata isn't
+//! published on L1) and for chains that commit pubdata in EIP-4844 blobs, since blobs aren't retrievable
+//! from the L1 execution client.
+
+use std::time::Instant;
+
+use anyhow::Context as _;
+use serde::Serialize;
+use tokio::sync::watch;
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_eth_client::clients::{DynClient, L1};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_node_genesis::{genesis_storage_state, GenesisParams};
+use zksync_types::{
+    commitment::L1BatchCommitmentMode, snapshots::SnapshotRecoveryStatus, Address, L1BatchNumber,
+    L2BlockNumber, H256,
+};
+
+use crate::{
+    commits::{CommittedBatch, L1CommitFetcher},
+    state::ReconstructedState,
+};
+
+mod commits;
+mod pubdata;
+mod state;
+#[cfg(test)]
+mod tests;
+
+/// Configuration for [`L1RecoveryTask`].
+#[derive(Debug, Clone)]
+pub struct L1RecoveryConfig {
+    /// Address of the diamond proxy contract of the chain on L1.
+    pub diamond_proxy_addr: Address,
+    /// L1 block to start searching for commit transactions from. Should be set to a block before the first
+    /// L1 batch commitment (e.g., the block the diamond proxy was deployed in).
+    pub from_l1_block: u64,
+    /// Maximum number of L1 blocks queried for commit events at once.
+    pub logs_block_range: u64,
+    /// Commitment mode of the chain. Only [`L1BatchCommitmentMode::Rollup`] is supported.
+    pub commitment_mode: L1BatchCommitmentMode,
+}
+
+impl L1RecoveryConfig {
+    pub fn new(diamond_proxy_addr: Address) -> Self {
+        Self {
+            diamond_proxy_addr,
+            from_l1_block: 0,
+            logs_block_range: 50_000,
+            commitment_mode: L1BatchCommitmentMode::Rollup,
+        }
+    }
+}
+
+/// Stats returned by [`L1RecoveryTask::run()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct L1RecoveryStats {
+    /// Last L1 batch recovered from L1.
+    pub l1_batch_number: L1BatchNumber,
+    /// Root hash of the recovered state.
+    pub root_hash: H256,
+    /// Number of storage logs in the recovered state.
+    pub storage_log_count: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct L1RecoveryHealthDetails {
+    last_recovered_l1_batch: L1BatchNumber,
+    last_l1_batch_to_recover: L1BatchNumber,
+}
+
+/// Task recovering node storage from pubdata published on L1. See the crate docs for details.
+#[derive(Debug)]
+pub struct L1RecoveryTask {
+    config: L1RecoveryConfig,
+    fetcher: L1CommitFetcher,
+    pool: ConnectionPool<Core>,
+    genesis_params: GenesisParams,
+    health_updater: HealthUpdater,
+}
+
+impl L1RecoveryTask {
+    pub fn new(
+        config: L1RecoveryConfig,
+        l1_client: Box<DynClient<L1>>,
+        pool: ConnectionPool<Core>,
+        genesis_params: GenesisParams,
+    ) -> Self {
+        let fetcher = L1CommitFetcher::new(
+            l1_client,
+            config.diamond_proxy_addr,
+            config.logs_block_range,
+        );
+        let (_, health_updater) = ReactiveHealthCheck::new("l1_recovery");
+        Self {
+            config,
+            fetcher,
+            pool,
+            genesis_params,
+            health_updater,
+        }
+    }
+
+    /// Returns a health check for this task.
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    /// Checks whether storage was already recovered (from L1 or from a snapshot).
+    pub async fn is_recovery_completed(pool: &ConnectionPool<Core>) -> anyhow::Result<bool> {
+        let mut storage = pool.connection_tagged("l1_recovery").await?;
+        let status = storage
+            .snapshot_recovery_dal()
+            .get_applied_snapshot_status()
+            .await?;
+        Ok(status.is_some())
+    }
+
+    /// Runs the recovery. Returns `None` if recovery was already completed or if a stop signal was received.
+    pub async fn run(
+        self,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<Option<L1RecoveryStats>> {
+        if Self::is_recovery_completed(&self.pool).await? {
+            tracing::info!("Storage is already recovered; skipping recovery from L1");
+            self.health_updater
+                .update(Health::from(HealthStatus::Ready));
+            return Ok(None);
+        }
+        anyhow::ensure!(
+            self.config.commitment_mode == L1BatchCommitmentMode::Rollup,
+            "L1 recovery is not supported for the {:?} commitment mode: pubdata isn't published on L1",
+            self.config.commitment_mode
+        );
+        self.check_storage_is_empty().await?;
+        self.health_updater
+            .update(Health::from(HealthStatus::Affected));
+
+        let last_batch = self.fetcher.last_executed_batch().await?;
+        anyhow::ensure!(
+            last_batch > L1BatchNumber(0),
+            "there are no executed L1 batches on L1; nothing to recover"
+        );
+        let commit_tx_hashes = self
+            .fetcher
+            .commit_tx_hashes(self.config.from_l1_block, last_batch)
+            .await?;
+        tracing::info!(
+            "Recovering storage from L1 up to L1 batch #{last_batch} using {} commit transactions",
+            commit_tx_hashes.len()
+        );
+        // Check the latest commitment before replaying the entire history, so that we fail early
+        // if the chain has switched to an unsupported pubdata sending mode (e.g., blobs).
+        let last_tx_hash = commit_tx_hashes
+            .get(&last_batch)
+            .with_context(|| format!("no commit transaction for L1 batch #{last_batch}"))?;
+        self.fetcher
+            .fetch_commit(*last_tx_hash)
+            .await
+            .with_context(|| format!("cannot recover L1 batch #{last_batch} from L1"))?;
+
+        let started_at = Instant::now();
+        let genesis_state = genesis_storage_state(&self.genesis_params);
+        let mut state = ReconstructedState::genesis(genesis_state)?;
+        if let Some(expected_root_hash) = self.genesis_params.config().genesis_root_hash {
+            anyhow::ensure!(
+                state.root_hash() == expected_root_hash,
+                "genesis root hash mismatch: computed {:?}, expected {expected_root_hash:?}",
+                state.root_hash()
+            );
+        }
+
+        let mut pending_batches = Vec::<CommittedBatch>::new();
+        let mut last_batch_timestamp = 0;
+        for (&batch_number, &tx_hash) in &commit_tx_hashes {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received; L1 recovery is interrupted");
+                return Ok(None);
+            }
+
+            if pending_batches.first().map(|batch| batch.number) != Some(batch_number) {
+                let batches = self.fetcher.fetch_commit(tx_hash).await?;
+                pending_batches = batches
+                    .into_iter()
+                    .filter(|batch| batch.number >= batch_number)
+                    .collect();
+            }
+            anyhow::ensure!(
+                !pending_batches.is_empty() && pending_batches[0].number == batch_number,
+                "commit transaction {tx_hash:?} doesn't commit L1 batch #{batch_number}"
+            );
+            let batch = pending_batches.remove(0);
+            // Subsequent batches are only valid if committed in the same transaction as reported by L1 events.
+            pending_batches.retain(|next| commit_tx_hashes.get(&next.number) == Some(&tx_hash));
+
+            state
+                .apply_batch(&batch)
+                .with_context(|| format!("failed applying L1 batch #{batch_number}"))?;
+            last_batch_timestamp = batch.timestamp;
+            tracing::debug!(
+                "Applied L1 batch #{batch_number}, new root hash: {:?}",
+                state.root_hash()
+            );
+            self.health_updater
+                .update(Health::from(HealthStatus::Affected).with_details(
+                    L1RecoveryHealthDetails {
+                        last_recovered_l1_batch: batch_number,
+                        last_l1_batch_to_recover: last_batch,
+                    },
+                ));
+            if batch_number == last_batch {
+                break;
+            }
+        }
+        tracing::info!(
+            "Reconstructed state up to L1 batch #{} in {:?}",
+            state.last_l1_batch(),
+            started_at.elapsed()
+        );
+
+        let stats = self.persist(&state, last_batch_timestamp).await?;
+        self.health_updater
+            .update(Health::from(HealthStatus::Ready));
+        Ok(Some(stats))
+    }
+
+    async fn check_storage_is_empty(&self) -> anyhow::Result<()> {
+        let mut storage = self.pool.connection_tagged("l1_recovery").await?;
+        anyhow::ensure!(
+            storage.blocks_dal().is_genesis_needed().await?,
+            "node contains L1 batches; recovery from L1 is unsafe"
+        );
+        let storage_logs_count = storage
+            .storage_logs_dal()
+            .get_storage_logs_row_count(L2BlockNumber(u32::MAX))
+            .await?;
+        anyhow::ensure!(
+            storage_logs_count == 0,
+            "storage_logs table has {storage_logs_count} rows; recovery from L1 is unsafe"
+        );
+        Ok(())
+    }
+
+    /// Persists the reconstructed state to Postgres in the snapshot recovery layout.
+    async fn persist(
+        &self,
+        state: &ReconstructedState,
+        l1_batch_timestamp: u64,
+    ) -> anyhow::Result<L1RecoveryStats> {
+        let l2_block = state.last_l2_block();
+        anyhow::ensure!(
+            l2_block.number > L2BlockNumber(0),
+            "reconstructed state doesn't contain L2 block info"
+        );
+        // The protocol version is not committed per L1 batch, so we use the current version from L1.
+        // This is fine since the state keeper will get the actual protocol version from the first synced L2 block.
+        let protocol_version = self.fetcher.protocol_version().await?;
+        let status = SnapshotRecoveryStatus {
+            l1_batch_number: state.last_l1_batch(),
+            l1_batch_root_hash: state.root_hash(),
+            l1_batch_timestamp,
+            l2_block_number: l2_block.number,
+            l2_block_hash: l2_block.hash,
+            l2_block_timestamp: l2_block.timestamp,
+            protocol_version,
+            storage_logs_chunks_processed: vec![true],
+        };
+        tracing::info!("Persisting state recovered from L1 to Postgres: {status:?}");
+
+        let storage_logs = state.storage_logs();
+        let mut storage = self.pool.connection_tagged("l1_recovery").await?;
+        let mut transaction = storage.start_transaction().await?;
+        transaction
+            .factory_deps_dal()
+            .insert_factory_deps(l2_block.number, state.factory_deps())
+            .await?;
+        transaction
+            .storage_logs_dedup_dal()
+            .insert_initial_writes_from_snapshot(&storage_logs)
+            .await?;
+        transaction
+            .storage_logs_dal()
+            .insert_storage_logs_from_snapshot(l2_block.number, &storage_logs)
+            .await?;
+        transaction
+            .snapshot_recovery_dal()
+            .insert_initial_recovery_status(&status)
+            .await?;
+        // Same as for snapshot recovery, insert artificial entries into the pruning log so that it matches the recovery status.
+        transaction
+            .pruning_dal()
+            .insert_soft_pruning_log(status.l1_batch_number, status.l2_block_number)
+            .await?;
+        transaction
+            .pruning_dal()
+            .insert_hard_pruning_log(
+                status.l1_batch_number,
+                status.l2_block_number,
+                status.l1_batch_root_hash,
+            )
+            .await?;
+        transaction.commit().await?;
+
+        Ok(L1RecoveryStats {
+            l1_batch_number: status.l1_batch_number,
+            root_hash: status.l1_batch_root_hash,
+            storage_log_count: storage_logs.len(),
+        })
+    }
+}
@@ -0,0 +1,180 @@
+//! Parsing of rollup pubdata published on L1.
+
+use anyhow::Context as _;
+use zksync_types::{
+    l2_to_l1_log::{L2ToL1Log, UserL2ToL1Log},
+    writes::CompressedStateDiffs,
+    Address, H256,
+};
+
+/// Size of a serialized L2-to-L1 log in pubdata.
+const L2_TO_L1_LOG_SIZE: usize = 88;
+
+/// Rollup pubdata for a single L1 batch in the format produced by `RollupPubdataBuilder` from the `zksync_multivm` crate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pubdata {
+    pub user_logs: Vec<UserL2ToL1Log>,
+    pub l2_to_l1_messages: Vec<Vec<u8>>,
+    pub published_bytecodes: Vec<Vec<u8>>,
+    pub state_diffs: CompressedStateDiffs,
+}
+
+impl Pubdata {
+    /// Parses pubdata from the start of `bytes`. Returns the parsed pubdata and the number of consumed bytes.
+    pub fn parse(bytes: &[u8]) -> anyhow::Result<(Self, usize)> {
+        let mut reader = Reader { bytes, offset: 0 };
+
+        let log_count = reader.read_u32().context("failed reading user log count")?;
+        let user_logs = (0..log_count)
+            .map(|i| {
+                let raw_log = reader
+                    .read_bytes(L2_TO_L1_LOG_SIZE)
+                    .with_context(|| format!("failed reading user log #{i}"))?;
+                Ok(UserL2ToL1Log(parse_l2_to_l1_log(raw_log)))
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        let l2_to_l1_messages = reader
+            .read_byte_arrays()
+            .context("failed reading L2-to-L1 messages")?;
+        let published_bytecodes = reader
+            .read_byte_arrays()
+            .context("failed reading published bytecodes")?;
+
+        let (state_diffs, state_diffs_len) = CompressedStateDiffs::parse(reader.remaining())
+            .context("failed parsing compressed state diffs")?;
+        let pubdata = Self {
+            user_logs,
+            l2_to_l1_messages,
+            published_bytecodes,
+            state_diffs,
+        };
+        Ok((pubdata, reader.offset + state_diffs_len))
+    }
+}
+
+fn parse_l2_to_l1_log(bytes: &[u8]) -> L2ToL1Log {
+    L2ToL1Log {
+        shard_id: bytes[0],
+        is_service: bytes[1] != 0,
+        tx_number_in_block: u16::from_be_bytes([bytes[2], bytes[3]]),
+        sender: Address::from_slice(&bytes[4..24]),
+        key: H256::from_slice(&bytes[24..56]),
+        value: H256::from_slice(&bytes[56..88]),
+    }
+}
+
+#[derive(Debug)]
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn remaining(&self) -> &'a [u8] {
+        &self.bytes[self.offset..]
+    }
+
+    fn read_bytes(&mut self, len: usize) -> anyhow::Result<&'a [u8]> {
+        let bytes = self.remaining().get(..len).with_context(|| {
+            format!(
+                "pubdata is truncated: expected {len} bytes at offset {}, got {}",
+                self.offset,
+                self.remaining().len()
+            )
+        })?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn read_u32(&mut self) -> anyhow::Result<u32> {
+        let bytes = self.read_bytes(4)?;
+        Ok(u32::from_be_bytes(bytes.try_into().unwrap()))
+    }
+
+    /// Reads a list of byte arrays, each prefixed with its length, itself prefixed with the number of arrays.
+    fn read_byte_arrays(&mut self) -> anyhow::Result<Vec<Vec<u8>>> {
+        let count = self.read_u32()?;
+        (0..count)
+            .map(|_| {
+                let len = self.read_u32()?;
+                Ok(self.read_bytes(len as usize)?.to_vec())
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_node_test_utils::{create_l1_batch, create_l1_batch_metadata};
+    use zksync_types::{
+        commitment::{L1BatchMetadata, L1BatchWithMetadata},
+        writes::{compress_state_diffs, StateDiffRecord},
+        U256,
+    };
+
+    use super::*;
+
+    #[test]
+    fn parsing_pubdata() {
+        let state_diffs = vec![
+            StateDiffRecord {
+                address: Address::repeat_byte(1),
+                key: U256::from(1),
+                derived_key: [1; 32],
+                enumeration_index: 0,
+                initial_value: U256::zero(),
+                final_value: U256::from(100),
+            },
+            StateDiffRecord {
+                address: Address::repeat_byte(2),
+                key: U256::from(2),
+                derived_key: [2; 32],
+                enumeration_index: 5,
+                initial_value: U256::from(100),
+                final_value: U256::from(50),
+            },
+        ];
+        let user_log = UserL2ToL1Log(L2ToL1Log {
+            shard_id: 0,
+            is_service: true,
+            tx_number_in_block: 3,
+            sender: Address::repeat_byte(0x11),
+            key: H256::repeat_byte(0x22),
+            value: H256::repeat_byte(0x33),
+        });
+        let mut header = create_l1_batch(1);
+        header.l2_to_l1_logs = vec![user_log.clone()];
+        header.l2_to_l1_messages = vec![b"message".to_vec()];
+        let l1_batch = L1BatchWithMetadata {
+            header,
+            metadata: L1BatchMetadata {
+                state_diffs_compressed: compress_state_diffs(state_diffs),
+                ..create_l1_batch_metadata(1)
+            },
+            raw_published_factory_deps: vec![vec![0xaa; 64], vec![0xbb; 32]],
+        };
+        let mut bytes = l1_batch.construct_pubdata();
+        let pubdata_len = bytes.len();
+        bytes.extend([0; 32]); // trailing data (e.g., a blob commitment) must not be consumed
+
+        let (pubdata, len) = Pubdata::parse(&bytes).unwrap();
+        assert_eq!(len, pubdata_len);
+        assert_eq!(pubdata.user_logs, [user_log]);
+        assert_eq!(pubdata.l2_to_l1_messages, [b"message".to_vec()]);
+        assert_eq!(
+            pubdata.published_bytecodes,
+            l1_batch.raw_published_factory_deps
+        );
+        assert_eq!(pubdata.state_diffs.initial_writes.len(), 1);
+        assert_eq!(
+            pubdata.state_diffs.initial_writes[0].0,
+            H256::repeat_byte(1)
+        );
+        assert_eq!(pubdata.state_diffs.repeated_writes.len(), 1);
+        assert_eq!(pubdata.state_diffs.repeated_writes[0].0, 5);
+
+        let err = Pubdata::parse(&bytes[..20]).unwrap_err();
+        assert!(format!("{err:#}").contains("truncated"), "{err:#}");
+    }
+}
@@ -0,0 +1,207 @@
+//! In-memory storage state reconstructed from L1 batch pubdata.
+
+use std::collections::HashMap;
+
+use anyhow::Context as _;
+use zksync_merkle_tree::{MerkleTree, PatchSet, TreeEntry};
+use zksync_node_genesis::GenesisStorageState;
+use zksync_types::{
+    block::unpack_block_info, bytecode::BytecodeHash, get_system_context_key, h256_to_u256,
+    snapshots::SnapshotStorageLog, u256_to_h256, web3::keccak256, L1BatchNumber, L2BlockNumber,
+    H256, SYSTEM_CONTEXT_CURRENT_L2_BLOCK_HASHES_POSITION,
+    SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION, SYSTEM_CONTEXT_CURRENT_TX_ROLLING_HASH_POSITION,
+    SYSTEM_CONTEXT_STORED_L2_BLOCK_HASHES, U256,
+};
+
+use crate::commits::CommittedBatch;
+
+#[derive(Debug, Clone, Copy)]
+struct StateEntry {
+    value: H256,
+    enumeration_index: u64,
+    l1_batch_number_of_initial_write: L1BatchNumber,
+}
+
+/// Last L2 block in the reconstructed state.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct L2BlockInfo {
+    pub number: L2BlockNumber,
+    pub timestamp: u64,
+    pub hash: H256,
+}
+
+/// Storage state reconstructed from L1 batch pubdata together with the corresponding Merkle tree.
+#[derive(Debug)]
+pub(crate) struct ReconstructedState {
+    tree: MerkleTree<PatchSet>,
+    /// Hashed keys ordered by enumeration index; the key at position `i` has enumeration index `i + 1`.
+    keys: Vec<H256>,
+    entries: HashMap<H256, StateEntry>,
+    factory_deps: HashMap<H256, Vec<u8>>,
+    last_l1_batch: L1BatchNumber,
+}
+
+impl ReconstructedState {
+    /// Creates the state after the genesis L1 batch.
+    pub fn genesis(genesis_state: GenesisStorageState) -> anyhow::Result<Self> {
+        let mut this = Self {
+            tree: MerkleTree::new(PatchSet::default())?,
+            keys: Vec::with_capacity(genesis_state.storage_writes.len()),
+            entries: HashMap::with_capacity(genesis_state.storage_writes.len()),
+            factory_deps: genesis_state.factory_deps,
+            last_l1_batch: L1BatchNumber(0),
+        };
+        let tree_entries = genesis_state
+            .storage_writes
+            .into_iter()
+            .map(|(key, value)| this.insert_new_key(key.hashed_key(), value, L1BatchNumber(0)))
+            .collect::<anyhow::Result<_>>()?;
+        this.tree.extend(tree_entries)?;
+        Ok(this)
+    }
+
+    pub fn root_hash(&self) -> H256 {
+        self.tree.latest_root_hash()
+    }
+
+    pub fn last_l1_batch(&self) -> L1BatchNumber {
+        self.last_l1_batch
+    }
+
+    fn insert_new_key(
+        &mut self,
+        hashed_key: H256,
+        value: H256,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<TreeEntry> {
+        self.keys.push(hashed_key);
+        let enumeration_index = self.keys.len() as u64;
+        let entry = StateEntry {
+            value,
+            enumeration_index,
+            l1_batch_number_of_initial_write: l1_batch_number,
+        };
+        let prev_entry = self.entries.insert(hashed_key, entry);
+        anyhow::ensure!(
+            prev_entry.is_none(),
+            "initial write for key {hashed_key:?} that is already present in storage"
+        );
+        Ok(tree_entry(hashed_key, enumeration_index, value))
+    }
+
+    /// Applies state diffs from the specified L1 batch and checks the resulting tree against the L1 commitment.
+    pub fn apply_batch(&mut self, batch: &CommittedBatch) -> anyhow::Result<()> {
+        let expected_number = self.last_l1_batch + 1;
+        anyhow::ensure!(
+            batch.number == expected_number,
+            "unexpected L1 batch: expected #{expected_number}, got #{}",
+            batch.number
+        );
+
+        let state_diffs = &batch.pubdata.state_diffs;
+        let mut tree_entries = Vec::with_capacity(
+            state_diffs.initial_writes.len() + state_diffs.repeated_writes.len(),
+        );
+        for &(hashed_key, value) in &state_diffs.initial_writes {
+            let value = u256_to_h256(value.apply(U256::zero()));
+            tree_entries.push(self.insert_new_key(hashed_key, value, batch.number)?);
+        }
+        for &(enumeration_index, value) in &state_diffs.repeated_writes {
+            let hashed_key = usize::try_from(enumeration_index)
+                .ok()
+                .and_then(|idx| self.keys.get(idx.checked_sub(1)?))
+                .copied()
+                .with_context(|| {
+                    format!(
+                        "repeated write refers to unknown enumeration index {enumeration_index}"
+                    )
+                })?;
+            let entry = self.entries.get_mut(&hashed_key).unwrap();
+            entry.value = u256_to_h256(value.apply(h256_to_u256(entry.value)));
+            tree_entries.push(tree_entry(hashed_key, enumeration_index, entry.value));
+        }
+
+        let output = self.tree.extend(tree_entries)?;
+        anyhow::ensure!(
+            output.root_hash == batch.new_state_root,
+            "root hash mismatch for L1 batch #{}: reconstructed {:?}, committed on L1 {:?}",
+            batch.number,
+            output.root_hash,
+            batch.new_state_root
+        );
+        anyhow::ensure!(
+            output.leaf_count + 1 == batch.index_repeated_storage_changes,
+            "enumeration index mismatch for L1 batch #{}: reconstructed {}, committed on L1 {}",
+            batch.number,
+            output.leaf_count + 1,
+            batch.index_repeated_storage_changes
+        );
+
+        self.factory_deps
+            .extend(batch.pubdata.published_bytecodes.iter().map(|bytecode| {
+                (
+                    BytecodeHash::for_bytecode(bytecode).value(),
+                    bytecode.clone(),
+                )
+            }));
+        self.last_l1_batch = batch.number;
+        Ok(())
+    }
+
+    fn system_context_value(&self, position: H256) -> H256 {
+        let hashed_key = get_system_context_key(position).hashed_key();
+        self.entries
+            .get(&hashed_key)
+            .map_or_else(H256::zero, |entry| entry.value)
+    }
+
+    /// Returns information about the last L2 block based on the system context contract storage.
+    pub fn last_l2_block(&self) -> L2BlockInfo {
+        let block_info = self.system_context_value(SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION);
+        let (number, timestamp) = unpack_block_info(h256_to_u256(block_info));
+        let number = L2BlockNumber(number as u32);
+
+        let prev_hash_position = h256_to_u256(SYSTEM_CONTEXT_CURRENT_L2_BLOCK_HASHES_POSITION)
+            + U256::from(number.0.saturating_sub(1) % SYSTEM_CONTEXT_STORED_L2_BLOCK_HASHES);
+        let prev_hash = self.system_context_value(u256_to_h256(prev_hash_position));
+        let txs_rolling_hash =
+            self.system_context_value(SYSTEM_CONTEXT_CURRENT_TX_ROLLING_HASH_POSITION);
+
+        // Same as the L2 block hash computed by the VM.
+        let mut digest = [0_u8; 128];
+        U256::from(number.0).to_big_endian(&mut digest[0..32]);
+        U256::from(timestamp).to_big_endian(&mut digest[32..64]);
+        digest[64..96].copy_from_slice(prev_hash.as_bytes());
+        digest[96..128].copy_from_slice(txs_rolling_hash.as_bytes());
+        L2BlockInfo {
+            number,
+            timestamp,
+            hash: H256(keccak256(&digest)),
+        }
+    }
+
+    /// Returns storage logs for the reconstructed state ordered by enumeration index.
+    pub fn storage_logs(&self) -> Vec<SnapshotStorageLog> {
+        self.keys
+            .iter()
+            .map(|hashed_key| {
+                let entry = &self.entries[hashed_key];
+                SnapshotStorageLog {
+                    key: *hashed_key,
+                    value: entry.value,
+                    l1_batch_number_of_initial_write: entry.l1_batch_number_of_initial_write,
+                    enumeration_index: entry.enumeration_index,
+                }
+            })
+            .collect()
+    }
+
+    pub fn factory_deps(&self) -> &HashMap<H256, Vec<u8>> {
+        &self.factory_deps
+    }
+}
+
+fn tree_entry(hashed_key: H256, enumeration_index: u64, value: H256) -> TreeEntry {
+    let key = U256::from_little_endian(hashed_key.as_bytes());
+    TreeEntry::new(key, enumeration_index, value)
+}
@@ -0,0 +1,518 @@
+//! Tests for L1 recovery.
+
+use std::collections::HashMap;
+
+use test_casing::{test_casing, Product};
+use zksync_contracts::POST_SHARED_BRIDGE_COMMIT_FUNCTION;
+use zksync_dal::Connection;
+use zksync_l1_contract_interface::{i_executor::methods::CommitBatches, Tokenize};
+use zksync_merkle_tree::{BlockOutput, MerkleTree, PatchSet, TreeEntry};
+use zksync_node_test_utils::{create_l1_batch, create_l1_batch_metadata};
+use zksync_types::{
+    block::{pack_block_info, L2BlockHasher},
+    commitment::{L1BatchCommitmentMode, L1BatchMetadata, L1BatchWithMetadata},
+    ethabi::{self, Token},
+    get_system_context_key, h256_to_u256,
+    pubdata_da::PubdataSendingMode,
+    u256_to_h256,
+    web3::{self, BlockId, CallRequest},
+    writes::{compress_state_diffs, StateDiffRecord},
+    AccountTreeId, ProtocolVersionId, StorageKey, SYSTEM_CONTEXT_CURRENT_L2_BLOCK_HASHES_POSITION,
+    SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION, U256, U64,
+};
+use zksync_web3_decl::client::MockClient;
+
+use super::*;
+
+const DIAMOND_PROXY_ADDR: Address = Address::repeat_byte(0x22);
+const ERA_CHAIN_ID: u64 = 270;
+
+/// Reference storage state maintained independently of [`ReconstructedState`].
+#[derive(Debug)]
+struct TestState {
+    tree: MerkleTree<PatchSet>,
+    values: HashMap<StorageKey, (u64, H256)>,
+}
+
+impl TestState {
+    fn genesis(params: &GenesisParams) -> Self {
+        let genesis_state = genesis_storage_state(params);
+        let mut tree = MerkleTree::new(PatchSet::default()).unwrap();
+        let mut values = HashMap::new();
+        let tree_entries = genesis_state
+            .storage_writes
+            .into_iter()
+            .enumerate()
+            .map(|(i, (key, value))| {
+                let enumeration_index = i as u64 + 1;
+                values.insert(key, (enumeration_index, value));
+                TreeEntry::new(key.hashed_key_u256(), enumeration_index, value)
+            })
+            .collect();
+        tree.extend(tree_entries).unwrap();
+        Self { tree, values }
+    }
+
+    fn apply(
+        &mut self,
+        mut writes: Vec<(StorageKey, H256)>,
+    ) -> (Vec<StateDiffRecord>, BlockOutput) {
+        // Initial writes are enumerated in the same order as they are published.
+        writes.sort_by_key(|(key, _)| (*key.address(), h256_to_u256(*key.key())));
+
+        let mut state_diffs = vec![];
+        let mut tree_entries = vec![];
+        for (key, value) in writes {
+            let (enumeration_index, initial_value) = match self.values.get(&key) {
+                Some(&(index, prev_value)) => (index, prev_value),
+                None => (0, H256::zero()),
+            };
+            let tree_index = if enumeration_index == 0 {
+                self.values.len() as u64 + 1
+            } else {
+                enumeration_index
+            };
+            self.values.insert(key, (tree_index, value));
+            tree_entries.push(TreeEntry::new(key.hashed_key_u256(), tree_index, value));
+            state_diffs.push(StateDiffRecord {
+                address: *key.address(),
+                key: h256_to_u256(*key.key()),
+                derived_key: key.hashed_key().0,
+                enumeration_index,
+                initial_value: h256_to_u256(initial_value),
+                final_value: h256_to_u256(value),
+            });
+        }
+        let output = self.tree.extend(tree_entries).unwrap();
+        (state_diffs, output)
+    }
+}
+
+fn user_key(i: u64) -> StorageKey {
+    StorageKey::new(
+        AccountTreeId::new(Address::repeat_byte(0x11)),
+        H256::from_low_u64_be(i),
+    )
+}
+
+/// Creates L1 batches with storage writes. Each L1 batch `n` finishes with L2 block `n`.
+fn create_l1_batches(
+    state: &mut TestState,
+    count: u32,
+    protocol_version: ProtocolVersionId,
+) -> Vec<L1BatchWithMetadata> {
+    (1..=count)
+        .map(|number| {
+            let mut writes = vec![
+                (
+                    user_key(number.into()),
+                    H256::from_low_u64_be(number.into()),
+                ),
+                // Repeated write
+                (user_key(1), H256::repeat_byte(number as u8)),
+                (
+                    get_system_context_key(SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION),
+                    u256_to_h256(pack_block_info(number.into(), number.into())),
+                ),
+            ];
+            if number > 1 {
+                let prev_hash_position =
+                    h256_to_u256(SYSTEM_CONTEXT_CURRENT_L2_BLOCK_HASHES_POSITION)
+                        + U256::from(number - 1);
+                writes.push((
+                    get_system_context_key(u256_to_h256(prev_hash_position)),
+                    H256::repeat_byte(0xff),
+                ));
+            }
+            if number == 1 {
+                writes.remove(1);
+            }
+            let (state_diffs, output) = state.apply(writes);
+
+            let mut header = create_l1_batch(number);
+            header.protocol_version = Some(protocol_version);
+            L1BatchWithMetadata {
+                header,
+                metadata: L1BatchMetadata {
+                    root_hash: output.root_hash,
+                    rollup_last_leaf_index: output.leaf_count + 1,
+                    state_diffs_compressed: compress_state_diffs(state_diffs),
+                    ..create_l1_batch_metadata(number)
+                },
+                raw_published_factory_deps: vec![vec![number as u8; 32]],
+            }
+        })
+        .collect()
+}
+
+fn build_commit_calldata(
+    batches: &[L1BatchWithMetadata],
+    pubdata_da: PubdataSendingMode,
+) -> Vec<u8> {
+    let protocol_version = batches[0].header.protocol_version.unwrap();
+    let tokens = CommitBatches {
+        last_committed_l1_batch: &batches[0],
+        l1_batches: batches,
+        pubdata_da,
+        mode: L1BatchCommitmentMode::Rollup,
+    }
+    .into_tokens();
+    let tokens: Vec<_> = [Token::Uint(ERA_CHAIN_ID.into())]
+        .into_iter()
+        .chain(tokens)
+        .collect();
+
+    if protocol_version.is_pre_gateway() {
+        POST_SHARED_BRIDGE_COMMIT_FUNCTION
+            .encode_input(&tokens)
+            .unwrap()
+    } else {
+        zksync_contracts::hyperchain_contract()
+            .function("commitBatchesSharedBridge")
+            .unwrap()
+            .encode_input(&tokens)
+            .unwrap()
+    }
+}
+
+/// Mock L1 with commit transactions; each transaction commits a chunk of L1 batches.
+#[derive(Debug)]
+struct MockL1 {
+    txs: HashMap<H256, Vec<u8>>,
+    logs: Vec<web3::Log>,
+    executed_batches: u32,
+    protocol_version: ProtocolVersionId,
+}
+
+impl MockL1 {
+    fn new(batches: &[L1BatchWithMetadata], batches_per_tx: usize) -> Self {
+        Self::with_blobs_from(batches, batches_per_tx, usize::MAX)
+    }
+
+    /// Creates a mock where commit transactions starting from the specified index publish pubdata in blobs.
+    fn with_blobs_from(
+        batches: &[L1BatchWithMetadata],
+        batches_per_tx: usize,
+        first_blob_tx: usize,
+    ) -> Self {
+        let block_commit_signature = zksync_contracts::hyperchain_contract()
+            .event("BlockCommit")
+            .unwrap()
+            .signature();
+        let mut txs = HashMap::new();
+        let mut logs = vec![];
+        for (i, chunk) in batches.chunks(batches_per_tx).enumerate() {
+            let tx_hash = H256::from_low_u64_be(i as u64 + 1);
+            let pubdata_da = if i >= first_blob_tx {
+                PubdataSendingMode::Blobs
+            } else {
+                PubdataSendingMode::Calldata
+            };
+            txs.insert(tx_hash, build_commit_calldata(chunk, pubdata_da));
+            logs.extend(chunk.iter().map(|batch| web3::Log {
+                address: DIAMOND_PROXY_ADDR,
+                topics: vec![
+                    block_commit_signature,
+                    H256::from_low_u64_be(batch.header.number.0.into()),
+                    H256::zero(),
+                    H256::zero(),
+                ],
+                block_number: Some(U64::from(i as u64 * 10 + 5)),
+                transaction_hash: Some(tx_hash),
+                ..web3::Log::default()
+            }));
+        }
+
+        Self {
+            txs,
+            logs,
+            executed_batches: batches.len() as u32,
+            protocol_version: batches[0].header.protocol_version.unwrap(),
+        }
+    }
+
+    fn into_client(self) -> MockClient<L1> {
+        let contract = zksync_contracts::hyperchain_contract();
+        let executed_batches_selector = contract
+            .function("getTotalBatchesExecuted")
+            .unwrap()
+            .short_signature();
+        let protocol_version_selector = contract
+            .function("getProtocolVersion")
+            .unwrap()
+            .short_signature();
+        let latest_block = U64::from(self.logs.len() as u64 * 10 + 100);
+
+        MockClient::builder(L1::default())
+            .method("eth_blockNumber", move || Ok(latest_block))
+            .method("eth_getLogs", move |filter: web3::Filter| {
+                let Some(web3::BlockNumber::Number(from)) = filter.from_block else {
+                    panic!("Unexpected filter: {filter:?}");
+                };
+                let Some(web3::BlockNumber::Number(to)) = filter.to_block else {
+                    panic!("Unexpected filter: {filter:?}");
+                };
+                let logs = self.logs.iter().filter(|log| {
+                    let block_number = log.block_number.unwrap();
+                    (from..=to).contains(&block_number)
+                });
+                Ok(logs.cloned().collect::<Vec<_>>())
+            })
+            .method("eth_getTransactionByHash", move |hash: H256| {
+                Ok(self.txs.get(&hash).map(|input| web3::Transaction {
+                    hash,
+                    input: web3::Bytes(input.clone()),
+                    ..web3::Transaction::default()
+                }))
+            })
+            .method("eth_call", move |req: CallRequest, _block_id: BlockId| {
+                assert_eq!(req.to, Some(DIAMOND_PROXY_ADDR));
+                let data = req.data.unwrap();
+                let selector = &data.0[..4];
+                let value = if selector == executed_batches_selector {
+                    U256::from(self.executed_batches)
+                } else if selector == protocol_version_selector {
+                    self.protocol_version.into_packed_semver_with_patch(0)
+                } else {
+                    panic!("Unexpected call: {selector:?}");
+                };
+                Ok(web3::Bytes(ethabi::encode(&[Token::Uint(value)])))
+            })
+            .build()
+    }
+}
+
+fn create_task(
+    client: MockClient<L1>,
+    pool: ConnectionPool<Core>,
+    genesis_params: GenesisParams,
+) -> L1RecoveryTask {
+    create_task_with_mode(client, pool, genesis_params, L1BatchCommitmentMode::Rollup)
+}
+
+fn create_task_with_mode(
+    client: MockClient<L1>,
+    pool: ConnectionPool<Core>,
+    genesis_params: GenesisParams,
+    commitment_mode: L1BatchCommitmentMode,
+) -> L1RecoveryTask {
+    let config = L1RecoveryConfig {
+        logs_block_range: 7, // to test splitting the queried block range
+        commitment_mode,
+        ..L1RecoveryConfig::new(DIAMOND_PROXY_ADDR)
+    };
+    L1RecoveryTask::new(config, Box::new(client), pool, genesis_params)
+}
+
+async fn assert_recovered_storage(
+    storage: &mut Connection<'_, Core>,
+    state: &TestState,
+    last_batch: &L1BatchWithMetadata,
+) {
+    let status = storage
+        .snapshot_recovery_dal()
+        .get_applied_snapshot_status()
+        .await
+        .unwrap()
+        .expect("no recovery status");
+    assert_eq!(status.l1_batch_number, last_batch.header.number);
+    assert_eq!(status.l1_batch_root_hash, last_batch.metadata.root_hash);
+    assert_eq!(status.l1_batch_timestamp, last_batch.header.timestamp);
+    let l2_block_number = L2BlockNumber(last_batch.header.number.0);
+    assert_eq!(status.l2_block_number, l2_block_number);
+    assert_eq!(status.l2_block_timestamp, l2_block_number.0.into());
+    let prev_l2_block_hash = if l2_block_number.0 > 1 {
+        H256::repeat_byte(0xff)
+    } else {
+        H256::zero()
+    };
+    let expected_l2_block_hash = L2BlockHasher::new(
+        l2_block_number,
+        l2_block_number.0.into(),
+        prev_l2_block_hash,
+    )
+    .finalize(status.protocol_version);
+    assert_eq!(status.l2_block_hash, expected_l2_block_hash);
+    assert_eq!(
+        status.protocol_version,
+        last_batch.header.protocol_version.unwrap()
+    );
+
+    let storage_logs = storage
+        .storage_logs_dal()
+        .dump_all_storage_logs_for_tests()
+        .await;
+    assert_eq!(storage_logs.len(), state.values.len());
+    for log in &storage_logs {
+        assert_eq!(log.l2_block_number, l2_block_number);
+    }
+    let storage_values: HashMap<_, _> = storage_logs
+        .into_iter()
+        .map(|log| (log.hashed_key, log.value))
+        .collect();
+    let hashed_keys: Vec<_> = state.values.keys().map(StorageKey::hashed_key).collect();
+    let indices = storage
+        .storage_logs_dal()
+        .get_l1_batches_and_indices_for_initial_writes(&hashed_keys)
+        .await
+        .unwrap();
+    for (key, &(enumeration_index, value)) in &state.values {
+        let hashed_key = key.hashed_key();
+        assert_eq!(storage_values[&hashed_key], value, "{key:?}");
+        assert_eq!(indices[&hashed_key].1, enumeration_index, "{key:?}");
+    }
+
+    let factory_deps = storage
+        .factory_deps_dal()
+        .dump_all_factory_deps_for_tests()
+        .await;
+    for dep in &last_batch.raw_published_factory_deps {
+        assert!(factory_deps.values().any(|bytecode| bytecode == dep));
+    }
+
+    let pruning_info = storage.pruning_dal().get_pruning_info().await.unwrap();
+    let last_hard_pruned = pruning_info.last_hard_pruned.unwrap();
+    assert_eq!(last_hard_pruned.l1_batch, last_batch.header.number);
+    assert_eq!(last_hard_pruned.l2_block, l2_block_number);
+}
+
+#[test_casing(4, Product(([ProtocolVersionId::latest(), ProtocolVersionId::gateway_upgrade()], [1, 3])))]
+#[tokio::test]
+async fn recovering_storage_from_l1(protocol_version: ProtocolVersionId, batches_per_tx: usize) {
+    let genesis_params = GenesisParams::mock();
+    let mut state = TestState::genesis(&genesis_params);
+    let batches = create_l1_batches(&mut state, 5, protocol_version);
+    let client = MockL1::new(&batches, batches_per_tx).into_client();
+
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let task = create_task(client, pool.clone(), genesis_params);
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let stats = task.run(stop_receiver).await.unwrap().expect("no stats");
+
+    let last_batch = batches.last().unwrap();
+    assert_eq!(
+        stats,
+        L1RecoveryStats {
+            l1_batch_number: last_batch.header.number,
+            root_hash: last_batch.metadata.root_hash,
+            storage_log_count: state.values.len(),
+        }
+    );
+
+    let mut storage = pool.connection().await.unwrap();
+    assert_recovered_storage(&mut storage, &state, last_batch).await;
+    assert!(L1RecoveryTask::is_recovery_completed(&pool).await.unwrap());
+}
+
+#[tokio::test]
+async fn recovery_is_skipped_if_already_completed() {
+    let genesis_params = GenesisParams::mock();
+    let mut state = TestState::genesis(&genesis_params);
+    let batches = create_l1_batches(&mut state, 2, ProtocolVersionId::latest());
+
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let client = MockL1::new(&batches, 1).into_client();
+    let task = create_task(client, pool.clone(), genesis_params.clone());
+    task.run(stop_receiver.clone())
+        .await
+        .unwrap()
+        .expect("no stats");
+
+    let client = MockL1::new(&batches, 1).into_client();
+    let task = create_task(client, pool.clone(), genesis_params);
+    let stats = task.run(stop_receiver).await.unwrap();
+    assert_eq!(stats, None);
+}
+
+#[tokio::test]
+async fn recovery_fails_on_root_hash_mismatch() {
+    let genesis_params = GenesisParams::mock();
+    let mut state = TestState::genesis(&genesis_params);
+    let mut batches = create_l1_batches(&mut state, 3, ProtocolVersionId::latest());
+    batches[1].metadata.root_hash = H256::repeat_byte(0xee);
+    let client = MockL1::new(&batches, 1).into_client();
+
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let task = create_task(client, pool.clone(), genesis_params);
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let err = task.run(stop_receiver).await.unwrap_err();
+    let err = format!("{err:#}");
+    assert!(
+        err.contains("L1 batch #2") && err.contains("root hash mismatch"),
+        "{err}"
+    );
+
+    // Nothing should be persisted.
+    assert!(!L1RecoveryTask::is_recovery_completed(&pool).await.unwrap());
+    let mut storage = pool.connection().await.unwrap();
+    let storage_logs = storage
+        .storage_logs_dal()
+        .dump_all_storage_logs_for_tests()
+        .await;
+    assert!(storage_logs.is_empty());
+}
+
+#[tokio::test]
+async fn recovery_fails_on_non_empty_storage() {
+    let genesis_params = GenesisParams::mock();
+    let mut state = TestState::genesis(&genesis_params);
+    let batches = create_l1_batches(&mut state, 1, ProtocolVersionId::latest());
+    let client = MockL1::new(&batches, 1).into_client();
+
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    zksync_node_genesis::insert_genesis_batch(&mut storage, &genesis_params)
+        .await
+        .unwrap();
+
+    let task = create_task(client, pool.clone(), genesis_params);
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let err = task.run(stop_receiver).await.unwrap_err();
+    assert!(format!("{err:#}").contains("unsafe"), "{err:#}");
+}
+
+#[tokio::test]
+async fn recovery_fails_for_validium() {
+    let genesis_params = GenesisParams::mock();
+    let mut state = TestState::genesis(&genesis_params);
+    let batches = create_l1_batches(&mut state, 1, ProtocolVersionId::latest());
+    let client = MockL1::new(&batches, 1).into_client();
+
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let task = create_task_with_mode(
+        client,
+        pool.clone(),
+        genesis_params,
+        L1BatchCommitmentMode::Validium,
+    );
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let err = task.run(stop_receiver).await.unwrap_err();
+    assert!(
+        format!("{err:#}").contains("Validium commitment mode"),
+        "{err:#}"
+    );
+    assert!(!L1RecoveryTask::is_recovery_completed(&pool).await.unwrap());
+}
+
+#[test_casing(2, [ProtocolVersionId::latest(), ProtocolVersionId::gateway_upgrade()])]
+#[tokio::test]
+async fn recovery_fails_early_on_blob_pubdata(protocol_version: ProtocolVersionId) {
+    let genesis_params = GenesisParams::mock();
+    let mut state = TestState::genesis(&genesis_params);
+    let mut batches = create_l1_batches(&mut state, 3, protocol_version);
+    // If batches were replayed before checking the latest commitment, recovery would fail on the root hash mismatch.
+    batches[0].metadata.root_hash = H256::repeat_byte(0xee);
+    let client = MockL1::with_blobs_from(&batches, 1, 2).into_client();
+
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let task = create_task(client, pool.clone(), genesis_params);
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let err = task.run(stop_receiver).await.unwrap_err();
+    let err = format!("{err:#}");
+    assert!(
+        err.contains("L1 batch #3") && err.contains("EIP-4844 blobs"),
+        "{err}"
+    );
+    assert!(!L1RecoveryTask::is_recovery_completed(&pool).await.unwrap());
+}
@@ -21,6 +21,7 @@ use zksync_dal::{Connection, Core, CoreDal};
 use zksync_health_check::{CheckHealth, Health, HealthStatus, ReactiveHealthCheck};
 use zksync_merkle_tree::{
     domain::{TreeMetadata, ZkSyncTree, ZkSyncTreeReader},
+    migration::LightweightMigrationStats,
     recovery::{MerkleTreeRecovery, PersistenceThreadHandle},
     repair::StaleKeysRepairTask,
     unstable::{NodeKey, RawNode},
@@ -333,6 +334,24 @@ impl AsyncTree {
     pub fn roll_back_logs(&mut self, last_l1_batch_to_keep: L1BatchNumber) -> anyhow::Result<()> {
         self.as_mut().roll_back_logs(last_l1_batch_to_keep)
     }
+
+    /// Returned errors are unrecoverable; the tree must not be used after an error is returned.
+    pub async fn migrate_to_lightweight(&mut self) -> anyhow::Result<LightweightMigrationStats> {
+        anyhow::ensure!(
+            self.mode == MerkleTreeMode::Lightweight,
+            "Cannot migrate Merkle tree to lightweight mode; the tree is configured to run in {:?} mode",
+            self.mode
+        );
+        let mut tree = self.inner.take().context(Self::INCONSISTENT_MSG)?;
+        let (tree, stats) = tokio::task::spawn_blocking(|| {
+            let stats = tree.migrate_to_lightweight()?;
+            anyhow::Ok((tree, stats))
+        })
+        .await
+        .context("Merkle tree panicked during migration to lightweight mode")??;
+        self.inner = Some(tree);
+        Ok(stats)
+    }
 }
 
 /// Async version of [`ZkSyncTreeReader`].
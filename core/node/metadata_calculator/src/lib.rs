@@ -25,6 +25,7 @@ use self::{
 };
 pub use self::{
     helpers::{AsyncTreeReader, LazyAsyncTreeReader, MerkleTreeInfo},
+    migration::MerkleTreeRebuildTask,
     pruning::MerkleTreePruningTask,
     repair::StaleKeysRepairTask,
 };
@@ -33,6 +34,7 @@ use crate::helpers::create_readonly_db;
 pub mod api_server;
 mod helpers;
 mod metrics;
+mod migration;
 mod pruning;
 mod recovery;
 mod repair;
@@ -134,6 +136,7 @@ pub struct MetadataCalculator {
     delayer: Delayer,
     health_updater: HealthUpdater,
     max_l1_batches_per_iter: usize,
+    migrate_to_lightweight: bool,
 }
 
 impl MetadataCalculator {
@@ -175,6 +178,7 @@ impl MetadataCalculator {
             delayer: Delayer::new(config.delay_interval),
             health_updater,
             max_l1_batches_per_iter: config.max_l1_batches_per_iter,
+            migrate_to_lightweight: false,
             config,
         })
     }
@@ -186,6 +190,13 @@ impl MetadataCalculator {
         self
     }
 
+    /// Migrates the tree to the lightweight mode in place on start, removing tree nodes not necessary for this mode.
+    /// The calculator must be configured to run in the lightweight mode.
+    pub fn with_lightweight_migration(mut self) -> Self {
+        self.migrate_to_lightweight = true;
+        self
+    }
+
     /// Returns a health check for this calculator.
     pub fn tree_health_check(&self) -> impl CheckHealth {
         MerkleTreeHealthCheck::new(self.health_updater.subscribe(), self.tree_reader())
@@ -210,6 +221,26 @@ impl MetadataCalculator {
         StaleKeysRepairTask::new(self.tree_reader())
     }
 
+    /// Returns a task that rebuilds the tree from Postgres storage logs in the full mode at the specified `db_path`
+    /// (e.g., to migrate the tree from the lightweight to the full mode). The path must differ from the path
+    /// of the tree maintained by this calculator.
+    pub fn rebuild_task(&self, db_path: String) -> anyhow::Result<MerkleTreeRebuildTask> {
+        anyhow::ensure!(
+            db_path != self.config.db_path,
+            "Path for the rebuilt Merkle tree must differ from the path of the main tree `{db_path}`"
+        );
+        let config = MetadataCalculatorConfig {
+            db_path,
+            mode: MerkleTreeMode::Full,
+            ..self.config.clone()
+        };
+        Ok(MerkleTreeRebuildTask::new(
+            config,
+            self.tree_reader(),
+            self.recovery_pool.clone(),
+        ))
+    }
+
     async fn create_tree(&self) -> anyhow::Result<GenericAsyncTree> {
         self.health_updater
             .update(MerkleTreeHealth::Initialization.into());
@@ -250,6 +281,14 @@ impl MetadataCalculator {
 
         tree.ensure_consistency(&self.delayer, &self.pool, &mut stop_receiver)
             .await?;
+        if self.migrate_to_lightweight {
+            // The migration must finish before pruning starts since the migration prunes the tree itself.
+            let stats = tree
+                .migrate_to_lightweight()
+                .await
+                .context("failed migrating Merkle tree to lightweight mode")?;
+            tracing::info!("Migrated Merkle tree to lightweight mode: {stats:?}");
+        }
         if !self.pruning_handles_sender.is_closed() {
             // Unlike tree reader, we shouldn't initialize pruning (as a task modifying the tree) before the tree is guaranteed
             // to be consistent with Postgres.
@@ -0,0 +1,286 @@
+//! Migration of the Merkle tree between the full and lightweight modes.
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_health_check::{HealthUpdater, ReactiveHealthCheck};
+use zksync_merkle_tree::migration::verify_rebuilt_tree;
+use zksync_types::L1BatchNumber;
+
+use super::{
+    helpers::{
+        create_db, AsyncTree, AsyncTreeRecovery, GenericAsyncTree, LazyAsyncTreeReader,
+        MerkleTreeHealth,
+    },
+    MetadataCalculatorConfig,
+};
+
+/// Task rebuilding the Merkle tree from Postgres storage logs in a separate RocksDB instance, e.g. in order to switch
+/// the tree from the lightweight to the full mode.
+///
+/// The tree is rebuilt at the latest L1 batch processed by the main tree when the rebuild starts. The main tree
+/// is not touched and continues operating normally. The rebuild is fault-tolerant; if it's interrupted, it will be resumed
+/// for the same L1 batch on the next run. After the rebuild, the rebuilt tree is checked for consistency and against
+/// the root hash of the L1 batch in Postgres; afterwards, the node can be restarted with the rebuilt tree
+/// and the updated tree mode.
+#[derive(Debug)]
+#[must_use = "Task should `run()` in a managed Tokio task"]
+pub struct MerkleTreeRebuildTask {
+    config: MetadataCalculatorConfig,
+    tree_reader: LazyAsyncTreeReader,
+    pool: ConnectionPool<Core>,
+    health_updater: HealthUpdater,
+}
+
+impl MerkleTreeRebuildTask {
+    pub(super) fn new(
+        config: MetadataCalculatorConfig,
+        tree_reader: LazyAsyncTreeReader,
+        pool: ConnectionPool<Core>,
+    ) -> Self {
+        let (_, health_updater) = ReactiveHealthCheck::new("tree_rebuild");
+        Self {
+            config,
+            tree_reader,
+            pool,
+            health_updater,
+        }
+    }
+
+    /// Returns a health check for this task.
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    /// Runs this task until the tree is rebuilt, or a stop signal is received.
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let Self {
+            config,
+            tree_reader,
+            pool,
+            health_updater,
+        } = self;
+        let verifier = RebuiltTreeVerifier {
+            db_path: &config.db_path,
+            pool: &pool,
+            health_updater: &health_updater,
+        };
+
+        health_updater.update(MerkleTreeHealth::Initialization.into());
+        let tree_reader = tokio::select! {
+            reader = tree_reader.wait() => {
+                if let Some(reader) = reader {
+                    reader
+                } else {
+                    tracing::info!("Merkle tree dropped; shutting down tree rebuild");
+                    return Ok(());
+                }
+            }
+            _ = stop_receiver.changed() => {
+                tracing::info!("Stop signal received before Merkle tree is initialized; shutting down tree rebuild");
+                return Ok(());
+            }
+        };
+
+        let db = create_db(config.clone()).await.with_context(|| {
+            format!(
+                "failed opening RocksDB for rebuilt Merkle tree at `{}`",
+                config.db_path
+            )
+        })?;
+        let recovery = match GenericAsyncTree::new(db, &config).await? {
+            GenericAsyncTree::Ready(tree) => {
+                tracing::info!("Merkle tree at `{}` is already rebuilt", config.db_path);
+                return verifier.verify(tree).await;
+            }
+            GenericAsyncTree::Recovering(recovery) => {
+                tracing::info!(
+                    "Resuming Merkle tree rebuild for L1 batch #{}",
+                    recovery.recovered_version()
+                );
+                recovery
+            }
+            GenericAsyncTree::Empty { db, mode } => {
+                let tree_info = tree_reader.info().await;
+                let l1_batch = tree_info
+                    .next_l1_batch_number
+                    .checked_sub(1)
+                    .context("Merkle tree is empty; nothing to rebuild")?;
+                tracing::info!("Starting Merkle tree rebuild for L1 batch #{l1_batch}");
+                AsyncTreeRecovery::new(db, l1_batch.into(), mode, &config.recovery)?
+            }
+        };
+
+        let tree = recovery
+            .rebuild(&config.recovery, &pool, &health_updater, &stop_receiver)
+            .await?;
+        let Some(tree) = tree else {
+            tracing::info!("Stop signal received, Merkle tree rebuild is shut down");
+            return Ok(());
+        };
+        verifier.verify(tree).await
+    }
+}
+
+/// Verifier of a tree built by [`MerkleTreeRebuildTask`].
+#[derive(Debug)]
+struct RebuiltTreeVerifier<'a> {
+    db_path: &'a str,
+    pool: &'a ConnectionPool<Core>,
+    health_updater: &'a HealthUpdater,
+}
+
+impl RebuiltTreeVerifier<'_> {
+    async fn verify(&self, tree: AsyncTree) -> anyhow::Result<()> {
+        let tree_info = tree.reader().info().await;
+        let l1_batch = tree_info
+            .next_l1_batch_number
+            .checked_sub(1)
+            .map(L1BatchNumber)
+            .context("rebuilt Merkle tree is empty")?;
+
+        let mut storage = self.pool.connection_tagged("metadata_calculator").await?;
+        let expected_root_hash = storage
+            .blocks_dal()
+            .get_l1_batch_tree_data(l1_batch)
+            .await?
+            .with_context(|| format!("L1 batch #{l1_batch} doesn't have tree data in Postgres"))?
+            .hash;
+        drop(storage);
+
+        let db = tree.reader().into_db();
+        drop(tree);
+        tokio::task::spawn_blocking(move || {
+            verify_rebuilt_tree(db, l1_batch.0.into(), expected_root_hash)
+        })
+        .await
+        .context("panicked verifying rebuilt Merkle tree")??;
+
+        tracing::info!(
+            "Merkle tree at `{}` is rebuilt for L1 batch #{l1_batch} and verified: {tree_info:?}. \
+             To switch to the rebuilt tree, restart the node with the updated Merkle tree path and mode",
+            self.db_path
+        );
+        self.health_updater
+            .update(MerkleTreeHealth::MainLoop(tree_info).into());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+    use zksync_config::configs::database::MerkleTreeMode;
+    use zksync_health_check::{CheckHealth, HealthStatus};
+    use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
+
+    use super::*;
+    use crate::{
+        tests::{mock_config, reset_db_state, run_calculator},
+        MetadataCalculator,
+    };
+
+    async fn prepare_postgres(pool: &ConnectionPool<Core>) {
+        let mut storage = pool.connection().await.unwrap();
+        insert_genesis_batch(&mut storage, &GenesisParams::mock())
+            .await
+            .unwrap();
+        reset_db_state(pool, 5).await;
+    }
+
+    #[tokio::test]
+    async fn migrating_tree_to_lightweight_mode() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        prepare_postgres(&pool).await;
+        let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+        let config = mock_config(temp_dir.path());
+        let calculator = MetadataCalculator::new(config.clone(), None, pool.clone())
+            .await
+            .unwrap();
+        let root_hash = run_calculator(calculator).await;
+
+        let config = MetadataCalculatorConfig {
+            mode: MerkleTreeMode::Lightweight,
+            ..config
+        };
+        let calculator = MetadataCalculator::new(config, None, pool.clone())
+            .await
+            .unwrap()
+            .with_lightweight_migration();
+        let reader = calculator.tree_reader();
+        assert_eq!(run_calculator(calculator).await, root_hash);
+
+        let tree_info = reader.wait().await.unwrap().info().await;
+        assert_eq!(tree_info.mode, MerkleTreeMode::Lightweight);
+        assert_eq!(tree_info.root_hash, root_hash);
+        assert_eq!(tree_info.next_l1_batch_number, L1BatchNumber(6));
+        // All tree versions except for the latest one should be removed.
+        assert_eq!(tree_info.min_l1_batch_number, Some(L1BatchNumber(5)));
+    }
+
+    #[tokio::test]
+    async fn migration_to_lightweight_mode_requires_lightweight_config() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        prepare_postgres(&pool).await;
+        let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+        let calculator = MetadataCalculator::new(mock_config(temp_dir.path()), None, pool)
+            .await
+            .unwrap()
+            .with_lightweight_migration();
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        let err = calculator.run(stop_receiver).await.unwrap_err();
+        assert!(
+            format!("{err:#}").contains("configured to run in Full mode"),
+            "{err:#}"
+        );
+    }
+
+    #[tokio::test]
+    async fn rebuilding_tree_in_full_mode() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        prepare_postgres(&pool).await;
+        let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+        let config = MetadataCalculatorConfig {
+            mode: MerkleTreeMode::Lightweight,
+            ..mock_config(&temp_dir.path().join("lightweight"))
+        };
+        let calculator = MetadataCalculator::new(config.clone(), None, pool.clone())
+            .await
+            .unwrap();
+        let root_hash = run_calculator(calculator).await;
+
+        let calculator = MetadataCalculator::new(config.clone(), None, pool.clone())
+            .await
+            .unwrap();
+        let err = calculator
+            .rebuild_task(config.db_path.clone())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("must differ"), "{err}");
+
+        let rebuilt_path = temp_dir.path().join("full");
+        let rebuild_task = calculator
+            .rebuild_task(rebuilt_path.to_str().unwrap().to_owned())
+            .unwrap();
+        let health_check = rebuild_task.health_check();
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let calculator_handle = tokio::spawn(calculator.run(stop_receiver.clone()));
+        rebuild_task.run(stop_receiver).await.unwrap();
+        assert_eq!(
+            health_check.check_health().await.status(),
+            HealthStatus::Ready
+        );
+        stop_sender.send_replace(true);
+        calculator_handle.await.unwrap().unwrap();
+
+        let config = mock_config(&rebuilt_path);
+        let db = create_db(config.clone()).await.unwrap();
+        let GenericAsyncTree::Ready(tree) = GenericAsyncTree::new(db, &config).await.unwrap()
+        else {
+            panic!("rebuilt tree is not ready");
+        };
+        assert_eq!(tree.mode(), MerkleTreeMode::Full);
+        assert_eq!(tree.next_l1_batch_number(), L1BatchNumber(6));
+        assert_eq!(tree.root_hash(), root_hash);
+    }
+}
@@ -173,6 +173,37 @@ impl InitParameters {
         }))
     }
 
+    /// Creates parameters to rebuild the tree from the Postgres storage state at the specified L1 batch.
+    async fn for_rebuild(
+        pool: &ConnectionPool<Core>,
+        l1_batch: L1BatchNumber,
+        config: &MetadataCalculatorRecoveryConfig,
+    ) -> anyhow::Result<Self> {
+        let mut storage = pool.connection_tagged("metadata_calculator").await?;
+        let (_, l2_block) = storage
+            .blocks_dal()
+            .get_l2_block_range_of_l1_batch(l1_batch)
+            .await?
+            .with_context(|| format!("L1 batch #{l1_batch} doesn't have L2 blocks in Postgres"))?;
+        let tree_data = storage
+            .blocks_dal()
+            .get_l1_batch_tree_data(l1_batch)
+            .await?
+            .with_context(|| format!("L1 batch #{l1_batch} doesn't have tree data in Postgres"))?;
+        let log_count = storage
+            .storage_logs_dal()
+            .get_storage_logs_row_count(l2_block)
+            .await?;
+
+        Ok(Self {
+            l1_batch,
+            l2_block,
+            expected_root_hash: Some(tree_data.hash),
+            log_count,
+            desired_chunk_size: config.desired_chunk_size,
+        })
+    }
+
     fn chunk_count(&self) -> u64 {
         self.log_count.div_ceil(self.desired_chunk_size)
     }
@@ -251,6 +282,29 @@ impl GenericAsyncTree {
 }
 
 impl AsyncTreeRecovery {
+    /// Rebuilds the tree from the Postgres storage state at the recovered tree version. Unlike with snapshot recovery,
+    /// the recovered L1 batch doesn't need to be the snapshot or the last pruned L1 batch.
+    pub(super) async fn rebuild(
+        self,
+        config: &MetadataCalculatorRecoveryConfig,
+        pool: &ConnectionPool<Core>,
+        health_updater: &HealthUpdater,
+        stop_receiver: &watch::Receiver<bool>,
+    ) -> anyhow::Result<Option<AsyncTree>> {
+        let l1_batch = u32::try_from(self.recovered_version())
+            .map(L1BatchNumber)
+            .context("recovered tree version overflow")?;
+        let init_params = InitParameters::for_rebuild(pool, l1_batch, config).await?;
+        tracing::info!("Rebuilding Merkle tree with parameters {init_params:?}");
+        let options = RecoveryOptions {
+            chunk_count: init_params.chunk_count(),
+            concurrency_limit: pool.max_size() as usize,
+            events: Box::new(RecoveryHealthUpdater::new(health_updater)),
+        };
+        self.recover(init_params, options, pool, stop_receiver)
+            .await
+    }
+
     async fn recover(
         mut self,
         init_params: InitParameters,
@@ -384,8 +438,13 @@ impl AsyncTreeRecovery {
     ) -> anyhow::Result<()> {
         let pruning_info = storage.pruning_dal().get_pruning_info().await?;
         if let Some(pruned) = pruning_info.last_hard_pruned {
+            // Storage logs for the snapshot L2 block can be loaded as long as no newer L2 blocks are pruned:
+            // hard pruning only removes logs overwritten in or before the pruned L2 block, so the state
+            // at any later L2 block is still fully represented in Postgres. For snapshot recovery, the snapshot
+            // L2 block is initially equal to the pruned one; for tree rebuilds (see `Self::rebuild()`), the rebuilt
+            // L2 block can be newer than the pruned one.
             anyhow::ensure!(
-                pruned.l2_block == snapshot_l2_block,
+                pruned.l2_block <= snapshot_l2_block,
                 "Additional data was pruned compared to tree recovery L2 block #{snapshot_l2_block}: {pruning_info:?}. \
                  Continuing recovery is impossible; to recover the tree, drop its RocksDB directory, stop pruning and restart recovery"
             );
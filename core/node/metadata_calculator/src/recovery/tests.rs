@@ -186,6 +186,78 @@ async fn recovery_workflow_for_partial_pruning() {
     assert_eq!(run_calculator(calculator).await, final_root_hash);
 }
 
+#[tokio::test]
+async fn checking_pruning_info_for_recovered_l2_block() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    prepare_storage_logs(pool.clone(), &temp_dir).await;
+    let logs = gen_storage_logs(200..400, 2);
+    extend_db_state(&mut pool.connection().await.unwrap(), logs).await;
+    let (calculator, _) = setup_calculator(&temp_dir.path().join("init"), pool.clone(), true).await;
+    run_calculator(calculator).await;
+    prune_storage(&pool, L1BatchNumber(2)).await;
+
+    let mut storage = pool.connection().await.unwrap();
+    let (_, pruned_l2_block) = storage
+        .blocks_dal()
+        .get_l2_block_range_of_l1_batch(L1BatchNumber(2))
+        .await
+        .unwrap()
+        .unwrap();
+    for l2_block in [pruned_l2_block, pruned_l2_block + 1] {
+        AsyncTreeRecovery::check_pruning_info(&mut storage, l2_block)
+            .await
+            .unwrap();
+    }
+    let err = AsyncTreeRecovery::check_pruning_info(&mut storage, pruned_l2_block - 1)
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("Additional data was pruned"),
+        "{err:#}"
+    );
+}
+
+#[tokio::test]
+async fn rebuilding_tree_after_pruning_older_l1_batches() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    prepare_storage_logs(pool.clone(), &temp_dir).await;
+    let logs = gen_storage_logs(200..400, 5);
+    extend_db_state(&mut pool.connection().await.unwrap(), logs).await;
+    let (calculator, _) = setup_calculator(&temp_dir.path().join("init"), pool.clone(), true).await;
+    run_calculator(calculator).await;
+
+    let rebuilt_l1_batch = L1BatchNumber(3);
+    let expected_root_hash = pool
+        .connection()
+        .await
+        .unwrap()
+        .blocks_dal()
+        .get_l1_batch_state_root(rebuilt_l1_batch)
+        .await
+        .unwrap()
+        .expect("no root hash");
+    // Pruned L2 blocks are older than the rebuilt L2 block.
+    prune_storage(&pool, L1BatchNumber(1)).await;
+
+    let config = MetadataCalculatorRecoveryConfig::default();
+    let tree_path = temp_dir.path().join("rebuild");
+    let (tree, _) = create_tree_recovery(&tree_path, rebuilt_l1_batch, &config).await;
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let tree = tree
+        .rebuild(
+            &config,
+            &pool,
+            &ReactiveHealthCheck::new("tree").1,
+            &stop_receiver,
+        )
+        .await
+        .unwrap()
+        .expect("Tree rebuild unexpectedly aborted");
+    assert_eq!(tree.root_hash(), expected_root_hash);
+}
+
 #[derive(Debug)]
 struct TestEventListener {
     expected_recovered_chunks: u64,
@@ -1,4 +1,7 @@
+use std::sync::Arc;
+
 use zksync_block_reverter::{BlockReverter, NodeRole};
+use zksync_vm_runner::impls::VmRunnerWorkload;
 
 use crate::{
     implementations::resources::{
@@ -17,6 +20,7 @@ pub struct BlockReverterLayer {
     should_roll_back_postgres: bool,
     state_keeper_cache_path: Option<String>,
     merkle_tree_path: Option<String>,
+    vm_runner_workloads: Vec<Arc<dyn VmRunnerWorkload>>,
 }
 
 impl BlockReverterLayer {
@@ -27,6 +31,7 @@ impl BlockReverterLayer {
             should_roll_back_postgres: false,
             state_keeper_cache_path: None,
             merkle_tree_path: None,
+            vm_runner_workloads: Vec::new(),
         }
     }
 
@@ -49,6 +54,14 @@ impl BlockReverterLayer {
         self.state_keeper_cache_path = Some(path);
         self
     }
+
+    pub fn add_vm_runner_workload_to_rollback(
+        &mut self,
+        workload: Arc<dyn VmRunnerWorkload>,
+    ) -> &mut Self {
+        self.vm_runner_workloads.push(workload);
+        self
+    }
 }
 
 #[derive(Debug, FromContext)]
@@ -87,6 +100,9 @@ impl WiringLayer for BlockReverterLayer {
         if let Some(path) = self.state_keeper_cache_path {
             block_reverter.add_rocksdb_storage_path_to_rollback(path);
         }
+        for workload in self.vm_runner_workloads {
+            block_reverter.add_vm_runner_workload_to_rollback(workload);
+        }
 
         Ok(Output {
             block_reverter: block_reverter.into(),
@@ -3,20 +3,26 @@ use std::{num::NonZeroUsize, sync::Arc};
 use anyhow::Context;
 use zksync_node_sync::MainNodeHealthCheck;
 use zksync_types::{url::SensitiveUrl, L2ChainId};
-use zksync_web3_decl::client::{Client, DynClient, L2};
+use zksync_web3_decl::client::{Client, DynClient, FailoverClient, L2};
 
 use crate::{
     implementations::resources::{
-        healthcheck::AppHealthCheckResource, main_node_client::MainNodeClientResource,
+        healthcheck::AppHealthCheckResource,
+        main_node_client::{MainNodeClientResource, MainNodeUpstreamsResource},
     },
     wiring_layer::{WiringError, WiringLayer},
     FromContext, IntoContext,
 };
 
 /// Wiring layer for main node client.
+///
+/// If fallback URLs are specified, the main node client is a [`FailoverClient`] switching among the main node URL
+/// and fallback URLs (e.g., trusted external nodes) if the active upstream fails. In this case, clients for
+/// all upstreams are additionally provided as a [`MainNodeUpstreamsResource`].
 #[derive(Debug)]
 pub struct MainNodeClientLayer {
     url: SensitiveUrl,
+    fallback_urls: Vec<SensitiveUrl>,
     rate_limit_rps: NonZeroUsize,
     l2_chain_id: L2ChainId,
 }
@@ -32,16 +38,30 @@ pub struct Input {
 #[context(crate = crate)]
 pub struct Output {
     pub main_node_client: MainNodeClientResource,
+    pub main_node_upstreams: Option<MainNodeUpstreamsResource>,
 }
 
 impl MainNodeClientLayer {
     pub fn new(url: SensitiveUrl, rate_limit_rps: NonZeroUsize, l2_chain_id: L2ChainId) -> Self {
         Self {
             url,
+            fallback_urls: vec![],
             rate_limit_rps,
             l2_chain_id,
         }
     }
+
+    /// Sets fallback URLs used if the main node is unavailable. Each URL is rate-limited separately.
+    pub fn with_fallback_urls(mut self, fallback_urls: Vec<SensitiveUrl>) -> Self {
+        self.fallback_urls = fallback_urls;
+        self
+    }
+
+    fn upstream_label(idx: usize, url: &SensitiveUrl) -> String {
+        // Only use the host in order to not expose potentially sensitive URL parts (e.g., API keys) in logs and metrics.
+        let host = url.expose_url().host_str().unwrap_or("unknown");
+        format!("{idx}:{host}")
+    }
 }
 
 #[async_trait::async_trait]
@@ -54,13 +74,33 @@ impl WiringLayer for MainNodeClientLayer {
     }
 
     async fn wire(self, input: Self::Input) -> Result<Self::Output, WiringError> {
-        let main_node_client = Client::http(self.url)
-            .context("failed creating JSON-RPC client for main node")?
-            .for_network(self.l2_chain_id.into())
-            .with_allowed_requests_per_second(self.rate_limit_rps)
-            .build();
+        let urls = [self.url].into_iter().chain(self.fallback_urls);
+        let upstreams = urls
+            .map(|url| {
+                let client = Client::http(url.clone())
+                    .with_context(|| format!("failed creating JSON-RPC client for {url:?}"))?
+                    .for_network(self.l2_chain_id.into())
+                    .with_allowed_requests_per_second(self.rate_limit_rps)
+                    .build();
+                Ok((url, Box::new(client) as Box<DynClient<L2>>))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
 
-        let client = Box::new(main_node_client) as Box<DynClient<L2>>;
+        let (client, main_node_upstreams) = if upstreams.len() == 1 {
+            let (_, client) = upstreams.into_iter().next().unwrap();
+            (client, None)
+        } else {
+            let mut builder = FailoverClient::builder(self.l2_chain_id.into());
+            for (idx, (url, client)) in upstreams.iter().enumerate() {
+                builder = builder.with_upstream(Self::upstream_label(idx, url), client.clone());
+            }
+            let failover_client = builder
+                .build()
+                .context("failed creating failover client for main node")?;
+            let upstreams = upstreams.into_iter().map(|(_, client)| client).collect();
+            let client = Box::new(failover_client) as Box<DynClient<L2>>;
+            (client, Some(MainNodeUpstreamsResource(upstreams)))
+        };
 
         // Insert healthcheck
         input
@@ -71,6 +111,7 @@ impl WiringLayer for MainNodeClientLayer {
 
         Ok(Output {
             main_node_client: client.into(),
+            main_node_upstreams,
         })
     }
 }
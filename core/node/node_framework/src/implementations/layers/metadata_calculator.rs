@@ -7,8 +7,8 @@ use std::{
 use anyhow::Context as _;
 use zksync_config::configs::{api::MerkleTreeApiConfig, database::MerkleTreeMode};
 use zksync_metadata_calculator::{
-    LazyAsyncTreeReader, MerkleTreePruningTask, MerkleTreeReaderConfig, MetadataCalculator,
-    MetadataCalculatorConfig, StaleKeysRepairTask, TreeReaderTask,
+    LazyAsyncTreeReader, MerkleTreePruningTask, MerkleTreeReaderConfig, MerkleTreeRebuildTask,
+    MetadataCalculator, MetadataCalculatorConfig, StaleKeysRepairTask, TreeReaderTask,
 };
 use zksync_storage::RocksDB;
 
@@ -32,6 +32,8 @@ pub struct MetadataCalculatorLayer {
     tree_api_config: Option<MerkleTreeApiConfig>,
     pruning_config: Option<Duration>,
     stale_keys_repair_enabled: bool,
+    lightweight_migration_enabled: bool,
+    rebuild_path: Option<String>,
 }
 
 #[derive(Debug, FromContext)]
@@ -60,6 +62,9 @@ pub struct Output {
     /// Only provided if enabled in the config.
     #[context(task)]
     pub stale_keys_repair_task: Option<StaleKeysRepairTask>,
+    /// Only provided if enabled in the config.
+    #[context(task)]
+    pub rebuild_task: Option<MerkleTreeRebuildTask>,
     pub rocksdb_shutdown_hook: ShutdownHook,
 }
 
@@ -70,6 +75,8 @@ impl MetadataCalculatorLayer {
             tree_api_config: None,
             pruning_config: None,
             stale_keys_repair_enabled: false,
+            lightweight_migration_enabled: false,
+            rebuild_path: None,
         }
     }
 
@@ -87,6 +94,18 @@ impl MetadataCalculatorLayer {
         self.stale_keys_repair_enabled = true;
         self
     }
+
+    /// Migrates the tree to the lightweight mode in place on start.
+    pub fn with_lightweight_migration(mut self) -> Self {
+        self.lightweight_migration_enabled = true;
+        self
+    }
+
+    /// Rebuilds the tree in the full mode at the specified path in the background.
+    pub fn with_rebuild(mut self, rebuild_path: String) -> Self {
+        self.rebuild_path = Some(rebuild_path);
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -124,6 +143,9 @@ impl WiringLayer for MetadataCalculatorLayer {
         )
         .await?
         .with_recovery_pool(recovery_pool);
+        if self.lightweight_migration_enabled {
+            metadata_calculator = metadata_calculator.with_lightweight_migration();
+        }
 
         app_health
             .insert_custom_component(Arc::new(metadata_calculator.tree_health_check()))
@@ -157,6 +179,21 @@ impl WiringLayer for MetadataCalculatorLayer {
             None
         };
 
+        let rebuild_task = self
+            .rebuild_path
+            .map(
+                |rebuild_path| -> Result<MerkleTreeRebuildTask, WiringError> {
+                    let rebuild_task = metadata_calculator
+                        .rebuild_task(rebuild_path)
+                        .map_err(|err| WiringError::Configuration(format!("{err:#}")))?;
+                    app_health
+                        .insert_component(rebuild_task.health_check())
+                        .map_err(|err| WiringError::Internal(err.into()))?;
+                    Ok(rebuild_task)
+                },
+            )
+            .transpose()?;
+
         let tree_api_client = TreeApiClientResource(Arc::new(metadata_calculator.tree_reader()));
 
         let rocksdb_shutdown_hook = ShutdownHook::new("rocksdb_terminaton", async {
@@ -172,6 +209,7 @@ impl WiringLayer for MetadataCalculatorLayer {
             tree_api_task,
             pruning_task,
             stale_keys_repair_task,
+            rebuild_task,
             rocksdb_shutdown_hook,
         })
     }
@@ -224,6 +262,21 @@ impl Task for StaleKeysRepairTask {
     }
 }
 
+#[async_trait::async_trait]
+impl Task for MerkleTreeRebuildTask {
+    fn kind(&self) -> TaskKind {
+        TaskKind::OneshotTask
+    }
+
+    fn id(&self) -> TaskId {
+        "merkle_tree_rebuild_task".into()
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        (*self).run(stop_receiver.0).await
+    }
+}
+
 #[async_trait::async_trait]
 impl Task for MerkleTreePruningTask {
     fn id(&self) -> TaskId {
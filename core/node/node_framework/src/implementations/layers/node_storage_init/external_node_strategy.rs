@@ -1,16 +1,21 @@
 use std::{num::NonZeroUsize, sync::Arc};
 
-// Re-export to initialize the layer without having to depend on the crate directly.
-pub use zksync_node_storage_init::SnapshotRecoveryConfig;
 use zksync_node_storage_init::{
-    external_node::{ExternalNodeGenesis, ExternalNodeReverter, ExternalNodeSnapshotRecovery},
+    external_node::{
+        ExternalNodeGenesis, ExternalNodeL1Recovery, ExternalNodeReverter,
+        ExternalNodeSnapshotRecovery,
+    },
     InitializeStorage, NodeInitializationStrategy, RevertStorage,
 };
+// Re-export to initialize the layer without having to depend on the crate directly.
+pub use zksync_node_storage_init::{L1RecoveryConfig, SnapshotRecoveryConfig};
+use zksync_reorg_detector::ReorgIncidents;
 use zksync_types::L2ChainId;
 
 use super::NodeInitializationStrategyResource;
 use crate::{
     implementations::resources::{
+        eth_interface::EthInterfaceResource,
         healthcheck::AppHealthCheckResource,
         main_node_client::MainNodeClientResource,
         pools::{MasterPool, PoolResource},
@@ -26,6 +31,10 @@ pub struct ExternalNodeInitStrategyLayer {
     pub l2_chain_id: L2ChainId,
     pub max_postgres_concurrency: NonZeroUsize,
     pub snapshot_recovery_config: Option<SnapshotRecoveryConfig>,
+    /// Config for recovery from L1 pubdata. Mutually exclusive with `snapshot_recovery_config`.
+    pub l1_recovery_config: Option<L1RecoveryConfig>,
+    /// Incidents recorded by the reorg detector in the auto-remediation mode.
+    pub reorg_incidents: Option<ReorgIncidents>,
 }
 
 #[derive(Debug, FromContext)]
@@ -33,6 +42,8 @@ pub struct ExternalNodeInitStrategyLayer {
 pub struct Input {
     pub master_pool: PoolResource<MasterPool>,
     pub main_node_client: MainNodeClientResource,
+    /// Only required for recovery from L1.
+    pub l1_client: Option<EthInterfaceResource>,
     pub block_reverter: Option<BlockReverterResource>,
     #[context(default)]
     pub app_health: AppHealthCheckResource,
@@ -74,8 +85,28 @@ impl WiringLayer for ExternalNodeInitStrategyLayer {
             client: client.clone(),
             pool: pool.clone(),
         });
-        let snapshot_recovery = match self.snapshot_recovery_config {
-            Some(recovery_config) => {
+        let snapshot_recovery = match (self.snapshot_recovery_config, self.l1_recovery_config) {
+            (Some(_), Some(_)) => {
+                return Err(WiringError::Configuration(
+                    "snapshot recovery and recovery from L1 cannot be enabled simultaneously"
+                        .into(),
+                ));
+            }
+            (None, Some(recovery_config)) => {
+                let EthInterfaceResource(l1_client) = input.l1_client.ok_or(
+                    WiringError::Configuration("L1 client is required for recovery from L1".into()),
+                )?;
+                let recovery: Arc<dyn InitializeStorage> = Arc::new(ExternalNodeL1Recovery {
+                    l2_chain_id: self.l2_chain_id,
+                    client: client.clone(),
+                    l1_client,
+                    pool: pool.clone(),
+                    recovery_config,
+                    app_health,
+                });
+                Some(recovery)
+            }
+            (Some(recovery_config), None) => {
                 // Add a connection for checking whether the storage is initialized.
                 let recovery_pool = input
                     .master_pool
@@ -90,13 +121,14 @@ impl WiringLayer for ExternalNodeInitStrategyLayer {
                 });
                 Some(recovery)
             }
-            None => None,
+            (None, None) => None,
         };
         // We always want to detect reorgs, even if we can't roll them back.
         let block_reverter = Some(Arc::new(ExternalNodeReverter {
             client,
             pool: pool.clone(),
             reverter: block_reverter,
+            reorg_incidents: self.reorg_incidents,
         }) as Arc<dyn RevertStorage>);
         let strategy = NodeInitializationStrategy {
             genesis,
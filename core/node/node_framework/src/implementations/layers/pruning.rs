@@ -1,6 +1,8 @@
 use std::time::Duration;
 
-use zksync_node_db_pruner::{DbPruner, DbPrunerConfig};
+use zksync_config::ObjectStoreConfig;
+use zksync_node_db_pruner::{DbPruner, DbPrunerConfig, PrunedDataExporter, RetentionRules};
+use zksync_object_store::ObjectStoreFactory;
 
 use crate::{
     implementations::resources::{
@@ -19,6 +21,8 @@ pub struct PruningLayer {
     pruning_removal_delay: Duration,
     pruning_chunk_size: u32,
     minimum_l1_batch_age: Duration,
+    retention_rules: RetentionRules,
+    export_object_store: Option<ObjectStoreConfig>,
 }
 
 #[derive(Debug, FromContext)]
@@ -46,8 +50,22 @@ impl PruningLayer {
             pruning_removal_delay,
             pruning_chunk_size,
             minimum_l1_batch_age,
+            retention_rules: RetentionRules::default(),
+            export_object_store: None,
         }
     }
+
+    /// Sets rules specifying data retained during hard pruning.
+    pub fn with_retention_rules(mut self, retention_rules: RetentionRules) -> Self {
+        self.retention_rules = retention_rules;
+        self
+    }
+
+    /// Enables exporting data to the specified object store before it is hard-pruned.
+    pub fn with_export_object_store(mut self, config: ObjectStoreConfig) -> Self {
+        self.export_object_store = Some(config);
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -62,14 +80,19 @@ impl WiringLayer for PruningLayer {
     async fn wire(self, input: Self::Input) -> Result<Self::Output, WiringError> {
         let main_pool = input.master_pool.get().await?;
 
-        let db_pruner = DbPruner::new(
+        let mut db_pruner = DbPruner::new(
             DbPrunerConfig {
                 removal_delay: self.pruning_removal_delay,
                 pruned_batch_chunk_size: self.pruning_chunk_size,
                 minimum_l1_batch_age: self.minimum_l1_batch_age,
+                retention_rules: self.retention_rules,
             },
             main_pool,
         );
+        if let Some(config) = self.export_object_store {
+            let object_store = ObjectStoreFactory::new(config).create_store().await?;
+            db_pruner = db_pruner.with_exporter(PrunedDataExporter::new(object_store));
+        }
 
         input
             .app_health
@@ -1,4 +1,4 @@
-use zksync_reorg_detector::{self, ReorgDetector};
+use zksync_reorg_detector::{self, ReorgDetector, ReorgIncidents};
 
 use crate::{
     implementations::resources::{
@@ -16,8 +16,25 @@ use crate::{
 /// This layer is responsible for detecting reorgs and shutting down the node if one is detected.
 ///
 /// This layer assumes that the node starts with the initialized state.
-#[derive(Debug)]
-pub struct ReorgDetectorLayer;
+///
+/// If auto-remediation is enabled, the detector records the detected reorg and finishes successfully,
+/// so that the node can be reverted and restarted in-process.
+#[derive(Debug, Default)]
+pub struct ReorgDetectorLayer {
+    auto_remediation: Option<ReorgIncidents>,
+}
+
+impl ReorgDetectorLayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables auto-remediation, recording detected reorgs to the provided `incidents`.
+    pub fn with_auto_remediation(mut self, incidents: ReorgIncidents) -> Self {
+        self.auto_remediation = Some(incidents);
+        self
+    }
+}
 
 #[derive(Debug, FromContext)]
 #[context(crate = crate)]
@@ -48,7 +65,10 @@ impl WiringLayer for ReorgDetectorLayer {
         let MainNodeClientResource(main_node_client) = input.main_node_client;
         let pool = input.master_pool.get().await?;
 
-        let reorg_detector = ReorgDetector::new(main_node_client, pool);
+        let mut reorg_detector = ReorgDetector::new(main_node_client, pool);
+        if let Some(incidents) = self.auto_remediation {
+            reorg_detector = reorg_detector.with_auto_remediation(incidents);
+        }
 
         let AppHealthCheckResource(app_health) = input.app_health;
         app_health
@@ -1,9 +1,10 @@
-use std::sync::Arc;
+use std::{num::NonZeroU32, sync::Arc};
 
 use anyhow::Context;
+use zksync_dal::{ConnectionPool, Core};
 use zksync_health_check::ReactiveHealthCheck;
-use zksync_state::AsyncCatchupTask;
 pub use zksync_state::RocksdbStorageOptions;
+use zksync_state::{AsyncCatchupTask, RocksdbCell, RocksdbStorageCompactor};
 use zksync_state_keeper::{AsyncRocksdbCache, ZkSyncStateKeeper};
 use zksync_storage::RocksDB;
 
@@ -32,6 +33,7 @@ pub mod output_handler;
 pub struct StateKeeperLayer {
     state_keeper_db_path: String,
     rocksdb_options: RocksdbStorageOptions,
+    cache_compaction_interval: Option<NonZeroU32>,
 }
 
 #[derive(Debug, FromContext)]
@@ -53,6 +55,8 @@ pub struct Output {
     pub state_keeper: StateKeeperTask,
     #[context(task)]
     pub rocksdb_catchup: AsyncCatchupTask,
+    #[context(task)]
+    pub rocksdb_compaction: Option<StateKeeperCacheCompactionTask>,
     pub rocksdb_termination_hook: ShutdownHook,
 }
 
@@ -61,8 +65,16 @@ impl StateKeeperLayer {
         Self {
             state_keeper_db_path,
             rocksdb_options,
+            cache_compaction_interval: None,
         }
     }
+
+    /// Enables compaction of the state keeper RocksDB cache once it processes the specified number of L1 batches
+    /// since the previous compaction.
+    pub fn with_cache_compaction(mut self, l1_batch_interval: NonZeroU32) -> Self {
+        self.cache_compaction_interval = Some(l1_batch_interval);
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -99,6 +111,16 @@ impl WiringLayer for StateKeeperLayer {
             self.rocksdb_options,
         );
 
+        let rocksdb_compaction = if let Some(l1_batch_interval) = self.cache_compaction_interval {
+            Some(StateKeeperCacheCompactionTask {
+                rocksdb_cell: storage_factory.rocksdb_cell(),
+                pool: master_pool.get_singleton().await?,
+                l1_batch_interval,
+            })
+        } else {
+            None
+        };
+
         let state_keeper = ZkSyncStateKeeper::new(
             io,
             batch_executor_base,
@@ -124,6 +146,7 @@ impl WiringLayer for StateKeeperLayer {
         Ok(Output {
             state_keeper,
             rocksdb_catchup,
+            rocksdb_compaction,
             rocksdb_termination_hook,
         })
     }
@@ -166,3 +189,28 @@ impl Task for AsyncCatchupTask {
         (*self).run(stop_receiver.0).await
     }
 }
+
+/// Task compacting the state keeper RocksDB cache once it is initialized.
+#[derive(Debug)]
+pub struct StateKeeperCacheCompactionTask {
+    rocksdb_cell: RocksdbCell,
+    pool: ConnectionPool<Core>,
+    l1_batch_interval: NonZeroU32,
+}
+
+#[async_trait::async_trait]
+impl Task for StateKeeperCacheCompactionTask {
+    fn id(&self) -> TaskId {
+        "state_keeper/rocksdb_compaction".into()
+    }
+
+    async fn run(self: Box<Self>, mut stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        let db = tokio::select! {
+            db = self.rocksdb_cell.wait() => db?,
+            _ = stop_receiver.0.changed() => return Ok(()),
+        };
+        RocksdbStorageCompactor::new(db, self.pool, self.l1_batch_interval)
+            .run(stop_receiver.0)
+            .await
+    }
+}
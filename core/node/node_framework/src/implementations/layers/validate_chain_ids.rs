@@ -4,7 +4,7 @@ use zksync_types::{L1ChainId, L2ChainId, SLChainId};
 use crate::{
     implementations::resources::{
         eth_interface::{EthInterfaceResource, GatewayEthInterfaceResource},
-        main_node_client::MainNodeClientResource,
+        main_node_client::{MainNodeClientResource, MainNodeUpstreamsResource},
     },
     service::StopReceiver,
     task::{Task, TaskId, TaskKind},
@@ -20,6 +20,7 @@ use crate::{
 /// - `EthInterfaceResource`
 /// - `GatewayEthInterfaceResource`
 /// - `MainNodeClientResource`
+/// - `MainNodeUpstreamsResource` (optional; if present, chain IDs are validated for each upstream)
 ///
 /// ## Adds preconditions
 ///
@@ -37,6 +38,7 @@ pub struct Input {
     pub l1_client: EthInterfaceResource,
     pub gateway_client: Option<GatewayEthInterfaceResource>,
     pub main_node_client: MainNodeClientResource,
+    pub main_node_upstreams: Option<MainNodeUpstreamsResource>,
 }
 
 #[derive(Debug, IntoContext)]
@@ -81,6 +83,11 @@ impl WiringLayer for ValidateChainIdsLayer {
             main_node_client,
             input.gateway_client.map(|c| c.0),
         );
+        let task = if let Some(MainNodeUpstreamsResource(upstreams)) = input.main_node_upstreams {
+            task.with_main_node_upstreams(upstreams)
+        } else {
+            task
+        };
 
         Ok(Output { task })
     }
@@ -3,7 +3,7 @@ use zksync_types::L2ChainId;
 use zksync_vm_executor::batch::MainBatchExecutorFactory;
 use zksync_vm_runner::{
     impls::{BasicWitnessInputProducer, BasicWitnessInputProducerIo},
-    ConcurrentOutputHandlerFactoryTask, StorageSyncTask,
+    ConcurrentOutputHandlerFactoryTask, StorageSyncTask, VmRunnerStorageOptions,
 };
 
 use crate::{
@@ -46,7 +46,7 @@ pub struct Output {
     pub output_handler_factory_task:
         ConcurrentOutputHandlerFactoryTask<BasicWitnessInputProducerIo>,
     #[context(task)]
-    pub loader_task: StorageSyncTask<BasicWitnessInputProducerIo>,
+    pub loader_task: Option<StorageSyncTask<BasicWitnessInputProducerIo>>,
     #[context(task)]
     pub basic_witness_input_producer: BasicWitnessInputProducer,
 }
@@ -73,7 +73,20 @@ impl WiringLayer for BasicWitnessInputProducerLayer {
         // - `window_size` connections for `BasicWitnessInputProducer`
         //   as there can be multiple output handlers holding multi-second connections to process
         //   BWIP data.
-        let connection_pool = master_pool.get_custom(self.config.window_size + 2).await?;
+        // - if snapshot storage is used, `window_size` connections for running VM instances in parallel.
+        let vm_connections = if self.config.use_snapshots {
+            self.config.window_size
+        } else {
+            0
+        };
+        let connection_pool = master_pool
+            .get_custom(self.config.window_size + vm_connections + 2)
+            .await?;
+        let storage = if self.config.use_snapshots {
+            VmRunnerStorageOptions::Snapshots { shadow: false }
+        } else {
+            VmRunnerStorageOptions::Rocksdb(self.config.db_path)
+        };
 
         // We don't get the executor from the context because it would contain state keeper-specific settings.
         let batch_executor = MainBatchExecutorFactory::<()>::new(false);
@@ -82,7 +95,7 @@ impl WiringLayer for BasicWitnessInputProducerLayer {
             connection_pool,
             object_store.0,
             Box::new(batch_executor),
-            self.config.db_path,
+            storage,
             self.zksync_network_id,
             self.config.first_processed_batch,
             self.config.window_size,
@@ -0,0 +1,135 @@
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+use zksync_types::L2ChainId;
+use zksync_vm_runner::impls::{
+    CustomVmRunner, CustomVmRunnerOptions, CustomVmRunnerTasks, VmRunnerWorkload,
+};
+
+use crate::{
+    implementations::resources::pools::{MasterPool, PoolResource},
+    service::StopReceiver,
+    task::{Task, TaskId},
+    wiring_layer::{WiringError, WiringLayer},
+    FromContext, IntoContext,
+};
+
+/// Wiring layer for custom VM runner workloads. Acts as a registry: workloads are added with
+/// [`Self::with_workload()`], and each of them is run by a dedicated VM runner with its own cursor.
+#[derive(Debug)]
+pub struct CustomVmRunnersLayer {
+    zksync_network_id: L2ChainId,
+    workloads: Vec<(Arc<dyn VmRunnerWorkload>, CustomVmRunnerOptions)>,
+}
+
+#[derive(Debug, FromContext)]
+#[context(crate = crate)]
+pub struct Input {
+    pub master_pool: PoolResource<MasterPool>,
+}
+
+#[derive(Debug, IntoContext)]
+#[context(crate = crate)]
+pub struct Output {
+    #[context(task)]
+    pub custom_vm_runners: CustomVmRunners,
+}
+
+impl CustomVmRunnersLayer {
+    pub fn new(zksync_network_id: L2ChainId) -> Self {
+        Self {
+            zksync_network_id,
+            workloads: vec![],
+        }
+    }
+
+    /// Registers a workload. Workload names must be unique.
+    pub fn with_workload(
+        mut self,
+        workload: Arc<dyn VmRunnerWorkload>,
+        options: CustomVmRunnerOptions,
+    ) -> Self {
+        self.workloads.push((workload, options));
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for CustomVmRunnersLayer {
+    type Input = Input;
+    type Output = Output;
+
+    fn layer_name(&self) -> &'static str {
+        "vm_runner_custom"
+    }
+
+    async fn wire(self, input: Self::Input) -> Result<Self::Output, WiringError> {
+        if self.workloads.is_empty() {
+            return Err(WiringError::Configuration(
+                "no custom VM runner workloads are registered".into(),
+            ));
+        }
+
+        let mut runners = Vec::with_capacity(self.workloads.len());
+        for (workload, options) in self.workloads {
+            let name = workload.name();
+            if runners
+                .iter()
+                .any(|(existing_name, ..)| *existing_name == name)
+            {
+                return Err(WiringError::Configuration(format!(
+                    "custom VM runner workload `{name}` is registered multiple times"
+                )));
+            }
+
+            // - 1 connection for `StorageSyncTask` which can hold a long-term connection in case it needs to
+            //   catch up cache.
+            // - 1 connection for `ConcurrentOutputHandlerFactoryTask` / `VmRunner` as they need occasional access
+            //   to DB for querying last processed batch and last ready to be loaded batch.
+            // - `window_size` connections for running VM instances (only used with snapshot storage).
+            // - `window_size` connections for the workload processing batch outputs.
+            let pool = input
+                .master_pool
+                .get_custom(2 * options.window_size + 2)
+                .await?;
+            let (runner, tasks) =
+                CustomVmRunner::new(pool, workload, self.zksync_network_id, options).await?;
+            runners.push((name, runner, tasks));
+        }
+        Ok(Output {
+            custom_vm_runners: CustomVmRunners { runners },
+        })
+    }
+}
+
+/// Task running all registered custom VM runner workloads.
+#[derive(Debug)]
+pub struct CustomVmRunners {
+    runners: Vec<(&'static str, CustomVmRunner, CustomVmRunnerTasks)>,
+}
+
+#[async_trait::async_trait]
+impl Task for CustomVmRunners {
+    fn id(&self) -> TaskId {
+        "vm_runner/custom".into()
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        let mut futures: Vec<BoxFuture<'static, anyhow::Result<()>>> = vec![];
+        for (name, runner, tasks) in self.runners {
+            tracing::info!("Starting custom VM runner workload `{name}`");
+            if let Some(loader_task) = tasks.loader_task {
+                futures.push(Box::pin(loader_task.run(stop_receiver.0.clone())));
+            }
+            futures.push(Box::pin(
+                tasks
+                    .output_handler_factory_task
+                    .run(stop_receiver.0.clone()),
+            ));
+            let stop_receiver = stop_receiver.0.clone();
+            futures.push(Box::pin(async move { runner.run(&stop_receiver).await }));
+        }
+        futures::future::try_join_all(futures).await?;
+        Ok(())
+    }
+}
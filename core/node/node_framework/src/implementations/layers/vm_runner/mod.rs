@@ -6,6 +6,7 @@ use crate::{
 };
 
 pub mod bwip;
+pub mod custom;
 pub mod playground;
 pub mod protective_reads;
 
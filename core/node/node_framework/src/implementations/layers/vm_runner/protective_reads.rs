@@ -3,7 +3,7 @@ use zksync_node_framework_derive::FromContext;
 use zksync_types::L2ChainId;
 use zksync_vm_runner::{
     impls::{ProtectiveReadsIo, ProtectiveReadsWriter},
-    ConcurrentOutputHandlerFactoryTask, StorageSyncTask,
+    ConcurrentOutputHandlerFactoryTask, StorageSyncTask, VmRunnerStorageOptions,
 };
 
 use crate::{
@@ -33,7 +33,7 @@ pub struct Output {
     #[context(task)]
     pub protective_reads_writer: ProtectiveReadsWriter,
     #[context(task)]
-    pub loader_task: StorageSyncTask<ProtectiveReadsIo>,
+    pub loader_task: Option<StorageSyncTask<ProtectiveReadsIo>>,
     #[context(task)]
     pub output_handler_factory_task: ConcurrentOutputHandlerFactoryTask<ProtectiveReadsIo>,
 }
@@ -62,23 +62,36 @@ impl WiringLayer for ProtectiveReadsWriterLayer {
     async fn wire(self, input: Self::Input) -> Result<Self::Output, WiringError> {
         let master_pool = input.master_pool;
 
+        let config = self.protective_reads_writer_config;
+        // One for `StorageSyncTask` which can hold a long-term connection in case it needs to
+        // catch up cache.
+        //
+        // One for `ConcurrentOutputHandlerFactoryTask`/`VmRunner` as they need occasional access
+        // to DB for querying last processed batch and last ready to be loaded batch.
+        //
+        // `window_size` connections for `ProtectiveReadsOutputHandlerFactory`
+        // as there can be multiple output handlers holding multi-second connections to write
+        // large amount of protective reads.
+        //
+        // If snapshot storage is used, `window_size` connections for running VM instances in parallel.
+        let vm_connections = if config.use_snapshots {
+            config.window_size
+        } else {
+            0
+        };
+        let storage = if config.use_snapshots {
+            VmRunnerStorageOptions::Snapshots { shadow: false }
+        } else {
+            VmRunnerStorageOptions::Rocksdb(config.db_path)
+        };
         let (protective_reads_writer, tasks) = ProtectiveReadsWriter::new(
-            // One for `StorageSyncTask` which can hold a long-term connection in case it needs to
-            // catch up cache.
-            //
-            // One for `ConcurrentOutputHandlerFactoryTask`/`VmRunner` as they need occasional access
-            // to DB for querying last processed batch and last ready to be loaded batch.
-            //
-            // `window_size` connections for `ProtectiveReadsOutputHandlerFactory`
-            // as there can be multiple output handlers holding multi-second connections to write
-            // large amount of protective reads.
             master_pool
-                .get_custom(self.protective_reads_writer_config.window_size + 2)
+                .get_custom(config.window_size + vm_connections + 2)
                 .await?,
-            self.protective_reads_writer_config.db_path,
+            storage,
             self.zksync_network_id,
-            self.protective_reads_writer_config.first_processed_batch,
-            self.protective_reads_writer_config.window_size,
+            config.first_processed_batch,
+            config.window_size,
         )
         .await?;
 
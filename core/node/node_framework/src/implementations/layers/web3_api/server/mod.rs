@@ -2,8 +2,9 @@ use std::{num::NonZeroU32, time::Duration};
 
 use tokio::{sync::oneshot, task::JoinHandle};
 use zksync_circuit_breaker::replication_lag::ReplicationLagChecker;
-use zksync_config::configs::api::MaxResponseSize;
+use zksync_config::configs::api::{ApiKeysConfig, MaxResponseSize};
 use zksync_node_api_server::web3::{
+    backend_jsonrpsee::ReadYourWritesConfig,
     state::{BridgeAddressesHandle, InternalApiConfig, SealedL2BlockNumber},
     ApiBuilder, ApiServer, Namespace,
 };
@@ -17,7 +18,7 @@ use crate::{
             circuit_breakers::CircuitBreakersResource,
             healthcheck::AppHealthCheckResource,
             main_node_client::MainNodeClientResource,
-            pools::{PoolResource, ReplicaPool},
+            pools::{MasterPool, PoolResource, ReplicaPool},
             sync_state::SyncStateResource,
             web3_api::{MempoolCacheResource, TreeApiClientResource, TxSenderResource},
         },
@@ -41,6 +42,9 @@ pub struct Web3ServerOptionalConfig {
     pub response_body_size_limit: Option<MaxResponseSize>,
     pub websocket_requests_per_minute_limit: Option<NonZeroU32>,
     pub with_extended_tracing: bool,
+    pub api_keys: Option<ApiKeysConfig>,
+    /// If set, enables read-your-writes consistency for the HTTP server. Requires the master pool.
+    pub read_your_writes_max_wait: Option<Duration>,
     // Used by circuit breaker.
     pub replication_lag_limit: Option<Duration>,
     // Used by the external node.
@@ -79,6 +83,9 @@ impl Web3ServerOptionalConfig {
             api_builder =
                 api_builder.with_pruning_info_refresh_interval(pruning_info_refresh_interval);
         }
+        if let Some(api_keys) = self.api_keys {
+            api_builder = api_builder.with_api_keys(api_keys);
+        }
         api_builder = api_builder.with_extended_tracing(self.with_extended_tracing);
         api_builder
     }
@@ -96,6 +103,7 @@ enum Transport {
 /// ## Requests resources
 ///
 /// - `PoolResource<ReplicaPool>`
+/// - `PoolResource<MasterPool>` (optional; required if read-your-writes consistency is enabled)
 /// - `TxSenderResource`
 /// - `SyncStateResource` (optional)
 /// - `TreeApiClientResource` (optional)
@@ -119,6 +127,7 @@ pub struct Web3ServerLayer {
 #[context(crate = crate)]
 pub struct Input {
     pub replica_pool: PoolResource<ReplicaPool>,
+    pub master_pool: Option<PoolResource<MasterPool>>,
     pub tx_sender: TxSenderResource,
     pub sync_state: Option<SyncStateResource>,
     pub tree_api_client: Option<TreeApiClientResource>,
@@ -233,6 +242,17 @@ impl WiringLayer for Web3ServerLayer {
         if let Some(sync_state) = sync_state {
             api_builder = api_builder.with_sync_state(sync_state);
         }
+        if let Some(max_wait) = self.optional_config.read_your_writes_max_wait {
+            let master_pool = input.master_pool.ok_or_else(|| {
+                WiringError::Configuration(
+                    "read-your-writes consistency requires the master pool".to_owned(),
+                )
+            })?;
+            api_builder = api_builder.with_read_your_writes(ReadYourWritesConfig {
+                master_pool: master_pool.get().await?,
+                max_wait,
+            });
+        }
         let replication_lag_limit = self.optional_config.replication_lag_limit;
         api_builder = self.optional_config.apply(api_builder);
 
@@ -1,11 +1,11 @@
-use std::{sync::Arc, time::Duration};
+use std::{path::PathBuf, sync::Arc, time::Duration};
 
 use tokio::sync::RwLock;
 use zksync_node_api_server::{
     execution_sandbox::{VmConcurrencyBarrier, VmConcurrencyLimiter},
     tx_sender::{SandboxExecutorOptions, TxSenderBuilder, TxSenderConfig},
 };
-use zksync_state::{PostgresStorageCaches, PostgresStorageCachesTask};
+use zksync_state::{PostgresStorageCaches, PostgresStorageCachesTask, WarmValuesStorage};
 use zksync_types::{vm::FastVmMode, AccountTreeId, Address};
 use zksync_web3_decl::{
     client::{DynClient, L2},
@@ -33,6 +33,8 @@ pub struct PostgresStorageCachesConfig {
     pub initial_writes_cache_size: u64,
     pub latest_values_cache_size: u64,
     pub latest_values_max_block_lag: u32,
+    /// Path to the RocksDB-backed warm storage for the latest values cache. Ignored if the latest values cache is disabled.
+    pub latest_values_warm_storage_path: Option<PathBuf>,
 }
 
 /// Wiring layer for the `TxSender`.
@@ -142,6 +144,13 @@ impl WiringLayer for TxSenderLayer {
             PostgresStorageCaches::new(factory_deps_capacity, initial_writes_capacity);
 
         let postgres_storage_caches_task = if values_capacity > 0 {
+            if let Some(path) = &self
+                .postgres_storage_caches_config
+                .latest_values_warm_storage_path
+            {
+                let warm_values = WarmValuesStorage::new(path.clone()).await?;
+                storage_caches.configure_warm_values_storage(warm_values);
+            }
             let update_task = storage_caches.configure_storage_values_cache(
                 values_capacity,
                 self.postgres_storage_caches_config
@@ -150,6 +159,16 @@ impl WiringLayer for TxSenderLayer {
             );
             Some(update_task)
         } else {
+            if self
+                .postgres_storage_caches_config
+                .latest_values_warm_storage_path
+                .is_some()
+            {
+                tracing::warn!(
+                    "Warm storage for the latest values cache is configured, but the cache itself is disabled; \
+                     the warm storage will not be used"
+                );
+            }
             None
         };
 
@@ -0,0 +1,76 @@
+use zksync_node_api_server::tx_sender::forwarding_queue::{
+    TxForwarder, TxForwardingConfig, TxForwardingQueue,
+};
+
+use crate::{
+    implementations::resources::{
+        main_node_client::MainNodeClientResource,
+        pools::{MasterPool, PoolResource},
+        web3_api::TxSinkResource,
+    },
+    service::StopReceiver,
+    task::{Task, TaskId},
+    wiring_layer::{WiringError, WiringLayer},
+    FromContext, IntoContext,
+};
+
+/// Wiring layer for [`TxForwardingQueue`], [`TxSink`](zksync_node_api_server::tx_sender::tx_sink::TxSink)
+/// implementation persisting transactions before forwarding them to the main node.
+#[derive(Debug)]
+pub struct ForwardingQueueSinkLayer {
+    config: TxForwardingConfig,
+}
+
+impl ForwardingQueueSinkLayer {
+    pub fn new(config: TxForwardingConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[derive(Debug, FromContext)]
+#[context(crate = crate)]
+pub struct Input {
+    pub main_node_client: MainNodeClientResource,
+    pub master_pool: PoolResource<MasterPool>,
+}
+
+#[derive(Debug, IntoContext)]
+#[context(crate = crate)]
+pub struct Output {
+    pub tx_sink: TxSinkResource,
+    #[context(task)]
+    pub tx_forwarder: TxForwarder,
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for ForwardingQueueSinkLayer {
+    type Input = Input;
+    type Output = Output;
+
+    fn layer_name(&self) -> &'static str {
+        "forwarding_queue_sink_layer"
+    }
+
+    async fn wire(self, input: Self::Input) -> Result<Self::Output, WiringError> {
+        let MainNodeClientResource(client) = input.main_node_client;
+        let pool = input.master_pool.get().await?;
+        let queue = TxForwardingQueue::new(pool);
+        let tx_forwarder = queue.forwarder(client, self.config);
+
+        Ok(Output {
+            tx_sink: queue.into(),
+            tx_forwarder,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for TxForwarder {
+    fn id(&self) -> TaskId {
+        "tx_forwarder".into()
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        (*self).run(stop_receiver.0).await
+    }
+}
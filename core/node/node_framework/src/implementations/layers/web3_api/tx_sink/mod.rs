@@ -1,4 +1,8 @@
-pub use self::{master_pool_sink::MasterPoolSinkLayer, proxy_sink::ProxySinkLayer};
+pub use self::{
+    forwarding_queue_sink::ForwardingQueueSinkLayer, master_pool_sink::MasterPoolSinkLayer,
+    proxy_sink::ProxySinkLayer,
+};
 
+pub mod forwarding_queue_sink;
 pub mod master_pool_sink;
 pub mod proxy_sink;
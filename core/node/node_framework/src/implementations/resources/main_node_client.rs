@@ -17,3 +17,14 @@ impl<T: Into<Box<DynClient<L2>>>> From<T> for MainNodeClientResource {
         Self(client.into())
     }
 }
+
+/// A resource that provides clients for all upstreams of the [`MainNodeClientResource`], if the main node client
+/// is configured with multiple upstreams.
+#[derive(Debug, Clone)]
+pub struct MainNodeUpstreamsResource(pub Vec<Box<DynClient<L2>>>);
+
+impl Resource for MainNodeUpstreamsResource {
+    fn name() -> String {
+        "external_node/main_node_upstreams".into()
+    }
+}
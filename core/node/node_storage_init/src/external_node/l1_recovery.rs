@@ -0,0 +1,61 @@
+use std::{sync::Arc, time::Instant};
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_dal::{ConnectionPool, Core};
+use zksync_health_check::AppHealthCheck;
+use zksync_l1_recovery::{L1RecoveryConfig, L1RecoveryTask};
+use zksync_shared_metrics::{SnapshotRecoveryStage, APP_METRICS};
+use zksync_types::L2ChainId;
+use zksync_web3_decl::client::{DynClient, L1, L2};
+
+use crate::InitializeStorage;
+
+/// Recovers node storage from pubdata published on L1. The main node is only used to fetch genesis params;
+/// the recovered state is checked against state roots committed on L1.
+#[derive(Debug)]
+pub struct ExternalNodeL1Recovery {
+    pub l2_chain_id: L2ChainId,
+    pub client: Box<DynClient<L2>>,
+    pub l1_client: Box<DynClient<L1>>,
+    pub pool: ConnectionPool<Core>,
+    pub recovery_config: L1RecoveryConfig,
+    pub app_health: Arc<AppHealthCheck>,
+}
+
+#[async_trait::async_trait]
+impl InitializeStorage for ExternalNodeL1Recovery {
+    async fn initialize_storage(&self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        tracing::warn!("Proceeding with recovery from L1. This is an experimental feature; use at your own risk");
+
+        let genesis_params = zksync_node_sync::genesis::create_genesis_params(
+            &self.client.clone().for_component("genesis"),
+            self.l2_chain_id,
+        )
+        .await
+        .context("failed fetching genesis params")?;
+        let task = L1RecoveryTask::new(
+            self.recovery_config.clone(),
+            self.l1_client.clone(),
+            self.pool.clone(),
+            genesis_params,
+        );
+        self.app_health.insert_component(task.health_check())?;
+
+        let recovery_started_at = Instant::now();
+        let stats = task
+            .run(stop_receiver)
+            .await
+            .context("recovery from L1 failed")?;
+        if let Some(stats) = stats {
+            let latency = recovery_started_at.elapsed();
+            APP_METRICS.snapshot_recovery_latency[&SnapshotRecoveryStage::Postgres].set(latency);
+            tracing::info!("Recovered Postgres from L1 in {latency:?}: {stats:?}");
+        }
+        Ok(())
+    }
+
+    async fn is_initialized(&self) -> anyhow::Result<bool> {
+        L1RecoveryTask::is_recovery_completed(&self.pool).await
+    }
+}
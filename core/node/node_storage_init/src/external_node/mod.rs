@@ -1,8 +1,9 @@
 pub use self::{
-    genesis::ExternalNodeGenesis, revert::ExternalNodeReverter,
-    snapshot_recovery::ExternalNodeSnapshotRecovery,
+    genesis::ExternalNodeGenesis, l1_recovery::ExternalNodeL1Recovery,
+    revert::ExternalNodeReverter, snapshot_recovery::ExternalNodeSnapshotRecovery,
 };
 
 mod genesis;
+mod l1_recovery;
 mod revert;
 mod snapshot_recovery;
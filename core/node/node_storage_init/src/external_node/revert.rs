@@ -2,7 +2,7 @@ use anyhow::Context as _;
 use tokio::sync::watch;
 use zksync_block_reverter::BlockReverter;
 use zksync_dal::{ConnectionPool, Core};
-use zksync_reorg_detector::ReorgDetector;
+use zksync_reorg_detector::{ReorgDetector, ReorgIncidents};
 use zksync_types::L1BatchNumber;
 use zksync_web3_decl::client::{DynClient, L2};
 
@@ -13,6 +13,9 @@ pub struct ExternalNodeReverter {
     pub client: Box<DynClient<L2>>,
     pub pool: ConnectionPool<Core>,
     pub reverter: Option<BlockReverter>,
+    /// Incidents recorded by the reorg detector in the auto-remediation mode. If there is a pending incident,
+    /// it's used instead of re-running reorg detection, and is marked as remediated after the revert.
+    pub reorg_incidents: Option<ReorgIncidents>,
 }
 
 #[async_trait::async_trait]
@@ -31,6 +34,9 @@ impl RevertStorage for ExternalNodeReverter {
         tracing::info!("Reverting to l1 batch number {to_batch}");
         block_reverter.roll_back(to_batch).await?;
         tracing::info!("Revert successfully completed");
+        if let Some(incidents) = &self.reorg_incidents {
+            incidents.mark_reverted(to_batch);
+        }
         Ok(())
     }
 
@@ -38,6 +44,17 @@ impl RevertStorage for ExternalNodeReverter {
         &self,
         stop_receiver: watch::Receiver<bool>,
     ) -> anyhow::Result<Option<L1BatchNumber>> {
+        if let Some(to_batch) = self
+            .reorg_incidents
+            .as_ref()
+            .and_then(ReorgIncidents::pending_revert)
+        {
+            tracing::info!(
+                "Reorg was detected before the restart; last correct L1 batch is #{to_batch}"
+            );
+            return Ok(Some(to_batch));
+        }
+
         let mut reorg_detector = ReorgDetector::new(self.client.clone(), self.pool.clone());
         let batch = match reorg_detector.run_once(stop_receiver).await {
             Ok(()) => {
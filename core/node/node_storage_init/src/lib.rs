@@ -3,6 +3,7 @@ use std::{future::Future, sync::Arc, time::Duration};
 use tokio::sync::watch;
 use zksync_config::ObjectStoreConfig;
 use zksync_dal::{ConnectionPool, Core, CoreDal as _};
+pub use zksync_l1_recovery::L1RecoveryConfig;
 use zksync_types::L1BatchNumber;
 
 pub use crate::traits::{InitializeStorage, RevertStorage};
@@ -31,7 +31,8 @@ pub async fn perform_genesis_if_needed(
     Ok(())
 }
 
-async fn create_genesis_params(
+/// Creates genesis params based on the genesis config and contracts fetched from the main node.
+pub async fn create_genesis_params(
     client: &dyn MainNodeClient,
     zksync_chain_id: L2ChainId,
 ) -> anyhow::Result<GenesisParams> {
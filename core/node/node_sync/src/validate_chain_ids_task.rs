@@ -19,7 +19,7 @@ pub struct ValidateChainIdsTask {
     l2_chain_id: L2ChainId,
     gateway_chain_id: Option<SLChainId>,
     l1_client: Box<DynClient<L1>>,
-    main_node_client: Box<DynClient<L2>>,
+    main_node_clients: Vec<Box<DynClient<L2>>>,
     gateway_client: Option<Box<DynClient<L1>>>,
 }
 
@@ -39,11 +39,25 @@ impl ValidateChainIdsTask {
             l2_chain_id,
             gateway_chain_id,
             l1_client: l1_client.for_component("chain_ids_validation"),
-            main_node_client: main_node_client.for_component("chain_ids_validation"),
+            main_node_clients: vec![main_node_client.for_component("chain_ids_validation")],
             gateway_client: gateway_client.map(|c| c.for_component("chain_ids_validation")),
         }
     }
 
+    /// Sets clients for all upstreams of the main node client (e.g., if the main node client is
+    /// a [`FailoverClient`](zksync_web3_decl::client::FailoverClient)). If set, chain IDs are checked
+    /// for each of the upstreams rather than for the main node client, which only queries a single upstream at a time.
+    #[must_use]
+    pub fn with_main_node_upstreams(mut self, upstreams: Vec<Box<DynClient<L2>>>) -> Self {
+        if !upstreams.is_empty() {
+            self.main_node_clients = upstreams
+                .into_iter()
+                .map(|client| client.for_component("chain_ids_validation"))
+                .collect();
+        }
+        self
+    }
+
     async fn check_client(
         l1_client: Option<Box<DynClient<L1>>>,
         expected: Option<SLChainId>,
@@ -146,23 +160,34 @@ impl ValidateChainIdsTask {
         }
     }
 
+    /// Checks L1 and L2 chain IDs for all main node clients.
+    async fn check_main_node_clients(
+        main_node_clients: Vec<Box<DynClient<L2>>>,
+        l1_chain_id: L1ChainId,
+        l2_chain_id: L2ChainId,
+    ) -> anyhow::Result<()> {
+        let checks = main_node_clients.into_iter().flat_map(|client| {
+            [
+                Self::check_l1_chain_using_main_node(client.clone(), l1_chain_id).boxed(),
+                Self::check_l2_chain_using_main_node(client, l2_chain_id).boxed(),
+            ]
+        });
+        futures::future::try_join_all(checks).await.map(drop)
+    }
+
     /// Runs the task once, exiting either when all the checks are performed or when the stop signal is received.
     pub async fn run_once(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
         let l1_client_check =
             Self::check_client(Some(self.l1_client), Some(self.l1_chain_id.0.into()));
-        let main_node_l1_check =
-            Self::check_l1_chain_using_main_node(self.main_node_client.clone(), self.l1_chain_id);
-        let main_node_l2_check =
-            Self::check_l2_chain_using_main_node(self.main_node_client, self.l2_chain_id);
+        let main_node_check = Self::check_main_node_clients(
+            self.main_node_clients,
+            self.l1_chain_id,
+            self.l2_chain_id,
+        );
         let gateway_check = Self::check_client(self.gateway_client, self.gateway_chain_id);
 
-        let joined_futures = futures::future::try_join4(
-            l1_client_check,
-            main_node_l1_check,
-            main_node_l2_check,
-            gateway_check,
-        )
-        .fuse();
+        let joined_futures =
+            futures::future::try_join3(l1_client_check, main_node_check, gateway_check).fuse();
         tokio::select! {
             res = joined_futures => res.map(drop),
             _ = stop_receiver.changed() =>  Ok(()),
@@ -175,16 +200,16 @@ impl ValidateChainIdsTask {
         // so we'll just wait for another check or a stop signal.
         let l1_client_check =
             Self::check_client(Some(self.l1_client), Some(self.l1_chain_id.0.into())).fuse();
-        let main_node_l1_check =
-            Self::check_l1_chain_using_main_node(self.main_node_client.clone(), self.l1_chain_id)
-                .fuse();
-        let main_node_l2_check =
-            Self::check_l2_chain_using_main_node(self.main_node_client, self.l2_chain_id).fuse();
+        let main_node_check = Self::check_main_node_clients(
+            self.main_node_clients,
+            self.l1_chain_id,
+            self.l2_chain_id,
+        )
+        .fuse();
         let gateway_check = Self::check_client(self.gateway_client, self.gateway_chain_id).fuse();
         tokio::select! {
             Err(err) = l1_client_check =>  Err(err),
-            Err(err) = main_node_l1_check =>  Err(err),
-            Err(err) = main_node_l2_check =>  Err(err),
+            Err(err) = main_node_check =>  Err(err),
             Err(err) = gateway_check =>  Err(err),
             _ = stop_receiver.changed() =>  Ok(()),
         }
@@ -269,6 +294,44 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn validating_chain_ids_for_all_main_node_upstreams() {
+        let eth_client = MockClient::builder(L1::default())
+            .method("eth_chainId", || Ok(U64::from(9)))
+            .build();
+        let main_node_client = MockClient::builder(L2::default())
+            .method("eth_chainId", || Ok(U64::from(270)))
+            .method("zks_L1ChainId", || Ok(U64::from(9)))
+            .build();
+        let misconfigured_upstream = MockClient::builder(L2::default())
+            .method("eth_chainId", || Ok(U64::from(271)))
+            .method("zks_L1ChainId", || Ok(U64::from(9)))
+            .build();
+
+        let validation_task = ValidateChainIdsTask::new(
+            L1ChainId(9),
+            L2ChainId::from(270),
+            None,
+            Box::new(eth_client),
+            Box::new(main_node_client.clone()),
+            None,
+        )
+        .with_main_node_upstreams(vec![
+            Box::new(main_node_client),
+            Box::new(misconfigured_upstream),
+        ]);
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        let err = validation_task
+            .run_once(stop_receiver)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("L2 chain ID") && err.contains("main node"),
+            "{err}"
+        );
+    }
+
     #[tokio::test]
     async fn validating_chain_ids_success() {
         let eth_client = MockClient::builder(L1::default())
@@ -1,7 +1,14 @@
-use std::{fmt, future::Future, time::Duration};
+use std::{
+    fmt,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use anyhow::Context as _;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
 use tokio::sync::watch;
 use zksync_dal::{ConnectionPool, Core, CoreDal, DalError};
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
@@ -103,6 +110,70 @@ impl From<EnrichedClientError> for Error {
     }
 }
 
+/// Status of a [`ReorgIncident`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReorgIncidentStatus {
+    /// The reorg is detected, but the node storage is not reverted yet.
+    RevertPending,
+    /// The node storage was reverted to the last correct L1 batch.
+    Reverted,
+}
+
+/// Reorg detected by [`ReorgDetector`] running in the auto-remediation mode.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReorgIncident {
+    pub detected_at: DateTime<Utc>,
+    pub last_correct_l1_batch: L1BatchNumber,
+    pub status: ReorgIncidentStatus,
+    pub reverted_at: Option<DateTime<Utc>>,
+}
+
+/// History of reorg incidents shared among node components. Since the history is stored in memory,
+/// it only survives node restarts performed within the same process.
+#[derive(Debug, Clone, Default)]
+pub struct ReorgIncidents(Arc<Mutex<Vec<ReorgIncident>>>);
+
+impl ReorgIncidents {
+    /// Returns all recorded incidents in the order of their detection.
+    pub fn all(&self) -> Vec<ReorgIncident> {
+        self.0.lock().expect("incidents are poisoned").clone()
+    }
+
+    /// Returns the last correct L1 batch for the incident that is not remediated yet, if any.
+    pub fn pending_revert(&self) -> Option<L1BatchNumber> {
+        let incidents = self.0.lock().expect("incidents are poisoned");
+        incidents
+            .last()
+            .filter(|incident| incident.status == ReorgIncidentStatus::RevertPending)
+            .map(|incident| incident.last_correct_l1_batch)
+    }
+
+    fn record(&self, last_correct_l1_batch: L1BatchNumber) {
+        let mut incidents = self.0.lock().expect("incidents are poisoned");
+        incidents.push(ReorgIncident {
+            detected_at: Utc::now(),
+            last_correct_l1_batch,
+            status: ReorgIncidentStatus::RevertPending,
+            reverted_at: None,
+        });
+    }
+
+    /// Marks the pending incident as remediated after the node storage was reverted to `l1_batch`.
+    pub fn mark_reverted(&self, l1_batch: L1BatchNumber) {
+        let mut incidents = self.0.lock().expect("incidents are poisoned");
+        let Some(incident) = incidents.last_mut() else {
+            return;
+        };
+        if incident.status == ReorgIncidentStatus::RevertPending
+            && incident.last_correct_l1_batch == l1_batch
+        {
+            incident.status = ReorgIncidentStatus::Reverted;
+            incident.reverted_at = Some(Utc::now());
+        }
+    }
+}
+
 #[async_trait]
 trait MainNodeClient: fmt::Debug + Send + Sync {
     async fn sealed_l2_block_number(&self) -> EnrichedClientResult<L2BlockNumber>;
@@ -169,6 +240,9 @@ impl MainNodeClient for Box<DynClient<L2>> {
 trait HandleReorgDetectorEvent: fmt::Debug + Send + Sync {
     fn initialize(&mut self);
 
+    /// Starts reporting the provided incident history. No-op by default.
+    fn track_incidents(&mut self, _incidents: ReorgIncidents) {}
+
     fn update_correct_block(
         &mut self,
         last_correct_l2_block: L2BlockNumber,
@@ -180,10 +254,46 @@ trait HandleReorgDetectorEvent: fmt::Debug + Send + Sync {
     fn start_shutting_down(&mut self);
 }
 
-/// Default implementation of [`HandleReorgDetectorEvent`] that reports values as metrics.
-impl HandleReorgDetectorEvent for HealthUpdater {
+/// Default implementation of [`HandleReorgDetectorEvent`] that reports values as metrics and via health checks.
+#[derive(Debug)]
+struct HealthReporter {
+    updater: HealthUpdater,
+    incidents: Option<ReorgIncidents>,
+}
+
+impl HealthReporter {
+    fn new(updater: HealthUpdater) -> Self {
+        Self {
+            updater,
+            incidents: None,
+        }
+    }
+
+    fn update(&self, health: Health, details: Option<serde_json::Value>) {
+        let incidents = self.incidents.as_ref().map(ReorgIncidents::all);
+        let details = match (details, incidents) {
+            (Some(mut details), Some(incidents)) => {
+                details["incidents"] = serde_json::json!(incidents);
+                Some(details)
+            }
+            (None, Some(incidents)) => Some(serde_json::json!({ "incidents": incidents })),
+            (details, None) => details,
+        };
+        let health = match details {
+            Some(details) => health.with_details(details),
+            None => health,
+        };
+        self.updater.update(health);
+    }
+}
+
+impl HandleReorgDetectorEvent for HealthReporter {
     fn initialize(&mut self) {
-        self.update(Health::from(HealthStatus::Ready));
+        self.update(Health::from(HealthStatus::Ready), None);
+    }
+
+    fn track_incidents(&mut self, incidents: ReorgIncidents) {
+        self.incidents = Some(incidents);
     }
 
     fn update_correct_block(
@@ -210,18 +320,18 @@ impl HandleReorgDetectorEvent for HealthUpdater {
             "last_correct_l2_block": last_correct_l2_block,
             "last_correct_l1_batch": last_correct_l1_batch,
         });
-        self.update(Health::from(HealthStatus::Ready).with_details(health_details));
+        self.update(Health::from(HealthStatus::Ready), Some(health_details));
     }
 
     fn report_divergence(&mut self, diverged_l1_batch: L1BatchNumber) {
         let health_details = serde_json::json!({
             "diverged_l1_batch": diverged_l1_batch,
         });
-        self.update(Health::from(HealthStatus::Affected).with_details(health_details));
+        self.update(Health::from(HealthStatus::Affected), Some(health_details));
     }
 
     fn start_shutting_down(&mut self) {
-        self.update(HealthStatus::ShuttingDown.into());
+        self.update(HealthStatus::ShuttingDown.into(), None);
     }
 }
 
@@ -239,6 +349,10 @@ impl HandleReorgDetectorEvent for HealthUpdater {
 /// This is the only component that is expected to finish its execution
 /// in the event of re-org, since we have to restart the node after a rollback is performed,
 /// and is special-cased in the `zksync_external_node` crate.
+///
+/// In the auto-remediation mode (see [`Self::with_auto_remediation()`]), a detected reorg is recorded as a [`ReorgIncident`]
+/// and the detector finishes successfully instead of returning [`Error::ReorgDetected`]. The node is then expected
+/// to stop all its tasks, revert its storage to the last correct L1 batch and restart in-process.
 #[derive(Debug)]
 pub struct ReorgDetector {
     client: Box<dyn MainNodeClient>,
@@ -246,6 +360,7 @@ pub struct ReorgDetector {
     pool: ConnectionPool<Core>,
     sleep_interval: Duration,
     health_check: ReactiveHealthCheck,
+    incidents: Option<ReorgIncidents>,
 }
 
 impl ReorgDetector {
@@ -255,13 +370,22 @@ impl ReorgDetector {
         let (health_check, health_updater) = ReactiveHealthCheck::new("reorg_detector");
         Self {
             client: Box::new(client.for_component("reorg_detector")),
-            event_handler: Box::new(health_updater),
+            event_handler: Box::new(HealthReporter::new(health_updater)),
             pool,
             sleep_interval: Self::DEFAULT_SLEEP_INTERVAL,
             health_check,
+            incidents: None,
         }
     }
 
+    /// Enables the auto-remediation mode. Detected reorgs will be recorded to `incidents`, which are also exposed
+    /// in the health check details.
+    pub fn with_auto_remediation(mut self, incidents: ReorgIncidents) -> Self {
+        self.event_handler.track_incidents(incidents.clone());
+        self.incidents = Some(incidents);
+        self
+    }
+
     pub fn health_check(&self) -> &ReactiveHealthCheck {
         &self.health_check
     }
@@ -440,9 +564,24 @@ impl ReorgDetector {
 
     /// Runs this detector continuously checking for a reorg until a fatal error occurs (including if a reorg is detected),
     /// or a stop signal is received.
+    ///
+    /// In the auto-remediation mode, a detected reorg is recorded as an incident, and the method returns `Ok(())`.
     pub async fn run(mut self, stop_receiver: watch::Receiver<bool>) -> Result<(), Error> {
         self.event_handler.initialize();
-        self.run_inner(false, stop_receiver).await?;
+        match self.run_inner(false, stop_receiver).await {
+            Err(Error::ReorgDetected(last_correct_l1_batch)) if self.incidents.is_some() => {
+                tracing::warn!(
+                    "Reorg detected; the node will be reverted to the last correct L1 batch #{last_correct_l1_batch} and restarted"
+                );
+                if let Some(incidents) = &self.incidents {
+                    incidents.record(last_correct_l1_batch);
+                }
+                self.event_handler
+                    .report_divergence(last_correct_l1_batch + 1);
+                return Ok(());
+            }
+            res => res?,
+        }
         self.event_handler.start_shutting_down();
         tracing::info!("Shutting down reorg detector");
         Ok(())
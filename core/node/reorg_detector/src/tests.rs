@@ -12,6 +12,7 @@ use assert_matches::assert_matches;
 use test_casing::{test_casing, Product};
 use tokio::sync::mpsc;
 use zksync_dal::{Connection, CoreDal};
+use zksync_health_check::CheckHealth;
 use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
 use zksync_node_test_utils::{create_l1_batch, create_l2_block};
 use zksync_types::{
@@ -166,10 +167,11 @@ fn create_mock_detector(
     let (health_check, health_updater) = ReactiveHealthCheck::new("reorg_detector");
     ReorgDetector {
         client: Box::new(client),
-        event_handler: Box::new(health_updater),
+        event_handler: Box::new(HealthReporter::new(health_updater)),
         pool,
         sleep_interval: Duration::from_millis(10),
         health_check,
+        incidents: None,
     }
 }
 
@@ -623,6 +625,83 @@ async fn reorg_is_detected_based_on_l2_block_hashes(last_correct_l1_batch: u32)
     );
 }
 
+#[tokio::test]
+async fn reorg_is_recorded_in_auto_remediation_mode() {
+    const LAST_CORRECT_L1_BATCH: u32 = 3;
+
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    let genesis_batch = insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    let mut client = MockMainNodeClient::default();
+    client.l2_block_hashes.insert(
+        L2BlockNumber(0),
+        L2BlockHasher::legacy_hash(L2BlockNumber(0)),
+    );
+    client
+        .l1_batch_root_hashes
+        .insert(L1BatchNumber(0), Ok(genesis_batch.root_hash));
+    for number in 1..=5 {
+        let l2_block_hash = H256::from_low_u64_be(number.into());
+        store_l2_block(&mut storage, number, l2_block_hash).await;
+        client
+            .l2_block_hashes
+            .insert(L2BlockNumber(number), l2_block_hash);
+        let l1_batch_root_hash = H256::repeat_byte(number as u8);
+        seal_l1_batch(&mut storage, number, l1_batch_root_hash).await;
+        let remote_root_hash = if number <= LAST_CORRECT_L1_BATCH {
+            l1_batch_root_hash
+        } else {
+            H256::zero()
+        };
+        client
+            .l1_batch_root_hashes
+            .insert(L1BatchNumber(number), Ok(remote_root_hash));
+    }
+    drop(storage);
+
+    let incidents = ReorgIncidents::default();
+    let detector =
+        create_mock_detector(client, pool.clone()).with_auto_remediation(incidents.clone());
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    detector.run(stop_receiver).await.unwrap();
+
+    assert_eq!(
+        incidents.pending_revert(),
+        Some(L1BatchNumber(LAST_CORRECT_L1_BATCH))
+    );
+    let recorded_incidents = incidents.all();
+    assert_eq!(recorded_incidents.len(), 1);
+    assert_eq!(
+        recorded_incidents[0].status,
+        ReorgIncidentStatus::RevertPending
+    );
+
+    // Marking a revert to another L1 batch shouldn't influence the incident.
+    incidents.mark_reverted(L1BatchNumber(LAST_CORRECT_L1_BATCH - 1));
+    assert!(incidents.pending_revert().is_some());
+    incidents.mark_reverted(L1BatchNumber(LAST_CORRECT_L1_BATCH));
+    assert_eq!(incidents.pending_revert(), None);
+    let recorded_incidents = incidents.all();
+    assert_eq!(recorded_incidents[0].status, ReorgIncidentStatus::Reverted);
+    assert!(recorded_incidents[0].reverted_at.is_some());
+
+    // The incident history should be exposed by a restarted detector.
+    let mut detector =
+        create_mock_detector(MockMainNodeClient::default(), pool).with_auto_remediation(incidents);
+    detector.event_handler.initialize();
+    let health = detector.health_check().check_health().await;
+    assert_matches!(health.status(), HealthStatus::Ready);
+    let details = health.details().unwrap();
+    assert_eq!(
+        details["incidents"][0]["last_correct_l1_batch"],
+        LAST_CORRECT_L1_BATCH
+    );
+    assert_eq!(details["incidents"][0]["status"], "reverted");
+}
+
 #[derive(Debug)]
 struct SlowMainNode {
     l1_batch_root_hash_call_count: Arc<AtomicUsize>,
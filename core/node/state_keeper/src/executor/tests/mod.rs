@@ -633,7 +633,7 @@ async fn execute_tx_with_large_packable_bytecode(vm_mode: FastVmMode) {
     executor.finish_batch().await.unwrap();
 }
 
-#[test_casing(2, [FastVmMode::Old, FastVmMode::Shadow])] // new VM doesn't support call tracing yet
+#[test_casing(3, FAST_VM_MODES)]
 #[tokio::test]
 async fn execute_tx_with_call_traces(vm_mode: FastVmMode) {
     let connection_pool = ConnectionPool::<Core>::constrained_test_pool(1).await;
@@ -48,6 +48,8 @@ fn halt_as_metric_label(halt: &Halt) -> &'static str {
         Halt::TracerCustom(_) => "TracerCustom",
         Halt::FailedToPublishCompressedBytecodes => "FailedToPublishCompressedBytecodes",
         Halt::FailedBlockTimestampAssertion => "FailedBlockTimestampAssertion",
+        Halt::ExecutionTimeout => "ExecutionTimeout",
+        Halt::ExecutionCyclesLimitReached => "ExecutionCyclesLimitReached",
     }
 }
 
@@ -32,6 +32,11 @@ impl AsyncRocksdbCache {
             task.with_db_options(state_keeper_db_options),
         )
     }
+
+    /// Returns a handle to the underlying RocksDB cache, e.g. to compact it.
+    pub fn rocksdb_cell(&self) -> RocksdbCell {
+        self.rocksdb_cell.clone()
+    }
 }
 
 #[async_trait]
@@ -19,7 +19,7 @@ use zksync_vm_interface::{executor::BatchExecutorFactory, L1BatchEnv, L2BlockEnv
 use crate::{
     storage::StorageSyncTask, ConcurrentOutputHandlerFactory, ConcurrentOutputHandlerFactoryTask,
     L1BatchOutput, L2BlockOutput, OutputHandler, OutputHandlerFactory, VmRunner, VmRunnerIo,
-    VmRunnerStorage,
+    VmRunnerStorageOptions,
 };
 
 /// A standalone component that retrieves all needed data for basic witness generation and saves it to the bucket
@@ -30,12 +30,13 @@ pub struct BasicWitnessInputProducer {
 
 impl BasicWitnessInputProducer {
     /// Create a new BWIP from the provided DB parameters and window size which
-    /// regulates how many batches this component can handle at the same time.
+    /// regulates how many batches this component can handle at the same time. With
+    /// [`VmRunnerStorageOptions::Snapshots`], all batches in the window are executed in parallel.
     pub async fn new(
         pool: ConnectionPool<Core>,
         object_store: Arc<dyn ObjectStore>,
         batch_executor_factory: Box<dyn BatchExecutorFactory<OwnedStorage>>,
-        rocksdb_path: String,
+        storage: VmRunnerStorageOptions,
         chain_id: L2ChainId,
         first_processed_batch: L1BatchNumber,
         window_size: u32,
@@ -44,8 +45,9 @@ impl BasicWitnessInputProducer {
             first_processed_batch,
             window_size,
         };
-        let (loader, loader_task) =
-            VmRunnerStorage::new(pool.clone(), rocksdb_path, io.clone(), chain_id).await?;
+        let (loader, loader_task) = storage
+            .into_loader(pool.clone(), io.clone(), chain_id)
+            .await?;
         let output_handler_factory = BasicWitnessInputProducerOutputHandlerFactory {
             pool: pool.clone(),
             object_store,
@@ -55,7 +57,7 @@ impl BasicWitnessInputProducer {
         let vm_runner = VmRunner::new(
             pool,
             Arc::new(io),
-            Arc::new(loader),
+            loader,
             Arc::new(output_handler_factory),
             batch_executor_factory,
         );
@@ -82,8 +84,8 @@ impl BasicWitnessInputProducer {
 /// Collection of tasks that need to be run in order for BWIP to work as intended.
 #[derive(Debug)]
 pub struct BasicWitnessInputProducerTasks {
-    /// Task that synchronizes storage with new available batches.
-    pub loader_task: StorageSyncTask<BasicWitnessInputProducerIo>,
+    /// Task that synchronizes storage with new available batches. Only present if RocksDB cache is used.
+    pub loader_task: Option<StorageSyncTask<BasicWitnessInputProducerIo>>,
     /// Task that handles output from processed batches.
     pub output_handler_factory_task:
         ConcurrentOutputHandlerFactoryTask<BasicWitnessInputProducerIo>,
@@ -158,7 +160,7 @@ impl OutputHandler for BasicWitnessInputProducerOutputHandler {
     async fn handle_l2_block(
         &mut self,
         _env: L2BlockEnv,
-        _output: &L2BlockOutput,
+        _output: Arc<L2BlockOutput>,
     ) -> anyhow::Result<()> {
         Ok(())
     }
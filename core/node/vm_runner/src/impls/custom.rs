@@ -0,0 +1,271 @@
+use std::{fmt, sync::Arc};
+
+use async_trait::async_trait;
+use tokio::sync::watch;
+use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_types::{L1BatchNumber, L2ChainId};
+use zksync_vm_executor::batch::MainBatchExecutorFactory;
+use zksync_vm_interface::{L1BatchEnv, L2BlockEnv, SystemEnv};
+
+use crate::{
+    storage::StorageSyncTask, ConcurrentOutputHandlerFactory, ConcurrentOutputHandlerFactoryTask,
+    L1BatchOutput, L2BlockOutput, OutputHandler, OutputHandlerFactory, VmRunner, VmRunnerIo,
+    VmRunnerStorageOptions,
+};
+
+/// Output of re-executing a single L1 batch passed to a [`VmRunnerWorkload`].
+#[derive(Debug)]
+pub struct ExecutedL1Batch {
+    /// Parameters of the L1 batch.
+    pub l1_batch_env: L1BatchEnv,
+    /// Execution process parameters.
+    pub system_env: SystemEnv,
+    /// Executed L2 blocks in the execution order.
+    pub l2_blocks: Vec<(L2BlockEnv, Arc<L2BlockOutput>)>,
+    /// Output from executing the L1 batch tip.
+    pub output: Arc<L1BatchOutput>,
+}
+
+impl ExecutedL1Batch {
+    /// Returns the number of this L1 batch.
+    pub fn number(&self) -> L1BatchNumber {
+        self.l1_batch_env.number
+    }
+}
+
+/// Custom workload computing derived data from re-executed L1 batches, e.g. indexes or analytics.
+///
+/// Each workload is run by a dedicated [`CustomVmRunner`] and has its own cursor in Postgres, so workloads
+/// can be added, removed or backfilled independently.
+#[async_trait]
+pub trait VmRunnerWorkload: 'static + fmt::Debug + Send + Sync {
+    /// Unique name of the workload. Used as a key for the workload cursor in Postgres, and to identify tasks
+    /// and DB connections of the workload VM runner.
+    fn name(&self) -> &'static str;
+
+    /// Whether call traces should be collected for executed transactions. Collecting call traces
+    /// slows down execution, so it's disabled by default.
+    fn save_call_traces(&self) -> bool {
+        false
+    }
+
+    /// Processes output of an L1 batch.
+    ///
+    /// Batches may be processed concurrently and out of order (up to the VM runner window size); the workload
+    /// cursor only advances once all preceding batches are processed. A batch can be re-processed after
+    /// a restart, so processing must be idempotent.
+    ///
+    /// # Errors
+    ///
+    /// Returned errors are fatal and stop the VM runner.
+    async fn process_l1_batch(
+        &self,
+        pool: &ConnectionPool<Core>,
+        batch: ExecutedL1Batch,
+    ) -> anyhow::Result<()>;
+
+    /// Removes data produced by the workload for all L1 batches after `last_l1_batch_to_keep`. Called by
+    /// the block reverter within its Postgres transaction; the workload cursor is rolled back separately.
+    async fn revert(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        last_l1_batch_to_keep: L1BatchNumber,
+    ) -> anyhow::Result<()>;
+}
+
+/// Options for a [`CustomVmRunner`].
+#[derive(Debug)]
+pub struct CustomVmRunnerOptions {
+    /// Storage used by the VM runner.
+    pub storage: VmRunnerStorageOptions,
+    /// All batches before this one (inclusive) are always considered to be processed.
+    pub first_processed_batch: L1BatchNumber,
+    /// How many max batches should be processed at the same time.
+    pub window_size: u32,
+}
+
+/// VM runner for a [`VmRunnerWorkload`].
+#[derive(Debug)]
+pub struct CustomVmRunner {
+    vm_runner: VmRunner,
+}
+
+impl CustomVmRunner {
+    /// Creates a new runner for the provided workload.
+    pub async fn new(
+        pool: ConnectionPool<Core>,
+        workload: Arc<dyn VmRunnerWorkload>,
+        chain_id: L2ChainId,
+        options: CustomVmRunnerOptions,
+    ) -> anyhow::Result<(Self, CustomVmRunnerTasks)> {
+        let io = CustomVmRunnerIo {
+            workload_name: workload.name(),
+            first_processed_batch: options.first_processed_batch,
+            window_size: options.window_size,
+        };
+        let (loader, loader_task) = options
+            .storage
+            .into_loader(pool.clone(), io.clone(), chain_id)
+            .await?;
+        let batch_executor_factory =
+            MainBatchExecutorFactory::<()>::new(workload.save_call_traces());
+        let output_handler_factory = CustomOutputHandlerFactory {
+            pool: pool.clone(),
+            workload,
+        };
+        let (output_handler_factory, output_handler_factory_task) =
+            ConcurrentOutputHandlerFactory::new(pool.clone(), io.clone(), output_handler_factory);
+        let vm_runner = VmRunner::new(
+            pool,
+            Arc::new(io),
+            loader,
+            Arc::new(output_handler_factory),
+            Box::new(batch_executor_factory),
+        );
+        Ok((
+            Self { vm_runner },
+            CustomVmRunnerTasks {
+                loader_task,
+                output_handler_factory_task,
+            },
+        ))
+    }
+
+    /// Continuously loads new available batches and passes their execution output to the workload.
+    ///
+    /// # Errors
+    ///
+    /// Propagates RocksDB and Postgres errors, and errors returned by the workload.
+    pub async fn run(self, stop_receiver: &watch::Receiver<bool>) -> anyhow::Result<()> {
+        self.vm_runner.run(stop_receiver).await
+    }
+}
+
+/// Collection of tasks that need to be run in order for a [`CustomVmRunner`] to work as intended.
+#[derive(Debug)]
+pub struct CustomVmRunnerTasks {
+    /// Task that synchronizes storage with new available batches. Only present if RocksDB cache is used.
+    pub loader_task: Option<StorageSyncTask<CustomVmRunnerIo>>,
+    /// Task that handles output from processed batches.
+    pub output_handler_factory_task: ConcurrentOutputHandlerFactoryTask<CustomVmRunnerIo>,
+}
+
+/// `VmRunnerIo` implementation for custom workloads. Progress is tracked in Postgres separately for each workload.
+#[derive(Debug, Clone)]
+pub struct CustomVmRunnerIo {
+    workload_name: &'static str,
+    first_processed_batch: L1BatchNumber,
+    window_size: u32,
+}
+
+#[async_trait]
+impl VmRunnerIo for CustomVmRunnerIo {
+    fn name(&self) -> &'static str {
+        self.workload_name
+    }
+
+    async fn latest_processed_batch(
+        &self,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        Ok(conn
+            .vm_runner_dal()
+            .get_workload_latest_processed_batch(self.workload_name)
+            .await?
+            .unwrap_or(self.first_processed_batch))
+    }
+
+    async fn last_ready_to_be_loaded_batch(
+        &self,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        Ok(conn
+            .vm_runner_dal()
+            .get_workload_last_ready_batch(
+                self.workload_name,
+                self.first_processed_batch,
+                self.window_size,
+            )
+            .await?)
+    }
+
+    async fn mark_l1_batch_as_processing(
+        &self,
+        conn: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        Ok(conn
+            .vm_runner_dal()
+            .mark_workload_batch_as_processing(self.workload_name, l1_batch_number)
+            .await?)
+    }
+
+    async fn mark_l1_batch_as_completed(
+        &self,
+        conn: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        conn.vm_runner_dal()
+            .mark_workload_batch_as_completed(self.workload_name, l1_batch_number)
+            .await
+    }
+}
+
+#[derive(Debug)]
+struct CustomOutputHandler {
+    pool: ConnectionPool<Core>,
+    workload: Arc<dyn VmRunnerWorkload>,
+    l1_batch_env: L1BatchEnv,
+    system_env: SystemEnv,
+    l2_blocks: Vec<(L2BlockEnv, Arc<L2BlockOutput>)>,
+}
+
+#[async_trait]
+impl OutputHandler for CustomOutputHandler {
+    async fn handle_l2_block(
+        &mut self,
+        env: L2BlockEnv,
+        output: Arc<L2BlockOutput>,
+    ) -> anyhow::Result<()> {
+        self.l2_blocks.push((env, output));
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "CustomOutputHandler::handle_l1_batch",
+        skip_all,
+        fields(workload = self.workload.name(), l1_batch = %self.l1_batch_env.number)
+    )]
+    async fn handle_l1_batch(self: Box<Self>, output: Arc<L1BatchOutput>) -> anyhow::Result<()> {
+        let batch = ExecutedL1Batch {
+            l1_batch_env: self.l1_batch_env,
+            system_env: self.system_env,
+            l2_blocks: self.l2_blocks,
+            output,
+        };
+        self.workload.process_l1_batch(&self.pool, batch).await
+    }
+}
+
+#[derive(Debug)]
+struct CustomOutputHandlerFactory {
+    pool: ConnectionPool<Core>,
+    workload: Arc<dyn VmRunnerWorkload>,
+}
+
+#[async_trait]
+impl OutputHandlerFactory for CustomOutputHandlerFactory {
+    async fn create_handler(
+        &self,
+        system_env: SystemEnv,
+        l1_batch_env: L1BatchEnv,
+    ) -> anyhow::Result<Box<dyn OutputHandler>> {
+        Ok(Box::new(CustomOutputHandler {
+            pool: self.pool.clone(),
+            workload: self.workload.clone(),
+            l1_batch_env,
+            system_env,
+            l2_blocks: vec![],
+        }))
+    }
+}
@@ -0,0 +1,84 @@
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use zksync_dal::{erc20_transfers_dal::Erc20Transfer, Connection, ConnectionPool, Core, CoreDal};
+use zksync_types::{h256_to_address, web3::keccak256, L1BatchNumber, L2BlockNumber, H256, U256};
+
+use super::custom::{ExecutedL1Batch, VmRunnerWorkload};
+
+/// Long signature of the ERC-20 `Transfer(address,address,uint256)` event.
+static TRANSFER_EVENT_SIGNATURE: Lazy<H256> =
+    Lazy::new(|| H256(keccak256(b"Transfer(address,address,uint256)")));
+
+/// Reference [`VmRunnerWorkload`] implementation indexing ERC-20 transfers.
+///
+/// Transfers are recognized by the event signature and shape: ERC-721 transfers, which have the same signature,
+/// are skipped since the token ID is indexed. Transfers of the base token are indexed as well. Events emitted outside
+/// of transactions (i.e., in the batch tip) are not indexed.
+#[derive(Debug, Default)]
+pub struct Erc20TransfersIndexer(());
+
+impl Erc20TransfersIndexer {
+    /// Creates a new indexer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn extract_transfers(batch: &ExecutedL1Batch) -> Vec<Erc20Transfer> {
+        let mut transfers = vec![];
+        for (block_env, block_output) in &batch.l2_blocks {
+            for (tx, exec_result) in &block_output.transactions {
+                let events = exec_result.tx_result.logs.events.iter().enumerate();
+                let tx_transfers = events.filter_map(|(i, event)| {
+                    let is_transfer = event.indexed_topics.len() == 3
+                        && event.indexed_topics[0] == *TRANSFER_EVENT_SIGNATURE
+                        && event.value.len() == 32;
+                    is_transfer.then(|| Erc20Transfer {
+                        tx_hash: tx.hash(),
+                        event_index_in_tx: i as u32,
+                        l2_block_number: L2BlockNumber(block_env.number),
+                        token_address: event.address,
+                        from: h256_to_address(&event.indexed_topics[1]),
+                        to: h256_to_address(&event.indexed_topics[2]),
+                        value: U256::from_big_endian(&event.value),
+                    })
+                });
+                transfers.extend(tx_transfers);
+            }
+        }
+        transfers
+    }
+}
+
+#[async_trait]
+impl VmRunnerWorkload for Erc20TransfersIndexer {
+    fn name(&self) -> &'static str {
+        "erc20_transfers_indexer"
+    }
+
+    #[tracing::instrument(skip_all, fields(l1_batch = %batch.number()))]
+    async fn process_l1_batch(
+        &self,
+        pool: &ConnectionPool<Core>,
+        batch: ExecutedL1Batch,
+    ) -> anyhow::Result<()> {
+        let transfers = Self::extract_transfers(&batch);
+        tracing::debug!("Indexing {} ERC-20 transfers", transfers.len());
+        let mut conn = pool.connection_tagged(self.name()).await?;
+        conn.erc20_transfers_dal()
+            .insert_transfers(batch.number(), &transfers)
+            .await?;
+        Ok(())
+    }
+
+    async fn revert(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        last_l1_batch_to_keep: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        storage
+            .erc20_transfers_dal()
+            .delete_transfers(last_l1_batch_to_keep)
+            .await?;
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
 //! Components powered by a VM runner.
 
 mod bwip;
+mod custom;
+mod erc20_transfers;
 mod playground;
 mod protective_reads;
 
@@ -8,6 +10,11 @@ pub use self::{
     bwip::{
         BasicWitnessInputProducer, BasicWitnessInputProducerIo, BasicWitnessInputProducerTasks,
     },
+    custom::{
+        CustomVmRunner, CustomVmRunnerIo, CustomVmRunnerOptions, CustomVmRunnerTasks,
+        ExecutedL1Batch, VmRunnerWorkload,
+    },
+    erc20_transfers::Erc20TransfersIndexer,
     playground::{
         VmPlayground, VmPlaygroundCursorOptions, VmPlaygroundIo, VmPlaygroundLoaderTask,
         VmPlaygroundStorageOptions, VmPlaygroundTasks,
@@ -28,7 +28,7 @@ use crate::{
     storage::{PostgresLoader, StorageLoader},
     ConcurrentOutputHandlerFactory, ConcurrentOutputHandlerFactoryTask, L1BatchOutput,
     L2BlockOutput, OutputHandler, OutputHandlerFactory, StorageSyncTask, VmRunner, VmRunnerIo,
-    VmRunnerStorage,
+    VmRunnerStorage, VmRunnerStorageOptions,
 };
 
 #[derive(Debug, Serialize)]
@@ -44,18 +44,7 @@ impl From<VmPlaygroundHealth> for Health {
 }
 
 /// Options configuring the storage loader for VM playground.
-#[derive(Debug)]
-#[non_exhaustive]
-pub enum VmPlaygroundStorageOptions {
-    /// Use RocksDB cache.
-    Rocksdb(String),
-    /// Use prefetched batch snapshots (with fallback to Postgres if protective reads are not available for a batch).
-    Snapshots {
-        /// Whether to shadow snapshot storage with Postgres. This degrades performance and is mostly useful
-        /// to test snapshot correctness.
-        shadow: bool,
-    },
-}
+pub type VmPlaygroundStorageOptions = VmRunnerStorageOptions;
 
 /// Options related to the VM playground cursor.
 #[derive(Debug)]
@@ -442,7 +431,7 @@ impl OutputHandler for VmPlaygroundOutputHandler {
     async fn handle_l2_block(
         &mut self,
         env: L2BlockEnv,
-        _output: &L2BlockOutput,
+        _output: Arc<L2BlockOutput>,
     ) -> anyhow::Result<()> {
         tracing::trace!("Processed L2 block #{}", env.number);
         Ok(())
@@ -10,7 +10,7 @@ use zksync_vm_interface::{L1BatchEnv, L2BlockEnv, SystemEnv};
 use crate::{
     storage::StorageSyncTask, ConcurrentOutputHandlerFactory, ConcurrentOutputHandlerFactoryTask,
     L1BatchOutput, L2BlockOutput, OutputHandler, OutputHandlerFactory, VmRunner, VmRunnerIo,
-    VmRunnerStorage,
+    VmRunnerStorageOptions,
 };
 
 /// A standalone component that writes protective reads asynchronously to state keeper.
@@ -21,10 +21,11 @@ pub struct ProtectiveReadsWriter {
 
 impl ProtectiveReadsWriter {
     /// Create a new protective reads writer from the provided DB parameters and window size which
-    /// regulates how many batches this component can handle at the same time.
+    /// regulates how many batches this component can handle at the same time. With
+    /// [`VmRunnerStorageOptions::Snapshots`], all batches in the window are executed in parallel.
     pub async fn new(
         pool: ConnectionPool<Core>,
-        rocksdb_path: String,
+        storage: VmRunnerStorageOptions,
         chain_id: L2ChainId,
         first_processed_batch: L1BatchNumber,
         window_size: u32,
@@ -33,8 +34,9 @@ impl ProtectiveReadsWriter {
             first_processed_batch,
             window_size,
         };
-        let (loader, loader_task) =
-            VmRunnerStorage::new(pool.clone(), rocksdb_path, io.clone(), chain_id).await?;
+        let (loader, loader_task) = storage
+            .into_loader(pool.clone(), io.clone(), chain_id)
+            .await?;
         let output_handler_factory = ProtectiveReadsOutputHandlerFactory { pool: pool.clone() };
         let (output_handler_factory, output_handler_factory_task) =
             ConcurrentOutputHandlerFactory::new(pool.clone(), io.clone(), output_handler_factory);
@@ -42,7 +44,7 @@ impl ProtectiveReadsWriter {
         let vm_runner = VmRunner::new(
             pool,
             Arc::new(io),
-            Arc::new(loader),
+            loader,
             Arc::new(output_handler_factory),
             Box::new(batch_processor),
         );
@@ -70,8 +72,8 @@ impl ProtectiveReadsWriter {
 /// intended.
 #[derive(Debug)]
 pub struct ProtectiveReadsWriterTasks {
-    /// Task that synchronizes storage with new available batches.
-    pub loader_task: StorageSyncTask<ProtectiveReadsIo>,
+    /// Task that synchronizes storage with new available batches. Only present if RocksDB cache is used.
+    pub loader_task: Option<StorageSyncTask<ProtectiveReadsIo>>,
     /// Task that handles output from processed batches.
     pub output_handler_factory_task: ConcurrentOutputHandlerFactoryTask<ProtectiveReadsIo>,
 }
@@ -143,7 +145,7 @@ impl OutputHandler for ProtectiveReadsOutputHandler {
     async fn handle_l2_block(
         &mut self,
         _env: L2BlockEnv,
-        _output: &L2BlockOutput,
+        _output: Arc<L2BlockOutput>,
     ) -> anyhow::Result<()> {
         Ok(())
     }
@@ -20,5 +20,5 @@ pub use self::{
         L2BlockOutput, OutputHandler, OutputHandlerFactory,
     },
     process::VmRunner,
-    storage::{BatchExecuteData, StorageSyncTask, VmRunnerStorage},
+    storage::{BatchExecuteData, StorageSyncTask, VmRunnerStorage, VmRunnerStorageOptions},
 };
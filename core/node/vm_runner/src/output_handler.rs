@@ -47,7 +47,7 @@ pub trait OutputHandler: fmt::Debug + Send {
     async fn handle_l2_block(
         &mut self,
         env: L2BlockEnv,
-        output: &L2BlockOutput,
+        output: Arc<L2BlockOutput>,
     ) -> anyhow::Result<()>;
 
     /// Handles an L1 batch processed by the VM.
@@ -190,7 +190,7 @@ impl OutputHandler for AsyncOutputHandler {
     async fn handle_l2_block(
         &mut self,
         env: L2BlockEnv,
-        output: &L2BlockOutput,
+        output: Arc<L2BlockOutput>,
     ) -> anyhow::Result<()> {
         self.handler.handle_l2_block(env, output).await
     }
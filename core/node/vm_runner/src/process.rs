@@ -121,7 +121,7 @@ impl VmRunner {
                 block_output.push(tx, exec_result);
             }
             output_handler
-                .handle_l2_block(block_env, &block_output)
+                .handle_l2_block(block_env, Arc::new(block_output))
                 .await
                 .context("VM runner failed to handle L2 block")?;
         }
@@ -1,6 +1,7 @@
 use std::{
     collections::{BTreeMap, HashMap},
     fmt,
+    num::NonZeroU32,
     sync::Arc,
     time::Duration,
 };
@@ -11,8 +12,9 @@ use tokio::sync::{watch, RwLock};
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
 use zksync_state::{
     AsyncCatchupTask, BatchDiff, OwnedStorage, RocksdbCell, RocksdbStorage, RocksdbStorageBuilder,
-    RocksdbWithMemory,
+    RocksdbStorageCompactor, RocksdbWithMemory, StateKeeperColumnFamily,
 };
+use zksync_storage::RocksDB;
 use zksync_types::{
     block::L2BlockExecutionData, commitment::PubdataParams, L1BatchNumber, L2ChainId,
 };
@@ -101,6 +103,45 @@ impl StorageLoader for PostgresLoader {
     }
 }
 
+/// Options configuring the storage loader for a VM runner.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum VmRunnerStorageOptions {
+    /// Use RocksDB cache. The cache is caught up batch by batch, so batches cannot be loaded faster
+    /// than the cache is updated. This mode is best suited for following the chain tip.
+    Rocksdb(String),
+    /// Use prefetched batch snapshots (with fallback to Postgres if protective reads are not available for a batch).
+    /// Each batch is loaded independently, so all batches in the VM runner window are executed in parallel. This mode
+    /// is best suited for re-executing historical batches.
+    Snapshots {
+        /// Whether to shadow snapshot storage with Postgres. This degrades performance and is mostly useful
+        /// to test snapshot correctness.
+        shadow: bool,
+    },
+}
+
+impl VmRunnerStorageOptions {
+    /// Creates a storage loader together with a task that needs to be run for the loader to work (if any).
+    pub(crate) async fn into_loader<Io: VmRunnerIo + Clone>(
+        self,
+        pool: ConnectionPool<Core>,
+        io: Io,
+        chain_id: L2ChainId,
+    ) -> anyhow::Result<(Arc<dyn StorageLoader>, Option<StorageSyncTask<Io>>)> {
+        Ok(match self {
+            Self::Rocksdb(path) => {
+                let (loader, loader_task) = VmRunnerStorage::new(pool, path, io, chain_id).await?;
+                (Arc::new(loader), Some(loader_task))
+            }
+            Self::Snapshots { shadow } => {
+                let mut loader = PostgresLoader::new(pool, chain_id).await?;
+                loader.shadow_snapshots(shadow);
+                (Arc::new(loader), None)
+            }
+        })
+    }
+}
+
 /// Data needed to execute an L1 batch.
 #[derive(Debug, Clone)]
 pub struct BatchExecuteData {
@@ -254,6 +295,7 @@ pub struct StorageSyncTask<Io: VmRunnerIo> {
     io: Io,
     state: Arc<RwLock<State>>,
     catchup_task: AsyncCatchupTask,
+    cache_compaction_interval: Option<NonZeroU32>,
 }
 
 impl<Io: VmRunnerIo> StorageSyncTask<Io> {
@@ -280,9 +322,18 @@ impl<Io: VmRunnerIo> StorageSyncTask<Io> {
             io,
             state,
             catchup_task: catchup_task.with_target_l1_batch_number(target_l1_batch_number),
+            cache_compaction_interval: None,
         })
     }
 
+    /// Enables compaction of the RocksDB cache once it processes the specified number of L1 batches
+    /// since the previous compaction. By default, the cache is not compacted explicitly.
+    #[must_use]
+    pub fn with_cache_compaction(mut self, l1_batch_interval: NonZeroU32) -> Self {
+        self.cache_compaction_interval = Some(l1_batch_interval);
+        self
+    }
+
     /// Access the underlying [`VmRunnerIo`].
     pub fn io(&self) -> &Io {
         &self.io
@@ -295,10 +346,30 @@ impl<Io: VmRunnerIo> StorageSyncTask<Io> {
     ///
     /// Propagates RocksDB and Postgres errors.
     pub async fn run(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
-        const SLEEP_INTERVAL: Duration = Duration::from_millis(50);
-
         self.catchup_task.run(stop_receiver.clone()).await?;
         let rocksdb = self.rocksdb_cell.wait().await?;
+        let Some(compaction_interval) = self.cache_compaction_interval else {
+            return self.sync_storage(rocksdb, &stop_receiver).await;
+        };
+
+        // Compaction runs concurrently with synchronization (and on a blocking thread), so that
+        // it doesn't stall loading new batches.
+        let compactor =
+            RocksdbStorageCompactor::new(rocksdb.clone(), self.pool.clone(), compaction_interval);
+        tokio::try_join!(
+            self.sync_storage(rocksdb, &stop_receiver),
+            compactor.run(stop_receiver.clone())
+        )?;
+        Ok(())
+    }
+
+    async fn sync_storage(
+        &self,
+        rocksdb: RocksDB<StateKeeperColumnFamily>,
+        stop_receiver: &watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        const SLEEP_INTERVAL: Duration = Duration::from_millis(50);
+
         loop {
             if *stop_receiver.borrow() {
                 tracing::info!("`StorageSyncTask` was interrupted");
@@ -327,7 +398,7 @@ impl<Io: VmRunnerIo> StorageSyncTask<Io> {
             // will cause them to have an inconsistent view on DB which we consider to be an
             // undefined behavior.
             let rocksdb = rocksdb_builder
-                .synchronize(&mut conn, &stop_receiver, Some(latest_processed_batch))
+                .synchronize(&mut conn, stop_receiver, Some(latest_processed_batch))
                 .await
                 .context("Failed to catch up state keeper RocksDB storage to Postgres")?;
             let Some(rocksdb) = rocksdb else {
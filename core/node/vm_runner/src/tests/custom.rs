@@ -0,0 +1,123 @@
+use tokio::sync::watch;
+use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
+use zksync_types::BOOTLOADER_ADDRESS;
+
+use super::*;
+use crate::{
+    impls::{CustomVmRunner, CustomVmRunnerOptions, Erc20TransfersIndexer, VmRunnerWorkload},
+    VmRunnerStorageOptions,
+};
+
+async fn wait_for_workload(
+    pool: &ConnectionPool<Core>,
+    workload: &str,
+    l1_batch_number: L1BatchNumber,
+) {
+    const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+    let started_at = tokio::time::Instant::now();
+    loop {
+        let mut conn = pool.connection().await.unwrap();
+        let latest_processed_batch = conn
+            .vm_runner_dal()
+            .get_workload_latest_processed_batch(workload)
+            .await
+            .unwrap();
+        if latest_processed_batch >= Some(l1_batch_number) {
+            return;
+        }
+        assert!(
+            started_at.elapsed() < TEST_TIMEOUT,
+            "Timed out waiting for workload `{workload}` to process batch #{l1_batch_number}"
+        );
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+#[tokio::test]
+async fn indexing_erc20_transfers() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    let genesis_params = GenesisParams::mock();
+    insert_genesis_batch(&mut conn, &genesis_params)
+        .await
+        .unwrap();
+    let mut accounts = vec![Account::random(), Account::random()];
+    fund(&mut conn, &accounts).await;
+    store_l1_batches(&mut conn, 1..=3, &genesis_params, &mut accounts)
+        .await
+        .unwrap();
+    drop(conn);
+    storage_writer::write_storage_logs(pool.clone(), true).await;
+
+    let workload = Arc::new(Erc20TransfersIndexer::new());
+    let options = CustomVmRunnerOptions {
+        storage: VmRunnerStorageOptions::Snapshots { shadow: true },
+        first_processed_batch: L1BatchNumber(0),
+        window_size: 2,
+    };
+    let (runner, tasks) = CustomVmRunner::new(
+        pool.clone(),
+        workload.clone(),
+        genesis_params.config().l2_chain_id,
+        options,
+    )
+    .await
+    .unwrap();
+    assert!(tasks.loader_task.is_none());
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let output_handler_task =
+        tokio::spawn(tasks.output_handler_factory_task.run(stop_receiver.clone()));
+    let runner_task = tokio::spawn(async move { runner.run(&stop_receiver).await });
+    wait_for_workload(&pool, workload.name(), L1BatchNumber(3)).await;
+    stop_sender.send_replace(true);
+    runner_task.await.unwrap().unwrap();
+    output_handler_task.await.unwrap().unwrap();
+
+    let account_addresses: Vec<_> = accounts.iter().map(Account::address).collect();
+    let mut conn = pool.connection().await.unwrap();
+    for l1_batch_number in 1..=3 {
+        let transfers = conn
+            .erc20_transfers_dal()
+            .get_transfers_for_l1_batch(L1BatchNumber(l1_batch_number))
+            .await
+            .unwrap();
+        // Each transaction pays fees in the base token.
+        assert!(!transfers.is_empty(), "{l1_batch_number}");
+        for transfer in &transfers {
+            assert_eq!(transfer.token_address, L2_BASE_TOKEN_ADDRESS);
+            assert!(
+                transfer.from == BOOTLOADER_ADDRESS || transfer.to == BOOTLOADER_ADDRESS,
+                "{transfer:?}"
+            );
+        }
+        assert!(transfers
+            .iter()
+            .any(|transfer| account_addresses.contains(&transfer.from)));
+    }
+
+    // Workload progress is tracked independently of other VM runners.
+    let latest_processed_batch = conn
+        .vm_runner_dal()
+        .get_workload_latest_processed_batch("other_workload")
+        .await
+        .unwrap();
+    assert_eq!(latest_processed_batch, None);
+
+    workload.revert(&mut conn, L1BatchNumber(1)).await.unwrap();
+    let transfers = conn
+        .erc20_transfers_dal()
+        .get_transfers_for_l1_batch(L1BatchNumber(1))
+        .await
+        .unwrap();
+    assert!(!transfers.is_empty());
+    for l1_batch_number in 2..=3 {
+        let transfers = conn
+            .erc20_transfers_dal()
+            .get_transfers_for_l1_batch(L1BatchNumber(l1_batch_number))
+            .await
+            .unwrap();
+        assert!(transfers.is_empty(), "{l1_batch_number}");
+    }
+}
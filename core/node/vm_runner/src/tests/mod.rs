@@ -27,6 +27,7 @@ use zksync_vm_interface::{
 
 use super::*;
 
+mod custom;
 mod output_handler;
 mod playground;
 mod process;
@@ -170,7 +171,7 @@ impl OutputHandlerFactory for TestOutputFactory {
             async fn handle_l2_block(
                 &mut self,
                 _env: L2BlockEnv,
-                _output: &L2BlockOutput,
+                _output: Arc<L2BlockOutput>,
             ) -> anyhow::Result<()> {
                 Ok(())
             }
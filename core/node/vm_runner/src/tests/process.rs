@@ -1,7 +1,7 @@
 use std::{collections::HashMap, sync::Arc};
 
 use tempfile::TempDir;
-use test_casing::test_casing;
+use test_casing::{test_casing, Product};
 use tokio::sync::{watch, RwLock};
 use zksync_dal::{ConnectionPool, Core};
 use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
@@ -10,11 +10,14 @@ use zksync_types::{L1BatchNumber, L2ChainId};
 use zksync_vm_executor::batch::MainBatchExecutorFactory;
 
 use super::*;
-use crate::{ConcurrentOutputHandlerFactory, VmRunner, VmRunnerStorage};
+use crate::{ConcurrentOutputHandlerFactory, VmRunner, VmRunnerStorageOptions};
 
-#[test_casing(4, [(1, 1), (5, 1), (5, 3), (5, 5)])]
+#[test_casing(8, Product(([(1, 1), (5, 1), (5, 3), (5, 5)], [false, true])))]
 #[tokio::test(flavor = "multi_thread")]
-async fn process_batches((batch_count, window): (u32, u32)) -> anyhow::Result<()> {
+async fn process_batches(
+    (batch_count, window): (u32, u32),
+    use_snapshots: bool,
+) -> anyhow::Result<()> {
     let rocksdb_dir = TempDir::new()?;
     let connection_pool = ConnectionPool::<Core>::test_pool().await;
     let mut conn = connection_pool.connection().await.unwrap();
@@ -35,16 +38,20 @@ async fn process_batches((batch_count, window): (u32, u32)) -> anyhow::Result<()
         current: 0.into(),
         max: window,
     }));
-    let (storage, task) = VmRunnerStorage::new(
-        connection_pool.clone(),
-        rocksdb_dir.path().to_str().unwrap().to_owned(),
-        io.clone(),
-        L2ChainId::default(),
-    )
-    .await?;
+    let storage_options = if use_snapshots {
+        VmRunnerStorageOptions::Snapshots { shadow: true }
+    } else {
+        VmRunnerStorageOptions::Rocksdb(rocksdb_dir.path().to_str().unwrap().to_owned())
+    };
+    let (storage, task) = storage_options
+        .into_loader(connection_pool.clone(), io.clone(), L2ChainId::default())
+        .await?;
+    assert_eq!(task.is_some(), !use_snapshots);
     let (_stop_sender, stop_receiver) = watch::channel(false);
-    let storage_stop_receiver = stop_receiver.clone();
-    tokio::task::spawn(async move { task.run(storage_stop_receiver).await.unwrap() });
+    if let Some(task) = task {
+        let storage_stop_receiver = stop_receiver.clone();
+        tokio::task::spawn(async move { task.run(storage_stop_receiver).await.unwrap() });
+    }
     let test_factory = TestOutputFactory {
         delays: HashMap::new(),
     };
@@ -53,7 +60,6 @@ async fn process_batches((batch_count, window): (u32, u32)) -> anyhow::Result<()
     let output_stop_receiver = stop_receiver.clone();
     tokio::task::spawn(async move { task.run(output_stop_receiver).await.unwrap() });
 
-    let storage = Arc::new(storage);
     let batch_executor = MainBatchExecutorFactory::<()>::new(false);
     let vm_runner = VmRunner::new(
         connection_pool,
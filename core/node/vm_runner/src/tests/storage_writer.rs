@@ -92,7 +92,7 @@ impl OutputHandler for StorageWriterIo {
     async fn handle_l2_block(
         &mut self,
         env: L2BlockEnv,
-        output: &L2BlockOutput,
+        output: Arc<L2BlockOutput>,
     ) -> anyhow::Result<()> {
         let mut conn = self.pool.connection().await?;
         let storage_logs = output
@@ -0,0 +1,169 @@
+//! Replays VM dumps on the legacy and fast VMs and outputs throughput, instruction counts
+//! and storage access counts for each dump as JSON.
+
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context as _;
+use clap::Parser;
+use vm_benchmark::{BatchReplayReport, VmReplayStats};
+use zksync_multivm::{interface::utils::VmDump, is_supported_by_fast_vm};
+
+/// Replay results keyed by the dump file name.
+type Results = BTreeMap<String, BatchReplayReport>;
+
+#[derive(Debug, Parser)]
+struct Cli {
+    /// Directory with VM dumps in the JSON format. Only dumps with protocol versions supported by the fast VM
+    /// are replayed; other dumps are skipped.
+    dumps_dir: PathBuf,
+    /// Number of timed replays for each dump and VM.
+    #[arg(long, default_value_t = 3)]
+    iterations: usize,
+    /// Path to results produced by this tool on another commit. If specified, a Markdown comparison
+    /// with these results is printed instead of the JSON results.
+    #[arg(long)]
+    diff: Option<PathBuf>,
+}
+
+impl Cli {
+    fn dump_paths(&self) -> anyhow::Result<Vec<PathBuf>> {
+        let entries = fs::read_dir(&self.dumps_dir)
+            .with_context(|| format!("failed reading `{}`", self.dumps_dir.display()))?;
+        let mut paths = vec![];
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                paths.push(path);
+            }
+        }
+        paths.sort_unstable();
+        Ok(paths)
+    }
+
+    fn read_dump(path: &Path) -> anyhow::Result<VmDump> {
+        let file = fs::File::open(path)?;
+        serde_json::from_reader(io::BufReader::new(file)).context("failed deserializing dump")
+    }
+
+    fn run(self) -> anyhow::Result<()> {
+        let mut results = Results::new();
+        for path in self.dump_paths()? {
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            let dump = Self::read_dump(&path)
+                .with_context(|| format!("failed reading dump `{}`", path.display()))?;
+            if !is_supported_by_fast_vm(dump.system_env.version) {
+                eprintln!(
+                    "Skipping dump `{name}`: protocol version {} is not supported by the fast VM",
+                    dump.system_env.version
+                );
+                continue;
+            }
+
+            eprintln!(
+                "Replaying dump `{name}` for L1 batch #{}",
+                dump.l1_batch_number()
+            );
+            let report = BatchReplayReport::new(&dump, self.iterations)
+                .with_context(|| format!("failed replaying dump `{name}`"))?;
+            if report.legacy.instructions != report.fast.instructions {
+                eprintln!(
+                    "Mismatch on number of instructions for dump `{name}`: legacy VM executed {}, fast VM executed {}",
+                    report.legacy.instructions, report.fast.instructions
+                );
+            }
+            results.insert(name, report);
+        }
+
+        if let Some(old_path) = &self.diff {
+            let old_results = fs::read(old_path)
+                .with_context(|| format!("failed reading `{}`", old_path.display()))?;
+            let old_results: Results =
+                serde_json::from_slice(&old_results).context("failed deserializing old results")?;
+            print_diff(&old_results, &results);
+        } else {
+            serde_json::to_writer_pretty(io::stdout().lock(), &results)?;
+            println!();
+        }
+        Ok(())
+    }
+}
+
+fn relative_change(old: f64, new: f64) -> f64 {
+    (new - old) / old * 100.0
+}
+
+fn vm_stats(report: &BatchReplayReport) -> [(&'static str, &VmReplayStats); 2] {
+    [("legacy", &report.legacy), ("fast", &report.fast)]
+}
+
+fn print_diff(old_results: &Results, new_results: &Results) {
+    let compared_results: Vec<_> = new_results
+        .iter()
+        .filter_map(|(name, new_report)| Some((name, old_results.get(name)?, new_report)))
+        .collect();
+
+    println!("## VM throughput on replayed batches");
+    println!("| Dump | VM | Old tx/s | New tx/s | Change |");
+    println!("|------|----|---------:|---------:|-------:|");
+    for &(name, old_report, new_report) in &compared_results {
+        for ((vm, old_stats), (_, new_stats)) in
+            vm_stats(old_report).into_iter().zip(vm_stats(new_report))
+        {
+            let (old_tps, new_tps) = (
+                old_stats.transactions_per_second,
+                new_stats.transactions_per_second,
+            );
+            let change = relative_change(old_tps, new_tps);
+            println!("| {name} | {vm} | {old_tps:.2} | {new_tps:.2} | {change:+.2}% |");
+        }
+    }
+
+    let differing_stats: Vec<_> = compared_results
+        .iter()
+        .flat_map(|&(name, old_report, new_report)| {
+            vm_stats(old_report)
+                .into_iter()
+                .zip(vm_stats(new_report))
+                .filter(|((_, old_stats), (_, new_stats))| !same_execution(old_stats, new_stats))
+                .map(move |((vm, old_stats), (_, new_stats))| (name, vm, old_stats, new_stats))
+        })
+        .collect();
+    if !differing_stats.is_empty() {
+        println!("\n## ⚠ Detected differing execution stats");
+        println!("| Dump | VM | Old instructions | New instructions | Old gas | New gas | Old reads / writes / missed | New reads / writes / missed |");
+        println!("|------|----|-----------------:|-----------------:|--------:|--------:|----------------------------:|----------------------------:|");
+        for (name, vm, old_stats, new_stats) in differing_stats {
+            let (old_storage, new_storage) = (old_stats.storage, new_stats.storage);
+            println!(
+                "| {name} | {vm} | {} | {} | {} | {} | {} / {} / {} | {} / {} / {} |",
+                old_stats.instructions,
+                new_stats.instructions,
+                old_stats.gas_used,
+                new_stats.gas_used,
+                old_storage.reads,
+                old_storage.writes,
+                old_storage.reads_missed,
+                new_storage.reads,
+                new_storage.writes,
+                new_storage.reads_missed
+            );
+        }
+        println!(
+            "\nChanges in execution stats on the same dumps indicate that VM behavior has changed between the compared commits."
+        );
+    }
+}
+
+fn same_execution(old_stats: &VmReplayStats, new_stats: &VmReplayStats) -> bool {
+    old_stats.instructions == new_stats.instructions
+        && old_stats.gas_used == new_stats.gas_used
+        && old_stats.storage == new_stats.storage
+}
+
+fn main() -> anyhow::Result<()> {
+    Cli::parse().run()
+}
@@ -3,6 +3,7 @@ use std::{cell::RefCell, rc::Rc};
 use zksync_multivm::{
     interface::{storage::WriteStorage, tracer::TracerExecutionStatus},
     tracers::dynamic::vm_1_5_0::DynTracer,
+    vm_fast::interface as vm2,
     vm_latest::{BootloaderState, HistoryMode, SimpleMemory, VmTracer, ZkSyncVmState},
 };
 
@@ -39,3 +40,20 @@ impl<S: WriteStorage, H: HistoryMode> VmTracer<S, H> for InstructionCounter {
 }
 
 impl<S: WriteStorage, H: HistoryMode> DynTracer<S, SimpleMemory<H>> for InstructionCounter {}
+
+/// Counterpart of [`InstructionCounter`] for the fast VM. The count is accumulated across all inspections
+/// the tracer participates in.
+#[derive(Debug, Default)]
+pub(crate) struct FastInstructionCounter(usize);
+
+impl FastInstructionCounter {
+    pub(crate) fn count(&self) -> usize {
+        self.0
+    }
+}
+
+impl vm2::Tracer for FastInstructionCounter {
+    fn before_instruction<OP: vm2::OpcodeType, S: vm2::GlobalStateInterface>(&mut self, _: &mut S) {
+        self.0 += 1;
+    }
+}
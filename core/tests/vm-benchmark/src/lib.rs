@@ -1,6 +1,7 @@
 use zksync_types::Transaction;
 
 pub use crate::{
+    replay::{BatchReplayReport, ReplayDump, ReplayOutput, StorageAccessCounts, VmReplayStats},
     transaction::{
         get_deploy_tx, get_deploy_tx_with_gas_limit, get_erc20_deploy_tx, get_erc20_transfer_tx,
         get_heavy_load_test_tx, get_load_test_deploy_tx, get_load_test_tx,
@@ -11,6 +12,7 @@ pub use crate::{
 
 pub mod criterion;
 mod instruction_counter;
+mod replay;
 mod transaction;
 mod vm;
 
@@ -0,0 +1,222 @@
+//! Benchmarking VMs on [`VmDump`]s, e.g. ones produced by the VM playground or shadow mode.
+
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use zksync_multivm::{
+    interface::{
+        storage::{ImmutableStorageView, StorageSnapshot, StorageView},
+        utils::VmDump,
+        L2BlockEnv, VmFactory, VmInterface,
+    },
+    is_supported_by_fast_vm, vm_fast,
+    vm_latest::{self, HistoryDisabled, ToTracerPointer},
+};
+use zksync_types::ProtocolVersionId;
+
+use crate::{
+    instruction_counter::{FastInstructionCounter, InstructionCounter},
+    vm::{BenchmarkingVmFactory, Fast, Legacy},
+};
+
+/// Storage access counts for a replayed batch as reported by [`StorageView`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageAccessCounts {
+    /// Number of processed read ops. The fast VM caches storage reads internally, so for it,
+    /// this number is expected to be close to [`Self::reads_missed`].
+    pub reads: usize,
+    /// Number of processed write ops. The fast VM doesn't write to [`StorageView`], so this is always 0 for it.
+    pub writes: usize,
+    /// Number of ops for which the value was read from the dumped storage snapshot.
+    pub reads_missed: usize,
+}
+
+/// Output of a single replay of a dump.
+#[derive(Debug)]
+pub struct ReplayOutput {
+    /// Wall-clock time spent on executing transactions, excluding VM initialization.
+    pub duration: Duration,
+    /// Total gas used by all transactions.
+    pub gas_used: u64,
+    pub storage: StorageAccessCounts,
+}
+
+/// VM that can replay [`VmDump`]s.
+pub trait ReplayDump: BenchmarkingVmFactory {
+    /// Replays the dump without any tracers.
+    fn replay(dump: &VmDump) -> anyhow::Result<ReplayOutput>;
+
+    /// Replays the dump counting instructions executed by the VM.
+    fn replay_counting_instructions(dump: &VmDump) -> anyhow::Result<(ReplayOutput, usize)>;
+}
+
+fn replay_with_tracer<Vm>(
+    dump: &VmDump,
+    tracer: &mut Vm::TracerDispatcher,
+) -> anyhow::Result<ReplayOutput>
+where
+    Vm: VmFactory<StorageView<StorageSnapshot>>,
+{
+    let storage = StorageView::new(dump.storage.clone()).to_rc_ptr();
+    let mut vm = Vm::new(
+        dump.l1_batch_env.clone(),
+        dump.system_env.clone(),
+        storage.clone(),
+    );
+    let l2_blocks = dump.l2_blocks.clone();
+
+    let started_at = Instant::now();
+    let mut gas_used = 0;
+    for (i, l2_block) in l2_blocks.into_iter().enumerate() {
+        if i > 0 {
+            // First block is already set.
+            vm.start_new_l2_block(L2BlockEnv {
+                number: l2_block.number.0,
+                timestamp: l2_block.timestamp,
+                prev_block_hash: l2_block.prev_block_hash,
+                max_virtual_blocks_to_create: l2_block.virtual_blocks,
+            });
+        }
+
+        for tx in l2_block.txs {
+            let (compression_result, tx_result) =
+                vm.inspect_transaction_with_bytecode_compression(tracer, tx, true);
+            compression_result
+                .map_err(|err| anyhow::anyhow!("failed compressing bytecodes: {err}"))?;
+            gas_used += tx_result.statistics.gas_used;
+        }
+    }
+    let duration = started_at.elapsed();
+    drop(vm);
+
+    let stats = storage.borrow().stats();
+    Ok(ReplayOutput {
+        duration,
+        gas_used,
+        storage: StorageAccessCounts {
+            reads: stats.get_value_storage_invocations,
+            writes: stats.set_value_storage_invocations,
+            reads_missed: stats.storage_invocations_missed,
+        },
+    })
+}
+
+type LegacyReplayVm = vm_latest::Vm<StorageView<StorageSnapshot>, HistoryDisabled>;
+
+impl ReplayDump for Legacy {
+    fn replay(dump: &VmDump) -> anyhow::Result<ReplayOutput> {
+        replay_with_tracer::<LegacyReplayVm>(dump, &mut Default::default())
+    }
+
+    fn replay_counting_instructions(dump: &VmDump) -> anyhow::Result<(ReplayOutput, usize)> {
+        let count = Rc::new(RefCell::new(0));
+        let mut tracer: <LegacyReplayVm as VmInterface>::TracerDispatcher =
+            InstructionCounter::new(count.clone())
+                .into_tracer_pointer()
+                .into();
+        let output = replay_with_tracer::<LegacyReplayVm>(dump, &mut tracer)?;
+        drop(tracer);
+        Ok((output, count.take()))
+    }
+}
+
+type FastReplayVm<Tr = ()> = vm_fast::Vm<ImmutableStorageView<StorageSnapshot>, Tr>;
+
+impl ReplayDump for Fast {
+    fn replay(dump: &VmDump) -> anyhow::Result<ReplayOutput> {
+        replay_with_tracer::<FastReplayVm>(dump, &mut ((), ()))
+    }
+
+    fn replay_counting_instructions(dump: &VmDump) -> anyhow::Result<(ReplayOutput, usize)> {
+        let mut tracer = (FastInstructionCounter::default(), ());
+        let output = replay_with_tracer::<FastReplayVm<FastInstructionCounter>>(dump, &mut tracer)?;
+        Ok((output, tracer.0.count()))
+    }
+}
+
+/// Replay statistics for a dump on a single VM.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VmReplayStats {
+    /// Number of instructions executed by the VM.
+    pub instructions: usize,
+    /// Total gas used by all transactions in the dump.
+    pub gas_used: u64,
+    pub storage: StorageAccessCounts,
+    /// Wall-clock durations of timed replays in seconds.
+    pub durations: Vec<f64>,
+    /// Throughput based on the fastest timed replay.
+    pub transactions_per_second: f64,
+    /// Throughput based on the fastest timed replay.
+    pub instructions_per_second: f64,
+    /// Throughput based on the fastest timed replay.
+    pub gas_per_second: f64,
+}
+
+impl VmReplayStats {
+    /// Replays the dump on the specified VM. The first replay counts instructions and isn't timed since tracing
+    /// significantly affects VM performance; it's followed by `iterations` timed replays without tracers.
+    pub fn new<VM: ReplayDump>(dump: &VmDump, iterations: usize) -> anyhow::Result<Self> {
+        anyhow::ensure!(iterations > 0, "at least one timed iteration is required");
+
+        let (output, instructions) = VM::replay_counting_instructions(dump)?;
+        let mut durations = Vec::with_capacity(iterations);
+        for _ in 0..iterations {
+            let timed_output = VM::replay(dump)?;
+            anyhow::ensure!(
+                timed_output.gas_used == output.gas_used,
+                "gas used differs between replays on {} VM: {} vs {}",
+                VM::LABEL.as_str(),
+                output.gas_used,
+                timed_output.gas_used
+            );
+            durations.push(timed_output.duration);
+        }
+
+        let best_duration = durations.iter().min().unwrap().as_secs_f64();
+        let transaction_count: usize = dump.l2_blocks.iter().map(|block| block.txs.len()).sum();
+        Ok(Self {
+            instructions,
+            gas_used: output.gas_used,
+            storage: output.storage,
+            durations: durations.iter().map(Duration::as_secs_f64).collect(),
+            transactions_per_second: transaction_count as f64 / best_duration,
+            instructions_per_second: instructions as f64 / best_duration,
+            gas_per_second: output.gas_used as f64 / best_duration,
+        })
+    }
+}
+
+/// Report for a dump replayed on the legacy and fast VMs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchReplayReport {
+    pub l1_batch_number: u32,
+    pub protocol_version: ProtocolVersionId,
+    pub l2_block_count: usize,
+    pub transaction_count: usize,
+    pub legacy: VmReplayStats,
+    pub fast: VmReplayStats,
+}
+
+impl BatchReplayReport {
+    /// Replays the dump on both VMs. Only dumps with protocol versions supported by the fast VM are supported.
+    pub fn new(dump: &VmDump, iterations: usize) -> anyhow::Result<Self> {
+        let protocol_version = dump.system_env.version;
+        anyhow::ensure!(
+            is_supported_by_fast_vm(protocol_version),
+            "protocol version {protocol_version} is not supported by the fast VM"
+        );
+
+        Ok(Self {
+            l1_batch_number: dump.l1_batch_number().0,
+            protocol_version,
+            l2_block_count: dump.l2_blocks.len(),
+            transaction_count: dump.l2_blocks.iter().map(|block| block.txs.len()).sum(),
+            legacy: VmReplayStats::new::<Legacy>(dump, iterations)?,
+            fast: VmReplayStats::new::<Fast>(dump, iterations)?,
+        })
+    }
+}
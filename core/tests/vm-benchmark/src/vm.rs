@@ -19,7 +19,10 @@ use zksync_types::{
     Transaction,
 };
 
-use crate::{instruction_counter::InstructionCounter, transaction::PRIVATE_KEY};
+use crate::{
+    instruction_counter::{FastInstructionCounter, InstructionCounter},
+    transaction::PRIVATE_KEY,
+};
 
 static SYSTEM_CONTRACTS: Lazy<BaseSystemContracts> = Lazy::new(BaseSystemContracts::load_from_disk);
 
@@ -98,26 +101,12 @@ impl BenchmarkingVmFactory for Fast {
 
 impl CountInstructions for Fast {
     fn count_instructions(tx: &Transaction) -> usize {
-        use vm_fast::interface as vm2;
-
-        #[derive(Default)]
-        struct InstructionCount(usize);
-
-        impl vm2::Tracer for InstructionCount {
-            fn before_instruction<OP: vm2::OpcodeType, S: vm2::GlobalStateInterface>(
-                &mut self,
-                _: &mut S,
-            ) {
-                self.0 += 1;
-            }
-        }
-
         let (system_env, l1_batch_env) = test_env();
         let mut vm = vm_fast::Vm::custom(l1_batch_env, system_env, &*STORAGE);
         vm.push_transaction(tx.clone());
-        let mut tracer = (InstructionCount(0), ());
+        let mut tracer = (FastInstructionCounter::default(), ());
         vm.inspect(&mut tracer, InspectExecutionMode::OneTx);
-        tracer.0 .0
+        tracer.0.count()
     }
 }
 
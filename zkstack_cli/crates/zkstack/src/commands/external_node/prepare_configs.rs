@@ -72,6 +72,7 @@ fn prepare_configs(
                 .web3_json_rpc
                 .http_url,
         )?,
+        main_node_fallback_urls: vec![],
         main_node_rate_limit_rps: None,
         bridge_addresses_refresh_interval_sec: None,
         gateway_chain_id: None,